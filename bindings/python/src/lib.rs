@@ -0,0 +1,117 @@
+#![doc = include_str!("../README.md")]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/alloy-rs/core/main/assets/alloy.jpg",
+    html_favicon_url = "https://raw.githubusercontent.com/alloy-rs/core/main/assets/favicon.ico"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
+use alloy_evm_ffi::{Chain, ExecuteBlockInput};
+use alloy_primitives::Bytes;
+use pyo3::{exceptions::PyValueError, prelude::*};
+use pythonize::{depythonize, pythonize};
+use serde::{de::DeserializeOwned, Serialize};
+
+pub mod simulate;
+pub use simulate::{SimulateInput, SimulationError, TraceOutput};
+
+pub mod tracer;
+pub use tracer::{CallFrame, CallTracer};
+
+impl From<SimulationError> for PyErr {
+    fn from(err: SimulationError) -> Self {
+        PyValueError::new_err(err.to_string())
+    }
+}
+
+/// Converts a Python object into `T`.
+fn extract<T: DeserializeOwned>(obj: &Bound<'_, PyAny>) -> PyResult<T> {
+    depythonize(obj).map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
+/// Converts `value` into a Python dict.
+fn to_python<'py>(py: Python<'py>, value: &impl Serialize) -> PyResult<Bound<'py, PyAny>> {
+    pythonize(py, value).map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
+/// Builds the input of a call from the Python arguments.
+fn simulate_input(
+    tx: &Bound<'_, PyAny>,
+    header: &Bound<'_, PyAny>,
+    state: Option<&Bound<'_, PyAny>>,
+    overrides: Option<&Bound<'_, PyAny>>,
+    chain: Option<&Bound<'_, PyAny>>,
+) -> PyResult<SimulateInput> {
+    Ok(SimulateInput {
+        chain: chain.map(extract).transpose()?.unwrap_or_default(),
+        header: extract(header)?,
+        tx: extract(tx)?,
+        state: state.map(extract).transpose()?.unwrap_or_default(),
+        overrides: overrides.map(extract).transpose()?,
+    })
+}
+
+/// Executes a transaction request without committing it.
+#[pyfunction]
+#[pyo3(signature = (tx, header, state = None, overrides = None, chain = None))]
+fn simulate_call<'py>(
+    py: Python<'py>,
+    tx: &Bound<'py, PyAny>,
+    header: &Bound<'py, PyAny>,
+    state: Option<&Bound<'py, PyAny>>,
+    overrides: Option<&Bound<'py, PyAny>>,
+    chain: Option<&Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let input = simulate_input(tx, header, state, overrides, chain)?;
+    let output = py.allow_threads(|| simulate::simulate_call(input))?;
+    to_python(py, &output)
+}
+
+/// Executes a transaction request without committing it and returns its call tree.
+#[pyfunction]
+#[pyo3(signature = (tx, header, state = None, overrides = None, chain = None))]
+fn trace_call<'py>(
+    py: Python<'py>,
+    tx: &Bound<'py, PyAny>,
+    header: &Bound<'py, PyAny>,
+    state: Option<&Bound<'py, PyAny>>,
+    overrides: Option<&Bound<'py, PyAny>>,
+    chain: Option<&Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let input = simulate_input(tx, header, state, overrides, chain)?;
+    let output = py.allow_threads(|| simulate::trace_call(input))?;
+    to_python(py, &output)
+}
+
+/// Executes an RLP encoded block on top of the pre-state.
+#[pyfunction]
+#[pyo3(signature = (block, pre_state, block_hashes = None, chain = None, witness = false))]
+fn execute_block<'py>(
+    py: Python<'py>,
+    block: Vec<u8>,
+    pre_state: &Bound<'py, PyAny>,
+    block_hashes: Option<&Bound<'py, PyAny>>,
+    chain: Option<&Bound<'py, PyAny>>,
+    witness: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    let input = ExecuteBlockInput {
+        chain: chain.map(extract::<Chain>).transpose()?.unwrap_or_default(),
+        block: Bytes::from(block),
+        pre_state: extract(pre_state)?,
+        block_hashes: block_hashes.map(extract).transpose()?.unwrap_or_default(),
+        witness,
+    };
+    let output =
+        py.allow_threads(|| alloy_evm_ffi::execute_block(input)).map_err(SimulationError::from)?;
+    to_python(py, &output)
+}
+
+/// Python module.
+#[pymodule]
+#[pyo3(name = "alloy_evm")]
+fn alloy_evm_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(simulate_call, m)?)?;
+    m.add_function(wrap_pyfunction!(trace_call, m)?)?;
+    m.add_function(wrap_pyfunction!(execute_block, m)?)?;
+    Ok(())
+}
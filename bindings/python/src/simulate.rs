@@ -0,0 +1,155 @@
+//! Call simulation and tracing.
+
+use crate::tracer::{CallFrame, CallTracer};
+use alloy_consensus::Header;
+use alloy_evm::{
+    overrides::{apply_state_overrides, StateOverrideError},
+    rpc::{EthTxEnvError, TryIntoTxEnv},
+    EthEvmFactory, Evm, EvmEnv, EvmFactory,
+};
+use alloy_evm_ffi::{input::build_db, Alloc, CallOutput, Chain, FfiError};
+use alloy_rpc_types_eth::{state::StateOverride, TransactionRequest};
+use revm::{
+    context::TxEnv,
+    database::{CacheDB, EmptyDB},
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, convert::Infallible};
+
+/// Errors raised by the bindings.
+#[derive(Debug, thiserror::Error)]
+pub enum SimulationError {
+    /// Decoding or executing a block failed.
+    #[error(transparent)]
+    Ffi(#[from] FfiError),
+    /// The transaction request could not be converted into a transaction environment.
+    #[error(transparent)]
+    TxEnv(#[from] EthTxEnvError),
+    /// The state overrides could not be applied.
+    #[error(transparent)]
+    StateOverride(#[from] StateOverrideError<Infallible>),
+    /// The EVM rejected the call.
+    #[error("call failed: {0}")]
+    Call(String),
+}
+
+/// Input of [`simulate_call`] and [`trace_call`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateInput {
+    /// Chain to execute the call on.
+    #[serde(default)]
+    pub chain: Chain,
+    /// Header of the block to execute the call in.
+    pub header: Header,
+    /// The call.
+    pub tx: TransactionRequest,
+    /// State to execute the call on.
+    #[serde(default)]
+    pub state: Alloc,
+    /// Overrides applied on top of the state.
+    #[serde(default)]
+    pub overrides: Option<StateOverride>,
+}
+
+/// Output of [`trace_call`].
+#[derive(Debug, Serialize)]
+pub struct TraceOutput {
+    /// Result of the call.
+    #[serde(flatten)]
+    pub call: CallOutput,
+    /// Call tree of the call.
+    pub trace: Option<CallFrame>,
+}
+
+impl SimulateInput {
+    /// Builds the environment, transaction and database of the call.
+    fn prepare(self) -> Result<(EvmEnv, TxEnv, CacheDB<EmptyDB>), SimulationError> {
+        let spec = self.chain.spec();
+        let blob_params = spec.blob_params_at_timestamp(self.header.timestamp);
+        let mut evm_env = EvmEnv::for_eth_block(&self.header, &spec, spec.chain_id(), blob_params);
+        // Like `eth_call`, don't require the caller to know its nonce.
+        evm_env.cfg_env.disable_nonce_check = true;
+
+        let tx = self.tx.try_into_tx_env(&evm_env)?;
+        let mut db = build_db(&self.state, &BTreeMap::new());
+        if let Some(overrides) = self.overrides {
+            apply_state_overrides(overrides, &mut db)?;
+        }
+        Ok((evm_env, tx, db))
+    }
+}
+
+/// Executes the call without committing it.
+pub fn simulate_call(input: SimulateInput) -> Result<CallOutput, SimulationError> {
+    let (evm_env, tx, db) = input.prepare()?;
+    let mut evm = EthEvmFactory.create_evm(db, evm_env);
+    let result = evm.transact(tx).map_err(|err| SimulationError::Call(err.to_string()))?.result;
+    Ok(result.into())
+}
+
+/// Executes the call without committing it, recording its call tree.
+pub fn trace_call(input: SimulateInput) -> Result<TraceOutput, SimulationError> {
+    let (evm_env, tx, db) = input.prepare()?;
+    let mut evm = EthEvmFactory.create_evm_with_inspector(db, evm_env, CallTracer::default());
+    let result = evm.transact(tx).map_err(|err| SimulationError::Call(err.to_string()))?.result;
+    let trace = core::mem::take(evm.inspector_mut()).into_root();
+    Ok(TraceOutput { call: result.into(), trace })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, bytes, Address, U256};
+    use alloy_rpc_types_eth::state::AccountOverride;
+
+    const CALLER: Address = address!("0x1111111111111111111111111111111111111111");
+    const CONTRACT: Address = address!("0x2222222222222222222222222222222222222222");
+
+    fn input() -> SimulateInput {
+        let mut state = Alloc::new();
+        state.insert(
+            CALLER,
+            alloy_evm_ffi::Account { balance: U256::from(10), ..Default::default() },
+        );
+        // PUSH1 0x2a PUSH1 0x00 SSTORE STOP
+        let overrides = StateOverride::from_iter([(
+            CONTRACT,
+            AccountOverride { code: Some(bytes!("602a60005500")), ..Default::default() },
+        )]);
+        SimulateInput {
+            chain: Chain::Mainnet,
+            header: Header {
+                number: 17_034_870,
+                timestamp: 1_681_338_455,
+                gas_limit: 30_000_000,
+                base_fee_per_gas: Some(0),
+                ..Default::default()
+            },
+            tx: TransactionRequest::default().from(CALLER).to(CONTRACT).value(U256::from(1)),
+            state,
+            overrides: Some(overrides),
+        }
+    }
+
+    #[test]
+    fn test_simulate_call() {
+        let output = simulate_call(input()).unwrap();
+        assert!(output.success);
+        assert!(output.gas_used > 21_000);
+    }
+
+    #[test]
+    fn test_trace_call() {
+        let output = trace_call(input()).unwrap();
+        assert!(output.call.success);
+
+        let trace = output.trace.unwrap();
+        assert_eq!(trace.typ, "CALL");
+        assert_eq!(trace.from, CALLER);
+        assert_eq!(trace.to, Some(CONTRACT));
+        assert_eq!(trace.value, Some(U256::from(1)));
+        assert!(trace.calls.is_empty());
+        assert!(trace.error.is_none());
+    }
+}
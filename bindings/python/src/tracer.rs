@@ -0,0 +1,137 @@
+//! Call tree tracer.
+
+use alloy_primitives::{Address, Bytes, U256};
+use revm::{
+    context_interface::ContextTr,
+    interpreter::{
+        CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome, CreateScheme,
+        InterpreterResult,
+    },
+    Inspector,
+};
+use serde::Serialize;
+
+/// A call frame in the format of geth's `callTracer`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+    /// Kind of the call, e.g. `CALL` or `CREATE2`.
+    #[serde(rename = "type")]
+    pub typ: &'static str,
+    /// Caller of the frame.
+    pub from: Address,
+    /// Callee of the frame, or the created contract.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<Address>,
+    /// Value transferred by the frame.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<U256>,
+    /// Gas available to the frame.
+    pub gas: u64,
+    /// Gas used by the frame.
+    pub gas_used: u64,
+    /// Calldata or init code.
+    pub input: Bytes,
+    /// Return or revert data.
+    #[serde(skip_serializing_if = "Bytes::is_empty")]
+    pub output: Bytes,
+    /// Error the frame failed with.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Frames called by this frame.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<Self>,
+}
+
+impl CallFrame {
+    fn end(&mut self, result: &InterpreterResult) {
+        self.gas_used = result.gas.spent();
+        self.output = result.output.clone();
+        if !result.is_ok() {
+            self.error = Some(if result.is_revert() {
+                "execution reverted".to_string()
+            } else {
+                format!("{:?}", result.result)
+            });
+        }
+    }
+}
+
+/// [`Inspector`] recording the call tree of a transaction.
+#[derive(Debug, Default)]
+pub struct CallTracer {
+    stack: Vec<CallFrame>,
+    root: Option<CallFrame>,
+}
+
+impl CallTracer {
+    /// Returns the root frame of the traced transaction, if any.
+    pub fn into_root(self) -> Option<CallFrame> {
+        self.root
+    }
+
+    fn pop(&mut self) {
+        let Some(frame) = self.stack.pop() else { return };
+        match self.stack.last_mut() {
+            Some(parent) => parent.calls.push(frame),
+            None => self.root = Some(frame),
+        }
+    }
+}
+
+impl<CTX: ContextTr> Inspector<CTX> for CallTracer {
+    fn call(&mut self, context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        let (typ, value) = match inputs.scheme {
+            CallScheme::Call => ("CALL", Some(inputs.call_value())),
+            CallScheme::CallCode => ("CALLCODE", Some(inputs.call_value())),
+            CallScheme::DelegateCall => ("DELEGATECALL", None),
+            CallScheme::StaticCall => ("STATICCALL", None),
+        };
+        self.stack.push(CallFrame {
+            typ,
+            from: inputs.caller,
+            to: Some(inputs.target_address),
+            value,
+            gas: inputs.gas_limit,
+            input: inputs.input.bytes(context),
+            ..Default::default()
+        });
+        None
+    }
+
+    fn call_end(&mut self, _context: &mut CTX, _inputs: &CallInputs, outcome: &mut CallOutcome) {
+        if let Some(frame) = self.stack.last_mut() {
+            frame.end(&outcome.result);
+        }
+        self.pop();
+    }
+
+    fn create(&mut self, _context: &mut CTX, inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        let typ = match inputs.scheme() {
+            CreateScheme::Create2 { .. } => "CREATE2",
+            _ => "CREATE",
+        };
+        self.stack.push(CallFrame {
+            typ,
+            from: inputs.caller(),
+            value: Some(inputs.value()),
+            gas: inputs.gas_limit(),
+            input: inputs.init_code().clone(),
+            ..Default::default()
+        });
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut CTX,
+        _inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        if let Some(frame) = self.stack.last_mut() {
+            frame.to = outcome.address;
+            frame.end(&outcome.result);
+        }
+        self.pop();
+    }
+}
@@ -0,0 +1,42 @@
+/* C declarations of the alloy-evm-ffi entry points. */
+
+#ifndef ALLOY_EVM_H
+#define ALLOY_EVM_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/*
+ * Executes a block.
+ *
+ * `input` is a JSON object with the fields `chain` (optional, one of "mainnet", "sepolia",
+ * "holesky" and "hoodi"), `block` (hex encoded RLP of the block), `preState` (accounts keyed by
+ * address), `blockHashes` (optional, hashes keyed by block number) and `witness` (optional bool).
+ *
+ * On return, `*output` points to a JSON string that must be released with `evm_free_string`.
+ * Returns 0 on success and -1 on failure.
+ */
+int32_t evm_execute_block(const char *input, char **output);
+
+/*
+ * Executes a call.
+ *
+ * `input` is a JSON object with the fields `chain` (optional), `header` (the header of the block
+ * to execute the call in), `tx` (an `eth_call` transaction request) and `preState`.
+ *
+ * On return, `*output` points to a JSON string that must be released with `evm_free_string`.
+ * Returns 0 on success and -1 on failure.
+ */
+int32_t evm_call(const char *input, char **output);
+
+/* Releases a string returned by one of the entry points. */
+void evm_free_string(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* ALLOY_EVM_H */
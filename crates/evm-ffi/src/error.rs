@@ -0,0 +1,33 @@
+use alloy_evm::{block::BlockExecutionError, rpc::EthTxEnvError};
+
+/// Errors returned by the entry points.
+#[derive(Debug, thiserror::Error)]
+pub enum FfiError {
+    /// The input is not valid UTF-8.
+    #[error("input is not valid UTF-8")]
+    Utf8(#[from] std::str::Utf8Error),
+    /// The input is not valid JSON or doesn't match the expected schema.
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// The block could not be decoded.
+    #[error("failed to decode block: {0}")]
+    BlockDecode(#[from] alloy_rlp::Error),
+    /// The signer of a transaction could not be recovered.
+    #[error("failed to recover signer of transaction {index}")]
+    SignerRecovery {
+        /// Index of the transaction in the block.
+        index: usize,
+    },
+    /// Executing the block failed.
+    #[error(transparent)]
+    Execution(#[from] BlockExecutionError),
+    /// The transaction request could not be converted into a transaction environment.
+    #[error(transparent)]
+    TxEnv(#[from] EthTxEnvError),
+    /// The EVM rejected the call.
+    #[error("call failed: {0}")]
+    Call(String),
+    /// Execution panicked.
+    #[error("execution panicked")]
+    Panic,
+}
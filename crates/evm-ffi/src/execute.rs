@@ -0,0 +1,91 @@
+//! Execution behind the entry points.
+
+use crate::{
+    input::{build_db, CallInput, ExecuteBlockInput},
+    FfiError, Witness, WitnessDb,
+};
+use alloy_consensus::{transaction::SignerRecoverable, Block, ReceiptEnvelope, TxEnvelope};
+use alloy_evm::{
+    block::{execute_block_stateless, BlockStateDiff},
+    eth::{receipt_builder::AlloyReceiptBuilder, EthBlockExecutionCtx, EthBlockExecutorFactory},
+    rpc::{CallOutput, TryIntoTxEnv},
+    EthEvmFactory, Evm, EvmEnv, EvmFactory,
+};
+use alloy_rlp::Decodable;
+use serde::Serialize;
+use std::{borrow::Cow, collections::BTreeMap};
+
+/// Output of `evm_execute_block`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecuteBlockOutput {
+    /// Receipts of the transactions.
+    pub receipts: Vec<ReceiptEnvelope>,
+    /// Gas used by the block.
+    pub gas_used: u64,
+    /// Blob gas used by the block.
+    pub blob_gas_used: u64,
+    /// Changes made by the block.
+    pub state_diff: BlockStateDiff,
+    /// Pre-state and block hashes accessed during execution, if requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub witness: Option<Witness>,
+}
+
+/// Executes the block on top of the pre-state.
+pub fn execute_block(input: ExecuteBlockInput) -> Result<ExecuteBlockOutput, FfiError> {
+    let block = Block::<TxEnvelope>::decode(&mut input.block.as_ref())?;
+    let header = &block.header;
+    let transactions = block
+        .body
+        .transactions
+        .iter()
+        .enumerate()
+        .map(|(index, tx)| {
+            tx.try_clone_into_recovered().map_err(|_| FfiError::SignerRecovery { index })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let spec = input.chain.spec();
+    let chain_id = spec.chain_id();
+    let blob_params = spec.blob_params_at_timestamp(header.timestamp);
+    let evm_env = EvmEnv::for_eth_block(header, &spec, chain_id, blob_params);
+    let factory = EthBlockExecutorFactory::new(AlloyReceiptBuilder, spec, EthEvmFactory);
+    let ctx = EthBlockExecutionCtx {
+        parent_hash: header.parent_hash,
+        parent_beacon_block_root: header.parent_beacon_block_root,
+        ommers: &block.body.ommers,
+        withdrawals: block.body.withdrawals.as_ref().map(|w| Cow::Borrowed(w.as_slice())),
+        extra_data: header.extra_data.clone(),
+        tx_count_hint: Some(transactions.len()),
+        blob_params,
+    };
+
+    let mut db = WitnessDb::new(build_db(&input.pre_state, &input.block_hashes));
+    let (result, bundle) =
+        execute_block_stateless(&factory, &mut db, evm_env, ctx, transactions.iter())?;
+
+    Ok(ExecuteBlockOutput {
+        receipts: result.receipts,
+        gas_used: result.gas_used,
+        blob_gas_used: result.blob_gas_used,
+        state_diff: BlockStateDiff::from_bundle(&bundle),
+        witness: input.witness.then(|| db.into_witness()),
+    })
+}
+
+/// Executes the call on top of the pre-state without committing it.
+pub fn call(input: CallInput) -> Result<CallOutput, FfiError> {
+    let spec = input.chain.spec();
+    let blob_params = spec.blob_params_at_timestamp(input.header.timestamp);
+    let mut evm_env = EvmEnv::for_eth_block(&input.header, &spec, spec.chain_id(), blob_params);
+    // Like `eth_call`, don't require the caller to know its nonce.
+    evm_env.cfg_env.disable_nonce_check = true;
+
+    let tx = input.tx.try_into_tx_env(&evm_env)?;
+    let db = build_db(&input.pre_state, &BTreeMap::new());
+    let mut evm = EthEvmFactory.create_evm(db, evm_env);
+    let result = evm.transact(tx).map_err(|err| FfiError::Call(err.to_string()))?.result;
+
+    Ok(result.into())
+}
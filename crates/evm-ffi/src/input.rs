@@ -0,0 +1,110 @@
+//! JSON inputs of the entry points.
+
+use alloy_consensus::Header;
+use alloy_evm::eth::spec::EthSpec;
+use alloy_primitives::{Address, Bytes, B256, U256};
+use alloy_rpc_types_eth::TransactionRequest;
+use revm::{
+    bytecode::Bytecode,
+    database::{CacheDB, EmptyDB},
+    state::AccountInfo,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Accounts keyed by address.
+pub type Alloc = BTreeMap<Address, Account>;
+
+/// State of an account.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Account {
+    /// Account balance.
+    pub balance: U256,
+    /// Account nonce.
+    pub nonce: u64,
+    /// Account code.
+    pub code: Bytes,
+    /// Account storage.
+    pub storage: BTreeMap<U256, U256>,
+}
+
+/// Chain whose hardfork schedule is used for execution.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Chain {
+    /// Ethereum mainnet.
+    #[default]
+    Mainnet,
+    /// Sepolia testnet.
+    Sepolia,
+    /// Holesky testnet.
+    Holesky,
+    /// Hoodi testnet.
+    Hoodi,
+}
+
+impl Chain {
+    /// Returns the chain specification.
+    pub fn spec(self) -> EthSpec {
+        match self {
+            Self::Mainnet => EthSpec::mainnet(),
+            Self::Sepolia => EthSpec::sepolia(),
+            Self::Holesky => EthSpec::holesky(),
+            Self::Hoodi => EthSpec::hoodi(),
+        }
+    }
+}
+
+/// Input of `evm_execute_block`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecuteBlockInput {
+    /// Chain the block belongs to.
+    #[serde(default)]
+    pub chain: Chain,
+    /// RLP encoded block.
+    pub block: Bytes,
+    /// State before the block.
+    pub pre_state: Alloc,
+    /// Hashes of previous blocks, for the `BLOCKHASH` opcode.
+    #[serde(default)]
+    pub block_hashes: BTreeMap<u64, B256>,
+    /// Whether to export the accessed pre-state.
+    #[serde(default)]
+    pub witness: bool,
+}
+
+/// Input of `evm_call`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallInput {
+    /// Chain to execute the call on.
+    #[serde(default)]
+    pub chain: Chain,
+    /// Header of the block to execute the call in.
+    pub header: Header,
+    /// The call.
+    pub tx: TransactionRequest,
+    /// State to execute the call on.
+    pub pre_state: Alloc,
+}
+
+/// Creates an in-memory database holding the given accounts.
+pub fn build_db(alloc: &Alloc, block_hashes: &BTreeMap<u64, B256>) -> CacheDB<EmptyDB> {
+    let mut db = CacheDB::new(EmptyDB::default());
+    for (address, account) in alloc {
+        let code = Bytecode::new_raw(account.code.clone());
+        db.insert_account_info(
+            *address,
+            AccountInfo::new(account.balance, account.nonce, code.hash_slow(), code),
+        );
+        for (slot, value) in &account.storage {
+            let Ok(()) = db.insert_account_storage(*address, *slot, *value);
+        }
+    }
+    for (number, hash) in block_hashes {
+        db.cache.block_hashes.insert(U256::from(*number), *hash);
+    }
+    db
+}
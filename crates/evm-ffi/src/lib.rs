@@ -0,0 +1,149 @@
+#![doc = include_str!("../README.md")]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/alloy-rs/core/main/assets/alloy.jpg",
+    html_favicon_url = "https://raw.githubusercontent.com/alloy-rs/core/main/assets/favicon.ico"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    ffi::{c_char, CStr, CString},
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+pub use alloy_evm::rpc::CallOutput;
+
+mod error;
+pub use error::FfiError;
+
+pub mod execute;
+pub use execute::{call, execute_block, ExecuteBlockOutput};
+
+pub mod input;
+pub use input::{Account, Alloc, CallInput, Chain, ExecuteBlockInput};
+
+mod witness;
+pub use witness::{Witness, WitnessDb};
+
+/// Executes a block, see `include/alloy_evm.h`.
+///
+/// # Safety
+///
+/// `input` must point to a NUL-terminated string and `output` must be valid for writes. The string
+/// written to `output` must be released with [`evm_free_string`].
+#[no_mangle]
+pub unsafe extern "C" fn evm_execute_block(input: *const c_char, output: *mut *mut c_char) -> i32 {
+    run(input, output, execute_block)
+}
+
+/// Executes a call, see `include/alloy_evm.h`.
+///
+/// # Safety
+///
+/// `input` must point to a NUL-terminated string and `output` must be valid for writes. The string
+/// written to `output` must be released with [`evm_free_string`].
+#[no_mangle]
+pub unsafe extern "C" fn evm_call(input: *const c_char, output: *mut *mut c_char) -> i32 {
+    run(input, output, call)
+}
+
+/// Releases a string returned by one of the entry points.
+///
+/// # Safety
+///
+/// `s` must have been returned by one of the entry points and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn evm_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Parses the input, runs `f` and writes its JSON encoded output.
+///
+/// Panics are caught, so they never unwind across the FFI boundary.
+unsafe fn run<I, O>(
+    input: *const c_char,
+    output: *mut *mut c_char,
+    f: fn(I) -> Result<O, FfiError>,
+) -> i32
+where
+    I: DeserializeOwned,
+    O: Serialize,
+{
+    let result = catch_unwind(AssertUnwindSafe(|| -> Result<String, FfiError> {
+        let input = serde_json::from_str(CStr::from_ptr(input).to_str()?)?;
+        let output = f(input)?;
+        Ok(serde_json::to_string(&output)?)
+    }))
+    .unwrap_or(Err(FfiError::Panic));
+
+    let (code, json) = match result {
+        Ok(json) => (0, json),
+        Err(err) => (-1, serde_json::json!({ "error": err.to_string() }).to_string()),
+    };
+    // JSON never contains NUL bytes.
+    *output = CString::new(json).expect("no NUL bytes").into_raw();
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invoke(
+        f: unsafe extern "C" fn(*const c_char, *mut *mut c_char) -> i32,
+        input: &str,
+    ) -> (i32, serde_json::Value) {
+        let input = CString::new(input).unwrap();
+        let mut output = std::ptr::null_mut();
+        unsafe {
+            let code = f(input.as_ptr(), &mut output);
+            let json = serde_json::from_str(CStr::from_ptr(output).to_str().unwrap()).unwrap();
+            evm_free_string(output);
+            (code, json)
+        }
+    }
+
+    #[test]
+    fn test_call() {
+        let input = r#"{
+            "header": {
+                "parentHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                "sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+                "miner": "0x0000000000000000000000000000000000000000",
+                "stateRoot": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                "transactionsRoot": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                "receiptsRoot": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                "logsBloom": "0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+                "difficulty": "0x0",
+                "number": "0x103e5f6",
+                "gasLimit": "0x1c9c380",
+                "gasUsed": "0x0",
+                "timestamp": "0x64377677",
+                "extraData": "0x",
+                "mixHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                "nonce": "0x0000000000000000",
+                "baseFeePerGas": "0x0"
+            },
+            "tx": {
+                "from": "0x1111111111111111111111111111111111111111",
+                "to": "0x2222222222222222222222222222222222222222",
+                "value": "0x1"
+            },
+            "preState": {
+                "0x1111111111111111111111111111111111111111": { "balance": "0x10" }
+            }
+        }"#;
+
+        let (code, output) = invoke(evm_call, input);
+        assert_eq!(code, 0, "{output}");
+        assert_eq!(output["success"], true);
+        assert_eq!(output["gasUsed"], 21_000);
+
+        let (code, output) = invoke(evm_call, "{}");
+        assert_eq!(code, -1);
+        assert!(output["error"].is_string());
+    }
+}
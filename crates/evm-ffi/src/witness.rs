@@ -0,0 +1,172 @@
+//! Recording of the accessed pre-state.
+
+use crate::input::Alloc;
+use alloy_primitives::{
+    map::{AddressMap, B256Map},
+    Address, B256, KECCAK256_EMPTY,
+};
+use revm::{
+    bytecode::Bytecode,
+    primitives::{StorageKey, StorageValue},
+    state::AccountInfo,
+    Database,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Everything read from the database during execution, as returned by
+/// [`WitnessDb::into_witness`].
+///
+/// The fields have the same shape as the corresponding fields of
+/// [`ExecuteBlockInput`](crate::ExecuteBlockInput), so the witness can be used as the input to
+/// re-execute the block.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Witness {
+    /// Pre-state of all accessed accounts and storage slots.
+    pub pre_state: Alloc,
+    /// Hashes of all blocks read with the `BLOCKHASH` opcode.
+    pub block_hashes: BTreeMap<u64, B256>,
+}
+
+/// A database recording the first value of every account, code, storage slot and block hash read
+/// from it.
+///
+/// When wrapped in a [`State`](revm::database::State), every value is read at most once, so the
+/// recorded values are the pre-state of everything accessed during execution.
+#[derive(Debug)]
+pub struct WitnessDb<DB> {
+    inner: DB,
+    accounts: Alloc,
+    code_hashes: AddressMap<B256>,
+    codes: B256Map<Bytecode>,
+    block_hashes: BTreeMap<u64, B256>,
+}
+
+impl<DB> WitnessDb<DB> {
+    /// Creates a new recording wrapper.
+    pub fn new(inner: DB) -> Self {
+        Self {
+            inner,
+            accounts: Alloc::new(),
+            code_hashes: AddressMap::default(),
+            codes: B256Map::default(),
+            block_hashes: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the recorded pre-state and block hashes.
+    ///
+    /// Accounts that did not exist are omitted, unless storage was read from them.
+    pub fn into_witness(self) -> Witness {
+        let Self { mut accounts, code_hashes, codes, block_hashes, .. } = self;
+        for (address, hash) in code_hashes {
+            if let (Some(account), Some(code)) = (accounts.get_mut(&address), codes.get(&hash)) {
+                account.code = code.original_bytes();
+            }
+        }
+        Witness { pre_state: accounts, block_hashes }
+    }
+}
+
+impl<DB: Database> Database for WitnessDb<DB> {
+    type Error = DB::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let info = self.inner.basic(address)?;
+        if let Some(info) = &info {
+            let account = self.accounts.entry(address).or_default();
+            account.balance = info.balance;
+            account.nonce = info.nonce;
+            if info.code_hash != KECCAK256_EMPTY {
+                if let Some(code) = &info.code {
+                    self.codes.insert(info.code_hash, code.clone());
+                }
+                self.code_hashes.insert(address, info.code_hash);
+            }
+        }
+        Ok(info)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        let code = self.inner.code_by_hash(code_hash)?;
+        self.codes.insert(code_hash, code.clone());
+        Ok(code)
+    }
+
+    fn storage(
+        &mut self,
+        address: Address,
+        index: StorageKey,
+    ) -> Result<StorageValue, Self::Error> {
+        let value = self.inner.storage(address, index)?;
+        self.accounts.entry(address).or_default().storage.entry(index).or_insert(value);
+        Ok(value)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        let hash = self.inner.block_hash(number)?;
+        self.block_hashes.insert(number, hash);
+        Ok(hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_evm::{EthEvmFactory, Evm, EvmEnv, EvmFactory};
+    use alloy_primitives::{bytes, TxKind, U256};
+    use revm::{
+        context::TxEnv,
+        database::{CacheDB, EmptyDB},
+    };
+
+    #[test]
+    fn test_witness_records_storage() {
+        let address = Address::repeat_byte(0x01);
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(address, AccountInfo { nonce: 1, ..Default::default() });
+        db.insert_account_storage(address, U256::from(7), U256::from(42)).unwrap();
+
+        let mut witness = WitnessDb::new(db);
+        witness.basic(address).unwrap();
+        assert_eq!(witness.storage(address, U256::from(7)).unwrap(), U256::from(42));
+        assert_eq!(witness.storage(address, U256::from(8)).unwrap(), U256::ZERO);
+
+        let alloc = witness.into_witness().pre_state;
+        let account = &alloc[&address];
+        assert_eq!(account.nonce, 1);
+        assert_eq!(
+            account.storage,
+            [(U256::from(7), U256::from(42)), (U256::from(8), U256::ZERO)].into_iter().collect()
+        );
+    }
+
+    #[test]
+    fn test_witness_records_block_hashes() {
+        let contract = Address::repeat_byte(0x02);
+        let hash = B256::repeat_byte(0x05);
+        // PUSH1 5, BLOCKHASH, POP, STOP
+        let code = Bytecode::new_raw(bytes!("6005405000"));
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(contract, AccountInfo::new(U256::ZERO, 0, code.hash_slow(), code));
+        db.cache.block_hashes.insert(U256::from(5), hash);
+
+        let mut evm_env = EvmEnv::default();
+        evm_env.block_env.number = U256::from(10);
+        let tx = TxEnv {
+            caller: Address::repeat_byte(0x01),
+            kind: TxKind::Call(contract),
+            gas_limit: 100_000,
+            ..Default::default()
+        };
+
+        let mut witness = WitnessDb::new(db);
+        let result = EthEvmFactory.create_evm(&mut witness, evm_env).transact(tx).unwrap();
+        assert!(result.result.is_success());
+
+        let witness = witness.into_witness();
+        assert_eq!(witness.block_hashes, [(5, hash)].into_iter().collect());
+        assert!(witness.pre_state.contains_key(&contract));
+    }
+}
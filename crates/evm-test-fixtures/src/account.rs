@@ -0,0 +1,156 @@
+use crate::FixtureError;
+use alloy_primitives::{Address, Bytes, B256, U256};
+use alloy_trie::{
+    root::{state_root_unhashed, storage_root_unhashed},
+    TrieAccount,
+};
+use revm::{
+    bytecode::Bytecode,
+    database::{CacheDB, EmptyDB},
+    state::AccountInfo,
+    DatabaseRef,
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Accounts of a fixture, keyed by address.
+pub type Alloc = BTreeMap<Address, FixtureAccount>;
+
+/// An account of a fixture's pre- or post-state.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct FixtureAccount {
+    /// Account balance.
+    pub balance: U256,
+    /// Account nonce.
+    pub nonce: U256,
+    /// Account code.
+    pub code: Bytes,
+    /// Account storage.
+    pub storage: BTreeMap<U256, U256>,
+}
+
+impl FixtureAccount {
+    fn is_empty(&self) -> bool {
+        self.balance.is_zero() && self.nonce.is_zero() && self.code.is_empty()
+    }
+}
+
+/// Creates an in-memory database holding the given accounts.
+pub(crate) fn build_db(alloc: &Alloc) -> CacheDB<EmptyDB> {
+    let mut db = CacheDB::new(EmptyDB::default());
+    for (address, account) in alloc {
+        let code = Bytecode::new_raw(account.code.clone());
+        db.insert_account_info(
+            *address,
+            AccountInfo::new(
+                account.balance,
+                account.nonce.saturating_to(),
+                code.hash_slow(),
+                code,
+            ),
+        );
+        for (slot, value) in &account.storage {
+            let Ok(()) = db.insert_account_storage(*address, *slot, *value);
+        }
+    }
+    db
+}
+
+/// Compares the accounts of `db` with the expected post-state.
+///
+/// Empty accounts are treated as non-existent.
+pub(crate) fn check_post_state(
+    db: &CacheDB<EmptyDB>,
+    expected: &Alloc,
+) -> Result<(), FixtureError> {
+    let mismatch = |address: Address, reason: String| FixtureError::PostState { address, reason };
+
+    for (address, expected) in expected {
+        let Ok(info) = db.basic_ref(*address);
+        let info = info.filter(|info| !info.is_empty());
+
+        let Some(info) = info else {
+            if expected.is_empty() {
+                continue;
+            }
+            return Err(mismatch(*address, "account missing".to_string()));
+        };
+
+        if info.balance != expected.balance {
+            return Err(mismatch(
+                *address,
+                format!("balance {}, expected {}", info.balance, expected.balance),
+            ));
+        }
+
+        if U256::from(info.nonce) != expected.nonce {
+            return Err(mismatch(
+                *address,
+                format!("nonce {}, expected {}", info.nonce, expected.nonce),
+            ));
+        }
+
+        let code = info.code.map(|code| code.original_bytes()).unwrap_or_default();
+        if code != expected.code {
+            return Err(mismatch(*address, format!("code {code}, expected {}", expected.code)));
+        }
+
+        for (slot, value) in &expected.storage {
+            let Ok(actual) = db.storage_ref(*address, *slot);
+            if actual != *value {
+                return Err(mismatch(
+                    *address,
+                    format!("storage slot {slot} is {actual}, expected {value}"),
+                ));
+            }
+        }
+
+        if let Some(account) = db.cache.accounts.get(address) {
+            for (slot, value) in &account.storage {
+                if !value.is_zero() && !expected.storage.contains_key(slot) {
+                    return Err(mismatch(
+                        *address,
+                        format!("unexpected storage slot {slot} with value {value}"),
+                    ));
+                }
+            }
+        }
+    }
+
+    for (address, account) in &db.cache.accounts {
+        if expected.contains_key(address) {
+            continue;
+        }
+        if account.info().is_some_and(|info| !info.is_empty()) {
+            return Err(mismatch(*address, "unexpected account".to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Computes the state root of the accounts in `db`.
+///
+/// Empty accounts are left out if `clear_empty` is set, as since EIP-161.
+pub(crate) fn state_root(db: &CacheDB<EmptyDB>, clear_empty: bool) -> B256 {
+    state_root_unhashed(db.cache.accounts.iter().filter_map(|(address, account)| {
+        let info = account.info().filter(|info| !(clear_empty && info.is_empty()))?;
+        let storage_root = storage_root_unhashed(
+            account
+                .storage
+                .iter()
+                .filter(|(_, value)| !value.is_zero())
+                .map(|(slot, value)| (B256::from(*slot), *value)),
+        );
+        Some((
+            *address,
+            TrieAccount {
+                nonce: info.nonce,
+                balance: info.balance,
+                storage_root,
+                code_hash: info.code_hash,
+            },
+        ))
+    }))
+}
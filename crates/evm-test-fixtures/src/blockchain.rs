@@ -0,0 +1,143 @@
+//! Blockchain test fixtures.
+//!
+//! A blockchain test imports a sequence of RLP encoded blocks on top of a genesis state. Blocks are
+//! executed and validated with [`validate_block_with_bundle`].
+
+use crate::{
+    account::{build_db, check_post_state},
+    Alloc, FixtureConfig, FixtureError, ForkSpec,
+};
+use alloy_consensus::{Block, TxEnvelope};
+use alloy_evm::eth::validate::{validate_block_with_bundle, ParentBlock};
+use alloy_primitives::{Bytes, B256, KECCAK256_EMPTY, U256};
+use alloy_rlp::Decodable;
+use revm::{
+    database::{BundleState, CacheDB, DbAccount, EmptyDB},
+    primitives::HashMap,
+};
+use serde::Deserialize;
+
+/// A blockchain test.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockchainTest {
+    /// Network name, e.g. `Cancun`.
+    pub network: String,
+    /// Genesis block header.
+    pub genesis_block_header: GenesisHeader,
+    /// RLP encoded genesis block, the parent of the first block.
+    #[serde(rename = "genesisRLP")]
+    pub genesis_rlp: Bytes,
+    /// Genesis state.
+    pub pre: Alloc,
+    /// Expected state after importing all blocks.
+    pub post_state: Option<Alloc>,
+    /// Expected hash of the last valid block.
+    pub lastblockhash: B256,
+    /// Blocks to import.
+    pub blocks: Vec<FixtureBlock>,
+    /// Test configuration.
+    #[serde(default)]
+    pub config: FixtureConfig,
+}
+
+/// Subset of the genesis header fields needed to run a [`BlockchainTest`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct GenesisHeader {
+    /// Hash of the genesis block.
+    pub hash: B256,
+}
+
+/// A block of a [`BlockchainTest`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FixtureBlock {
+    /// RLP encoded block.
+    pub rlp: Bytes,
+    /// Expected exception, if the block is invalid.
+    pub expect_exception: Option<String>,
+}
+
+impl BlockchainTest {
+    /// Imports all blocks and checks the last block hash and post-state.
+    pub fn run(&self) -> Result<(), FixtureError> {
+        let spec = ForkSpec::from_network(&self.network)?;
+        let chain_id = self.config.chain_id();
+
+        let mut db = build_db(&self.pre);
+        db.cache.block_hashes.insert(U256::ZERO, self.genesis_block_header.hash);
+        let mut last_block_hash = self.genesis_block_header.hash;
+        let mut parent = Block::<TxEnvelope>::decode(&mut self.genesis_rlp.as_ref())
+            .map_err(FixtureError::GenesisDecode)?
+            .header;
+
+        for (index, fixture_block) in self.blocks.iter().enumerate() {
+            let block = match Block::<TxEnvelope>::decode(&mut fixture_block.rlp.as_ref()) {
+                Ok(block) => block,
+                Err(_) if fixture_block.expect_exception.is_some() => continue,
+                Err(error) => return Err(FixtureError::BlockDecode { index, error }),
+            };
+
+            let result = validate_block_with_bundle(
+                &block,
+                ParentBlock::new(&parent),
+                &spec,
+                chain_id,
+                spec.blob_params(),
+                &mut db,
+            );
+
+            match (result, &fixture_block.expect_exception) {
+                (Ok(_), Some(expected)) => {
+                    return Err(FixtureError::UnexpectedSuccess { expected: expected.clone() })
+                }
+                (Err(error), None) => return Err(FixtureError::InvalidBlock { index, error }),
+                (Err(_), Some(_)) => {}
+                (Ok((_, bundle)), None) => {
+                    apply_bundle(&mut db, &bundle);
+
+                    last_block_hash = block.header.hash_slow();
+                    db.cache.block_hashes.insert(U256::from(block.header.number), last_block_hash);
+                    parent = block.header;
+                }
+            }
+        }
+
+        if last_block_hash != self.lastblockhash {
+            return Err(FixtureError::LastBlockHash {
+                got: last_block_hash,
+                expected: self.lastblockhash,
+            });
+        }
+
+        if let Some(expected) = &self.post_state {
+            check_post_state(&db, expected)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes the changes of an executed block to `db`.
+fn apply_bundle(db: &mut CacheDB<EmptyDB>, bundle: &BundleState) {
+    for (address, account) in &bundle.state {
+        let Some(mut info) = account.info.clone() else {
+            db.cache.accounts.insert(*address, DbAccount::new_not_existing());
+            continue;
+        };
+
+        if info.code.is_none() && info.code_hash != KECCAK256_EMPTY {
+            info.code = bundle.contracts.get(&info.code_hash).cloned();
+        }
+        db.insert_account_info(*address, info);
+
+        let storage = account.storage.iter().map(|(slot, value)| (*slot, value.present_value));
+        if account.was_destroyed() {
+            let Ok(()) = db.replace_account_storage(*address, storage.collect::<HashMap<_, _>>());
+        } else {
+            for (slot, value) in storage {
+                let Ok(()) = db.insert_account_storage(*address, slot, value);
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use alloy_evm::eth::validate::BlockValidityError;
+use alloy_primitives::{Address, B256};
+
+/// Errors that can occur when loading or running fixtures.
+#[derive(Debug, thiserror::Error)]
+pub enum FixtureError {
+    /// Failed to read a fixture file.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// Failed to parse a fixture.
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// The fixture targets a network that is not supported.
+    #[error("unsupported network: {0}")]
+    UnsupportedNetwork(String),
+    /// The fixture transaction has no sender.
+    #[error("transaction sender missing")]
+    MissingSender,
+    /// A transaction or block that was expected to fail was executed successfully.
+    #[error("expected exception {expected}, but execution succeeded")]
+    UnexpectedSuccess {
+        /// The expected exception.
+        expected: String,
+    },
+    /// A transaction that was expected to succeed failed.
+    #[error("unexpected transaction failure: {0}")]
+    UnexpectedFailure(String),
+    /// The genesis block could not be decoded.
+    #[error("failed to decode genesis block: {0}")]
+    GenesisDecode(alloy_rlp::Error),
+    /// A block could not be decoded.
+    #[error("failed to decode block {index}: {error}")]
+    BlockDecode {
+        /// Index of the block in the fixture.
+        index: usize,
+        /// The decoding error.
+        error: alloy_rlp::Error,
+    },
+    /// A block that was expected to be valid failed validation.
+    #[error("block {index} is invalid: {error}")]
+    InvalidBlock {
+        /// Index of the block in the fixture.
+        index: usize,
+        /// The validation error.
+        error: BlockValidityError,
+    },
+    /// Hash of the logs differs from the fixture.
+    #[error("logs hash mismatch: got {got}, expected {expected}")]
+    LogsHash {
+        /// Hash of the produced logs.
+        got: B256,
+        /// Expected hash.
+        expected: B256,
+    },
+    /// Post-state root differs from the fixture.
+    #[error("state root mismatch: got {got}, expected {expected}")]
+    StateRoot {
+        /// Root of the produced post-state.
+        got: B256,
+        /// Expected root.
+        expected: B256,
+    },
+    /// Hash of the last block differs from the fixture.
+    #[error("last block hash mismatch: got {got}, expected {expected}")]
+    LastBlockHash {
+        /// Hash of the last valid block.
+        got: B256,
+        /// Expected hash.
+        expected: B256,
+    },
+    /// The post-state of an account differs from the fixture.
+    #[error("post-state mismatch for {address}: {reason}")]
+    PostState {
+        /// The mismatching account.
+        address: Address,
+        /// Description of the mismatch.
+        reason: String,
+    },
+}
@@ -0,0 +1,115 @@
+//! Mapping of fixture network names to chain specifications.
+
+use crate::FixtureError;
+use alloy_eips::{eip6110::MAINNET_DEPOSIT_CONTRACT_ADDRESS, eip7840::BlobParams};
+use alloy_evm::eth::spec::EthExecutorSpec;
+use alloy_hardforks::{EthereumChainHardforks, EthereumHardfork, EthereumHardforks, ForkCondition};
+use alloy_primitives::Address;
+
+/// Supported hardforks, in activation order, with their fixture network names.
+const FORKS: &[(EthereumHardfork, &[&str])] = &[
+    (EthereumHardfork::Frontier, &["Frontier"]),
+    (EthereumHardfork::Homestead, &["Homestead"]),
+    (EthereumHardfork::Tangerine, &["EIP150", "TangerineWhistle"]),
+    (EthereumHardfork::SpuriousDragon, &["EIP158", "SpuriousDragon"]),
+    (EthereumHardfork::Byzantium, &["Byzantium"]),
+    (EthereumHardfork::Constantinople, &["Constantinople"]),
+    (EthereumHardfork::Petersburg, &["ConstantinopleFix", "Petersburg"]),
+    (EthereumHardfork::Istanbul, &["Istanbul"]),
+    (EthereumHardfork::Berlin, &["Berlin"]),
+    (EthereumHardfork::London, &["London"]),
+    (EthereumHardfork::Paris, &["Paris", "Merge"]),
+    (EthereumHardfork::Shanghai, &["Shanghai"]),
+    (EthereumHardfork::Cancun, &["Cancun"]),
+    (EthereumHardfork::Prague, &["Prague"]),
+    (EthereumHardfork::Osaka, &["Osaka"]),
+];
+
+/// Chain specification of a fixture network, with all hardforks up to and including the
+/// network's fork active from genesis.
+#[derive(Debug, Clone)]
+pub struct ForkSpec {
+    hardforks: EthereumChainHardforks,
+    blob_params: Option<BlobParams>,
+}
+
+impl ForkSpec {
+    /// Creates the [`ForkSpec`] for the given fixture network name, e.g. `Cancun`.
+    ///
+    /// Transition networks, e.g. `ShanghaiToCancunAtTime15k`, are not supported.
+    pub fn from_network(network: &str) -> Result<Self, FixtureError> {
+        let position = FORKS
+            .iter()
+            .position(|(_, names)| names.contains(&network))
+            .ok_or_else(|| FixtureError::UnsupportedNetwork(network.to_string()))?;
+
+        let hardforks = FORKS[..=position]
+            .iter()
+            .map(|(fork, _)| {
+                // Post-merge forks are activated by timestamp.
+                let condition = if matches!(
+                    fork,
+                    EthereumHardfork::Shanghai
+                        | EthereumHardfork::Cancun
+                        | EthereumHardfork::Prague
+                        | EthereumHardfork::Osaka
+                ) {
+                    ForkCondition::Timestamp(0)
+                } else {
+                    ForkCondition::Block(0)
+                };
+                (*fork, condition)
+            })
+            .collect::<Vec<_>>();
+
+        let blob_params = match FORKS[position].0 {
+            EthereumHardfork::Cancun => Some(BlobParams::cancun()),
+            EthereumHardfork::Prague => Some(BlobParams::prague()),
+            EthereumHardfork::Osaka => Some(BlobParams::osaka()),
+            _ => None,
+        };
+
+        Ok(Self { hardforks: EthereumChainHardforks::new(hardforks), blob_params })
+    }
+
+    /// Returns the blob parameters of the network, if blobs are supported.
+    pub const fn blob_params(&self) -> Option<BlobParams> {
+        self.blob_params
+    }
+}
+
+impl EthereumHardforks for ForkSpec {
+    fn ethereum_fork_activation(&self, fork: EthereumHardfork) -> ForkCondition {
+        self.hardforks.ethereum_fork_activation(fork)
+    }
+}
+
+impl EthExecutorSpec for ForkSpec {
+    fn deposit_contract_address(&self) -> Option<Address> {
+        Some(MAINNET_DEPOSIT_CONTRACT_ADDRESS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_evm::spec_by_timestamp_and_block_number;
+    use revm::primitives::hardfork::SpecId;
+
+    #[test]
+    fn network_to_spec() {
+        for (network, expected) in [
+            ("Frontier", SpecId::FRONTIER),
+            ("EIP150", SpecId::TANGERINE),
+            ("ConstantinopleFix", SpecId::PETERSBURG),
+            ("Paris", SpecId::MERGE),
+            ("Cancun", SpecId::CANCUN),
+            ("Prague", SpecId::PRAGUE),
+        ] {
+            let spec = ForkSpec::from_network(network).unwrap();
+            assert_eq!(spec_by_timestamp_and_block_number(&spec, 0, 0), expected, "{network}");
+        }
+
+        assert!(ForkSpec::from_network("ShanghaiToCancunAtTime15k").is_err());
+    }
+}
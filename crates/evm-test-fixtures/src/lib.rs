@@ -0,0 +1,40 @@
+#![doc = include_str!("../README.md")]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/alloy-rs/core/main/assets/alloy.jpg",
+    html_favicon_url = "https://raw.githubusercontent.com/alloy-rs/core/main/assets/favicon.ico"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
+use serde::de::DeserializeOwned;
+use std::{collections::BTreeMap, path::Path};
+
+mod account;
+pub use account::{Alloc, FixtureAccount};
+
+pub mod blockchain;
+pub use blockchain::BlockchainTest;
+
+mod error;
+pub use error::FixtureError;
+
+pub mod fork;
+pub use fork::ForkSpec;
+
+pub mod state;
+pub use state::{FixtureConfig, StateTest};
+
+/// Loads a fixture file, which maps test names to fixtures of type `T`.
+pub fn load_fixtures<T: DeserializeOwned>(
+    path: impl AsRef<Path>,
+) -> Result<BTreeMap<String, T>, FixtureError> {
+    let contents = std::fs::read_to_string(path)?;
+    parse_fixtures(&contents)
+}
+
+/// Parses the contents of a fixture file, which maps test names to fixtures of type `T`.
+pub fn parse_fixtures<T: DeserializeOwned>(
+    contents: &str,
+) -> Result<BTreeMap<String, T>, FixtureError> {
+    Ok(serde_json::from_str(contents)?)
+}
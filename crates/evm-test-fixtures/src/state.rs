@@ -0,0 +1,339 @@
+//! State test fixtures.
+//!
+//! A state test executes a single transaction on top of a pre-state, for every combination of
+//! fork and transaction parameters listed in its `post` section.
+
+use crate::{
+    account::{build_db, check_post_state, state_root},
+    Alloc, FixtureError, ForkSpec,
+};
+use alloy_consensus::Header;
+use alloy_eips::{eip2930::AccessList, eip7702::SignedAuthorization};
+use alloy_evm::{EthEvmFactory, Evm, EvmEnv, EvmFactory};
+use alloy_hardforks::EthereumHardforks;
+use alloy_primitives::{keccak256, Address, Bytes, TxKind, B256, U256};
+use revm::{context::TxEnv, context_interface::either::Either};
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+
+/// A state test.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateTest {
+    /// Block environment the transaction is executed in.
+    pub env: StateEnv,
+    /// Pre-state.
+    pub pre: Alloc,
+    /// Transaction parameters.
+    pub transaction: StateTransaction,
+    /// Expected results, keyed by fork name.
+    pub post: BTreeMap<String, Vec<StatePost>>,
+    /// Test configuration.
+    #[serde(default)]
+    pub config: FixtureConfig,
+}
+
+/// Fixture configuration.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct FixtureConfig {
+    /// Chain id, defaults to mainnet.
+    pub chainid: Option<U256>,
+}
+
+impl FixtureConfig {
+    /// Returns the configured chain id.
+    pub fn chain_id(&self) -> u64 {
+        self.chainid.map_or(1, |chain_id| chain_id.saturating_to())
+    }
+}
+
+/// Block environment of a state test.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateEnv {
+    /// Block beneficiary.
+    pub current_coinbase: Address,
+    /// Block gas limit.
+    pub current_gas_limit: U256,
+    /// Block number.
+    pub current_number: U256,
+    /// Block timestamp.
+    pub current_timestamp: U256,
+    /// Block difficulty.
+    #[serde(default)]
+    pub current_difficulty: U256,
+    /// Block prevrandao.
+    pub current_random: Option<B256>,
+    /// Block base fee.
+    pub current_base_fee: Option<U256>,
+    /// Block excess blob gas.
+    pub current_excess_blob_gas: Option<U256>,
+}
+
+impl StateEnv {
+    /// Returns a header carrying the environment, to be passed to [`EvmEnv::for_eth_block`].
+    fn header(&self) -> Header {
+        Header {
+            beneficiary: self.current_coinbase,
+            gas_limit: self.current_gas_limit.saturating_to(),
+            number: self.current_number.saturating_to(),
+            timestamp: self.current_timestamp.saturating_to(),
+            difficulty: self.current_difficulty,
+            mix_hash: self.current_random.unwrap_or_default(),
+            base_fee_per_gas: self.current_base_fee.map(|fee| fee.saturating_to()),
+            excess_blob_gas: self.current_excess_blob_gas.map(|gas| gas.saturating_to()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Transaction parameters of a state test.
+///
+/// `data`, `gas_limit` and `value` list the alternatives selected by [`PostIndexes`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateTransaction {
+    /// Transaction input alternatives.
+    pub data: Vec<Bytes>,
+    /// Gas limit alternatives.
+    pub gas_limit: Vec<U256>,
+    /// Value alternatives.
+    pub value: Vec<U256>,
+    /// Legacy gas price.
+    pub gas_price: Option<U256>,
+    /// EIP-1559 max fee per gas.
+    pub max_fee_per_gas: Option<U256>,
+    /// EIP-1559 max priority fee per gas.
+    pub max_priority_fee_per_gas: Option<U256>,
+    /// Sender nonce.
+    pub nonce: U256,
+    /// Transaction sender.
+    pub sender: Option<Address>,
+    /// Transaction recipient, `None` for contract creation.
+    #[serde(default, deserialize_with = "deserialize_to")]
+    pub to: Option<Address>,
+    /// Access list alternatives, selected by the data index.
+    pub access_lists: Option<Vec<Option<AccessList>>>,
+    /// EIP-4844 blob versioned hashes.
+    pub blob_versioned_hashes: Option<Vec<B256>>,
+    /// EIP-4844 max fee per blob gas.
+    pub max_fee_per_blob_gas: Option<U256>,
+    /// EIP-7702 authorization list.
+    pub authorization_list: Option<Vec<SignedAuthorization>>,
+}
+
+impl StateTransaction {
+    /// Builds the [`TxEnv`] for the given indexes.
+    pub fn tx_env(&self, indexes: &PostIndexes, chain_id: u64) -> Result<TxEnv, FixtureError> {
+        let caller = self.sender.ok_or(FixtureError::MissingSender)?;
+        let access_list =
+            self.access_lists.as_ref().and_then(|lists| lists.get(indexes.data).cloned().flatten());
+
+        let tx_type = if self.authorization_list.is_some() {
+            4
+        } else if self.blob_versioned_hashes.is_some() {
+            3
+        } else if self.max_fee_per_gas.is_some() {
+            2
+        } else if access_list.is_some() {
+            1
+        } else {
+            0
+        };
+
+        Ok(TxEnv {
+            tx_type,
+            caller,
+            gas_limit: self.gas_limit[indexes.gas].saturating_to(),
+            gas_price: self.gas_price.or(self.max_fee_per_gas).unwrap_or_default().saturating_to(),
+            gas_priority_fee: self.max_priority_fee_per_gas.map(|fee| fee.saturating_to()),
+            kind: self.to.map_or(TxKind::Create, TxKind::Call),
+            value: self.value[indexes.value],
+            data: self.data[indexes.data].clone(),
+            nonce: self.nonce.saturating_to(),
+            chain_id: Some(chain_id),
+            access_list: access_list.unwrap_or_default(),
+            blob_hashes: self.blob_versioned_hashes.clone().unwrap_or_default(),
+            max_fee_per_blob_gas: self.max_fee_per_blob_gas.unwrap_or_default().saturating_to(),
+            authorization_list: self
+                .authorization_list
+                .iter()
+                .flatten()
+                .cloned()
+                .map(Either::Left)
+                .collect(),
+        })
+    }
+}
+
+/// Expected result of a state test for a single combination of transaction parameters.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatePost {
+    /// Post-state root.
+    pub hash: B256,
+    /// Hash of the RLP encoded logs.
+    pub logs: B256,
+    /// Selected transaction parameters.
+    pub indexes: PostIndexes,
+    /// Expected exception, if the transaction is invalid.
+    pub expect_exception: Option<String>,
+    /// Expected post-state.
+    pub state: Option<Alloc>,
+}
+
+/// Indexes into the alternatives of [`StateTransaction`].
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct PostIndexes {
+    /// Index into [`StateTransaction::data`].
+    pub data: usize,
+    /// Index into [`StateTransaction::gas_limit`].
+    pub gas: usize,
+    /// Index into [`StateTransaction::value`].
+    pub value: usize,
+}
+
+impl StateTest {
+    /// Runs the test for every fork and parameter combination in [`StateTest::post`].
+    pub fn run(&self) -> Result<(), FixtureError> {
+        for (network, posts) in &self.post {
+            let spec = ForkSpec::from_network(network)?;
+            for post in posts {
+                self.run_post(&spec, post)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Executes the transaction and checks the result against `post`.
+    pub fn run_post(&self, spec: &ForkSpec, post: &StatePost) -> Result<(), FixtureError> {
+        let chain_id = self.config.chain_id();
+        let mut db = build_db(&self.pre);
+
+        let evm_env = EvmEnv::for_eth_block(self.env.header(), spec, chain_id, spec.blob_params());
+        let tx = self.transaction.tx_env(&post.indexes, chain_id)?;
+        let mut evm = EthEvmFactory::default().create_evm(&mut db, evm_env);
+
+        match (evm.transact_commit(tx), &post.expect_exception) {
+            (Ok(_), Some(expected)) => {
+                return Err(FixtureError::UnexpectedSuccess { expected: expected.clone() })
+            }
+            (Err(err), None) => return Err(FixtureError::UnexpectedFailure(err.to_string())),
+            (Err(_), Some(_)) => {}
+            (Ok(result), None) => {
+                let logs_hash = keccak256(alloy_rlp::encode(result.logs()));
+                if logs_hash != post.logs {
+                    return Err(FixtureError::LogsHash { got: logs_hash, expected: post.logs });
+                }
+            }
+        }
+
+        if let Some(expected) = &post.state {
+            check_post_state(&db, expected)?;
+        }
+
+        let number = self.env.current_number.saturating_to();
+        let root = state_root(&db, spec.is_spurious_dragon_active_at_block(number));
+        if root != post.hash {
+            return Err(FixtureError::StateRoot { got: root, expected: post.hash });
+        }
+
+        Ok(())
+    }
+}
+
+/// Deserializes a transaction recipient, where an empty string denotes contract creation.
+fn deserialize_to<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Address>, D::Error> {
+    let to = Option::<String>::deserialize(deserializer)?.unwrap_or_default();
+    if to.is_empty() {
+        return Ok(None);
+    }
+    to.parse().map(Some).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_fixtures;
+
+    const VALUE_TRANSFER: &str = r#"{
+        "value_transfer": {
+            "env": {
+                "currentCoinbase": "0x2adc25665018aa1fe0e6bc666dac8fc2697ff9ba",
+                "currentGasLimit": "0x055d4a80",
+                "currentNumber": "0x01",
+                "currentTimestamp": "0x03e8",
+                "currentRandom": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                "currentDifficulty": "0x00",
+                "currentBaseFee": "0x0a",
+                "currentExcessBlobGas": "0x00"
+            },
+            "pre": {
+                "0xa94f5374fce5edbc8e2a8697c15331677e6ebf0b": {
+                    "nonce": "0x00",
+                    "balance": "0x3b9aca00",
+                    "code": "0x",
+                    "storage": {}
+                }
+            },
+            "transaction": {
+                "nonce": "0x00",
+                "gasPrice": "0x0a",
+                "gasLimit": ["0x5208"],
+                "to": "0x0000000000000000000000000000000000001000",
+                "value": ["0x01"],
+                "data": ["0x"],
+                "sender": "0xa94f5374fce5edbc8e2a8697c15331677e6ebf0b",
+                "secretKey": "0x45a915e4d060149eb4365960e6a7a45f334393093061116b197e3240065ff2d8"
+            },
+            "post": {
+                "Cancun": [
+                    {
+                        "hash": "0x1b79fdeba6bcbe203fe4cfe366119a29d67f3ad0f8f1d41799979a38f9f7a63c",
+                        "logs": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+                        "indexes": { "data": 0, "gas": 0, "value": 0 },
+                        "state": {
+                            "0xa94f5374fce5edbc8e2a8697c15331677e6ebf0b": {
+                                "nonce": "0x01",
+                                "balance": "0x3b9795af",
+                                "code": "0x",
+                                "storage": {}
+                            },
+                            "0x0000000000000000000000000000000000001000": {
+                                "nonce": "0x00",
+                                "balance": "0x01",
+                                "code": "0x",
+                                "storage": {}
+                            }
+                        }
+                    }
+                ]
+            }
+        }
+    }"#;
+
+    #[test]
+    fn run_value_transfer() {
+        let fixtures = parse_fixtures::<StateTest>(VALUE_TRANSFER).unwrap();
+        fixtures["value_transfer"].run().unwrap();
+    }
+
+    #[test]
+    fn detect_post_state_mismatch() {
+        let mut fixtures = parse_fixtures::<StateTest>(VALUE_TRANSFER).unwrap();
+        let test = fixtures.get_mut("value_transfer").unwrap();
+        test.transaction.value[0] = U256::from(2);
+
+        assert!(matches!(test.run(), Err(FixtureError::PostState { .. })));
+    }
+
+    #[test]
+    fn detect_state_root_mismatch() {
+        let mut fixtures = parse_fixtures::<StateTest>(VALUE_TRANSFER).unwrap();
+        let test = fixtures.get_mut("value_transfer").unwrap();
+        let post = &mut test.post.get_mut("Cancun").unwrap()[0];
+        post.hash = B256::ZERO;
+
+        assert!(matches!(test.run(), Err(FixtureError::StateRoot { expected: B256::ZERO, .. })));
+    }
+}
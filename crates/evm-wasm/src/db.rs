@@ -0,0 +1,97 @@
+//! [`Database`] backed by a JavaScript object.
+
+use alloy_primitives::{map::B256Map, Address, Bytes, B256, U256};
+use revm::{bytecode::Bytecode, context_interface::DBErrorMarker, state::AccountInfo, Database};
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    /// JavaScript object providing the state, see the crate documentation for the interface.
+    pub type StateBackend;
+
+    #[wasm_bindgen(method, catch)]
+    fn basic(this: &StateBackend, address: String) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    fn storage(this: &StateBackend, address: String, slot: String) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name = blockHash)]
+    fn block_hash(this: &StateBackend, number: u64) -> Result<JsValue, JsValue>;
+}
+
+/// Error thrown by the [`StateBackend`] or returned when its result can't be decoded.
+#[derive(Debug, thiserror::Error)]
+#[error("state backend: {0}")]
+pub struct BackendError(String);
+
+impl DBErrorMarker for BackendError {}
+
+impl From<JsValue> for BackendError {
+    fn from(value: JsValue) -> Self {
+        Self(value.as_string().unwrap_or_else(|| format!("{value:?}")))
+    }
+}
+
+impl From<serde_wasm_bindgen::Error> for BackendError {
+    fn from(err: serde_wasm_bindgen::Error) -> Self {
+        Self(err.to_string())
+    }
+}
+
+/// Account returned by [`StateBackend`].
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct BackendAccount {
+    balance: U256,
+    nonce: u64,
+    code: Bytes,
+}
+
+/// [`Database`] loading state from a [`StateBackend`].
+///
+/// Codes of loaded accounts are kept, so that they can be served by hash.
+#[derive(Debug)]
+pub struct JsDatabase<'a> {
+    backend: &'a StateBackend,
+    codes: B256Map<Bytecode>,
+}
+
+impl<'a> JsDatabase<'a> {
+    /// Creates a new database reading from the backend.
+    pub fn new(backend: &'a StateBackend) -> Self {
+        Self { backend, codes: Default::default() }
+    }
+}
+
+impl Database for JsDatabase<'_> {
+    type Error = BackendError;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let value = self.backend.basic(address.to_string())?;
+        if value.is_null() || value.is_undefined() {
+            return Ok(None);
+        }
+        let account: BackendAccount = serde_wasm_bindgen::from_value(value)?;
+        let code = Bytecode::new_raw(account.code);
+        let code_hash = code.hash_slow();
+        self.codes.insert(code_hash, code.clone());
+        Ok(Some(AccountInfo::new(account.balance, account.nonce, code_hash, code)))
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.codes
+            .get(&code_hash)
+            .cloned()
+            .ok_or_else(|| BackendError(format!("unknown code hash {code_hash}")))
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let value = self.backend.storage(address.to_string(), B256::from(index).to_string())?;
+        Ok(serde_wasm_bindgen::from_value(value)?)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        Ok(serde_wasm_bindgen::from_value(self.backend.block_hash(number)?)?)
+    }
+}
@@ -0,0 +1,130 @@
+#![doc = include_str!("../README.md")]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/alloy-rs/core/main/assets/alloy.jpg",
+    html_favicon_url = "https://raw.githubusercontent.com/alloy-rs/core/main/assets/favicon.ico"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
+use alloy_consensus::Header;
+use alloy_evm::{
+    eth::spec::EthSpec,
+    rpc::{CallOutput, EthTxEnvError, TryIntoTxEnv},
+    Database, EthEvmFactory, Evm, EvmEnv, EvmFactory,
+};
+use alloy_primitives::ChainId;
+use alloy_rpc_types_eth::TransactionRequest;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+mod db;
+pub use db::{BackendError, JsDatabase, StateBackend};
+
+/// Errors returned by [`call`].
+#[derive(Debug, thiserror::Error)]
+pub enum CallError {
+    /// The transaction request couldn't be converted into a transaction.
+    #[error(transparent)]
+    TxEnv(#[from] EthTxEnvError),
+    /// Executing the call failed.
+    #[error("{0}")]
+    Execution(String),
+}
+
+/// Executes the transaction request on top of the block with the given header without committing
+/// it, using the hardfork schedule of `spec`.
+pub fn call<DB: Database>(
+    db: DB,
+    spec: &EthSpec,
+    header: &Header,
+    request: TransactionRequest,
+) -> Result<CallOutput, CallError> {
+    let blob_params = spec.blob_params_at_timestamp(header.timestamp);
+    let mut evm_env = EvmEnv::for_eth_block(header, spec, spec.chain_id(), blob_params);
+    // Like `eth_call`, don't require the caller to know its nonce.
+    evm_env.cfg_env.disable_nonce_check = true;
+
+    let tx = request.try_into_tx_env(&evm_env)?;
+    let mut evm = EthEvmFactory.create_evm(db, evm_env);
+    let result = evm.transact(tx).map_err(|err| CallError::Execution(err.to_string()))?.result;
+    Ok(result.into())
+}
+
+/// Executes the transaction request on top of the block with the given header without committing
+/// it, loading state from the backend.
+///
+/// `chain_id` selects the hardfork schedule, see [`EthSpec::from_chain_id`] for the supported
+/// networks.
+#[wasm_bindgen(js_name = ethCall)]
+pub fn eth_call(
+    backend: &StateBackend,
+    chain_id: ChainId,
+    header: JsValue,
+    request: JsValue,
+) -> Result<JsValue, JsError> {
+    let spec = EthSpec::from_chain_id(chain_id)
+        .ok_or_else(|| JsError::new(&format!("unsupported chain id {chain_id}")))?;
+    let header: Header = serde_wasm_bindgen::from_value(header)?;
+    let request: TransactionRequest = serde_wasm_bindgen::from_value(request)?;
+
+    let output = call(JsDatabase::new(backend), &spec, &header, request)?;
+    Ok(output.serialize(&serde_wasm_bindgen::Serializer::json_compatible())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, bytes, Address, Bytes, B256};
+    use revm::{
+        database::{CacheDB, EmptyDB},
+        state::{AccountInfo, Bytecode},
+    };
+
+    const CALLER: Address = address!("0x1111111111111111111111111111111111111111");
+    const CONTRACT: Address = address!("0x2222222222222222222222222222222222222222");
+
+    fn db(code: Bytes) -> CacheDB<EmptyDB> {
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(CONTRACT, AccountInfo::default().with_code(Bytecode::new_raw(code)));
+        db
+    }
+
+    fn header(timestamp: u64) -> Header {
+        Header {
+            number: 17_034_870,
+            timestamp,
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(0),
+            ..Default::default()
+        }
+    }
+
+    fn request() -> TransactionRequest {
+        TransactionRequest::default().from(CALLER).to(CONTRACT)
+    }
+
+    #[test]
+    fn test_call() {
+        // PUSH1 0x2a PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN
+        let db = db(bytes!("602a60005260206000f3"));
+        let output = call(db, &EthSpec::mainnet(), &header(1_681_338_455), request()).unwrap();
+
+        assert!(output.success);
+        assert_eq!(output.output, Bytes::from(B256::with_last_byte(0x2a)));
+        assert_eq!(output.halt_reason, None);
+    }
+
+    #[test]
+    fn test_call_uses_spec() {
+        // PUSH0 PUSH0 RETURN, only valid from Shanghai on.
+        let code = bytes!("5f5ff3");
+        let spec = EthSpec::from_chain_id(1).unwrap();
+
+        let output = call(db(code.clone()), &spec, &header(1_681_338_455), request()).unwrap();
+        assert!(output.success);
+
+        let output = call(db(code), &spec, &header(1_681_338_443), request()).unwrap();
+        assert!(!output.success);
+        assert!(output.halt_reason.is_some());
+    }
+}
@@ -0,0 +1,16 @@
+//! Workloads shared between benchmarks.
+
+use alloy_consensus::Header;
+
+#[cfg(feature = "op")]
+pub mod op;
+
+/// A London block, so that no system contracts are required.
+pub fn header() -> Header {
+    Header {
+        number: 13_000_000,
+        gas_limit: 1_000_000_000,
+        base_fee_per_gas: Some(0),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,125 @@
+//! A deposit-heavy OP workload.
+//!
+//! OP transactions are executed with the Ethereum block executor, which converts deposits into
+//! plain transaction environments. Deposits don't mint anything this way, so the workload doesn't
+//! transfer value.
+
+use alloy_consensus::{transaction::Recovered, Eip658Value, Header, Receipt, ReceiptWithBloom};
+use alloy_evm::{
+    eth::{
+        receipt_builder::{ReceiptBuilder, ReceiptBuilderCtx},
+        spec::EthSpec,
+        EthBlockExecutionCtx, EthBlockExecutor,
+    },
+    EthEvmFactory, Evm, EvmEnv, EvmFactory,
+};
+use alloy_primitives::{hex, Address, Bytes, Sealed, TxKind, B256, U256};
+use op_alloy::consensus::{OpDepositReceipt, OpReceiptEnvelope, OpTxEnvelope, OpTxType, TxDeposit};
+use revm::{
+    bytecode::Bytecode,
+    database::{CacheDB, EmptyDB, State},
+    inspector::NoOpInspector,
+    state::AccountInfo,
+};
+
+/// Number of deposits in the workload, each from a different depositor.
+pub const DEPOSITS: usize = 1_000;
+
+const BRIDGE: Address = Address::repeat_byte(0x42);
+
+/// Stores the first calldata word in the slot of the caller, like a bridge finalizing a deposit.
+const BRIDGE_CODE: &str = "600035335500";
+
+/// The Ethereum block executor building OP receipts.
+pub type OpExecutor<'a> = EthBlockExecutor<
+    'a,
+    <EthEvmFactory as EvmFactory>::Evm<&'a mut State<CacheDB<EmptyDB>>, NoOpInspector>,
+    EthSpec,
+    OpReceiptBuilder,
+>;
+
+/// Receipt builder for OP transactions.
+#[derive(Debug, Clone, Copy)]
+pub struct OpReceiptBuilder;
+
+impl ReceiptBuilder for OpReceiptBuilder {
+    type Transaction = OpTxEnvelope;
+    type Receipt = OpReceiptEnvelope;
+    type Extra = ();
+
+    fn build_receipt<E: Evm>(&self, ctx: ReceiptBuilderCtx<'_, OpTxType, E>) -> Self::Receipt {
+        let ReceiptWithBloom { receipt, logs_bloom } = Receipt {
+            status: Eip658Value::Eip658(ctx.result.is_success()),
+            cumulative_gas_used: ctx.cumulative_gas_used,
+            logs: ctx.result.into_logs(),
+        }
+        .with_bloom();
+
+        match ctx.tx_type {
+            OpTxType::Legacy => {
+                OpReceiptEnvelope::Legacy(ReceiptWithBloom::new(receipt, logs_bloom))
+            }
+            OpTxType::Eip2930 => {
+                OpReceiptEnvelope::Eip2930(ReceiptWithBloom::new(receipt, logs_bloom))
+            }
+            OpTxType::Eip1559 => {
+                OpReceiptEnvelope::Eip1559(ReceiptWithBloom::new(receipt, logs_bloom))
+            }
+            OpTxType::Eip7702 => {
+                OpReceiptEnvelope::Eip7702(ReceiptWithBloom::new(receipt, logs_bloom))
+            }
+            OpTxType::Deposit => OpReceiptEnvelope::Deposit(ReceiptWithBloom::new(
+                OpDepositReceipt {
+                    inner: receipt,
+                    deposit_nonce: None,
+                    deposit_receipt_version: None,
+                },
+                logs_bloom,
+            )),
+        }
+    }
+}
+
+/// Returns the pre-state and the deposits of the workload.
+pub fn deposits() -> (CacheDB<EmptyDB>, Vec<Recovered<OpTxEnvelope>>) {
+    let mut db = CacheDB::new(EmptyDB::default());
+    db.insert_account_info(
+        BRIDGE,
+        AccountInfo::default()
+            .with_code(Bytecode::new_raw(hex::decode(BRIDGE_CODE).unwrap().into())),
+    );
+
+    let txs = (0..DEPOSITS as u64)
+        .map(|i| {
+            let from = Address::left_padding_from(&(i + 1).to_be_bytes());
+            db.insert_account_info(from, AccountInfo::default());
+            let tx = TxDeposit {
+                source_hash: B256::from(U256::from(i)),
+                from,
+                to: TxKind::Call(BRIDGE),
+                gas_limit: 100_000,
+                input: B256::from(U256::from(i + 1)).into(),
+                ..Default::default()
+            };
+            Recovered::new_unchecked(OpTxEnvelope::Deposit(Sealed::new(tx)), from)
+        })
+        .collect();
+
+    (db, txs)
+}
+
+/// Creates an executor for a block with the given header on top of `state`.
+pub fn executor<'a>(state: &'a mut State<CacheDB<EmptyDB>>, header: &Header) -> OpExecutor<'a> {
+    let evm_env = EvmEnv::for_eth_block(header, EthSpec::mainnet(), 1, None);
+    let evm = EthEvmFactory.create_evm(state, evm_env);
+    let ctx = EthBlockExecutionCtx {
+        parent_hash: B256::ZERO,
+        parent_beacon_block_root: None,
+        ommers: &[],
+        withdrawals: None,
+        extra_data: Bytes::new(),
+        tx_count_hint: Some(DEPOSITS),
+        blob_params: None,
+    };
+    EthBlockExecutor::new(evm, ctx, EthSpec::mainnet(), OpReceiptBuilder)
+}
@@ -0,0 +1,154 @@
+//! Benchmarks of block execution and environment construction.
+//!
+//! Every workload executes a full block with the Ethereum block executor on top of a fresh
+//! [`State`], so the numbers include receipt building and state commits, but not state root
+//! computation. With the `op` feature, this includes the deposit-heavy OP workload shared with the
+//! `op_deposits` benchmarks.
+
+#![allow(missing_docs)]
+
+mod common;
+
+use alloy_consensus::{transaction::Recovered, SignableTransaction, TxEnvelope, TxLegacy};
+use alloy_evm::{
+    block::{BlockExecutor, BlockExecutorFactory},
+    eth::{
+        receipt_builder::AlloyReceiptBuilder, spec::EthSpec, EthBlockExecutionCtx,
+        EthBlockExecutorFactory,
+    },
+    EthEvmFactory, EvmEnv, EvmFactory,
+};
+use alloy_primitives::{hex, Address, Bytes, Signature, TxKind, B256, U256};
+use common::header;
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use revm::{
+    bytecode::Bytecode,
+    database::{CacheDB, EmptyDB, State},
+    state::AccountInfo,
+};
+
+type Factory = EthBlockExecutorFactory<AlloyReceiptBuilder, EthSpec, EthEvmFactory>;
+
+const SENDER: Address = Address::repeat_byte(0x01);
+const CONTRACT: Address = Address::repeat_byte(0x02);
+
+/// Decrements the balance slot of the caller and increments the one of the address in the first
+/// calldata word, like an ERC-20 `transfer` without checks.
+const TOKEN: &str = "33546001900333556000358054600101905500";
+/// Swaps the amount in the first calldata word against constant product reserves in slots 0 and
+/// 1, like a Uniswap V2 pair without fees.
+const PAIR: &str = "6000358060005401806000559060015480910282900490036001555000";
+/// Writes `i` to slot `i` for all `i` in `1..=10_000`.
+const SSTORE_LOOP: &str = "6127105b8015601357808055600190036003565b00";
+
+fn call(nonce: u64, input: Bytes, gas_limit: u64) -> Recovered<TxEnvelope> {
+    let tx = TxLegacy {
+        nonce,
+        gas_price: 0,
+        gas_limit,
+        to: TxKind::Call(CONTRACT),
+        input,
+        ..Default::default()
+    };
+    Recovered::new_unchecked(tx.into_signed(Signature::test_signature()).into(), SENDER)
+}
+
+fn db(code: &str, storage: impl IntoIterator<Item = (U256, U256)>) -> CacheDB<EmptyDB> {
+    let mut db = CacheDB::new(EmptyDB::default());
+    db.insert_account_info(SENDER, AccountInfo::default());
+    db.insert_account_info(
+        CONTRACT,
+        AccountInfo::default().with_code(Bytecode::new_raw(hex::decode(code).unwrap().into())),
+    );
+    for (slot, value) in storage {
+        db.insert_account_storage(CONTRACT, slot, value).unwrap();
+    }
+    db
+}
+
+fn execute(factory: &Factory, db: CacheDB<EmptyDB>, txs: &[Recovered<TxEnvelope>]) -> u64 {
+    let header = header();
+    let mut state = State::builder().with_database(db).with_bundle_update().build();
+    let evm_env = EvmEnv::for_eth_block(&header, factory.spec().clone(), 1, None);
+    let evm = factory.evm_factory().create_evm(&mut state, evm_env);
+    let ctx = EthBlockExecutionCtx {
+        parent_hash: B256::ZERO,
+        parent_beacon_block_root: None,
+        ommers: &[],
+        withdrawals: None,
+        extra_data: Bytes::new(),
+        tx_count_hint: Some(txs.len()),
+        blob_params: None,
+    };
+    factory.create_executor(evm, ctx).execute_block(txs).unwrap().gas_used
+}
+
+fn bench_workload(
+    c: &mut Criterion,
+    name: &str,
+    db: CacheDB<EmptyDB>,
+    txs: Vec<Recovered<TxEnvelope>>,
+) {
+    let factory = Factory::new(AlloyReceiptBuilder, EthSpec::mainnet(), EthEvmFactory);
+    let gas_used = execute(&factory, db.clone(), &txs);
+
+    let mut group = c.benchmark_group("execute_block");
+    group.throughput(Throughput::Elements(gas_used));
+    group.bench_function(name, |b| {
+        b.iter_batched(|| db.clone(), |db| execute(&factory, db, &txs), BatchSize::LargeInput)
+    });
+    group.finish();
+}
+
+fn erc20_transfers(c: &mut Criterion) {
+    let db = db(TOKEN, [(U256::from_be_slice(SENDER.as_slice()), U256::MAX)]);
+    let txs = (0..1_000)
+        .map(|nonce| call(nonce, B256::with_last_byte(nonce as u8).into(), 100_000))
+        .collect();
+    bench_workload(c, "erc20_transfers", db, txs);
+}
+
+fn swaps(c: &mut Criterion) {
+    let reserves = U256::from(10).pow(U256::from(30));
+    let db = db(PAIR, [(U256::ZERO, reserves), (U256::from(1), reserves)]);
+    let txs = (0..1_000)
+        .map(|nonce| call(nonce, B256::from(U256::from(1_000 + nonce)).into(), 100_000))
+        .collect();
+    bench_workload(c, "swaps", db, txs);
+}
+
+fn sstore_10k(c: &mut Criterion) {
+    let db = db(SSTORE_LOOP, []);
+    bench_workload(c, "sstore_10k", db, vec![call(0, Bytes::new(), 500_000_000)]);
+}
+
+#[cfg(feature = "op")]
+fn op_deposits(c: &mut Criterion) {
+    let (db, txs) = common::op::deposits();
+    let execute = |db| {
+        let mut state = State::builder().with_database(db).with_bundle_update().build();
+        common::op::executor(&mut state, &header()).execute_block(&txs).unwrap().gas_used
+    };
+    let gas_used = execute(db.clone());
+
+    let mut group = c.benchmark_group("execute_block");
+    group.throughput(Throughput::Elements(gas_used));
+    group.bench_function("op_deposits", |b| {
+        b.iter_batched(|| db.clone(), execute, BatchSize::LargeInput)
+    });
+    group.finish();
+}
+
+fn evm_env(c: &mut Criterion) {
+    let header = header();
+    let spec = EthSpec::mainnet();
+    c.bench_function("evm_env/for_eth_block", |b| {
+        b.iter(|| EvmEnv::for_eth_block(&header, spec.clone(), 1, None))
+    });
+}
+
+#[cfg(not(feature = "op"))]
+criterion_group!(benches, erc20_transfers, swaps, sstore_10k, evm_env);
+#[cfg(feature = "op")]
+criterion_group!(benches, erc20_transfers, swaps, sstore_10k, op_deposits, evm_env);
+criterion_main!(benches);
@@ -0,0 +1,46 @@
+//! Benchmarks of executing the deposits at the start of an OP block.
+//!
+//! Compares [`execute_deposit_transactions`], which loads all depositors before executing the
+//! deposits, with executing the deposits one by one. Both execute the deposit-heavy workload of the
+//! `executor` benchmarks on top of a fresh [`State`].
+
+#![allow(missing_docs)]
+
+mod common;
+
+use alloy_evm::{block::BlockExecutor, op::execute_deposit_transactions};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use revm::database::State;
+
+fn deposits(c: &mut Criterion) {
+    let (db, txs) = common::op::deposits();
+    let header = common::header();
+    let state = || State::builder().with_database(db.clone()).with_bundle_update().build();
+
+    let mut group = c.benchmark_group("op_deposits");
+    group.throughput(Throughput::Elements(txs.len() as u64));
+    group.bench_function("execute_transaction", |b| {
+        b.iter_batched(
+            state,
+            |mut state| {
+                let mut executor = common::op::executor(&mut state, &header);
+                txs.iter().map(|tx| executor.execute_transaction(tx).unwrap()).sum::<u64>()
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("execute_deposit_transactions", |b| {
+        b.iter_batched(
+            state,
+            |mut state| {
+                let mut executor = common::op::executor(&mut state, &header);
+                execute_deposit_transactions(&mut executor, &txs).unwrap()
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, deposits);
+criterion_main!(benches);
@@ -0,0 +1,62 @@
+//! Benchmarks of building OP transaction environments from recovered transactions.
+//!
+//! Deposits skip the EIP-2718 encoding, all other transactions encode themselves unless their
+//! encoding is passed along with `WithEncoded`.
+
+#![allow(missing_docs)]
+
+use alloy_consensus::{Signed, TxEip1559};
+use alloy_eips::Encodable2718;
+use alloy_evm::{FromRecoveredTx, FromTxWithEncoded};
+use alloy_primitives::{Address, Bytes, Sealed, Signature, TxKind, U256};
+use criterion::{criterion_group, criterion_main, Criterion};
+use op_alloy::consensus::{OpTxEnvelope, TxDeposit};
+use op_revm::OpTransaction;
+use revm::context::TxEnv;
+use std::hint::black_box;
+
+fn deposit() -> OpTxEnvelope {
+    OpTxEnvelope::Deposit(Sealed::new(TxDeposit {
+        to: TxKind::Call(Address::repeat_byte(0x02)),
+        mint: 1_000,
+        value: U256::from(1_000),
+        gas_limit: 100_000,
+        input: Bytes::from(vec![0xab; 512]),
+        ..Default::default()
+    }))
+}
+
+fn eip1559() -> OpTxEnvelope {
+    let tx = TxEip1559 {
+        chain_id: 10,
+        gas_limit: 100_000,
+        to: TxKind::Call(Address::repeat_byte(0x02)),
+        input: Bytes::from(vec![0xab; 512]),
+        ..Default::default()
+    };
+    OpTxEnvelope::Eip1559(Signed::new_unhashed(tx, Signature::test_signature()))
+}
+
+fn tx_env(c: &mut Criterion) {
+    let mut group = c.benchmark_group("op_tx_env");
+    for (name, tx) in [("deposit", deposit()), ("eip1559", eip1559())] {
+        group.bench_function(format!("{name}/recovered"), |b| {
+            b.iter(|| OpTransaction::<TxEnv>::from_recovered_tx(black_box(&tx), Address::ZERO))
+        });
+
+        let encoded: Bytes = tx.encoded_2718().into();
+        group.bench_function(format!("{name}/with_encoded"), |b| {
+            b.iter(|| {
+                OpTransaction::<TxEnv>::from_encoded_tx(
+                    black_box(&tx),
+                    Address::ZERO,
+                    encoded.clone(),
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, tx_env);
+criterion_main!(benches);
@@ -0,0 +1,137 @@
+//! Benchmarks of pipelined range execution against recovering, executing and computing receipts
+//! roots of every block in sequence.
+
+#![allow(missing_docs)]
+
+use alloy_consensus::{
+    proofs::calculate_receipt_root, transaction::SignerRecoverable, Header, SignableTransaction,
+    TxEnvelope, TxLegacy,
+};
+use alloy_evm::{
+    block::{
+        execute_range, execute_range_pipelined, ExecutableBlock, ExecutableTxParts, PipelineBlock,
+    },
+    eth::{
+        receipt_builder::AlloyReceiptBuilder, spec::EthSpec, EthBlockExecutionCtx,
+        EthBlockExecutorFactory,
+    },
+    EthEvmFactory, EvmEnv,
+};
+use alloy_primitives::{Address, Bytes, Signature, TxKind, U256};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use revm::{
+    context::TxEnv,
+    database::{CacheDB, EmptyDB},
+    state::AccountInfo,
+};
+
+type Factory = EthBlockExecutorFactory<AlloyReceiptBuilder, EthSpec, EthEvmFactory>;
+
+const BLOCKS: u64 = 16;
+const TXS_PER_BLOCK: u64 = 200;
+
+struct Block {
+    header: Header,
+    transactions: Vec<TxEnvelope>,
+}
+
+impl ExecutableBlock<Factory> for Block {
+    fn number(&self) -> u64 {
+        self.header.number
+    }
+
+    fn evm_env(&self, factory: &Factory) -> EvmEnv {
+        EvmEnv::for_eth_block(&self.header, factory.spec().clone(), 1, None)
+    }
+
+    fn execution_ctx(&self) -> EthBlockExecutionCtx<'_> {
+        EthBlockExecutionCtx {
+            parent_hash: self.header.parent_hash,
+            parent_beacon_block_root: None,
+            ommers: &[],
+            withdrawals: None,
+            extra_data: Bytes::new(),
+            tx_count_hint: Some(self.transactions.len()),
+            blob_params: None,
+        }
+    }
+
+    fn transactions(&self) -> impl Iterator<Item = impl ExecutableTxParts<TxEnv, TxEnvelope> + '_> {
+        self.transactions.iter().map(|tx| tx.try_to_recovered_ref().unwrap())
+    }
+}
+
+impl PipelineBlock<Factory> for Block {
+    fn signed_transactions(&self) -> &[TxEnvelope] {
+        &self.transactions
+    }
+}
+
+fn transfer(nonce: u64) -> TxEnvelope {
+    let tx = TxLegacy {
+        nonce,
+        gas_limit: 21_000,
+        to: TxKind::Call(Address::with_last_byte(nonce as u8)),
+        value: U256::from(1),
+        ..Default::default()
+    };
+    tx.into_signed(Signature::test_signature()).into()
+}
+
+/// London blocks of plain transfers, so that signer recovery is a large share of the work.
+fn blocks() -> Vec<Block> {
+    (0..BLOCKS)
+        .map(|number| Block {
+            header: Header {
+                number: 13_000_000 + number,
+                gas_limit: 30_000_000,
+                base_fee_per_gas: Some(0),
+                ..Default::default()
+            },
+            transactions: (0..TXS_PER_BLOCK)
+                .map(|i| transfer(number * TXS_PER_BLOCK + i))
+                .collect(),
+        })
+        .collect()
+}
+
+fn db(sender: Address) -> CacheDB<EmptyDB> {
+    let mut db = CacheDB::new(EmptyDB::default());
+    db.insert_account_info(sender, AccountInfo::from_balance(U256::MAX));
+    db
+}
+
+/// Executes the blocks on the calling thread, recovering signers during execution and computing
+/// the receipts roots afterwards.
+fn sequential(factory: &Factory, blocks: &[Block], db: CacheDB<EmptyDB>) -> u64 {
+    let output = execute_range(factory, blocks, db, |_| {}).unwrap();
+    for result in &output.results {
+        calculate_receipt_root(&result.receipts);
+    }
+    output.gas_used
+}
+
+fn range_execution(c: &mut Criterion) {
+    let blocks = blocks();
+    let sender = blocks[0].transactions[0].recover_signer().unwrap();
+    let db = db(sender);
+    let factory = Factory::new(AlloyReceiptBuilder, EthSpec::mainnet(), EthEvmFactory);
+    let gas_used = sequential(&factory, &blocks, db.clone());
+
+    let mut group = c.benchmark_group("execute_range");
+    group.throughput(Throughput::Elements(gas_used));
+    group.bench_function("sequential", |b| {
+        b.iter_batched(|| db.clone(), |db| sequential(&factory, &blocks, db), BatchSize::LargeInput)
+    });
+    group.bench_function("pipelined", |b| {
+        b.iter_batched(
+            || db.clone(),
+            |db| execute_range_pipelined(&factory, &blocks, db, |_| {}).unwrap().0.gas_used,
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, range_execution);
+criterion_main!(benches);
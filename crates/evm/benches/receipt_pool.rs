@@ -0,0 +1,115 @@
+//! Benchmarks of executing log-heavy blocks with and without a [`ReceiptPool`].
+
+#![allow(missing_docs)]
+
+use alloy_consensus::{
+    transaction::Recovered, Header, ReceiptEnvelope, SignableTransaction, TxEnvelope, TxLegacy,
+};
+use alloy_evm::{
+    block::{BlockExecutor, ReceiptPool},
+    eth::{
+        receipt_builder::AlloyReceiptBuilder, spec::EthSpec, EthBlockExecutionCtx, EthBlockExecutor,
+    },
+    EthEvmFactory, EvmEnv, EvmFactory,
+};
+use alloy_primitives::{hex, Address, Bytes, Signature, TxKind, B256};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use revm::{
+    bytecode::Bytecode,
+    database::{CacheDB, EmptyDB, State},
+    state::AccountInfo,
+};
+
+const SENDER: Address = Address::repeat_byte(0x01);
+const CONTRACT: Address = Address::repeat_byte(0x02);
+
+/// Emits a single log with one topic and 32 bytes of data.
+const LOGGER: &str = "600160206000a100";
+
+const TXS: u64 = 2_000;
+
+/// A London block, so that no system contracts are required.
+fn header() -> Header {
+    Header {
+        number: 13_000_000,
+        gas_limit: 1_000_000_000,
+        base_fee_per_gas: Some(0),
+        ..Default::default()
+    }
+}
+
+fn txs() -> Vec<Recovered<TxEnvelope>> {
+    (0..TXS)
+        .map(|nonce| {
+            let tx = TxLegacy {
+                nonce,
+                gas_price: 0,
+                gas_limit: 100_000,
+                to: TxKind::Call(CONTRACT),
+                ..Default::default()
+            };
+            Recovered::new_unchecked(tx.into_signed(Signature::test_signature()).into(), SENDER)
+        })
+        .collect()
+}
+
+fn db() -> CacheDB<EmptyDB> {
+    let mut db = CacheDB::new(EmptyDB::default());
+    db.insert_account_info(SENDER, AccountInfo::default());
+    db.insert_account_info(
+        CONTRACT,
+        AccountInfo::default().with_code(Bytecode::new_raw(hex::decode(LOGGER).unwrap().into())),
+    );
+    db
+}
+
+fn execute(
+    db: CacheDB<EmptyDB>,
+    txs: &[Recovered<TxEnvelope>],
+    buffer: Option<Vec<ReceiptEnvelope>>,
+) -> Vec<ReceiptEnvelope> {
+    let header = header();
+    let mut state = State::builder().with_database(db).with_bundle_update().build();
+    let evm_env = EvmEnv::for_eth_block(&header, EthSpec::mainnet(), 1, None);
+    let evm = EthEvmFactory.create_evm(&mut state, evm_env);
+    let ctx = EthBlockExecutionCtx {
+        parent_hash: B256::ZERO,
+        parent_beacon_block_root: None,
+        ommers: &[],
+        withdrawals: None,
+        extra_data: Bytes::new(),
+        tx_count_hint: Some(txs.len()),
+        blob_params: None,
+    };
+    let mut executor = EthBlockExecutor::new(evm, ctx, EthSpec::mainnet(), AlloyReceiptBuilder);
+    if let Some(buffer) = buffer {
+        executor = executor.with_receipts_buffer(buffer);
+    }
+    executor.execute_block(txs).unwrap().receipts
+}
+
+fn receipt_pool(c: &mut Criterion) {
+    let db = db();
+    let txs = txs();
+
+    let mut group = c.benchmark_group("receipt_pool");
+    group.throughput(Throughput::Elements(TXS));
+    group.bench_function("without_pool", |b| {
+        b.iter_batched(|| db.clone(), |db| execute(db, &txs, None), BatchSize::LargeInput)
+    });
+    let mut pool = ReceiptPool::default();
+    group.bench_function("with_pool", |b| {
+        b.iter_batched(
+            || db.clone(),
+            |db| {
+                let receipts = execute(db, &txs, Some(pool.take(txs.len())));
+                pool.recycle(receipts);
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, receipt_pool);
+criterion_main!(benches);
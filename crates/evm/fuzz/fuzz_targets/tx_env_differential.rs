@@ -0,0 +1,152 @@
+//! Differential fuzzing of the env translation layer.
+//!
+//! Random transaction requests and block headers are executed twice: once through
+//! [`EvmEnv::for_eth_block`], [`TryIntoTxEnv`] and [`EthEvmFactory`], and once through a plain revm
+//! setup whose environment is derived directly from the inputs. Both executions must agree.
+
+#![no_main]
+
+use alloy_consensus::Header;
+use alloy_eips::eip7840::BlobParams;
+use alloy_evm::{eth::spec::EthSpec, rpc::TryIntoTxEnv, EthEvmFactory, Evm, EvmEnv, EvmFactory};
+use alloy_primitives::{Address, Bytes, TxKind, B256, U256};
+use alloy_rpc_types_eth::{TransactionInput, TransactionRequest};
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use revm::{
+    context::{BlockEnv, CfgEnv, TxEnv},
+    context_interface::block::{calc_blob_gasprice, BlobExcessGasAndPrice},
+    database::{CacheDB, EmptyDB},
+    primitives::{eip4844::BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE, hardfork::SpecId},
+    state::AccountInfo,
+    Context, ExecuteEvm, MainBuilder, MainContext,
+};
+
+/// Mainnet Prague activation timestamp.
+const PRAGUE_TIMESTAMP: u64 = 1_746_612_311;
+/// First mainnet block after Prague activation.
+const PRAGUE_BLOCK: u64 = 22_431_084;
+
+const CALLER: Address = Address::repeat_byte(0xca);
+
+#[derive(Debug, Arbitrary)]
+enum Fees {
+    Legacy { gas_price: u64 },
+    Eip1559 { max_fee_per_gas: u64, max_priority_fee_per_gas: u64 },
+}
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    number_offset: u16,
+    timestamp_offset: u16,
+    beneficiary: Address,
+    mix_hash: B256,
+    gas_limit: u32,
+    base_fee: u32,
+    excess_blob_gas: u32,
+    to: Option<Address>,
+    value: u64,
+    gas: Option<u32>,
+    fees: Fees,
+    input: Vec<u8>,
+}
+
+fuzz_target!(|input: Input| {
+    let header = Header {
+        number: PRAGUE_BLOCK + input.number_offset as u64,
+        timestamp: PRAGUE_TIMESTAMP + input.timestamp_offset as u64 * 100,
+        beneficiary: input.beneficiary,
+        mix_hash: input.mix_hash,
+        gas_limit: input.gas_limit as u64,
+        base_fee_per_gas: Some(input.base_fee as u64),
+        excess_blob_gas: Some(input.excess_blob_gas as u64),
+        ..Default::default()
+    };
+
+    let mut db = CacheDB::new(EmptyDB::default());
+    db.insert_account_info(
+        CALLER,
+        AccountInfo { balance: U256::from(u128::MAX), ..Default::default() },
+    );
+
+    let kind = input.to.map_or(TxKind::Create, TxKind::Call);
+    let data = Bytes::from(input.input);
+
+    // alloy-evm
+    let evm_env = EvmEnv::for_eth_block(&header, EthSpec::mainnet(), 1, Some(BlobParams::prague()));
+    let mut request = TransactionRequest {
+        from: Some(CALLER),
+        to: Some(kind),
+        gas: input.gas.map(Into::into),
+        value: Some(U256::from(input.value)),
+        input: TransactionInput::new(data.clone()),
+        ..Default::default()
+    };
+    match input.fees {
+        Fees::Legacy { gas_price } => request.gas_price = Some(gas_price.into()),
+        Fees::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas } => {
+            request.max_fee_per_gas = Some(max_fee_per_gas.into());
+            request.max_priority_fee_per_gas = Some(max_priority_fee_per_gas.into());
+        }
+    }
+    let alloy_result = request.try_into_tx_env(&evm_env).ok().map(|tx| {
+        EthEvmFactory::default().create_evm(db.clone(), evm_env).transact(tx).map_err(|_| ())
+    });
+
+    // revm
+    let block_env = BlockEnv {
+        number: U256::from(header.number),
+        beneficiary: header.beneficiary,
+        timestamp: U256::from(header.timestamp),
+        gas_limit: header.gas_limit,
+        basefee: input.base_fee as u64,
+        difficulty: U256::ZERO,
+        prevrandao: Some(header.mix_hash),
+        blob_excess_gas_and_price: Some(BlobExcessGasAndPrice {
+            excess_blob_gas: input.excess_blob_gas as u64,
+            blob_gasprice: calc_blob_gasprice(
+                input.excess_blob_gas as u64,
+                BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE,
+            ),
+        }),
+        ..Default::default()
+    };
+    let mut cfg_env = CfgEnv::new_with_spec(SpecId::PRAGUE).with_chain_id(1);
+    cfg_env.set_max_blobs_per_tx(BlobParams::prague().max_blobs_per_tx);
+
+    let (tx_type, gas_price, gas_priority_fee) = match input.fees {
+        Fees::Legacy { gas_price } => (0, gas_price as u128, None),
+        Fees::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas } => {
+            (2, max_fee_per_gas as u128, Some(max_priority_fee_per_gas as u128))
+        }
+    };
+    let tx = TxEnv {
+        tx_type,
+        caller: CALLER,
+        gas_limit: input.gas.map_or(header.gas_limit, Into::into),
+        gas_price,
+        gas_priority_fee,
+        kind,
+        value: U256::from(input.value),
+        data,
+        chain_id: Some(1),
+        ..Default::default()
+    };
+    let revm_result = Context::mainnet()
+        .with_db(db)
+        .with_block(block_env)
+        .with_cfg(cfg_env)
+        .build_mainnet()
+        .transact(tx)
+        .map_err(|_| ());
+
+    match (alloy_result, revm_result) {
+        (Some(Ok(alloy)), Ok(revm)) => {
+            assert_eq!(alloy.result, revm.result);
+            assert_eq!(alloy.state, revm.state);
+        }
+        // Both sides rejected the transaction, either during env translation or execution.
+        (None | Some(Err(())), Err(())) => {}
+        (alloy, revm) => panic!("result mismatch: alloy-evm {alloy:?}, revm {revm:?}"),
+    }
+});
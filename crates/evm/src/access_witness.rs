@@ -0,0 +1,367 @@
+//! Experimental recording of [EIP-4762] witness access events.
+//!
+//! Stateless Ethereum charges gas for the parts of the state tree a transaction touches: every
+//! accessed stem (a group of 256 leaves) and every accessed leaf, with additional costs for
+//! writes. The [`AccessWitnessInspector`] records these access events during execution, so that
+//! gas schedules can be prototyped before the fork ships. Tree keys are derived with the layout of
+//! [EIP-6800], but not hashed, so stems are identified by address and tree index.
+//!
+//! The recording is an approximation: code chunks read by `EXTCODECOPY` and transaction-level
+//! events of the sender are not recorded.
+//!
+//! [EIP-4762]: https://eips.ethereum.org/EIPS/eip-4762
+//! [EIP-6800]: https://eips.ethereum.org/EIPS/eip-6800
+
+use alloc::{collections::BTreeSet, vec::Vec};
+use alloy_primitives::{Address, B256, U256};
+use revm::{
+    bytecode::opcode::{
+        BALANCE, EXTCODECOPY, EXTCODEHASH, EXTCODESIZE, PUSH1, PUSH32, SLOAD, SSTORE,
+    },
+    context_interface::ContextTr,
+    interpreter::{
+        interpreter_types::Jumps, CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter,
+    },
+    Inspector,
+};
+
+/// Leaf of the account header holding version, nonce, code size and balance.
+pub const BASIC_DATA_LEAF_KEY: u8 = 0;
+/// Leaf of the account header holding the code hash.
+pub const CODE_HASH_LEAF_KEY: u8 = 1;
+/// Position of the first storage slot stored in the account header stem.
+const HEADER_STORAGE_OFFSET: u64 = 64;
+/// Position of the first code chunk.
+const CODE_OFFSET: u64 = 128;
+/// Number of leaves of a stem.
+const STEM_SUBTREE_WIDTH: u64 = 256;
+/// Size of a code chunk in bytes, excluding the leading byte.
+const CODE_CHUNK_SIZE: usize = 31;
+
+/// A leaf of the state tree, identified by its unhashed location.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TreeKey {
+    /// Account the leaf belongs to.
+    pub address: Address,
+    /// Index of the stem within the account.
+    pub tree_index: U256,
+    /// Index of the leaf within the stem.
+    pub sub_index: u8,
+}
+
+impl TreeKey {
+    /// Returns the key of a leaf of the account header.
+    pub const fn header(address: Address, leaf: u8) -> Self {
+        Self { address, tree_index: U256::ZERO, sub_index: leaf }
+    }
+
+    /// Returns the key of the code chunk with the given index.
+    pub fn code_chunk(address: Address, chunk: u64) -> Self {
+        let position = CODE_OFFSET + chunk;
+        Self {
+            address,
+            tree_index: U256::from(position / STEM_SUBTREE_WIDTH),
+            sub_index: (position % STEM_SUBTREE_WIDTH) as u8,
+        }
+    }
+
+    /// Returns the key of a storage slot.
+    ///
+    /// The first 64 slots share the stem of the account header, all other slots are stored in
+    /// the main storage area starting at position `256^31`.
+    pub fn storage_slot(address: Address, slot: U256) -> Self {
+        let width = U256::from(STEM_SUBTREE_WIDTH);
+        if slot < U256::from(CODE_OFFSET - HEADER_STORAGE_OFFSET) {
+            return Self {
+                address,
+                tree_index: U256::ZERO,
+                sub_index: (HEADER_STORAGE_OFFSET + slot.to::<u64>()) as u8,
+            };
+        }
+        // (256^31 + slot) / 256 = 256^30 + slot / 256
+        Self {
+            address,
+            tree_index: (U256::from(1) << 240) + slot / width,
+            sub_index: (slot % width).to::<u8>(),
+        }
+    }
+
+    /// Returns the stem of the leaf.
+    pub const fn stem(&self) -> (Address, U256) {
+        (self.address, self.tree_index)
+    }
+}
+
+/// Leaves and stems accessed during execution.
+///
+/// Writes are also recorded as reads.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccessWitness {
+    reads: BTreeSet<TreeKey>,
+    writes: BTreeSet<TreeKey>,
+}
+
+impl AccessWitness {
+    /// Records a read of the leaf. Returns `true` if it was not accessed before.
+    pub fn read(&mut self, key: TreeKey) -> bool {
+        self.reads.insert(key)
+    }
+
+    /// Records a write of the leaf. Returns `true` if it was not written before.
+    pub fn write(&mut self, key: TreeKey) -> bool {
+        self.reads.insert(key);
+        self.writes.insert(key)
+    }
+
+    /// Returns the accessed leaves.
+    pub const fn reads(&self) -> &BTreeSet<TreeKey> {
+        &self.reads
+    }
+
+    /// Returns the written leaves.
+    pub const fn writes(&self) -> &BTreeSet<TreeKey> {
+        &self.writes
+    }
+
+    /// Returns the number of accessed stems.
+    pub fn branch_reads(&self) -> usize {
+        count_stems(&self.reads)
+    }
+
+    /// Returns the number of written stems.
+    pub fn branch_writes(&self) -> usize {
+        count_stems(&self.writes)
+    }
+
+    /// Merges the accesses of another witness, e.g. of a previous transaction of the block.
+    pub fn extend(&mut self, other: &Self) {
+        self.reads.extend(&other.reads);
+        self.writes.extend(&other.writes);
+    }
+
+    /// Removes all recorded accesses.
+    pub fn clear(&mut self) {
+        self.reads.clear();
+        self.writes.clear();
+    }
+}
+
+fn count_stems(keys: &BTreeSet<TreeKey>) -> usize {
+    // keys are ordered by stem, so equal stems are adjacent
+    let mut stems = keys.iter().map(TreeKey::stem).collect::<Vec<_>>();
+    stems.dedup();
+    stems.len()
+}
+
+/// Costs of witness accesses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WitnessGasSchedule {
+    /// Cost of accessing a stem.
+    pub branch_read: u64,
+    /// Cost of accessing a leaf.
+    pub chunk_read: u64,
+    /// Cost of writing to a stem.
+    pub subtree_edit: u64,
+    /// Cost of writing a leaf.
+    pub chunk_edit: u64,
+}
+
+impl Default for WitnessGasSchedule {
+    fn default() -> Self {
+        Self::eip4762()
+    }
+}
+
+impl WitnessGasSchedule {
+    /// The costs proposed by EIP-4762.
+    ///
+    /// Filling previously empty leaves is not charged, since it depends on the state.
+    pub const fn eip4762() -> Self {
+        Self { branch_read: 1_900, chunk_read: 200, subtree_edit: 3_000, chunk_edit: 500 }
+    }
+
+    /// Returns the gas charged for the accesses of the witness.
+    pub fn gas(&self, witness: &AccessWitness) -> u64 {
+        self.branch_read * witness.branch_reads() as u64
+            + self.chunk_read * witness.reads().len() as u64
+            + self.subtree_edit * witness.branch_writes() as u64
+            + self.chunk_edit * witness.writes().len() as u64
+    }
+}
+
+/// An [`Inspector`] recording the witness accesses of executed transactions.
+///
+/// The witness accumulates over all transactions executed with the inspector, use
+/// [`AccessWitness::clear`] to record transactions separately.
+#[derive(Debug, Clone, Default)]
+pub struct AccessWitnessInspector {
+    witness: AccessWitness,
+    /// Address of the executed code per frame, `None` for initcode, which is not part of the tree.
+    frames: Vec<Option<Address>>,
+}
+
+impl AccessWitnessInspector {
+    /// Creates a new inspector with an empty witness.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the recorded witness.
+    pub const fn witness(&self) -> &AccessWitness {
+        &self.witness
+    }
+
+    /// Returns a mutable reference to the recorded witness.
+    pub const fn witness_mut(&mut self) -> &mut AccessWitness {
+        &mut self.witness
+    }
+
+    /// Consumes the inspector, returning the recorded witness.
+    pub fn into_witness(self) -> AccessWitness {
+        self.witness
+    }
+
+    fn read_header(&mut self, address: Address, leaf: u8) {
+        self.witness.read(TreeKey::header(address, leaf));
+    }
+
+    fn write_header(&mut self, address: Address) {
+        self.witness.write(TreeKey::header(address, BASIC_DATA_LEAF_KEY));
+    }
+}
+
+impl<CTX: ContextTr> Inspector<CTX> for AccessWitnessInspector {
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut CTX) {
+        let opcode = interp.bytecode.opcode();
+        let pc = interp.bytecode.pc();
+
+        if let Some(Some(code_address)) = self.frames.last() {
+            let code_address = *code_address;
+            self.witness.read(TreeKey::code_chunk(code_address, (pc / CODE_CHUNK_SIZE) as u64));
+            // push data may extend into the next chunk
+            if (PUSH1..=PUSH32).contains(&opcode) {
+                let end = pc + (opcode - PUSH1 + 1) as usize;
+                self.witness
+                    .read(TreeKey::code_chunk(code_address, (end / CODE_CHUNK_SIZE) as u64));
+            }
+        }
+
+        let stack = interp.stack.data().as_slice();
+        let Some(top) = stack.last().copied() else { return };
+        let top_address = Address::from_word(B256::from(top));
+        match opcode {
+            BALANCE | EXTCODESIZE | EXTCODECOPY => {
+                self.read_header(top_address, BASIC_DATA_LEAF_KEY)
+            }
+            EXTCODEHASH => self.read_header(top_address, CODE_HASH_LEAF_KEY),
+            SLOAD => {
+                self.witness.read(TreeKey::storage_slot(interp.input.target_address, top));
+            }
+            SSTORE => {
+                self.witness.write(TreeKey::storage_slot(interp.input.target_address, top));
+            }
+            _ => {}
+        }
+    }
+
+    fn call(&mut self, _context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        self.read_header(inputs.bytecode_address, BASIC_DATA_LEAF_KEY);
+        self.read_header(inputs.bytecode_address, CODE_HASH_LEAF_KEY);
+        if !inputs.call_value().is_zero() {
+            self.write_header(inputs.caller);
+            self.write_header(inputs.target_address);
+        }
+        self.frames.push(Some(inputs.bytecode_address));
+        None
+    }
+
+    fn call_end(&mut self, _context: &mut CTX, _inputs: &CallInputs, _outcome: &mut CallOutcome) {
+        self.frames.pop();
+    }
+
+    fn create(&mut self, _context: &mut CTX, _inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        self.frames.push(None);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut CTX,
+        _inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        self.frames.pop();
+        if let Some(address) = outcome.address.filter(|_| outcome.result.is_ok()) {
+            self.write_header(address);
+            self.witness.write(TreeKey::header(address, CODE_HASH_LEAF_KEY));
+        }
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, _value: U256) {
+        self.write_header(contract);
+        self.write_header(target);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EthEvmFactory, Evm, EvmEnv, EvmFactory};
+    use alloy_primitives::{Bytes, TxKind};
+    use revm::{
+        bytecode::Bytecode,
+        context::TxEnv,
+        database::{CacheDB, EmptyDB},
+        state::AccountInfo,
+    };
+
+    #[test]
+    fn test_tree_keys() {
+        let address = Address::repeat_byte(0x01);
+        assert_eq!(TreeKey::storage_slot(address, U256::from(3)).sub_index, 67);
+        assert_eq!(TreeKey::storage_slot(address, U256::from(3)).tree_index, U256::ZERO);
+        assert_eq!(TreeKey::storage_slot(address, U256::from(64)).tree_index, U256::from(1) << 240);
+        assert_eq!(TreeKey::code_chunk(address, 0).sub_index, 128);
+        assert_eq!(TreeKey::code_chunk(address, 128).tree_index, U256::from(1));
+    }
+
+    #[test]
+    fn test_record_accesses() {
+        let contract = Address::repeat_byte(0x10);
+        let mut db = CacheDB::new(EmptyDB::default());
+        // PUSH1 1; SLOAD; PUSH1 0x80; SSTORE; STOP
+        let code =
+            Bytecode::new_raw(Bytes::from_static(&[0x60, 0x01, 0x54, 0x60, 0x80, 0x55, 0x00]));
+        db.insert_account_info(contract, AccountInfo::default().with_code(code));
+
+        let mut evm = EthEvmFactory.create_evm_with_inspector(
+            db,
+            EvmEnv::default(),
+            AccessWitnessInspector::new(),
+        );
+        let tx = TxEnv::builder()
+            .caller(Address::repeat_byte(0x01))
+            .kind(TxKind::Call(contract))
+            .gas_limit(100_000)
+            .gas_price(0)
+            .build()
+            .unwrap();
+        evm.transact(tx).unwrap();
+
+        let witness = evm.inspector().witness();
+        assert!(witness.reads().contains(&TreeKey::header(contract, CODE_HASH_LEAF_KEY)));
+        assert!(witness.reads().contains(&TreeKey::storage_slot(contract, U256::from(1))));
+        assert!(witness.reads().contains(&TreeKey::code_chunk(contract, 0)));
+        assert_eq!(
+            witness.writes().iter().collect::<Vec<_>>(),
+            [&TreeKey::storage_slot(contract, U256::from(0x80))]
+        );
+        // header stem and main storage stem
+        assert_eq!(witness.branch_reads(), 2);
+        assert_eq!(witness.branch_writes(), 1);
+        assert_eq!(
+            WitnessGasSchedule::eip4762().gas(witness),
+            2 * 1_900 + 200 * witness.reads().len() as u64 + 3_000 + 500
+        );
+    }
+}
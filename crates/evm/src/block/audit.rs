@@ -0,0 +1,261 @@
+//! Auditing of state accesses.
+//!
+//! An [`AuditingDatabase`] hashes every state read and committed write into a running commitment.
+//! Two executions of the same block perform the exact same sequence of operations, so comparing
+//! their commitments detects divergence even if the final state happens to match. With logging
+//! enabled, [`first_divergence`] pinpoints the first operation where two runs differ.
+
+use crate::{
+    block::{BlockExecutionResult, BundleStateDB},
+    Database,
+};
+use alloc::vec::Vec;
+use alloy_primitives::{Address, Keccak256, B256};
+use revm::{
+    bytecode::Bytecode,
+    database::{states::bundle_state::BundleRetention, BundleState},
+    primitives::{StorageKey, StorageValue},
+    state::{AccountInfo, EvmState},
+    DatabaseCommit,
+};
+
+/// Tags distinguishing the audited operations.
+mod tag {
+    pub(super) const BASIC: u8 = 1;
+    pub(super) const CODE: u8 = 2;
+    pub(super) const STORAGE: u8 = 3;
+    pub(super) const BLOCK_HASH: u8 = 4;
+    pub(super) const COMMIT: u8 = 5;
+}
+
+/// A [`Database`] hashing all reads and committed writes into a running commitment.
+///
+/// Wrap the [`State`](revm::database::State) used for execution to audit the operations performed
+/// by the EVM, e.g. `AuditingDatabase::new(&mut state)`. Failed reads are not audited.
+#[derive(Debug, Clone)]
+pub struct AuditingDatabase<DB> {
+    inner: DB,
+    commitment: B256,
+    operations: u64,
+    log: Option<Vec<B256>>,
+}
+
+impl<DB> AuditingDatabase<DB> {
+    /// Creates a new auditing wrapper with a zero commitment.
+    pub const fn new(inner: DB) -> Self {
+        Self { inner, commitment: B256::ZERO, operations: 0, log: None }
+    }
+
+    /// Records the commitment after every operation, see [`Self::log`].
+    pub fn with_log(mut self) -> Self {
+        self.log = Some(Vec::new());
+        self
+    }
+
+    /// Returns the current commitment over all audited operations.
+    pub const fn commitment(&self) -> B256 {
+        self.commitment
+    }
+
+    /// Returns the number of audited operations.
+    pub const fn operations(&self) -> u64 {
+        self.operations
+    }
+
+    /// Returns the commitment after each operation, if logging is enabled.
+    pub fn log(&self) -> Option<&[B256]> {
+        self.log.as_deref()
+    }
+
+    /// Returns a reference to the inner database.
+    pub const fn inner(&self) -> &DB {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner database.
+    ///
+    /// Accesses through the inner database are not audited.
+    pub const fn inner_mut(&mut self) -> &mut DB {
+        &mut self.inner
+    }
+
+    /// Consumes the wrapper and returns the inner database.
+    pub fn into_inner(self) -> DB {
+        self.inner
+    }
+
+    /// Folds an operation into the commitment.
+    fn record(&mut self, tag: u8, f: impl FnOnce(&mut Keccak256)) {
+        let mut hasher = Keccak256::new();
+        hasher.update(self.commitment);
+        hasher.update([tag]);
+        f(&mut hasher);
+        self.commitment = hasher.finalize();
+        self.operations += 1;
+        if let Some(log) = &mut self.log {
+            log.push(self.commitment);
+        }
+    }
+}
+
+/// Hashes an optional account info.
+fn hash_info(hasher: &mut Keccak256, info: Option<&AccountInfo>) {
+    match info {
+        Some(info) => {
+            hasher.update([1]);
+            hasher.update(info.balance.to_be_bytes::<32>());
+            hasher.update(info.nonce.to_be_bytes());
+            hasher.update(info.code_hash);
+        }
+        None => hasher.update([0]),
+    }
+}
+
+impl<DB: Database> revm::Database for AuditingDatabase<DB> {
+    type Error = DB::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let info = self.inner.basic(address)?;
+        self.record(tag::BASIC, |hasher| {
+            hasher.update(address);
+            hash_info(hasher, info.as_ref());
+        });
+        Ok(info)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        let code = self.inner.code_by_hash(code_hash)?;
+        self.record(tag::CODE, |hasher| {
+            hasher.update(code_hash);
+            hasher.update(code.original_byte_slice());
+        });
+        Ok(code)
+    }
+
+    fn storage(
+        &mut self,
+        address: Address,
+        index: StorageKey,
+    ) -> Result<StorageValue, Self::Error> {
+        let value = self.inner.storage(address, index)?;
+        self.record(tag::STORAGE, |hasher| {
+            hasher.update(address);
+            hasher.update(index.to_be_bytes::<32>());
+            hasher.update(value.to_be_bytes::<32>());
+        });
+        Ok(value)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        let hash = self.inner.block_hash(number)?;
+        self.record(tag::BLOCK_HASH, |hasher| {
+            hasher.update(number.to_be_bytes());
+            hasher.update(hash);
+        });
+        Ok(hash)
+    }
+}
+
+impl<DB: DatabaseCommit> DatabaseCommit for AuditingDatabase<DB> {
+    fn commit(&mut self, changes: EvmState) {
+        // Hash in a canonical order, the iteration order of the map is not deterministic.
+        let mut accounts: Vec<_> =
+            changes.iter().filter(|(_, account)| account.is_touched()).collect();
+        accounts.sort_unstable_by_key(|(address, _)| **address);
+
+        self.record(tag::COMMIT, |hasher| {
+            for (address, account) in accounts {
+                hasher.update(address);
+                hasher.update([account.is_selfdestructed() as u8, account.is_created() as u8]);
+                hash_info(hasher, Some(&account.info));
+
+                let mut slots: Vec<_> =
+                    account.storage.iter().filter(|(_, slot)| slot.is_changed()).collect();
+                slots.sort_unstable_by_key(|(key, _)| **key);
+                for (key, slot) in slots {
+                    hasher.update(key.to_be_bytes::<32>());
+                    hasher.update(slot.present_value.to_be_bytes::<32>());
+                }
+            }
+        });
+
+        self.inner.commit(changes);
+    }
+}
+
+impl<DB: BundleStateDB> BundleStateDB for AuditingDatabase<DB> {
+    fn merge_transitions(&mut self, retention: BundleRetention) {
+        self.inner.merge_transitions(retention);
+    }
+
+    fn bundle_state(&self) -> &BundleState {
+        self.inner.bundle_state()
+    }
+
+    fn take_bundle(&mut self) -> BundleState {
+        self.inner.take_bundle()
+    }
+}
+
+/// A [`BlockExecutionResult`] together with the commitment over the state operations performed
+/// during execution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditedExecutionResult<T> {
+    /// The result of executing the block.
+    pub result: BlockExecutionResult<T>,
+    /// Commitment over all audited state operations, see [`AuditingDatabase::commitment`].
+    pub commitment: B256,
+    /// Number of audited state operations.
+    pub operations: u64,
+}
+
+/// Returns the index of the first operation at which the two logs differ, or `None` if they are
+/// identical.
+///
+/// If one log is a prefix of the other, the length of the shorter log is returned.
+pub fn first_divergence(a: &[B256], b: &[B256]) -> Option<usize> {
+    a.iter()
+        .zip(b)
+        .position(|(a, b)| a != b)
+        .or_else(|| (a.len() != b.len()).then_some(a.len().min(b.len())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+    use revm::database::{CacheDB, EmptyDB};
+
+    fn db(balance: u64) -> AuditingDatabase<CacheDB<EmptyDB>> {
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            Address::repeat_byte(0x01),
+            AccountInfo { balance: U256::from(balance), ..Default::default() },
+        );
+        AuditingDatabase::new(db).with_log()
+    }
+
+    fn run(db: &mut AuditingDatabase<CacheDB<EmptyDB>>) {
+        use revm::Database as _;
+
+        db.basic(Address::repeat_byte(0x02)).unwrap();
+        db.storage(Address::repeat_byte(0x02), StorageKey::ZERO).unwrap();
+        db.basic(Address::repeat_byte(0x01)).unwrap();
+        db.block_hash(1).unwrap();
+    }
+
+    #[test]
+    fn test_detects_divergence() {
+        let (mut a, mut b, mut c) = (db(1), db(1), db(2));
+        run(&mut a);
+        run(&mut b);
+        run(&mut c);
+
+        assert_eq!(a.operations(), 4);
+        assert_eq!(a.commitment(), b.commitment());
+        assert_eq!(first_divergence(a.log().unwrap(), b.log().unwrap()), None);
+
+        assert_ne!(a.commitment(), c.commitment());
+        assert_eq!(first_divergence(a.log().unwrap(), c.log().unwrap()), Some(2));
+    }
+}
@@ -0,0 +1,159 @@
+//! Serving `BLOCKHASH` lookups without database round trips.
+//!
+//! The `BLOCKHASH` opcode can access the hashes of the 256 most recent blocks. By default these
+//! are read with [`Database::block_hash`], which may be expensive for remote or persistent
+//! backends. A [`BlockHashReader`], e.g. a [`BlockHashCache`] filled from recently seen headers,
+//! can serve them instead when wrapped around the database with a [`BlockHashOverlay`].
+
+use alloc::collections::VecDeque;
+use alloy_primitives::{map::HashMap, Address, B256, U256};
+use revm::{
+    bytecode::Bytecode,
+    state::{Account, AccountInfo},
+    Database, DatabaseCommit,
+};
+
+/// Number of ancestors whose hashes are accessible with `BLOCKHASH`.
+pub const BLOCK_HASH_WINDOW: usize = 256;
+
+/// Provides hashes of recent blocks.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait BlockHashReader {
+    /// Returns the hash of the block with the given number, if known.
+    fn block_hash(&self, number: u64) -> Option<B256>;
+}
+
+/// Ring buffer with the hashes of the [`BLOCK_HASH_WINDOW`] most recent consecutive blocks.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockHashCache {
+    /// Hashes in ascending block order.
+    hashes: VecDeque<B256>,
+    /// Number of the last block in `hashes`.
+    last: u64,
+}
+
+impl BlockHashCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts the hash of a block.
+    ///
+    /// If the block doesn't extend the most recent block, e.g. after a reorg, the cache is reset to
+    /// only hold the given block.
+    pub fn insert(&mut self, number: u64, hash: B256) {
+        if self.hashes.is_empty() || number != self.last + 1 {
+            self.hashes.clear();
+        } else if self.hashes.len() == BLOCK_HASH_WINDOW {
+            self.hashes.pop_front();
+        }
+        self.hashes.push_back(hash);
+        self.last = number;
+    }
+
+    /// Returns the number of cached hashes.
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// Returns `true` if no hashes are cached.
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+}
+
+impl BlockHashReader for BlockHashCache {
+    fn block_hash(&self, number: u64) -> Option<B256> {
+        let age = usize::try_from(self.last.checked_sub(number)?).ok()?;
+        self.hashes.len().checked_sub(age + 1).map(|index| self.hashes[index])
+    }
+}
+
+/// A [`Database`] serving block hashes from a [`BlockHashReader`] first, falling back to the
+/// wrapped database for blocks unknown to the reader.
+#[derive(Debug, Clone)]
+pub struct BlockHashOverlay<DB, R> {
+    db: DB,
+    reader: R,
+}
+
+impl<DB, R> BlockHashOverlay<DB, R> {
+    /// Wraps the database.
+    pub const fn new(db: DB, reader: R) -> Self {
+        Self { db, reader }
+    }
+
+    /// Returns the block hash reader.
+    pub const fn reader(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the block hash reader, e.g. to insert new hashes.
+    pub const fn reader_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Consumes the overlay, returning the wrapped database and the reader.
+    pub fn into_parts(self) -> (DB, R) {
+        (self.db, self.reader)
+    }
+}
+
+impl<DB: Database, R: BlockHashReader> Database for BlockHashOverlay<DB, R> {
+    type Error = DB::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.db.basic(address)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.db.code_by_hash(code_hash)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.db.storage(address, index)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        match self.reader.block_hash(number) {
+            Some(hash) => Ok(hash),
+            None => self.db.block_hash(number),
+        }
+    }
+}
+
+impl<DB: DatabaseCommit, R> DatabaseCommit for BlockHashOverlay<DB, R> {
+    fn commit(&mut self, changes: HashMap<Address, Account>) {
+        self.db.commit(changes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::database::{CacheDB, EmptyDB};
+
+    #[test]
+    fn test_block_hash_cache() {
+        let mut cache = BlockHashCache::new();
+        for number in 0..300 {
+            cache.insert(number, B256::with_last_byte(number as u8));
+        }
+        assert_eq!(cache.len(), BLOCK_HASH_WINDOW);
+        assert_eq!(cache.block_hash(299), Some(B256::with_last_byte(43)));
+        assert_eq!(cache.block_hash(44), Some(B256::with_last_byte(44)));
+        assert_eq!(cache.block_hash(43), None);
+        assert_eq!(cache.block_hash(300), None);
+
+        cache.insert(10, B256::repeat_byte(0x0a));
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.block_hash(10), Some(B256::repeat_byte(0x0a)));
+        assert_eq!(cache.block_hash(9), None);
+
+        let mut db = BlockHashOverlay::new(CacheDB::new(EmptyDB::default()), cache);
+        assert_eq!(db.block_hash(10).unwrap(), B256::repeat_byte(0x0a));
+        // falls back to the database, which derives the hash from the number
+        assert_eq!(db.block_hash(9).unwrap(), EmptyDB::default().block_hash(9).unwrap());
+    }
+}
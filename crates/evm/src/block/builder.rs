@@ -0,0 +1,537 @@
+//! Building blocks from a pool of candidate transactions.
+//!
+//! A [`BlockBuilder`] drives a [`BlockExecutor`] through the payload building loop: it pulls
+//! transactions from an [`OrderingPolicy`], skips transactions that are invalid or exceed the
+//! remaining gas, blob gas or data availability budget of the block, tracks the fees paid to the
+//! beneficiary and finally returns an [`ExecutedBlock`].
+//!
+//! With a deadline, e.g. for short block times, the builder stops packing once the deadline is
+//! near and reports how many candidates were not attempted.
+
+use super::{BlockExecutionError, BlockExecutionResult, BlockExecutor};
+use crate::Evm;
+use alloc::vec::Vec;
+use alloy_consensus::{transaction::Recovered, Transaction};
+use alloy_eips::eip2718::Encodable2718;
+use alloy_primitives::{Address, B256, U256};
+use revm::context::Block;
+
+/// Minimum gas required by any transaction.
+///
+/// The builder stops pulling transactions once less gas than this is left in the block.
+const MIN_TRANSACTION_GAS: u64 = 21_000;
+
+/// Budgets of a block being built.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockBuilderLimits {
+    /// Maximum gas used by all transactions of the block.
+    pub gas_limit: u64,
+    /// Maximum blob gas used by all transactions of the block, unlimited if `None`.
+    pub blob_gas_limit: Option<u64>,
+    /// Maximum total EIP-2718 encoded size of all transactions of the block, unlimited if `None`.
+    ///
+    /// This bounds the data that has to be made available, e.g. posted to L1 by a rollup
+    /// sequencer.
+    pub da_limit: Option<u64>,
+}
+
+impl BlockBuilderLimits {
+    /// Creates limits with the given gas limit and no blob gas or data availability limits.
+    pub const fn new(gas_limit: u64) -> Self {
+        Self { gas_limit, blob_gas_limit: None, da_limit: None }
+    }
+
+    /// Sets the blob gas limit.
+    pub const fn with_blob_gas_limit(mut self, blob_gas_limit: u64) -> Self {
+        self.blob_gas_limit = Some(blob_gas_limit);
+        self
+    }
+
+    /// Sets the data availability limit.
+    pub const fn with_da_limit(mut self, da_limit: u64) -> Self {
+        self.da_limit = Some(da_limit);
+        self
+    }
+}
+
+/// Reason for a transaction not being included into the block.
+#[derive(Debug, thiserror::Error)]
+pub enum SkipReason {
+    /// The gas limit of the transaction exceeds the gas left in the block.
+    #[error("transaction gas limit {gas_limit} exceeds available block gas {available}")]
+    GasLimit {
+        /// Gas limit of the transaction.
+        gas_limit: u64,
+        /// Gas left in the block.
+        available: u64,
+    },
+    /// The blob gas of the transaction exceeds the blob gas left in the block.
+    #[error("transaction blob gas {blob_gas} exceeds available block blob gas {available}")]
+    BlobGasLimit {
+        /// Blob gas used by the transaction.
+        blob_gas: u64,
+        /// Blob gas left in the block.
+        available: u64,
+    },
+    /// The encoded size of the transaction exceeds the data availability budget left in the block.
+    #[error("transaction size {size} exceeds available data availability budget {available}")]
+    DaLimit {
+        /// Encoded size of the transaction.
+        size: u64,
+        /// Data availability budget left in the block.
+        available: u64,
+    },
+    /// The transaction failed validation.
+    #[error(transparent)]
+    Invalid(BlockExecutionError),
+}
+
+/// A transaction that was not included into the block.
+#[derive(Debug)]
+pub struct SkippedTransaction {
+    /// Hash of the transaction.
+    pub hash: B256,
+    /// Sender of the transaction.
+    pub sender: Address,
+    /// Why the transaction was skipped.
+    pub reason: SkipReason,
+}
+
+/// Source of the transactions included by a [`BlockBuilder`].
+///
+/// The policy decides the order in which candidate transactions are tried and is notified about
+/// transactions that could not be included, e.g. to stop yielding transactions of a sender whose
+/// transaction turned out to be invalid.
+pub trait OrderingPolicy<T> {
+    /// Returns the next transaction to try, or `None` if there are no more candidates.
+    fn next_transaction(&mut self) -> Option<Recovered<T>>;
+
+    /// Invoked with each transaction returned by [`Self::next_transaction`] that was skipped.
+    fn on_skipped(&mut self, tx: &Recovered<T>, reason: &SkipReason) {
+        let _ = (tx, reason);
+    }
+
+    /// Returns the number of candidates left, if the policy knows it without yielding them.
+    ///
+    /// Used to report the candidates [`BlockBuilder::fill`] didn't attempt before the deadline.
+    fn remaining(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// An [`OrderingPolicy`] yielding transactions in the order of the wrapped iterator.
+#[derive(Debug, Clone)]
+pub struct InOrder<I>(pub I);
+
+impl<T, I: Iterator<Item = Recovered<T>>> OrderingPolicy<T> for InOrder<I> {
+    fn next_transaction(&mut self) -> Option<Recovered<T>> {
+        self.0.next()
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        let (lower, upper) = self.0.size_hint();
+        (upper == Some(lower)).then_some(lower)
+    }
+}
+
+/// Output of a [`BlockBuilder`].
+#[derive(Debug)]
+pub struct ExecutedBlock<T, R> {
+    /// Included transactions, in execution order.
+    pub transactions: Vec<Recovered<T>>,
+    /// Result of executing the block.
+    pub result: BlockExecutionResult<R>,
+    /// Total priority fees paid to the beneficiary by the included transactions.
+    pub fees: U256,
+    /// Transactions that were tried but not included.
+    pub skipped: Vec<SkippedTransaction>,
+    /// Whether packing stopped because the deadline was near, see [`BlockBuilder::with_deadline`].
+    pub deadline_reached: bool,
+    /// Number of candidate transactions that were not attempted because the deadline was near.
+    ///
+    /// `None` if the policy doesn't know how many candidates it has left, see
+    /// [`OrderingPolicy::remaining`].
+    pub not_attempted: Option<usize>,
+}
+
+/// Builds a block on top of a [`BlockExecutor`].
+///
+/// Creating the builder applies the pre-execution changes. Transactions are then added either
+/// individually with [`Self::add_transaction`] or from an [`OrderingPolicy`] with [`Self::fill`].
+/// Transactions failing validation or exceeding the [`BlockBuilderLimits`] are skipped instead of
+/// aborting the block, only internal errors such as database failures are returned.
+#[derive(Debug)]
+pub struct BlockBuilder<E: BlockExecutor> {
+    executor: E,
+    limits: BlockBuilderLimits,
+    gas_used: u64,
+    blob_gas_used: u64,
+    da_used: u64,
+    fees: U256,
+    transactions: Vec<Recovered<E::Transaction>>,
+    skipped: Vec<SkippedTransaction>,
+    #[cfg(all(feature = "std", not(target_os = "zkvm")))]
+    deadline: Option<crate::time::Instant>,
+    /// Execution time of the slowest transaction so far, used to anticipate the deadline.
+    #[cfg(all(feature = "std", not(target_os = "zkvm")))]
+    slowest_tx: std::time::Duration,
+    deadline_reached: bool,
+    not_attempted: Option<usize>,
+}
+
+impl<E> BlockBuilder<E>
+where
+    E: BlockExecutor<Transaction: Transaction + Encodable2718>,
+{
+    /// Creates a new builder and applies the pre-execution changes of the block.
+    pub fn new(mut executor: E, limits: BlockBuilderLimits) -> Result<Self, BlockExecutionError> {
+        executor.apply_pre_execution_changes()?;
+        Ok(Self {
+            executor,
+            limits,
+            gas_used: 0,
+            blob_gas_used: 0,
+            da_used: 0,
+            fees: U256::ZERO,
+            transactions: Vec::new(),
+            skipped: Vec::new(),
+            #[cfg(all(feature = "std", not(target_os = "zkvm")))]
+            deadline: None,
+            #[cfg(all(feature = "std", not(target_os = "zkvm")))]
+            slowest_tx: std::time::Duration::ZERO,
+            deadline_reached: false,
+            not_attempted: Some(0),
+        })
+    }
+
+    /// Sets a deadline for packing transactions.
+    ///
+    /// Before each transaction, [`Self::fill`] checks the elapsed time and stops once the time
+    /// left until the deadline is shorter than the slowest transaction executed so far. The
+    /// remaining candidates are left in the policy and reported in
+    /// [`ExecutedBlock::not_attempted`].
+    #[cfg(all(feature = "std", not(target_os = "zkvm")))]
+    pub const fn with_deadline(mut self, deadline: crate::time::Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Sets a deadline `timeout` from now, see [`Self::with_deadline`].
+    #[cfg(all(feature = "std", not(target_os = "zkvm")))]
+    pub fn with_timeout(self, timeout: std::time::Duration) -> Self {
+        self.with_deadline(crate::time::Instant::now() + timeout)
+    }
+
+    /// Returns `true` if packing stopped because the deadline was near.
+    pub const fn deadline_reached(&self) -> bool {
+        self.deadline_reached
+    }
+
+    /// Returns the limits of the block.
+    pub const fn limits(&self) -> &BlockBuilderLimits {
+        &self.limits
+    }
+
+    /// Returns the gas used by the included transactions.
+    pub const fn gas_used(&self) -> u64 {
+        self.gas_used
+    }
+
+    /// Returns the blob gas used by the included transactions.
+    pub const fn blob_gas_used(&self) -> u64 {
+        self.blob_gas_used
+    }
+
+    /// Returns the total encoded size of the included transactions.
+    pub const fn da_used(&self) -> u64 {
+        self.da_used
+    }
+
+    /// Returns the priority fees paid to the beneficiary so far.
+    pub const fn fees(&self) -> U256 {
+        self.fees
+    }
+
+    /// Returns the included transactions.
+    pub fn transactions(&self) -> &[Recovered<E::Transaction>] {
+        &self.transactions
+    }
+
+    /// Returns the skipped transactions.
+    pub fn skipped(&self) -> &[SkippedTransaction] {
+        &self.skipped
+    }
+
+    /// Returns a reference to the underlying executor.
+    pub const fn executor(&self) -> &E {
+        &self.executor
+    }
+
+    /// Returns `true` if no further transaction fits into the block.
+    pub const fn is_full(&self) -> bool {
+        self.limits.gas_limit.saturating_sub(self.gas_used) < MIN_TRANSACTION_GAS
+    }
+
+    /// Tries to include the transaction into the block.
+    ///
+    /// Returns the gas used by the transaction, or `None` if it was skipped, in which case it is
+    /// recorded in [`Self::skipped`].
+    pub fn add_transaction(
+        &mut self,
+        tx: Recovered<E::Transaction>,
+    ) -> Result<Option<u64>, BlockExecutionError> {
+        Ok(match self.try_add_transaction(tx)? {
+            Ok(gas_used) => Some(gas_used),
+            Err((tx, reason)) => {
+                self.record_skipped(&tx, reason);
+                None
+            }
+        })
+    }
+
+    /// Includes transactions from the policy until it is exhausted, the block is full or the
+    /// deadline is near.
+    ///
+    /// If the deadline is near, the remaining candidates are left in the policy and counted as not
+    /// attempted if the policy knows their number, see [`OrderingPolicy::remaining`].
+    pub fn fill(
+        &mut self,
+        policy: &mut impl OrderingPolicy<E::Transaction>,
+    ) -> Result<(), BlockExecutionError> {
+        while !self.is_full() {
+            if self.is_deadline_near() {
+                self.deadline_reached = true;
+                self.not_attempted =
+                    self.not_attempted.zip(policy.remaining()).map(|(sum, left)| sum + left);
+                break;
+            }
+            let Some(tx) = policy.next_transaction() else { break };
+            if let Err((tx, reason)) = self.try_add_transaction(tx)? {
+                policy.on_skipped(&tx, &reason);
+                self.record_skipped(&tx, reason);
+            }
+        }
+        Ok(())
+    }
+
+    /// Includes the transactions in the order of the iterator, see [`Self::fill`].
+    pub fn add_transactions(
+        &mut self,
+        transactions: impl IntoIterator<Item = Recovered<E::Transaction>>,
+    ) -> Result<(), BlockExecutionError> {
+        self.fill(&mut InOrder(transactions.into_iter()))
+    }
+
+    /// Applies the post-execution changes and returns the built block along with the EVM.
+    pub fn finish(
+        self,
+    ) -> Result<(E::Evm, ExecutedBlock<E::Transaction, E::Receipt>), BlockExecutionError> {
+        let (evm, result) = self.executor.finish()?;
+        let block = ExecutedBlock {
+            transactions: self.transactions,
+            result,
+            fees: self.fees,
+            skipped: self.skipped,
+            deadline_reached: self.deadline_reached,
+            not_attempted: self.not_attempted,
+        };
+        Ok((evm, block))
+    }
+
+    /// Checks the budgets and executes the transaction, handing it back along with the reason if
+    /// it is skipped.
+    #[expect(clippy::type_complexity)]
+    fn try_add_transaction(
+        &mut self,
+        tx: Recovered<E::Transaction>,
+    ) -> Result<Result<u64, (Recovered<E::Transaction>, SkipReason)>, BlockExecutionError> {
+        let available = self.limits.gas_limit.saturating_sub(self.gas_used);
+        if tx.gas_limit() > available {
+            let reason = SkipReason::GasLimit { gas_limit: tx.gas_limit(), available };
+            return Ok(Err((tx, reason)));
+        }
+
+        let blob_gas = tx.blob_gas_used().unwrap_or_default();
+        if let Some(limit) = self.limits.blob_gas_limit {
+            let available = limit.saturating_sub(self.blob_gas_used);
+            if blob_gas > available {
+                return Ok(Err((tx, SkipReason::BlobGasLimit { blob_gas, available })));
+            }
+        }
+
+        let size = tx.encode_2718_len() as u64;
+        if let Some(limit) = self.limits.da_limit {
+            let available = limit.saturating_sub(self.da_used);
+            if size > available {
+                return Ok(Err((tx, SkipReason::DaLimit { size, available })));
+            }
+        }
+
+        let base_fee = self.executor.evm().block().basefee();
+        #[cfg(all(feature = "std", not(target_os = "zkvm")))]
+        let started_at = crate::time::Instant::now();
+        let result = self.executor.execute_transaction(&tx);
+        #[cfg(all(feature = "std", not(target_os = "zkvm")))]
+        {
+            self.slowest_tx = self.slowest_tx.max(started_at.elapsed());
+        }
+        let gas_used = match result {
+            Ok(gas_used) => gas_used,
+            Err(err) if err.as_validation().is_some() => {
+                return Ok(Err((tx, SkipReason::Invalid(err))))
+            }
+            Err(err) => return Err(err),
+        };
+
+        let tip = tx.effective_tip_per_gas(base_fee).unwrap_or_default();
+        self.fees += U256::from(tip) * U256::from(gas_used);
+        self.gas_used += gas_used;
+        self.blob_gas_used += blob_gas;
+        self.da_used += size;
+        self.transactions.push(tx);
+
+        Ok(Ok(gas_used))
+    }
+
+    /// Returns `true` if the slowest transaction so far would not finish before the deadline.
+    fn is_deadline_near(&self) -> bool {
+        #[cfg(all(feature = "std", not(target_os = "zkvm")))]
+        if let Some(deadline) = self.deadline {
+            return crate::time::Instant::now() + self.slowest_tx >= deadline;
+        }
+        false
+    }
+
+    fn record_skipped(&mut self, tx: &Recovered<E::Transaction>, reason: SkipReason) {
+        self.skipped.push(SkippedTransaction { hash: tx.trie_hash(), sender: tx.signer(), reason });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        block::{BlockExecutorFactory, FifoOrdering},
+        eth::{
+            receipt_builder::AlloyReceiptBuilder, spec::EthSpec, EthBlockExecutionCtx,
+            EthBlockExecutorFactory,
+        },
+        EthEvmFactory, EvmEnv, EvmFactory,
+    };
+    use alloy_consensus::{Header, SignableTransaction, TxEip1559, TxEnvelope};
+    use alloy_primitives::{Bytes, Signature, TxKind};
+    use revm::{
+        database::{CacheDB, EmptyDB, State},
+        state::AccountInfo,
+    };
+
+    const SENDER: Address = Address::repeat_byte(0xa1);
+
+    fn transfer(nonce: u64, gas_limit: u64) -> Recovered<TxEnvelope> {
+        let tx = TxEip1559 {
+            chain_id: 1,
+            nonce,
+            gas_limit,
+            max_fee_per_gas: 3_000_000_000,
+            max_priority_fee_per_gas: 2_000_000_000,
+            to: TxKind::Call(Address::repeat_byte(0xb1)),
+            value: U256::from(1),
+            ..Default::default()
+        };
+        Recovered::new_unchecked(tx.into_signed(Signature::test_signature()).into(), SENDER)
+    }
+
+    #[test]
+    fn test_build_block() {
+        let factory =
+            EthBlockExecutorFactory::new(AlloyReceiptBuilder, EthSpec::mainnet(), EthEvmFactory);
+        // Shanghai, so that no system contracts are required.
+        let header = Header {
+            number: 17_034_870,
+            timestamp: 1_681_338_455,
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            ..Default::default()
+        };
+
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            SENDER,
+            AccountInfo { balance: U256::from(10).pow(U256::from(18)), ..Default::default() },
+        );
+        let mut state = State::builder().with_database(db).build();
+        let evm = factory
+            .evm_factory()
+            .create_evm(&mut state, EvmEnv::for_eth_block(&header, EthSpec::mainnet(), 1, None));
+        let executor = factory.create_executor(
+            evm,
+            EthBlockExecutionCtx {
+                parent_hash: header.parent_hash,
+                parent_beacon_block_root: None,
+                ommers: &[],
+                withdrawals: None,
+                extra_data: Bytes::new(),
+                tx_count_hint: None,
+                blob_params: None,
+            },
+        );
+
+        let mut builder = BlockBuilder::new(executor, BlockBuilderLimits::new(50_000)).unwrap();
+        builder
+            .add_transactions([
+                transfer(0, 21_000),
+                // nonce gap
+                transfer(2, 21_000),
+                // exceeds the remaining gas
+                transfer(1, 30_000),
+                transfer(1, 21_000),
+            ])
+            .unwrap();
+        assert!(builder.is_full());
+
+        let (_, block) = builder.finish().unwrap();
+        assert_eq!(block.transactions.len(), 2);
+        assert_eq!(block.result.gas_used, 42_000);
+        assert_eq!(block.fees, U256::from(42_000u64 * 2_000_000_000));
+        assert_eq!(block.skipped.len(), 2);
+        assert!(matches!(block.skipped[0].reason, SkipReason::Invalid(_)));
+        assert!(matches!(
+            block.skipped[1].reason,
+            SkipReason::GasLimit { gas_limit: 30_000, available: 29_000 }
+        ));
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", not(target_os = "zkvm")))]
+    fn test_build_block_deadline() {
+        let factory =
+            EthBlockExecutorFactory::new(AlloyReceiptBuilder, EthSpec::mainnet(), EthEvmFactory);
+        let header = Header {
+            number: 17_034_870,
+            timestamp: 1_681_338_455,
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            ..Default::default()
+        };
+        let mut state = State::builder().with_database(CacheDB::<EmptyDB>::default()).build();
+        let evm = factory
+            .evm_factory()
+            .create_evm(&mut state, EvmEnv::for_eth_block(&header, EthSpec::mainnet(), 1, None));
+        let executor = factory.create_executor(evm, EthBlockExecutionCtx::from_header(&header));
+
+        // the deadline has already passed
+        let mut builder = BlockBuilder::new(executor, BlockBuilderLimits::new(header.gas_limit))
+            .unwrap()
+            .with_deadline(crate::time::Instant::now());
+        let mut policy: FifoOrdering<_> = (0..3).map(|nonce| transfer(nonce, 21_000)).collect();
+        builder.fill(&mut policy).unwrap();
+        assert!(builder.deadline_reached());
+        // the candidates are left in the policy
+        assert_eq!(policy.len(), 3);
+
+        let (_, block) = builder.finish().unwrap();
+        assert!(block.transactions.is_empty());
+        assert!(block.skipped.is_empty());
+        assert_eq!(block.not_attempted, Some(3));
+    }
+}
@@ -0,0 +1,377 @@
+//! Following a chain across reorgs.
+//!
+//! A [`ChainExecutor`] executes blocks on top of a canonical [`State`] and keeps the reverts of
+//! the executed blocks, so that it can rewind to an earlier block and re-execute a different
+//! branch when the chain reorganizes, without the caller having to roll back state itself. With
+//! [`ChainExecutor::with_max_reorg_depth`] only the reverts of the latest blocks are kept.
+
+use super::{
+    BlockExecutionError, BlockExecutionResult, BlockExecutor, BlockExecutorFactory,
+    ExecutableTxParts,
+};
+use crate::{Database, EvmEnv, EvmFactory};
+use alloc::vec::Vec;
+use alloy_primitives::B256;
+use revm::database::{
+    states::{bundle_state::BundleRetention, CacheState},
+    BundleState, State,
+};
+
+/// A block that can be executed by a [`ChainExecutor`] using the factory `F`.
+pub trait ExecutableBlock<F: BlockExecutorFactory> {
+    /// Returns the number of the block.
+    fn number(&self) -> u64;
+
+    /// Returns the hash of the block, if known.
+    ///
+    /// Used by [`execute_range`](super::execute_range) to serve `BLOCKHASH` lookups of the
+    /// following blocks without database round trips.
+    fn hash(&self) -> Option<B256> {
+        None
+    }
+
+    /// Returns the environment to execute the block in.
+    fn evm_env(
+        &self,
+        factory: &F,
+    ) -> EvmEnv<<F::EvmFactory as EvmFactory>::Spec, <F::EvmFactory as EvmFactory>::BlockEnv>;
+
+    /// Returns the execution context of the block.
+    fn execution_ctx(&self) -> F::ExecutionCtx<'_>;
+
+    /// Returns the transactions of the block, in execution order.
+    fn transactions(
+        &self,
+    ) -> impl Iterator<
+        Item = impl ExecutableTxParts<<F::EvmFactory as EvmFactory>::Tx, F::Transaction> + '_,
+    >;
+}
+
+/// Errors returned by a [`ChainExecutor`].
+#[derive(Debug, thiserror::Error)]
+pub enum ChainExecutorError {
+    /// The block doesn't extend the current tip.
+    #[error("block {got} doesn't extend the tip, expected block {expected}")]
+    NonContiguous {
+        /// Number of the next block.
+        expected: u64,
+        /// Number of the given block.
+        got: u64,
+    },
+    /// The target block is outside of the range that can be rewound to.
+    #[error("can't rewind to block {target}, rewindable range is {oldest}..={tip}")]
+    Unreachable {
+        /// Block to rewind to.
+        target: u64,
+        /// Oldest block that can be rewound to.
+        oldest: u64,
+        /// Current tip.
+        tip: u64,
+    },
+    /// Executing a block failed.
+    #[error(transparent)]
+    Execution(#[from] BlockExecutionError),
+}
+
+/// Executes consecutive blocks on top of a canonical [`State`], supporting rewinds.
+///
+/// The state of the wrapped database is the state after block [`Self::base`]. Changes of executed
+/// blocks are accumulated in the [`BundleState`] of the [`State`] together with their reverts,
+/// so the executor can rewind to any block between [`Self::oldest`] and the tip. Failed executions
+/// leave the tip unchanged.
+///
+/// By default the reverts of all executed blocks are kept, which grows without bound when
+/// following a chain for long. [`Self::with_max_reorg_depth`] limits how far the executor can
+/// rewind and drops older reverts.
+#[derive(Debug)]
+pub struct ChainExecutor<F, DB> {
+    factory: F,
+    state: State<DB>,
+    base: u64,
+    oldest: u64,
+    tip: u64,
+    max_reorg_depth: Option<u64>,
+}
+
+impl<F, DB> ChainExecutor<F, DB>
+where
+    F: BlockExecutorFactory,
+    DB: Database,
+{
+    /// Creates a new executor on top of the database, which holds the state after block `base`.
+    pub fn new(factory: F, db: DB, base: u64) -> Self {
+        let state = State::builder().with_database(db).with_bundle_update().build();
+        Self { factory, state, base, oldest: base, tip: base, max_reorg_depth: None }
+    }
+
+    /// Keeps the reverts of at most the latest `depth` blocks, so that the executor can rewind to
+    /// at most `depth` blocks below the tip.
+    pub fn with_max_reorg_depth(mut self, depth: u64) -> Self {
+        self.max_reorg_depth = Some(depth);
+        self.prune_reverts();
+        self
+    }
+
+    /// Returns the executor factory.
+    pub const fn factory(&self) -> &F {
+        &self.factory
+    }
+
+    /// Returns the number of the block the wrapped database holds the state of.
+    pub const fn base(&self) -> u64 {
+        self.base
+    }
+
+    /// Returns the number of the oldest block the executor can rewind to.
+    pub const fn oldest(&self) -> u64 {
+        self.oldest
+    }
+
+    /// Returns the number of the last executed block.
+    pub const fn tip(&self) -> u64 {
+        self.tip
+    }
+
+    /// Returns the state, including the changes of all executed blocks.
+    pub const fn state(&self) -> &State<DB> {
+        &self.state
+    }
+
+    /// Returns a mutable reference to the state.
+    pub const fn state_mut(&mut self) -> &mut State<DB> {
+        &mut self.state
+    }
+
+    /// Returns the changes of all blocks executed on top of the base.
+    pub const fn bundle(&self) -> &BundleState {
+        &self.state.bundle_state
+    }
+
+    /// Consumes the executor, returning the state.
+    pub fn into_state(self) -> State<DB> {
+        self.state
+    }
+
+    /// Executes the block on top of the tip, which becomes the new tip.
+    pub fn advance<B>(
+        &mut self,
+        block: &B,
+    ) -> Result<BlockExecutionResult<F::Receipt>, ChainExecutorError>
+    where
+        B: ExecutableBlock<F>,
+    {
+        let expected = self.tip + 1;
+        if block.number() != expected {
+            return Err(ChainExecutorError::NonContiguous { expected, got: block.number() });
+        }
+
+        let evm =
+            self.factory.evm_factory().create_evm(&mut self.state, block.evm_env(&self.factory));
+        let executor = self.factory.create_executor(evm, block.execution_ctx());
+        let result = match executor.execute_block(block.transactions()) {
+            Ok(result) => result,
+            Err(err) => {
+                // Discard the partial changes of the failed block.
+                self.state.transition_state = Some(Default::default());
+                self.reset_cache();
+                return Err(err.into());
+            }
+        };
+
+        self.state.merge_transitions(BundleRetention::Reverts);
+        self.tip = expected;
+        self.prune_reverts();
+        Ok(result)
+    }
+
+    /// Reverts the changes of all blocks after `number`, which becomes the new tip.
+    pub fn rewind_to(&mut self, number: u64) -> Result<(), ChainExecutorError> {
+        if number < self.oldest || number > self.tip {
+            return Err(ChainExecutorError::Unreachable {
+                target: number,
+                oldest: self.oldest,
+                tip: self.tip,
+            });
+        }
+
+        let blocks = (self.tip - number) as usize;
+        if blocks > 0 {
+            self.state.bundle_state.revert(blocks);
+            self.reset_cache();
+            self.tip = number;
+        }
+        Ok(())
+    }
+
+    /// Rewinds to the parent of the first block and executes the blocks, e.g. the new branch after
+    /// a reorg.
+    ///
+    /// Returns the results of the executed blocks. If a block fails, the blocks before it remain
+    /// executed.
+    pub fn reexecute_range<'b, B>(
+        &mut self,
+        blocks: impl IntoIterator<Item = &'b B>,
+    ) -> Result<Vec<BlockExecutionResult<F::Receipt>>, ChainExecutorError>
+    where
+        B: ExecutableBlock<F> + 'b,
+    {
+        let mut blocks = blocks.into_iter().peekable();
+        let Some(first) = blocks.peek() else { return Ok(Vec::new()) };
+        self.rewind_to(first.number().saturating_sub(1))?;
+        blocks.map(|block| self.advance(block)).collect()
+    }
+
+    /// Drops the reverts of the blocks exceeding the maximum reorg depth.
+    fn prune_reverts(&mut self) {
+        let Some(depth) = self.max_reorg_depth else { return };
+        let excess = (self.tip - self.oldest).saturating_sub(depth);
+        if excess > 0 {
+            self.state.bundle_state.take_n_reverts(excess as usize);
+            self.oldest += excess;
+        }
+    }
+
+    /// Clears the cached accounts, so that subsequent reads are served from the bundle state and
+    /// the database.
+    fn reset_cache(&mut self) {
+        self.state.cache = CacheState::new(self.state.cache.has_state_clear);
+        self.state.use_preloaded_bundle = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        eth::{
+            receipt_builder::AlloyReceiptBuilder, spec::EthSpec, EthBlockExecutionCtx,
+            EthBlockExecutorFactory,
+        },
+        EthEvmFactory,
+    };
+    use alloc::borrow::Cow;
+    use alloy_consensus::{transaction::Recovered, Header, TxEnvelope};
+    use alloy_eips::eip4895::Withdrawal;
+    use alloy_primitives::{Address, Bytes, U256};
+    use revm::{
+        context::TxEnv,
+        database::{CacheDB, EmptyDB},
+        Database as _,
+    };
+
+    type Factory = EthBlockExecutorFactory<AlloyReceiptBuilder, EthSpec, EthEvmFactory>;
+
+    const RECIPIENT: Address = Address::repeat_byte(0x42);
+
+    /// A block withdrawing `amount` gwei to [`RECIPIENT`].
+    struct TestBlock {
+        header: Header,
+        withdrawals: [Withdrawal; 1],
+    }
+
+    impl TestBlock {
+        fn new(number: u64, amount: u64) -> Self {
+            // Shanghai, so that no system contracts are required.
+            let header = Header {
+                number,
+                timestamp: 1_681_338_455 + (number - 17_034_870) * 12,
+                gas_limit: 30_000_000,
+                base_fee_per_gas: Some(1_000_000_000),
+                ..Default::default()
+            };
+            let withdrawals =
+                [Withdrawal { index: 0, validator_index: 0, address: RECIPIENT, amount }];
+            Self { header, withdrawals }
+        }
+    }
+
+    impl ExecutableBlock<Factory> for TestBlock {
+        fn number(&self) -> u64 {
+            self.header.number
+        }
+
+        fn evm_env(&self, factory: &Factory) -> EvmEnv {
+            EvmEnv::for_eth_block(&self.header, factory.spec().clone(), 1, None)
+        }
+
+        fn execution_ctx(&self) -> EthBlockExecutionCtx<'_> {
+            EthBlockExecutionCtx {
+                parent_hash: self.header.parent_hash,
+                parent_beacon_block_root: None,
+                ommers: &[],
+                withdrawals: Some(Cow::Borrowed(&self.withdrawals)),
+                extra_data: Bytes::new(),
+                tx_count_hint: Some(0),
+                blob_params: None,
+            }
+        }
+
+        fn transactions(
+            &self,
+        ) -> impl Iterator<Item = impl ExecutableTxParts<TxEnv, TxEnvelope> + '_> {
+            core::iter::empty::<Recovered<TxEnvelope>>()
+        }
+    }
+
+    fn balance(executor: &mut ChainExecutor<Factory, CacheDB<EmptyDB>>) -> U256 {
+        executor.state_mut().basic(RECIPIENT).unwrap().unwrap_or_default().balance
+    }
+
+    #[test]
+    fn test_reorg() {
+        let factory = Factory::new(AlloyReceiptBuilder, EthSpec::mainnet(), EthEvmFactory);
+        let mut executor =
+            ChainExecutor::new(factory, CacheDB::new(EmptyDB::default()), 17_034_869);
+        let gwei = U256::from(1_000_000_000u64);
+
+        for (number, amount) in [(17_034_870, 1), (17_034_871, 2), (17_034_872, 3)] {
+            executor.advance(&TestBlock::new(number, amount)).unwrap();
+        }
+        assert_eq!(executor.tip(), 17_034_872);
+        assert_eq!(balance(&mut executor), gwei * U256::from(6));
+
+        assert!(matches!(
+            executor.advance(&TestBlock::new(17_034_872, 1)),
+            Err(ChainExecutorError::NonContiguous { expected: 17_034_873, got: 17_034_872 })
+        ));
+
+        executor.rewind_to(17_034_870).unwrap();
+        assert_eq!(executor.tip(), 17_034_870);
+        assert_eq!(balance(&mut executor), gwei);
+
+        let branch = [TestBlock::new(17_034_871, 10), TestBlock::new(17_034_872, 20)];
+        executor.reexecute_range(&branch).unwrap();
+        assert_eq!(executor.tip(), 17_034_872);
+        assert_eq!(balance(&mut executor), gwei * U256::from(31));
+
+        assert!(matches!(
+            executor.rewind_to(17_034_868),
+            Err(ChainExecutorError::Unreachable { target: 17_034_868, .. })
+        ));
+    }
+
+    #[test]
+    fn test_max_reorg_depth() {
+        let factory = Factory::new(AlloyReceiptBuilder, EthSpec::mainnet(), EthEvmFactory);
+        let mut executor =
+            ChainExecutor::new(factory, CacheDB::new(EmptyDB::default()), 17_034_869)
+                .with_max_reorg_depth(1);
+        let gwei = U256::from(1_000_000_000u64);
+
+        for (number, amount) in [(17_034_870, 1), (17_034_871, 2), (17_034_872, 3)] {
+            executor.advance(&TestBlock::new(number, amount)).unwrap();
+        }
+        assert_eq!(executor.oldest(), 17_034_871);
+        assert_eq!(executor.bundle().reverts.len(), 1);
+
+        assert!(matches!(
+            executor.rewind_to(17_034_870),
+            Err(ChainExecutorError::Unreachable { target: 17_034_870, oldest: 17_034_871, .. })
+        ));
+
+        executor.rewind_to(17_034_871).unwrap();
+        assert_eq!(balance(&mut executor), gwei * U256::from(3));
+        executor.advance(&TestBlock::new(17_034_872, 10)).unwrap();
+        assert_eq!(balance(&mut executor), gwei * U256::from(13));
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{EvmError, InvalidTxError};
+use crate::{
+    block::{BlockOutcomeMismatch, SystemCall, SystemCallOutcome},
+    EvmError, InvalidTxError,
+};
 use alloc::{
     boxed::Box,
     string::{String, ToString},
@@ -29,6 +32,16 @@ pub enum BlockValidationError {
         /// The available block gas
         block_available_gas: u64,
     },
+    /// Error when transaction blob gas exceeds available block blob gas
+    #[error(
+        "transaction blob gas used {transaction_blob_gas_used} is more than blocks available blob gas {block_available_blob_gas}"
+    )]
+    BlobGasUsedMoreThanAvailableBlockBlobGas {
+        /// The transaction's blob gas used
+        transaction_blob_gas_used: u64,
+        /// The available block blob gas
+        block_available_blob_gas: u64,
+    },
     /// Error for EIP-4788 when parent beacon block root is missing
     #[error("EIP-4788 parent beacon block root missing for active Cancun block")]
     MissingParentBeaconBlockRoot,
@@ -74,11 +87,25 @@ pub enum BlockValidationError {
         /// The error message.
         message: String,
     },
+    /// A system call failed and its policy is [`SystemCallPolicy::Fail`].
+    ///
+    /// [`SystemCallPolicy::Fail`]: crate::block::SystemCallPolicy::Fail
+    #[error("{call:?} system call failed: {outcome:?}")]
+    SystemCallFailed {
+        /// The failed call.
+        call: SystemCall,
+        /// Outcome of the call.
+        outcome: Box<SystemCallOutcome>,
+    },
     /// Error when decoding deposit requests from receipts [EIP-6110]
     ///
     /// [EIP-6110]: https://eips.ethereum.org/EIPS/eip-6110
     #[error("failed to decode deposit requests from receipts: {_0}")]
     DepositRequestDecode(String),
+    /// The execution outcome differs from the one expected by the block header, see
+    /// [`BlockExecutor::finish_verified`](crate::block::BlockExecutor::finish_verified).
+    #[error("block outcome mismatch: {_0}")]
+    OutcomeMismatch(Box<BlockOutcomeMismatch>),
     /// Arbitrary Block validation errors.
     #[error(transparent)]
     Other(Box<dyn core::error::Error + Send + Sync + 'static>),
@@ -108,6 +135,9 @@ pub enum BlockExecutionError {
     /// Internal, i.e. non consensus or validation related Block Executor Errors
     #[error(transparent)]
     Internal(#[from] InternalBlockExecutionError),
+    /// Internal error of a specific transaction of the block, see [`TxExecutionError`].
+    #[error(transparent)]
+    Tx(#[from] TxExecutionError),
 }
 
 impl BlockExecutionError {
@@ -130,24 +160,88 @@ impl BlockExecutionError {
     pub const fn as_validation(&self) -> Option<&BlockValidationError> {
         match self {
             Self::Validation(err) => Some(err),
+            Self::Tx(err) => err.source.as_validation(),
+            Self::Internal(_) => None,
+        }
+    }
+
+    /// Wraps an internal error into a [`TxExecutionError`] for the transaction at `index` with
+    /// the given hash.
+    ///
+    /// Validation errors are returned unchanged, so that they can still be matched as
+    /// [`BlockExecutionError::Validation`]. Errors that are already attributed to a transaction are
+    /// returned unchanged as well.
+    pub fn tx(index: usize, hash: B256, error: Self) -> Self {
+        match error {
+            err @ (Self::Validation(_) | Self::Tx(_)) => err,
+            err => Self::Tx(TxExecutionError { index, hash, source: Box::new(err) }),
+        }
+    }
+
+    /// Returns the [`TxExecutionError`] if the error is attributed to a transaction.
+    pub const fn as_tx(&self) -> Option<&TxExecutionError> {
+        match self {
+            Self::Tx(err) => Some(err),
             _ => None,
         }
     }
 
+    /// Returns the index of the transaction that caused the error, if known.
+    pub fn tx_index(&self) -> Option<usize> {
+        self.as_tx().map(|err| err.index)
+    }
+
+    /// Returns the hash of the transaction that caused the error, if known.
+    pub fn tx_hash(&self) -> Option<B256> {
+        self.as_tx().map(|err| err.hash)
+    }
+
     /// Handles an EVM error occurred when executing a transaction.
     ///
     /// If an error matches [`EvmError::InvalidTransaction`], it will be wrapped into
-    /// [`BlockValidationError::InvalidTx`], otherwise into [`InternalBlockExecutionError::EVM`].
+    /// [`BlockValidationError::InvalidTx`]. Database errors are wrapped into
+    /// [`InternalBlockExecutionError::Database`], any other error into
+    /// [`InternalBlockExecutionError::EVM`].
     pub fn evm<E: EvmError>(error: E, hash: B256) -> Self {
         match error.try_into_invalid_tx_err() {
             Ok(err) => {
                 Self::Validation(BlockValidationError::InvalidTx { hash, error: Box::new(err) })
             }
+            Err(err) if err.is_database_error() => {
+                Self::Internal(InternalBlockExecutionError::Database { hash, error: Box::new(err) })
+            }
             Err(err) => {
                 Self::Internal(InternalBlockExecutionError::EVM { hash, error: Box::new(err) })
             }
         }
     }
+
+    /// Returns `true` if the error might not occur when retrying the execution, e.g. because it
+    /// was caused by a database I/O failure rather than by the block itself.
+    ///
+    /// Blocks failing with a transient error must not be marked invalid.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Validation(_) => false,
+            Self::Internal(err) => err.is_transient(),
+            Self::Tx(err) => err.source.is_transient(),
+        }
+    }
+}
+
+/// Internal error that occurred when executing a transaction of the block.
+///
+/// Identifies the transaction that failed the block by its index and hash. The underlying error is
+/// exposed as the [`source`](core::error::Error::source) of this error.
+#[derive(Debug, thiserror::Error)]
+#[error("transaction {index} ({hash}) failed")]
+pub struct TxExecutionError {
+    /// Index of the transaction in the block.
+    pub index: usize,
+    /// Hash of the transaction.
+    pub hash: B256,
+    /// The underlying error.
+    pub source: Box<BlockExecutionError>,
 }
 
 /// Internal (i.e., not validation or consensus related) `BlockExecutor` Errors
@@ -163,12 +257,27 @@ pub enum InternalBlockExecutionError {
         /// The EVM error.
         error: Box<dyn core::error::Error + Send + Sync>,
     },
+    /// Database error occurred when executing transaction.
+    #[error("database error occurred when executing transaction {hash}: {error}")]
+    Database {
+        /// The hash of the transaction
+        hash: B256,
+        /// The EVM error wrapping the database error.
+        error: Box<dyn core::error::Error + Send + Sync>,
+    },
     /// Arbitrary Block Executor Errors
     #[error(transparent)]
     Other(Box<dyn core::error::Error + Send + Sync + 'static>),
 }
 
 impl InternalBlockExecutionError {
+    /// Returns `true` if the error is a [`InternalBlockExecutionError::Database`] error.
+    ///
+    /// See [`BlockExecutionError::is_transient`].
+    pub const fn is_transient(&self) -> bool {
+        matches!(self, Self::Database { .. })
+    }
+
     /// Create a new [`InternalBlockExecutionError::Other`] variant.
     pub fn other<E>(error: E) -> Self
     where
@@ -228,4 +337,36 @@ mod tests {
         assert!(err.downcast_other::<E>().is_some());
         assert!(err.downcast::<E>().is_ok());
     }
+
+    #[test]
+    fn tx_error_keeps_validation() {
+        let err = BlockExecutionError::tx(
+            3,
+            B256::ZERO,
+            BlockValidationError::IncrementBalanceFailed.into(),
+        );
+        assert!(matches!(
+            err,
+            BlockExecutionError::Validation(BlockValidationError::IncrementBalanceFailed)
+        ));
+        assert_eq!(err.tx_index(), None);
+    }
+
+    #[test]
+    fn tx_error_wraps_internal() {
+        use core::error::Error;
+
+        let err = BlockExecutionError::tx(3, B256::ZERO, BlockExecutionError::other(E));
+        assert_eq!(err.tx_index(), Some(3));
+        assert!(err.as_validation().is_none());
+        // the underlying error is only exposed as the source
+        assert_eq!(
+            alloc::format!("{err}"),
+            alloc::format!("transaction 3 ({}) failed", B256::ZERO)
+        );
+        assert_eq!(alloc::format!("{}", err.source().unwrap()), "err");
+
+        let rewrapped = BlockExecutionError::tx(4, B256::ZERO, err);
+        assert_eq!(rewrapped.tx_index(), Some(3));
+    }
 }
@@ -0,0 +1,103 @@
+//! Tracking of the fees collected by a block.
+//!
+//! A [`FeeTracker`] records the balance increase of the fee recipients, e.g. the block beneficiary
+//! and, on OP stack chains, the fee vaults, for every committed transaction. Builders can use
+//! [`FeeTracker::total_fees`] to rank payloads by their value without diffing bundle states.
+
+use crate::Database;
+use alloc::vec::Vec;
+use alloy_primitives::{Address, U256};
+use revm::state::EvmState;
+
+/// Balance increases of fee recipients per transaction.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeeTracker {
+    recipients: Vec<Address>,
+    tx_fees: Vec<U256>,
+    total: U256,
+}
+
+impl FeeTracker {
+    /// Creates a tracker for the given recipients.
+    pub fn new(recipients: impl IntoIterator<Item = Address>) -> Self {
+        let mut tracker = Self::default();
+        tracker.add_recipients(recipients);
+        tracker
+    }
+
+    /// Adds recipients to track, ignoring the ones already tracked.
+    pub fn add_recipients(&mut self, recipients: impl IntoIterator<Item = Address>) {
+        for recipient in recipients {
+            if !self.recipients.contains(&recipient) {
+                self.recipients.push(recipient);
+            }
+        }
+    }
+
+    /// Returns the tracked recipients.
+    pub fn recipients(&self) -> &[Address] {
+        &self.recipients
+    }
+
+    /// Records the fees of a transaction from its state changes.
+    ///
+    /// Must be called before `state` is committed to `db`, since the balances before the
+    /// transaction are read from it. Balance decreases of a recipient are not counted as negative
+    /// fees. Returns the fees of the transaction.
+    pub fn record<DB: Database>(
+        &mut self,
+        db: &mut DB,
+        state: &EvmState,
+    ) -> Result<U256, DB::Error> {
+        let mut fees = U256::ZERO;
+        for recipient in &self.recipients {
+            let Some(account) = state.get(recipient).filter(|account| account.is_touched()) else {
+                continue;
+            };
+            let before = db.basic(*recipient)?.map(|info| info.balance).unwrap_or_default();
+            fees += account.info.balance.saturating_sub(before);
+        }
+        self.tx_fees.push(fees);
+        self.total += fees;
+        Ok(fees)
+    }
+
+    /// Returns the fees of every recorded transaction, in execution order.
+    pub fn tx_fees(&self) -> &[U256] {
+        &self.tx_fees
+    }
+
+    /// Returns the total fees of all recorded transactions.
+    pub const fn total_fees(&self) -> U256 {
+        self.total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::{
+        database::{CacheDB, EmptyDB},
+        state::{Account, AccountInfo},
+    };
+
+    #[test]
+    fn test_track_fees() {
+        let (coinbase, vault) = (Address::repeat_byte(0x01), Address::repeat_byte(0x02));
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(coinbase, AccountInfo::from_balance(U256::from(100)));
+        let mut tracker = FeeTracker::new([coinbase, vault, coinbase]);
+        assert_eq!(tracker.recipients(), &[coinbase, vault]);
+
+        let mut state = EvmState::default();
+        for (address, balance) in [(coinbase, 130), (vault, 5)] {
+            let mut account = Account::from(AccountInfo::from_balance(U256::from(balance)));
+            account.mark_touch();
+            state.insert(address, account);
+        }
+        assert_eq!(tracker.record(&mut db, &state).unwrap(), U256::from(35));
+        assert_eq!(tracker.record(&mut db, &EvmState::default()).unwrap(), U256::ZERO);
+        assert_eq!(tracker.tx_fees(), &[U256::from(35), U256::ZERO]);
+        assert_eq!(tracker.total_fees(), U256::from(35));
+    }
+}
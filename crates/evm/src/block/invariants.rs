@@ -0,0 +1,267 @@
+//! Property-based invariant checks for [`BlockExecutor`] implementations.
+//!
+//! [`transaction_sequence`] generates random transaction sequences sent from the [`SENDERS`]
+//! accounts, and [`check_invariants`] executes a sequence while asserting gas accounting
+//! invariants that every executor must uphold. Chains with custom executors can reuse both to run
+//! the same checks against their own implementation.
+
+use super::{BlockExecutionError, BlockExecutionResult, BlockExecutor, ExecutableTx};
+use alloc::vec::Vec;
+use alloy_consensus::{
+    transaction::Recovered, SignableTransaction, TxEip1559, TxEnvelope, TxLegacy, TxReceipt,
+};
+use alloy_primitives::{Address, Bytes, Signature, TxKind, U256};
+use proptest::{collection::vec, prelude::*};
+
+/// Accounts that send the generated transactions.
+///
+/// The accounts must be funded with at least [`SENDER_BALANCE`] in the state the transactions are
+/// executed on.
+pub const SENDERS: [Address; 4] = [
+    Address::repeat_byte(0xa1),
+    Address::repeat_byte(0xa2),
+    Address::repeat_byte(0xa3),
+    Address::repeat_byte(0xa4),
+];
+
+/// Minimum balance of each of the [`SENDERS`] accounts.
+pub const SENDER_BALANCE: U256 = U256::from_limbs([0, 0, 1, 0]);
+
+/// Maximum fee per gas of the generated transactions.
+///
+/// Blocks the transactions are executed in must have a base fee of at most this value.
+pub const MAX_FEE_PER_GAS: u128 = 100_000_000_000;
+
+/// Recipients of the generated calls: a few precompiles and empty accounts.
+const RECIPIENTS: [Address; 4] = [
+    Address::with_last_byte(1),
+    Address::with_last_byte(4),
+    Address::repeat_byte(0xb1),
+    Address::repeat_byte(0xb2),
+];
+
+/// Returns a strategy generating sequences of up to `max_len` transactions.
+///
+/// Transactions are sent by the [`SENDERS`] accounts with consecutive nonces per sender, and are
+/// a mix of legacy and EIP-1559 calls and contract creations with random input. Signatures are not
+/// valid, the transactions are recovered with the sender set explicitly.
+pub fn transaction_sequence(max_len: usize) -> impl Strategy<Value = Vec<Recovered<TxEnvelope>>> {
+    let tx = (
+        0..SENDERS.len(),
+        any::<bool>(),
+        prop::option::of(prop::sample::select(RECIPIENTS.to_vec())),
+        0..1_000_000_000_000_000_000u128,
+        21_000..1_000_000u64,
+        vec(any::<u8>(), 0..64),
+    );
+
+    vec(tx, 0..=max_len).prop_map(|txs| {
+        let mut nonces = [0u64; SENDERS.len()];
+        txs.into_iter()
+            .map(|(sender, eip1559, to, value, gas_limit, input)| {
+                let nonce = nonces[sender];
+                nonces[sender] += 1;
+
+                let to = to.map_or(TxKind::Create, TxKind::Call);
+                let value = U256::from(value);
+                let input = Bytes::from(input);
+                let signature = Signature::test_signature();
+
+                let tx: TxEnvelope = if eip1559 {
+                    TxEip1559 {
+                        chain_id: 1,
+                        nonce,
+                        gas_limit,
+                        max_fee_per_gas: MAX_FEE_PER_GAS,
+                        max_priority_fee_per_gas: 1,
+                        to,
+                        value,
+                        input,
+                        ..Default::default()
+                    }
+                    .into_signed(signature)
+                    .into()
+                } else {
+                    TxLegacy {
+                        chain_id: Some(1),
+                        nonce,
+                        gas_price: MAX_FEE_PER_GAS,
+                        gas_limit,
+                        to,
+                        value,
+                        input,
+                    }
+                    .into_signed(signature)
+                    .into()
+                };
+
+                Recovered::new_unchecked(tx, SENDERS[sender])
+            })
+            .collect()
+    })
+}
+
+/// A violated executor invariant.
+#[derive(Debug, thiserror::Error)]
+pub enum InvariantViolation {
+    /// Execution failed with an error that is not a transaction validation error.
+    #[error(transparent)]
+    Execution(#[from] BlockExecutionError),
+    /// The number of receipts differs from the number of executed transactions.
+    #[error("{receipts} receipts recorded for {executed} executed transactions")]
+    ReceiptCount {
+        /// Number of recorded receipts.
+        receipts: usize,
+        /// Number of executed transactions.
+        executed: usize,
+    },
+    /// The cumulative gas used of a receipt doesn't match the gas used by the transactions so far.
+    #[error("receipt {index} reports cumulative gas {reported}, expected {expected}")]
+    CumulativeGas {
+        /// Index of the receipt.
+        index: usize,
+        /// Cumulative gas used reported by the receipt.
+        reported: u64,
+        /// Sum of the gas used by all transactions up to and including this one.
+        expected: u64,
+    },
+    /// The gas used by the block doesn't match the cumulative gas used of the last receipt.
+    #[error("block gas used {reported}, but receipts report {expected}")]
+    BlockGasUsed {
+        /// Gas used reported by the block result.
+        reported: u64,
+        /// Cumulative gas used of the last receipt.
+        expected: u64,
+    },
+    /// The data availability footprint of the block exceeds the limit.
+    #[error("block DA footprint {used} exceeds the limit of {limit}")]
+    DaFootprint {
+        /// DA footprint of the block.
+        used: u64,
+        /// Maximum DA footprint of the block.
+        limit: u64,
+    },
+}
+
+/// Executes a block with the given transactions and checks the executor's gas accounting.
+///
+/// Transactions rejected with a [`BlockValidationError`](super::BlockValidationError) are skipped,
+/// any other error is returned as [`InvariantViolation::Execution`]. The following invariants are
+/// checked:
+/// - cumulative gas used is monotonic and matches the gas used by the executed transactions
+/// - the number of receipts equals the number of executed transactions
+/// - the block's gas used matches the cumulative gas used of the last receipt
+/// - the block's DA footprint (`da_footprint_used`) doesn't exceed `max_da_footprint`, if given
+pub fn check_invariants<E, T>(
+    mut executor: E,
+    transactions: impl IntoIterator<Item = T>,
+    max_da_footprint: Option<u64>,
+) -> Result<BlockExecutionResult<E::Receipt>, InvariantViolation>
+where
+    E: BlockExecutor<Receipt: TxReceipt>,
+    T: ExecutableTx<E>,
+{
+    executor.apply_pre_execution_changes()?;
+
+    let mut executed = 0;
+    let mut cumulative_gas_used = 0;
+    for tx in transactions {
+        match executor.execute_transaction(tx) {
+            Ok(gas_used) => {
+                executed += 1;
+                cumulative_gas_used += gas_used;
+            }
+            Err(err) if err.as_validation().is_some() => {}
+            Err(err) => return Err(err.into()),
+        }
+
+        let receipts = executor.receipts();
+        if receipts.len() != executed {
+            return Err(InvariantViolation::ReceiptCount { receipts: receipts.len(), executed });
+        }
+        if let Some(receipt) = receipts.last() {
+            if receipt.cumulative_gas_used() != cumulative_gas_used {
+                return Err(InvariantViolation::CumulativeGas {
+                    index: receipts.len() - 1,
+                    reported: receipt.cumulative_gas_used(),
+                    expected: cumulative_gas_used,
+                });
+            }
+        }
+    }
+
+    let result = executor.apply_post_execution_changes()?;
+
+    if result.receipts.len() != executed {
+        return Err(InvariantViolation::ReceiptCount { receipts: result.receipts.len(), executed });
+    }
+    if result.gas_used != cumulative_gas_used {
+        return Err(InvariantViolation::BlockGasUsed {
+            reported: result.gas_used,
+            expected: cumulative_gas_used,
+        });
+    }
+    if let Some(limit) = max_da_footprint {
+        if result.da_footprint_used > limit {
+            return Err(InvariantViolation::DaFootprint { used: result.da_footprint_used, limit });
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        eth::{
+            receipt_builder::AlloyReceiptBuilder, spec::EthSpec, EthBlockExecutionCtx,
+            EthBlockExecutor,
+        },
+        EthEvmFactory, EvmEnv, EvmFactory,
+    };
+    use alloc::borrow::Cow;
+    use alloy_consensus::Header;
+    use revm::{
+        database::{CacheDB, EmptyDB, State},
+        state::AccountInfo,
+    };
+
+    proptest! {
+        #[test]
+        fn eth_executor_invariants(txs in transaction_sequence(16)) {
+            let mut db = CacheDB::new(EmptyDB::default());
+            for sender in SENDERS {
+                db.insert_account_info(
+                    sender,
+                    AccountInfo { balance: SENDER_BALANCE, ..Default::default() },
+                );
+            }
+            let mut state = State::builder().with_database(db).build();
+
+            // Shanghai, so that no system contracts are required.
+            let header = Header {
+                number: 17_034_870,
+                timestamp: 1_681_338_455,
+                gas_limit: 30_000_000,
+                base_fee_per_gas: Some(1_000_000_000),
+                ..Default::default()
+            };
+            let evm_env = EvmEnv::for_eth_block(&header, EthSpec::mainnet(), 1, None);
+            let evm = EthEvmFactory.create_evm(&mut state, evm_env);
+            let ctx = EthBlockExecutionCtx {
+                parent_hash: header.parent_hash,
+                parent_beacon_block_root: None,
+                ommers: &[],
+                withdrawals: Some(Cow::Owned(Vec::new())),
+                extra_data: Bytes::new(),
+                tx_count_hint: Some(txs.len()),
+                blob_params: None,
+            };
+            let executor =
+                EthBlockExecutor::new(evm, ctx, EthSpec::mainnet(), AlloyReceiptBuilder);
+
+            check_invariants(executor, txs.iter(), None).unwrap();
+        }
+    }
+}
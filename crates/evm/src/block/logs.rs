@@ -0,0 +1,169 @@
+//! Log filtering and bloom indexing over execution results.
+//!
+//! Allows answering `eth_getLogs` style queries for blocks that were just executed and are not
+//! persisted yet. Filters are checked against the block bloom and the receipt blooms first, so
+//! only receipts that may contain matching logs are scanned.
+
+use super::BlockExecutionResult;
+use alloc::vec::Vec;
+use alloy_consensus::TxReceipt;
+use alloy_primitives::{Address, Bloom, BloomInput, Log, B256};
+
+/// A log together with its position in the block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexedLog<'a> {
+    /// The log.
+    pub log: &'a Log,
+    /// Index of the transaction that emitted the log.
+    pub tx_index: usize,
+    /// Index of the log in the block.
+    pub log_index: u64,
+}
+
+/// Returns the bloom of a block, combining the blooms of all receipts.
+pub fn block_bloom<R: TxReceipt<Log = Log>>(result: &BlockExecutionResult<R>) -> Bloom {
+    result.receipts.iter().fold(Bloom::ZERO, |bloom, receipt| bloom | receipt.bloom())
+}
+
+/// Returns all logs of the block with their block-wide log index.
+pub fn indexed_logs<R: TxReceipt<Log = Log>>(
+    result: &BlockExecutionResult<R>,
+) -> impl Iterator<Item = IndexedLog<'_>> {
+    result
+        .receipts
+        .iter()
+        .enumerate()
+        .flat_map(|(tx_index, receipt)| receipt.logs().iter().map(move |log| (tx_index, log)))
+        .enumerate()
+        .map(|(log_index, (tx_index, log))| IndexedLog {
+            log,
+            tx_index,
+            log_index: log_index as u64,
+        })
+}
+
+/// Filter on the emitter and topics of logs.
+///
+/// A log matches if it was emitted by one of the addresses and, for every topic position, its
+/// topic is one of the given topics. Empty sets match any value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogFilter {
+    /// Accepted emitters.
+    pub addresses: Vec<Address>,
+    /// Accepted topics by position.
+    pub topics: [Vec<B256>; 4],
+}
+
+impl LogFilter {
+    /// Creates a filter matching all logs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an accepted emitter.
+    pub fn address(mut self, address: Address) -> Self {
+        self.addresses.push(address);
+        self
+    }
+
+    /// Adds an accepted topic at the given position.
+    ///
+    /// # Panics
+    ///
+    /// If `position` is greater than 3.
+    pub fn topic(mut self, position: usize, topic: B256) -> Self {
+        self.topics[position].push(topic);
+        self
+    }
+
+    /// Returns `true` if the log matches the filter.
+    pub fn matches(&self, log: &Log) -> bool {
+        if !self.addresses.is_empty() && !self.addresses.contains(&log.address) {
+            return false;
+        }
+        self.topics.iter().enumerate().all(|(position, topics)| {
+            topics.is_empty() || log.topics().get(position).is_some_and(|t| topics.contains(t))
+        })
+    }
+
+    /// Returns `false` if the bloom proves that no log matches the filter.
+    pub fn matches_bloom(&self, bloom: &Bloom) -> bool {
+        let contains = |value: &[u8]| bloom.contains_input(BloomInput::Raw(value));
+        (self.addresses.is_empty() || self.addresses.iter().any(|a| contains(a.as_slice())))
+            && self.topics.iter().all(|topics| {
+                topics.is_empty() || topics.iter().any(|topic| contains(topic.as_slice()))
+            })
+    }
+}
+
+/// Returns the logs of the block matching the filter.
+pub fn filter_logs<'a, R: TxReceipt<Log = Log>>(
+    result: &'a BlockExecutionResult<R>,
+    filter: &LogFilter,
+) -> Vec<IndexedLog<'a>> {
+    if !filter.matches_bloom(&block_bloom(result)) {
+        return Vec::new();
+    }
+
+    let mut logs = Vec::new();
+    let mut log_index = 0;
+    for (tx_index, receipt) in result.receipts.iter().enumerate() {
+        let receipt_logs = receipt.logs();
+        if filter.matches_bloom(&receipt.bloom()) {
+            logs.extend(receipt_logs.iter().enumerate().filter_map(|(index, log)| {
+                filter.matches(log).then_some(IndexedLog {
+                    log,
+                    tx_index,
+                    log_index: log_index + index as u64,
+                })
+            }));
+        }
+        log_index += receipt_logs.len() as u64;
+    }
+    logs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::Receipt;
+    use alloy_primitives::{Bytes, LogData};
+
+    fn log(address: u8, topic: u8) -> Log {
+        Log {
+            address: Address::repeat_byte(address),
+            data: LogData::new_unchecked(alloc::vec![B256::repeat_byte(topic)], Bytes::new()),
+        }
+    }
+
+    #[test]
+    fn test_filter_logs() {
+        let receipts = alloc::vec![
+            Receipt { status: true.into(), cumulative_gas_used: 1, logs: alloc::vec![log(1, 1)] },
+            Receipt { status: true.into(), cumulative_gas_used: 2, logs: Vec::new() },
+            Receipt {
+                status: true.into(),
+                cumulative_gas_used: 3,
+                logs: alloc::vec![log(2, 1), log(1, 2)],
+            },
+        ];
+        let result = BlockExecutionResult { receipts, ..Default::default() };
+
+        let indices: Vec<_> =
+            indexed_logs(&result).map(|log| (log.tx_index, log.log_index)).collect();
+        assert_eq!(indices, [(0, 0), (2, 1), (2, 2)]);
+
+        let filter = LogFilter::new().address(Address::repeat_byte(1));
+        let logs = filter_logs(&result, &filter);
+        assert_eq!(logs.len(), 2);
+        assert_eq!((logs[1].tx_index, logs[1].log_index), (2, 2));
+
+        let filter = LogFilter::new().topic(0, B256::repeat_byte(1));
+        let logs = filter_logs(&result, &filter);
+        assert_eq!(logs.iter().map(|log| log.log_index).collect::<Vec<_>>(), [0, 1]);
+
+        let filter = LogFilter::new().address(Address::repeat_byte(3));
+        assert!(!filter.matches_bloom(&block_bloom(&result)));
+        assert!(filter_logs(&result, &filter).is_empty());
+    }
+}
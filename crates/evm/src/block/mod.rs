@@ -2,11 +2,12 @@
 
 use crate::{Evm, EvmFactory, FromRecoveredTx, FromTxWithEncoded, RecoveredTx, ToTxEnv};
 use alloc::{boxed::Box, vec::Vec};
-use alloy_consensus::transaction::Recovered;
+use alloy_consensus::{transaction::Recovered, Eip2718EncodableReceipt, TxReceipt};
 use alloy_eips::{eip2718::WithEncoded, eip7685::Requests};
 use revm::{
     context::result::{ExecutionResult, ResultAndState},
     context_interface::either::Either,
+    database::{states::bundle_state::BundleRetention, BundleState},
     inspector::NoOpInspector,
     Inspector,
 };
@@ -25,8 +26,70 @@ pub mod state_changes;
 pub mod state;
 pub use state::*;
 
+pub mod state_diff;
+pub use state_diff::BlockStateDiff;
+
+pub mod state_root;
+pub use state_root::{InMemoryStateRoot, StateRootProvider};
+
 pub mod calc;
 
+pub mod block_hashes;
+pub use block_hashes::{BlockHashCache, BlockHashOverlay, BlockHashReader};
+
+pub mod retry;
+pub use retry::RetryDatabase;
+
+pub mod profile;
+pub use profile::{ExecutionProfile, ExecutionProfiler, TxExecutionProfile};
+
+#[cfg(all(feature = "std", not(any(target_os = "zkvm", target_arch = "wasm32"))))]
+pub mod prewarm;
+
+pub mod prefetch;
+pub use prefetch::PrefetchHints;
+#[cfg(feature = "perf")]
+pub mod pool;
+#[cfg(feature = "perf")]
+pub use pool::ReceiptPool;
+
+pub mod stateless;
+pub use stateless::execute_block_stateless;
+
+pub mod audit;
+pub use audit::{AuditedExecutionResult, AuditingDatabase};
+
+pub mod builder;
+pub use builder::{BlockBuilder, BlockBuilderLimits, ExecutedBlock, OrderingPolicy};
+
+pub mod chain;
+pub use chain::{ChainExecutor, ChainExecutorError, ExecutableBlock};
+
+pub mod range;
+pub use range::{execute_range, RangeExecutionError, RangeExecutionOutput, RangeProgress};
+#[cfg(all(feature = "pipeline", not(any(target_os = "zkvm", target_arch = "wasm32"))))]
+pub use range::{execute_range_pipelined, PipelineBlock};
+
+pub mod replay;
+pub use replay::{replay_transaction, replay_transaction_with_inspector, ReplayError};
+
+pub mod logs;
+pub use logs::{block_bloom, filter_logs, indexed_logs, IndexedLog, LogFilter};
+
+pub mod ordering;
+pub use ordering::{
+    EffectiveTip, FifoOrdering, PriorityOrdering, SenderNonceOrdering, TransactionPriority,
+};
+
+pub mod fees;
+pub use fees::FeeTracker;
+
+pub mod verify;
+pub use verify::{BlockOutcomeMismatch, ExpectedBlockOutcome};
+
+#[cfg(feature = "test-utils")]
+pub mod invariants;
+
 /// The result of executing a block.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BlockExecutionResult<T> {
@@ -37,7 +100,15 @@ pub struct BlockExecutionResult<T> {
     /// The total gas used by the block.
     pub gas_used: u64,
     /// Blob gas used by the block.
+    ///
+    /// This is always the gas of the blobs of EIP-4844 transactions, see
+    /// [`Self::da_footprint_used`] for the DA footprint of OP blocks.
     pub blob_gas_used: u64,
+    /// The data availability footprint of the block since OP Jovian, zero otherwise.
+    ///
+    /// Since Jovian the header's `blob_gas_used` field commits to this value, see
+    /// [`Self::header_blob_gas_used`].
+    pub da_footprint_used: u64,
 }
 
 impl<T> Default for BlockExecutionResult<T> {
@@ -47,10 +118,33 @@ impl<T> Default for BlockExecutionResult<T> {
             requests: Default::default(),
             gas_used: 0,
             blob_gas_used: 0,
+            da_footprint_used: 0,
         }
     }
 }
 
+impl<T> BlockExecutionResult<T> {
+    /// Returns the value of the header's `blob_gas_used` field for this result.
+    ///
+    /// This is the DA footprint if `da_footprint_in_header` is set, as on OP chains since Jovian,
+    /// and the blob gas used otherwise.
+    pub const fn header_blob_gas_used(&self, da_footprint_in_header: bool) -> u64 {
+        if da_footprint_in_header {
+            self.da_footprint_used
+        } else {
+            self.blob_gas_used
+        }
+    }
+
+    /// Converts a result of an executor reporting the DA footprint in `blob_gas_used`, moving it to
+    /// [`Self::da_footprint_used`].
+    pub const fn with_blob_gas_used_as_da_footprint(mut self) -> Self {
+        self.da_footprint_used = self.blob_gas_used;
+        self.blob_gas_used = 0;
+        self
+    }
+}
+
 /// Helper trait to encapsulate requirements for a type to be used as input for [`BlockExecutor`].
 ///
 /// This trait combines the requirements for a transaction to be executable by a block executor:
@@ -261,6 +355,23 @@ pub trait BlockExecutor {
         self.execute_transaction_with_result_closure(tx, |_| ())
     }
 
+    /// Executes the transactions in order, stopping at the first failure.
+    ///
+    /// Returns the gas used by all transactions. Transactions whose encodings are held in a single
+    /// buffer, e.g. the RLP of a block body, can be passed via
+    /// [`with_encoded_slices`](crate::with_encoded_slices) to avoid allocating the encoding of
+    /// every transaction.
+    fn execute_transactions(
+        &mut self,
+        transactions: impl IntoIterator<Item = impl ExecutableTx<Self>>,
+    ) -> Result<u64, BlockExecutionError> {
+        let mut gas_used = 0;
+        for tx in transactions {
+            gas_used += self.execute_transaction(tx)?;
+        }
+        Ok(gas_used)
+    }
+
     /// Executes a single transaction and applies execution result to internal state. Invokes the
     /// given closure with an internal [`ExecutionResult`] produced by the EVM.
     ///
@@ -319,6 +430,38 @@ pub trait BlockExecutor {
         Ok(Some(gas_used))
     }
 
+    /// Executes and commits a single transaction with the given inspector.
+    ///
+    /// The inspector of the EVM is swapped for `inspector` and enabled for this transaction only,
+    /// so that e.g. a single transaction of a block can be traced: the preceding transactions are
+    /// executed without inspector via [`execute_transaction`](Self::execute_transaction), then the
+    /// target transaction is executed with the tracer.
+    ///
+    /// Returns the gas used by the transaction and the inspector after executing it. The previous
+    /// inspector is restored afterwards, enabled only if it was before, also if execution fails.
+    fn execute_transaction_with_inspector(
+        &mut self,
+        tx: impl ExecutableTx<Self>,
+        inspector: <Self::Evm as Evm>::Inspector,
+    ) -> Result<(u64, <Self::Evm as Evm>::Inspector), BlockExecutionError> {
+        let was_enabled = self.evm().is_inspector_enabled();
+        let previous = core::mem::replace(self.evm_mut().inspector_mut(), inspector);
+        self.evm_mut().enable_inspector();
+        let result = self.execute_transaction(tx);
+        self.evm_mut().set_inspector_enabled(was_enabled);
+        let inspector = core::mem::replace(self.evm_mut().inspector_mut(), previous);
+        result.map(|gas_used| (gas_used, inspector))
+    }
+
+    /// Loads the hinted accounts and storage slots into the state before executing transactions.
+    ///
+    /// See [`prefetch`](prefetch::prefetch).
+    fn prefetch(&mut self, hints: &PrefetchHints) -> Result<(), BlockExecutionError> {
+        prefetch::prefetch(self.evm_mut().db_mut(), hints)
+            .map_err(InternalBlockExecutionError::other)?;
+        Ok(())
+    }
+
     /// Executes a single transaction without committing state changes.
     ///
     /// This method performs the transaction execution through the EVM but does not
@@ -365,6 +508,63 @@ pub trait BlockExecutor {
         self.finish().map(|(_, result)| result)
     }
 
+    /// Invokes [`BlockExecutor::finish`], then merges all state transitions of the block and takes
+    /// the accumulated [`BundleState`] out of the EVM's database.
+    ///
+    /// The database must have been configured to track bundle updates, see [`BundleStateDB`].
+    fn finish_with_bundle(
+        self,
+    ) -> Result<(Self::Evm, BlockExecutionResult<Self::Receipt>, BundleState), BlockExecutionError>
+    where
+        Self: Sized,
+        <Self::Evm as Evm>::DB: BundleStateDB,
+    {
+        let (mut evm, result) = self.finish()?;
+        let db = evm.db_mut();
+        db.merge_transitions(BundleRetention::Reverts);
+        let bundle = db.take_bundle();
+        Ok((evm, result, bundle))
+    }
+
+    /// Invokes [`BlockExecutor::finish`] and embeds the commitment of the EVM's
+    /// [`AuditingDatabase`] into the result.
+    fn finish_audited<DB>(
+        self,
+    ) -> Result<(Self::Evm, AuditedExecutionResult<Self::Receipt>), BlockExecutionError>
+    where
+        Self: Sized,
+        Self::Evm: Evm<DB = AuditingDatabase<DB>>,
+    {
+        let (evm, result) = self.finish()?;
+        let db = evm.db();
+        let result = AuditedExecutionResult {
+            result,
+            commitment: db.commitment(),
+            operations: db.operations(),
+        };
+        Ok((evm, result))
+    }
+
+    /// Invokes [`BlockExecutor::finish`] and verifies the result against the expected outcome,
+    /// usually taken from the block header.
+    ///
+    /// Returns [`BlockValidationError::OutcomeMismatch`] listing all divergent fields if the
+    /// outcome differs.
+    fn finish_verified(
+        self,
+        expected: &ExpectedBlockOutcome<'_, Self::Receipt>,
+    ) -> Result<(Self::Evm, BlockExecutionResult<Self::Receipt>), BlockExecutionError>
+    where
+        Self: Sized,
+        Self::Receipt: Eip2718EncodableReceipt + TxReceipt + PartialEq,
+    {
+        let (evm, result) = self.finish()?;
+        expected
+            .verify(&result)
+            .map_err(|mismatch| BlockValidationError::OutcomeMismatch(Box::new(mismatch)))?;
+        Ok((evm, result))
+    }
+
     /// Sets a hook to be called after each state change during execution.
     fn set_state_hook(&mut self, hook: Option<Box<dyn OnStateHook>>);
 
@@ -416,11 +616,7 @@ pub trait BlockExecutor {
         Self: Sized,
     {
         self.apply_pre_execution_changes()?;
-
-        for tx in transactions {
-            self.execute_transaction(tx)?;
-        }
-
+        self.execute_transactions(transactions)?;
         self.apply_post_execution_changes()
     }
 }
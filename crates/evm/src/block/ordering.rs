@@ -0,0 +1,364 @@
+//! [`OrderingPolicy`] implementations for the [`BlockBuilder`](super::BlockBuilder).
+//!
+//! - [`FifoOrdering`] tries transactions in arrival order.
+//! - [`PriorityOrdering`] tries transactions by descending priority, ignoring their senders.
+//! - [`SenderNonceOrdering`] tries the highest priority transaction among the lowest nonce
+//!   transactions of each sender, so that transactions of a sender are included in nonce order.
+//!
+//! The priority is computed by a [`TransactionPriority`], [`EffectiveTip`] orders by the tip paid
+//! to the beneficiary. Chains with custom ordering rules, e.g. rollup sequencers prioritizing
+//! certain transaction types, can implement [`TransactionPriority`] or [`OrderingPolicy`] directly.
+
+use super::builder::{OrderingPolicy, SkipReason};
+use alloc::{
+    collections::{BinaryHeap, VecDeque},
+    vec::Vec,
+};
+use alloy_consensus::{transaction::Recovered, Transaction};
+use alloy_primitives::{map::AddressMap, Address};
+use core::cmp::{Ordering, Reverse};
+
+/// Computes the priority of a transaction, higher priorities are tried first.
+pub trait TransactionPriority<T> {
+    /// The priority of a transaction.
+    type Priority: Ord;
+
+    /// Returns the priority of the transaction, or `None` if it can't be included at all.
+    fn priority(&self, tx: &Recovered<T>) -> Option<Self::Priority>;
+}
+
+/// Prioritizes transactions by the tip per gas they pay to the beneficiary at the given base fee.
+///
+/// Transactions with a max fee per gas below the base fee are excluded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EffectiveTip {
+    /// Base fee of the block being built.
+    pub base_fee: u64,
+}
+
+impl EffectiveTip {
+    /// Creates a new priority for the given base fee.
+    pub const fn new(base_fee: u64) -> Self {
+        Self { base_fee }
+    }
+}
+
+impl<T: Transaction> TransactionPriority<T> for EffectiveTip {
+    type Priority = u128;
+
+    fn priority(&self, tx: &Recovered<T>) -> Option<u128> {
+        tx.effective_tip_per_gas(self.base_fee)
+    }
+}
+
+/// An [`OrderingPolicy`] trying transactions in the order they were inserted.
+#[derive(Debug, Clone)]
+pub struct FifoOrdering<T> {
+    queue: VecDeque<Recovered<T>>,
+}
+
+impl<T> Default for FifoOrdering<T> {
+    fn default() -> Self {
+        Self { queue: VecDeque::new() }
+    }
+}
+
+impl<T> FifoOrdering<T> {
+    /// Appends a transaction to the queue.
+    pub fn insert(&mut self, tx: Recovered<T>) {
+        self.queue.push_back(tx);
+    }
+
+    /// Returns the number of queued transactions.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns `true` if no transactions are queued.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+impl<T> FromIterator<Recovered<T>> for FifoOrdering<T> {
+    fn from_iter<I: IntoIterator<Item = Recovered<T>>>(iter: I) -> Self {
+        Self { queue: iter.into_iter().collect() }
+    }
+}
+
+impl<T> OrderingPolicy<T> for FifoOrdering<T> {
+    fn next_transaction(&mut self) -> Option<Recovered<T>> {
+        self.queue.pop_front()
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        Some(self.queue.len())
+    }
+}
+
+/// A transaction in a priority queue.
+///
+/// Ordered by priority, ties are broken by insertion order.
+#[derive(Debug, Clone)]
+struct Prioritized<P, T> {
+    priority: P,
+    id: u64,
+    tx: Recovered<T>,
+}
+
+impl<P: Ord, T> Prioritized<P, T> {
+    fn key(&self) -> (&P, Reverse<u64>) {
+        (&self.priority, Reverse(self.id))
+    }
+}
+
+impl<P: Ord, T> PartialEq for Prioritized<P, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl<P: Ord, T> Eq for Prioritized<P, T> {}
+
+impl<P: Ord, T> PartialOrd for Prioritized<P, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<P: Ord, T> Ord for Prioritized<P, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/// An [`OrderingPolicy`] trying transactions by descending priority.
+///
+/// Senders are not taken into account, so this is only suitable if each sender has at most one
+/// pending transaction, otherwise use [`SenderNonceOrdering`].
+#[derive(Debug, Clone)]
+pub struct PriorityOrdering<T, P: TransactionPriority<T>> {
+    priority: P,
+    queue: BinaryHeap<Prioritized<P::Priority, T>>,
+    next_id: u64,
+}
+
+impl<T, P: TransactionPriority<T>> PriorityOrdering<T, P> {
+    /// Creates an empty queue ordered by the given priority.
+    pub const fn new(priority: P) -> Self {
+        Self { priority, queue: BinaryHeap::new(), next_id: 0 }
+    }
+
+    /// Inserts a transaction, returning `false` if it was discarded because it has no priority.
+    pub fn insert(&mut self, tx: Recovered<T>) -> bool {
+        let Some(priority) = self.priority.priority(&tx) else { return false };
+        self.queue.push(Prioritized { priority, id: self.next_id, tx });
+        self.next_id += 1;
+        true
+    }
+
+    /// Inserts all transactions of the iterator.
+    pub fn extend(&mut self, transactions: impl IntoIterator<Item = Recovered<T>>) {
+        for tx in transactions {
+            self.insert(tx);
+        }
+    }
+
+    /// Returns the number of queued transactions.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns `true` if no transactions are queued.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+impl<T, P: TransactionPriority<T>> OrderingPolicy<T> for PriorityOrdering<T, P> {
+    fn next_transaction(&mut self) -> Option<Recovered<T>> {
+        self.queue.pop().map(|entry| entry.tx)
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        Some(self.queue.len())
+    }
+}
+
+/// An [`OrderingPolicy`] trying transactions by descending priority while keeping the
+/// transactions of each sender in nonce order.
+///
+/// Only the lowest nonce transaction of each sender competes for inclusion, its successor becomes
+/// eligible once it was included. If a transaction is skipped, all remaining transactions of its
+/// sender are dropped because their nonces can no longer be valid.
+#[derive(Debug, Clone)]
+pub struct SenderNonceOrdering<T, P: TransactionPriority<T>> {
+    priority: P,
+    /// Transactions of each sender not yet eligible, sorted by nonce.
+    senders: AddressMap<VecDeque<Recovered<T>>>,
+    /// The lowest nonce transaction of each sender.
+    queue: BinaryHeap<Prioritized<P::Priority, T>>,
+    next_id: u64,
+    /// Sender of the last returned transaction, whose successor is promoted on the next call.
+    last_sender: Option<Address>,
+}
+
+impl<T: Transaction, P: TransactionPriority<T>> SenderNonceOrdering<T, P> {
+    /// Creates a queue from the given transactions, ordered by the given priority.
+    ///
+    /// Transactions of equal priority are ordered by the first appearance of their sender.
+    pub fn new(priority: P, transactions: impl IntoIterator<Item = Recovered<T>>) -> Self {
+        let mut senders = AddressMap::<VecDeque<Recovered<T>>>::default();
+        let mut order = Vec::new();
+        for tx in transactions {
+            let txs = senders.entry(tx.signer()).or_default();
+            if txs.is_empty() {
+                order.push(tx.signer());
+            }
+            txs.push_back(tx);
+        }
+
+        let mut this = Self {
+            priority,
+            senders: AddressMap::default(),
+            queue: BinaryHeap::new(),
+            next_id: 0,
+            last_sender: None,
+        };
+        for sender in order {
+            let Some(mut txs) = senders.remove(&sender) else { continue };
+            txs.make_contiguous().sort_by_key(|tx| tx.nonce());
+            this.senders.insert(sender, txs);
+            this.promote(sender);
+        }
+        this
+    }
+
+    /// Moves the next transaction of the sender into the queue.
+    ///
+    /// Drops all transactions of the sender if the next one has no priority.
+    fn promote(&mut self, sender: Address) {
+        let Some(txs) = self.senders.get_mut(&sender) else { return };
+        let Some(tx) = txs.pop_front() else {
+            self.senders.remove(&sender);
+            return;
+        };
+        match self.priority.priority(&tx) {
+            Some(priority) => {
+                self.queue.push(Prioritized { priority, id: self.next_id, tx });
+                self.next_id += 1;
+            }
+            None => {
+                self.senders.remove(&sender);
+            }
+        }
+    }
+}
+
+impl<T: Transaction, P: TransactionPriority<T>> OrderingPolicy<T> for SenderNonceOrdering<T, P> {
+    fn next_transaction(&mut self) -> Option<Recovered<T>> {
+        if let Some(sender) = self.last_sender.take() {
+            self.promote(sender);
+        }
+        let tx = self.queue.pop()?.tx;
+        self.last_sender = Some(tx.signer());
+        Some(tx)
+    }
+
+    fn on_skipped(&mut self, tx: &Recovered<T>, _reason: &SkipReason) {
+        let sender = tx.signer();
+        self.senders.remove(&sender);
+        if self.last_sender == Some(sender) {
+            self.last_sender = None;
+        }
+    }
+
+    /// Counts the queued transactions of all senders, including those dropped later because
+    /// they have no priority.
+    fn remaining(&self) -> Option<usize> {
+        Some(self.queue.len() + self.senders.values().map(VecDeque::len).sum::<usize>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::{SignableTransaction, TxEip1559, TxEnvelope};
+    use alloy_primitives::Signature;
+
+    fn tx(sender: u8, nonce: u64, tip: u128) -> Recovered<TxEnvelope> {
+        let tx = TxEip1559 {
+            nonce,
+            max_fee_per_gas: 100 + tip,
+            max_priority_fee_per_gas: tip,
+            ..Default::default()
+        };
+        Recovered::new_unchecked(
+            tx.into_signed(Signature::test_signature()).into(),
+            Address::repeat_byte(sender),
+        )
+    }
+
+    fn drain(policy: &mut impl OrderingPolicy<TxEnvelope>) -> Vec<(u8, u64)> {
+        core::iter::from_fn(|| policy.next_transaction())
+            .map(|tx| (tx.signer().0[0], tx.nonce()))
+            .collect()
+    }
+
+    #[test]
+    fn test_fifo() {
+        let mut policy: FifoOrdering<_> =
+            [tx(1, 0, 1), tx(2, 0, 5), tx(1, 1, 9)].into_iter().collect();
+        assert_eq!(drain(&mut policy), [(1, 0), (2, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn test_priority() {
+        let mut policy = PriorityOrdering::new(EffectiveTip::new(100));
+        policy.extend([tx(1, 0, 1), tx(2, 0, 5), tx(3, 0, 5), tx(4, 0, 9)]);
+        assert_eq!(drain(&mut policy), [(4, 0), (2, 0), (3, 0), (1, 0)]);
+
+        // max fee below the base fee
+        assert!(!PriorityOrdering::new(EffectiveTip::new(1_000)).insert(tx(1, 0, 1)));
+    }
+
+    #[test]
+    fn test_sender_nonce() {
+        let mut policy = SenderNonceOrdering::new(
+            EffectiveTip::new(100),
+            [tx(1, 1, 9), tx(1, 0, 1), tx(2, 0, 5), tx(2, 1, 3)],
+        );
+        assert_eq!(drain(&mut policy), [(2, 0), (2, 1), (1, 0), (1, 1)]);
+
+        let mut policy = SenderNonceOrdering::new(
+            EffectiveTip::new(100),
+            [tx(1, 0, 9), tx(1, 1, 9), tx(2, 0, 5)],
+        );
+        let skipped = policy.next_transaction().unwrap();
+        policy.on_skipped(&skipped, &SkipReason::GasLimit { gas_limit: 1, available: 0 });
+        assert_eq!(drain(&mut policy), [(2, 0)]);
+    }
+
+    #[test]
+    fn test_sender_nonce_ties_in_input_order() {
+        let txs = [tx(3, 0, 5), tx(1, 0, 5), tx(4, 0, 5), tx(2, 0, 5), tx(1, 1, 5)];
+        let mut policy = SenderNonceOrdering::new(EffectiveTip::new(100), txs.clone());
+        assert_eq!(drain(&mut policy), [(3, 0), (1, 0), (4, 0), (2, 0), (1, 1)]);
+
+        let mut policy = SenderNonceOrdering::new(EffectiveTip::new(100), txs.into_iter().rev());
+        assert_eq!(drain(&mut policy), [(1, 0), (2, 0), (4, 0), (3, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn test_remaining() {
+        let txs = [tx(1, 0, 5), tx(1, 1, 5), tx(2, 0, 5)];
+        let mut policy = SenderNonceOrdering::new(EffectiveTip::new(100), txs.clone());
+        assert_eq!(policy.remaining(), Some(3));
+        policy.next_transaction();
+        assert_eq!(policy.remaining(), Some(2));
+
+        let mut policy: FifoOrdering<_> = txs.into_iter().collect();
+        policy.next_transaction();
+        assert_eq!(policy.remaining(), Some(2));
+    }
+}
@@ -0,0 +1,98 @@
+//! Reuse of receipt buffers across blocks.
+//!
+//! Re-executing long ranges of blocks, e.g. for an archive node, allocates and grows a receipts
+//! buffer for every block, only to drop it once the receipts root has been computed. A
+//! [`ReceiptPool`] keeps these buffers around, so that executors created with
+//! [`EthBlockExecutor::with_receipts_buffer`](crate::eth::EthBlockExecutor::with_receipts_buffer)
+//! start with enough capacity for the whole block.
+//!
+//! Only the receipts buffer itself is pooled. The logs of a receipt are moved out of the execution
+//! result produced by revm, so their vectors are allocated by the interpreter and can't be taken
+//! from a pool without copying every log.
+
+use alloc::vec::Vec;
+
+/// A pool of receipt buffers.
+#[derive(Debug, Clone)]
+pub struct ReceiptPool<R> {
+    buffers: Vec<Vec<R>>,
+    max_buffers: usize,
+}
+
+impl<R> Default for ReceiptPool<R> {
+    fn default() -> Self {
+        Self::new(4)
+    }
+}
+
+impl<R> ReceiptPool<R> {
+    /// Creates a pool keeping at most `max_buffers` buffers.
+    pub const fn new(max_buffers: usize) -> Self {
+        Self { buffers: Vec::new(), max_buffers }
+    }
+
+    /// Returns an empty buffer with a capacity of at least `capacity`.
+    ///
+    /// Reuses the largest pooled buffer if there is one.
+    pub fn take(&mut self, capacity: usize) -> Vec<R> {
+        let mut buffer = self.buffers.pop().unwrap_or_default();
+        buffer.reserve(capacity);
+        buffer
+    }
+
+    /// Returns the buffer to the pool, dropping the receipts in it.
+    pub fn recycle(&mut self, mut buffer: Vec<R>) {
+        buffer.clear();
+        if buffer.capacity() == 0 {
+            return;
+        }
+        if self.buffers.len() < self.max_buffers {
+            self.buffers.push(buffer);
+        } else if let Some(smallest) = self.buffers.first_mut() {
+            if smallest.capacity() < buffer.capacity() {
+                *smallest = buffer;
+            }
+        }
+        self.buffers.sort_unstable_by_key(Vec::capacity);
+    }
+
+    /// Returns the number of pooled buffers.
+    pub fn len(&self) -> usize {
+        self.buffers.len()
+    }
+
+    /// Returns `true` if no buffers are pooled.
+    pub fn is_empty(&self) -> bool {
+        self.buffers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_receipt_pool() {
+        let mut pool = ReceiptPool::new(2);
+        let mut buffer = pool.take(10);
+        assert!(buffer.capacity() >= 10);
+        buffer.extend(0..100u64);
+        let ptr = buffer.as_ptr();
+        pool.recycle(buffer);
+        assert_eq!(pool.len(), 1);
+
+        let buffer = pool.take(10);
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.as_ptr(), ptr);
+
+        pool.recycle(Vec::with_capacity(1));
+        pool.recycle(Vec::with_capacity(2));
+        pool.recycle(Vec::with_capacity(3));
+        pool.recycle(Vec::new());
+        assert_eq!(pool.len(), 2);
+        // the largest buffers are kept and handed out first
+        assert!(pool.take(0).capacity() >= 3);
+        assert!(pool.take(0).capacity() >= 2);
+        assert!(pool.is_empty());
+    }
+}
@@ -0,0 +1,229 @@
+//! Batch loading of state that is known to be accessed.
+//!
+//! For transactions with an EIP-2930 access list, or calls that repeatedly hit the same contracts,
+//! the accessed accounts and storage slots are known upfront. Loading them before execution, e.g.
+//! into the cache of a [`State`], replaces the sequential database round trips made by the
+//! interpreter with a single batch that can be loaded in parallel.
+
+use alloc::vec::Vec;
+use alloy_eips::eip2930::AccessList;
+use alloy_primitives::{map::AddressMap, Address, U256};
+use revm::Database;
+#[cfg(all(feature = "std", not(any(target_os = "zkvm", target_arch = "wasm32"))))]
+use {
+    core::num::NonZeroUsize,
+    revm::{database::State, state::AccountInfo, DatabaseRef},
+};
+
+/// Accounts and storage slots to load before execution.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrefetchHints {
+    accounts: AddressMap<Vec<U256>>,
+}
+
+impl PrefetchHints {
+    /// Creates empty hints.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an account to load.
+    pub fn add_account(&mut self, address: Address) {
+        self.accounts.entry(address).or_default();
+    }
+
+    /// Adds storage slots of an account to load, the account itself is loaded as well.
+    pub fn add_slots(&mut self, address: Address, slots: impl IntoIterator<Item = U256>) {
+        let entry = self.accounts.entry(address).or_default();
+        for slot in slots {
+            if !entry.contains(&slot) {
+                entry.push(slot);
+            }
+        }
+    }
+
+    /// Adds all accounts and storage slots of an access list.
+    pub fn add_access_list(&mut self, access_list: &AccessList) {
+        for item in access_list.iter() {
+            self.add_slots(item.address, item.storage_keys.iter().map(|key| (*key).into()));
+        }
+    }
+
+    /// Returns the number of accounts to load.
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    /// Returns `true` if there is nothing to load.
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// Returns the accounts to load together with their storage slots.
+    pub fn iter(&self) -> impl Iterator<Item = (Address, &[U256])> {
+        self.accounts.iter().map(|(address, slots)| (*address, slots.as_slice()))
+    }
+}
+
+impl From<&AccessList> for PrefetchHints {
+    fn from(access_list: &AccessList) -> Self {
+        let mut hints = Self::new();
+        hints.add_access_list(access_list);
+        hints
+    }
+}
+
+/// Loads the hinted accounts, their bytecode and storage slots from the database.
+///
+/// This is useful for databases with a cache layer such as [`State`](revm::database::State).
+/// Call it on [`Evm::db_mut`](crate::Evm::db_mut) before `transact`, or use
+/// [`BlockExecutor::prefetch`](super::BlockExecutor::prefetch) before executing transactions of
+/// a block.
+pub fn prefetch<DB: Database>(db: &mut DB, hints: &PrefetchHints) -> Result<(), DB::Error> {
+    for (address, slots) in hints.iter() {
+        if let Some(info) = db.basic(address)? {
+            if info.code.is_none() && !info.is_empty_code_hash() {
+                db.code_by_hash(info.code_hash)?;
+            }
+        }
+        for slot in slots {
+            db.storage(address, *slot)?;
+        }
+    }
+    Ok(())
+}
+
+/// Loads the hinted state with up to `workers` threads and inserts it into the cache of `state`.
+///
+/// Accounts that are already cached are skipped, so that changes made to them are kept.
+#[cfg(all(feature = "std", not(any(target_os = "zkvm", target_arch = "wasm32"))))]
+pub fn prefetch_parallel<DB>(
+    state: &mut State<DB>,
+    hints: &PrefetchHints,
+    workers: NonZeroUsize,
+) -> Result<(), <DB as DatabaseRef>::Error>
+where
+    DB: Database + DatabaseRef + Sync,
+{
+    let pending: Vec<_> =
+        hints.iter().filter(|(address, _)| !state.cache.accounts.contains_key(address)).collect();
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let db = &state.database;
+    let chunk_size = pending.len().div_ceil(workers.get());
+    let loaded = std::thread::scope(|scope| {
+        let handles: Vec<_> = pending
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|(address, slots)| load_account(db, *address, slots))
+                        .collect::<Result<Vec<_>, _>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap_or_else(|err| std::panic::resume_unwind(err)))
+            .collect::<Result<Vec<_>, _>>()
+    })?;
+
+    for account in loaded.into_iter().flatten() {
+        let Some(info) = account.info else {
+            state.cache.insert_not_existing(account.address);
+            continue;
+        };
+        if let Some(code) = &info.code {
+            state.cache.contracts.insert(info.code_hash, code.clone());
+        }
+        state.cache.insert_account_with_storage(
+            account.address,
+            info,
+            account.storage.into_iter().collect(),
+        );
+    }
+    Ok(())
+}
+
+/// An account loaded by [`prefetch_parallel`].
+#[cfg(all(feature = "std", not(any(target_os = "zkvm", target_arch = "wasm32"))))]
+struct LoadedAccount {
+    address: Address,
+    info: Option<AccountInfo>,
+    storage: Vec<(U256, U256)>,
+}
+
+/// Loads an account with its bytecode and the given storage slots.
+#[cfg(all(feature = "std", not(any(target_os = "zkvm", target_arch = "wasm32"))))]
+fn load_account<DB: DatabaseRef>(
+    db: &DB,
+    address: Address,
+    slots: &[U256],
+) -> Result<LoadedAccount, DB::Error> {
+    let mut info = db.basic_ref(address)?;
+    if let Some(info) = &mut info {
+        if info.code.is_none() && !info.is_empty_code_hash() {
+            info.code = Some(db.code_by_hash_ref(info.code_hash)?);
+        }
+    }
+    let storage = match info {
+        Some(_) => slots
+            .iter()
+            .map(|slot| Ok((*slot, db.storage_ref(address, *slot)?)))
+            .collect::<Result<_, _>>()?,
+        None => Vec::new(),
+    };
+    Ok(LoadedAccount { address, info, storage })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_eips::eip2930::AccessListItem;
+    use alloy_primitives::B256;
+    use revm::{
+        database::{CacheDB, EmptyDB, State},
+        state::AccountInfo,
+    };
+
+    fn db() -> CacheDB<EmptyDB> {
+        let mut db = CacheDB::new(EmptyDB::default());
+        let info = AccountInfo { nonce: 1, ..Default::default() };
+        db.insert_account_info(Address::repeat_byte(0x01), info);
+        db.insert_account_storage(Address::repeat_byte(0x01), U256::from(1), U256::from(2))
+            .unwrap();
+        db
+    }
+
+    #[test]
+    fn test_prefetch() {
+        let access_list = AccessList(alloc::vec![
+            AccessListItem {
+                address: Address::repeat_byte(0x01),
+                storage_keys: alloc::vec![B256::with_last_byte(1), B256::with_last_byte(1)],
+            },
+            AccessListItem { address: Address::repeat_byte(0x02), storage_keys: Vec::new() },
+        ]);
+        let hints = PrefetchHints::from(&access_list);
+        assert_eq!(hints.len(), 2);
+
+        let mut state = State::builder().with_database(db()).build();
+        prefetch(&mut state, &hints).unwrap();
+        assert!(state.cache.accounts.contains_key(&Address::repeat_byte(0x01)));
+        assert!(state.cache.accounts.contains_key(&Address::repeat_byte(0x02)));
+
+        #[cfg(all(feature = "std", not(any(target_os = "zkvm", target_arch = "wasm32"))))]
+        {
+            let mut state = State::builder().with_database(db()).build();
+            prefetch_parallel(&mut state, &hints, NonZeroUsize::new(2).unwrap()).unwrap();
+            assert_eq!(
+                state.storage(Address::repeat_byte(0x01), U256::from(1)).unwrap(),
+                U256::from(2)
+            );
+            assert!(state.cache.accounts.contains_key(&Address::repeat_byte(0x02)));
+        }
+    }
+}
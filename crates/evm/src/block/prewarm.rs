@@ -0,0 +1,195 @@
+//! Speculative state prewarming.
+//!
+//! Prewarming executes a block's transactions ahead of the canonical, sequential execution against
+//! read-only views of the state. All results are discarded: the only purpose of the run is to pull
+//! accounts, storage and bytecode into whatever cache layer sits behind the provided
+//! [`DatabaseRef`], so that the canonical execution hits warm caches.
+
+use crate::{Evm, EvmEnv, EvmFactory, IntoTxEnv};
+use alloc::vec::Vec;
+use core::{fmt::Debug, num::NonZeroUsize};
+use revm::{database::CacheDB, DatabaseRef};
+
+/// Outcome of a prewarming run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PrewarmOutcome {
+    /// Number of transactions that were executed without an EVM error.
+    pub executed: usize,
+    /// Number of transactions that failed to execute.
+    ///
+    /// Failures are expected: a transaction may depend on state changes of transactions that were
+    /// assigned to a different worker.
+    pub failed: usize,
+}
+
+impl PrewarmOutcome {
+    const fn merge(self, other: Self) -> Self {
+        Self { executed: self.executed + other.executed, failed: self.failed + other.failed }
+    }
+}
+
+/// Speculatively executes the given transactions in parallel to warm up the caches of `db`.
+///
+/// Transactions are split into up to `workers` contiguous chunks. Every worker executes its chunk
+/// sequentially on top of its own [`CacheDB`] layer, so changes made by a transaction are visible
+/// to the following transactions of the same chunk, but never written to `db`.
+///
+/// This is a best-effort operation, EVM errors are only counted in the returned
+/// [`PrewarmOutcome`].
+pub fn prewarm_transactions<F, DB, T>(
+    evm_factory: &F,
+    db: &DB,
+    evm_env: &EvmEnv<F::Spec, F::BlockEnv>,
+    transactions: impl IntoIterator<Item = T>,
+    workers: NonZeroUsize,
+) -> PrewarmOutcome
+where
+    F: EvmFactory + Sync,
+    F::Tx: Send,
+    DB: DatabaseRef<Error: core::error::Error + Send + Sync + 'static> + Debug + Sync,
+    T: IntoTxEnv<F::Tx>,
+{
+    let mut remaining: Vec<F::Tx> = transactions.into_iter().map(IntoTxEnv::into_tx_env).collect();
+    if remaining.is_empty() {
+        return PrewarmOutcome::default();
+    }
+
+    let chunk_size = remaining.len().div_ceil(workers.get());
+    let mut chunks = Vec::with_capacity(workers.get());
+    while !remaining.is_empty() {
+        let rest = remaining.split_off(chunk_size.min(remaining.len()));
+        chunks.push(core::mem::replace(&mut remaining, rest));
+    }
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = chunks
+            .into_iter()
+            .map(|chunk| {
+                let evm_env = evm_env.clone();
+                scope.spawn(move || prewarm_chunk(evm_factory, db, evm_env, chunk))
+            })
+            .collect();
+
+        handles.into_iter().fold(PrewarmOutcome::default(), |outcome, handle| {
+            outcome.merge(handle.join().unwrap_or_else(|err| std::panic::resume_unwind(err)))
+        })
+    })
+}
+
+/// Executes the given transactions sequentially on top of a fresh [`CacheDB`] layer.
+fn prewarm_chunk<F, DB>(
+    evm_factory: &F,
+    db: &DB,
+    evm_env: EvmEnv<F::Spec, F::BlockEnv>,
+    transactions: Vec<F::Tx>,
+) -> PrewarmOutcome
+where
+    F: EvmFactory,
+    DB: DatabaseRef<Error: core::error::Error + Send + Sync + 'static> + Debug,
+{
+    let _span = tracing::trace_span!("prewarm_chunk", txs = transactions.len()).entered();
+    let mut evm = evm_factory.create_evm(CacheDB::new(db), evm_env);
+
+    let mut outcome = PrewarmOutcome::default();
+    for tx in transactions {
+        match evm.transact_commit(tx) {
+            Ok(_) => outcome.executed += 1,
+            Err(_) => outcome.failed += 1,
+        }
+    }
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EthEvmFactory;
+    use alloy_primitives::{Address, TxKind, B256, U256};
+    use revm::{
+        bytecode::Bytecode,
+        context::TxEnv,
+        database::EmptyDB,
+        primitives::{StorageKey, StorageValue},
+        state::AccountInfo,
+    };
+    use std::{collections::HashSet, sync::Mutex};
+
+    /// A database recording the accounts that were loaded from it.
+    #[derive(Debug, Default)]
+    struct RecordingDb {
+        inner: CacheDB<EmptyDB>,
+        loaded: Mutex<HashSet<Address>>,
+    }
+
+    impl DatabaseRef for RecordingDb {
+        type Error = core::convert::Infallible;
+
+        fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+            self.loaded.lock().unwrap().insert(address);
+            self.inner.basic_ref(address)
+        }
+
+        fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+            self.inner.code_by_hash_ref(code_hash)
+        }
+
+        fn storage_ref(
+            &self,
+            address: Address,
+            index: StorageKey,
+        ) -> Result<StorageValue, Self::Error> {
+            self.inner.storage_ref(address, index)
+        }
+
+        fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+            self.inner.block_hash_ref(number)
+        }
+    }
+
+    #[test]
+    fn test_prewarm_transactions() {
+        let mut db = RecordingDb::default();
+        let transactions: Vec<TxEnv> = (1..=4u8)
+            .map(|i| {
+                let caller = Address::repeat_byte(i);
+                db.inner.insert_account_info(
+                    caller,
+                    AccountInfo { balance: U256::from(1), ..Default::default() },
+                );
+                TxEnv {
+                    caller,
+                    gas_limit: 21_000,
+                    kind: TxKind::Call(Address::repeat_byte(0x10 + i)),
+                    value: U256::from(1),
+                    ..Default::default()
+                }
+            })
+            .collect();
+        // fails due to its nonce, but still loads its accounts
+        let invalid = TxEnv { nonce: 1, ..transactions[0].clone() };
+
+        let outcome = prewarm_transactions(
+            &EthEvmFactory::default(),
+            &db,
+            &EvmEnv::default(),
+            transactions.iter().cloned().chain([invalid]),
+            NonZeroUsize::new(2).unwrap(),
+        );
+        assert_eq!(outcome, PrewarmOutcome { executed: 4, failed: 1 });
+
+        // all callers and recipients were loaded
+        let loaded = db.loaded.lock().unwrap();
+        for tx in &transactions {
+            assert!(loaded.contains(&tx.caller));
+            assert!(loaded.contains(&tx.kind.to().copied().unwrap()));
+        }
+        drop(loaded);
+
+        // nothing was written to the database
+        for tx in &transactions {
+            let caller = db.inner.basic_ref(tx.caller).unwrap().unwrap();
+            assert_eq!((caller.nonce, caller.balance), (0, U256::from(1)));
+        }
+        assert!(db.inner.basic_ref(Address::repeat_byte(0x11)).unwrap().is_none());
+    }
+}
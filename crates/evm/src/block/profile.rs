@@ -0,0 +1,142 @@
+//! Per-transaction resource accounting.
+
+use alloc::vec::Vec;
+use alloy_primitives::Address;
+use revm::{context::result::ExecutionResult, state::EvmState};
+
+#[cfg(feature = "std")]
+use crate::time::Instant;
+#[cfg(feature = "std")]
+use core::time::Duration;
+
+/// Resources consumed by a single transaction.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TxExecutionProfile {
+    /// Index of the transaction in the block.
+    pub index: usize,
+    /// Gas used by the transaction, after refunds.
+    pub gas_used: u64,
+    /// Gas refunded to the sender.
+    pub gas_refunded: u64,
+    /// Data availability footprint of the transaction.
+    ///
+    /// For Ethereum this is the blob gas used by the transaction.
+    pub da_footprint: u64,
+    /// Number of distinct storage slots that were read but not modified.
+    pub storage_reads: usize,
+    /// Number of distinct storage slots that were modified.
+    pub storage_writes: usize,
+    /// Addresses of the contracts created by the transaction.
+    pub created_contracts: Vec<Address>,
+    /// Wall time spent executing and committing the transaction.
+    #[cfg(feature = "std")]
+    pub elapsed: Duration,
+}
+
+impl TxExecutionProfile {
+    /// Creates the profile of a transaction from its execution result and state changes.
+    ///
+    /// Storage accesses are derived from the touched state, so slots accessed multiple times are
+    /// only counted once.
+    pub fn new<H>(
+        index: usize,
+        result: &ExecutionResult<H>,
+        state: &EvmState,
+        da_footprint: u64,
+    ) -> Self {
+        let gas_refunded = match result {
+            ExecutionResult::Success { gas_refunded, .. } => *gas_refunded,
+            _ => 0,
+        };
+
+        let mut profile = Self {
+            index,
+            gas_used: result.gas_used(),
+            gas_refunded,
+            da_footprint,
+            ..Default::default()
+        };
+
+        for (address, account) in state {
+            if account.is_created() {
+                profile.created_contracts.push(*address);
+            }
+            for slot in account.storage.values() {
+                if slot.is_changed() {
+                    profile.storage_writes += 1;
+                } else {
+                    profile.storage_reads += 1;
+                }
+            }
+        }
+        profile.created_contracts.sort_unstable();
+
+        profile
+    }
+}
+
+/// Resource accounting report of an executed block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExecutionProfile {
+    /// Profiles of the executed transactions, in execution order.
+    pub transactions: Vec<TxExecutionProfile>,
+}
+
+impl ExecutionProfile {
+    /// Returns the total gas used by all profiled transactions.
+    pub fn total_gas_used(&self) -> u64 {
+        self.transactions.iter().map(|tx| tx.gas_used).sum()
+    }
+
+    /// Returns the total data availability footprint of all profiled transactions.
+    pub fn total_da_footprint(&self) -> u64 {
+        self.transactions.iter().map(|tx| tx.da_footprint).sum()
+    }
+
+    /// Returns the total wall time spent on all profiled transactions.
+    #[cfg(feature = "std")]
+    pub fn total_elapsed(&self) -> Duration {
+        self.transactions.iter().map(|tx| tx.elapsed).sum()
+    }
+}
+
+/// Collects an [`ExecutionProfile`] while a block is being executed.
+///
+/// Executors call [`ExecutionProfiler::start_transaction`] before executing a transaction and
+/// [`ExecutionProfiler::record`] once it's committed.
+#[derive(Debug, Default)]
+pub struct ExecutionProfiler {
+    profile: ExecutionProfile,
+    #[cfg(feature = "std")]
+    started_at: Option<Instant>,
+}
+
+impl ExecutionProfiler {
+    /// Marks the start of a transaction's execution.
+    pub fn start_transaction(&mut self) {
+        #[cfg(feature = "std")]
+        {
+            self.started_at = Some(Instant::now());
+        }
+    }
+
+    /// Records the profile of a committed transaction.
+    pub fn record(&mut self, tx: TxExecutionProfile) {
+        #[cfg(feature = "std")]
+        let tx = TxExecutionProfile {
+            elapsed: self.started_at.take().map(|start| start.elapsed()).unwrap_or_default(),
+            ..tx
+        };
+        self.profile.transactions.push(tx);
+    }
+
+    /// Returns the profile collected so far.
+    pub const fn profile(&self) -> &ExecutionProfile {
+        &self.profile
+    }
+
+    /// Consumes the profiler and returns the collected [`ExecutionProfile`].
+    pub fn into_profile(self) -> ExecutionProfile {
+        self.profile
+    }
+}
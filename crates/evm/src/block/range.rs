@@ -0,0 +1,567 @@
+//! Re-execution of block ranges.
+//!
+//! [`execute_range`] executes blocks one after another on the calling thread. With the `pipeline`
+//! feature, `execute_range_pipelined` additionally recovers signers and computes receipts roots on
+//! worker threads.
+
+use super::{
+    block_hashes::{BlockHashCache, BlockHashOverlay},
+    chain::ExecutableBlock,
+    BlockExecutionError, BlockExecutionResult, BlockExecutor, BlockExecutorFactory,
+    ExecutableTxParts,
+};
+use crate::{Database, EvmFactory};
+use alloc::vec::Vec;
+use core::time::Duration;
+use revm::database::{states::bundle_state::BundleRetention, BundleState, State};
+#[cfg(all(feature = "pipeline", not(any(target_os = "zkvm", target_arch = "wasm32"))))]
+use {
+    crate::FromRecoveredTx,
+    alloy_consensus::{
+        proofs::calculate_receipt_root,
+        transaction::{Recovered, SignerRecoverable},
+        Eip2718EncodableReceipt,
+    },
+    alloy_primitives::B256,
+};
+
+/// Progress of an [`execute_range`] run, reported after every block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeProgress<Spec> {
+    /// Number of the block that was just executed.
+    pub block: u64,
+    /// Number of blocks executed so far.
+    pub blocks: u64,
+    /// Gas used by the block that was just executed.
+    pub block_gas_used: u64,
+    /// Gas used by all blocks executed so far.
+    pub gas_used: u64,
+    /// Spec the block was executed with.
+    pub spec: Spec,
+    /// Whether the block activated a different spec than its predecessor in the range.
+    pub fork_boundary: bool,
+    /// Time spent executing so far.
+    ///
+    /// Always zero without the `std` feature or on zkVM targets, which lack a clock.
+    pub elapsed: Duration,
+}
+
+impl<Spec> RangeProgress<Spec> {
+    /// Returns the execution throughput in gas per second, or `None` if no time was measured.
+    pub fn gas_per_second(&self) -> Option<f64> {
+        let secs = self.elapsed.as_secs_f64();
+        (secs > 0.0).then(|| self.gas_used as f64 / secs)
+    }
+}
+
+/// Errors returned by [`execute_range`].
+#[derive(Debug, thiserror::Error)]
+pub enum RangeExecutionError {
+    /// The block doesn't follow its predecessor in the range.
+    #[error("block {got} doesn't follow its predecessor, expected block {expected}")]
+    NonContiguous {
+        /// Number of the block following the predecessor.
+        expected: u64,
+        /// Number of the given block.
+        got: u64,
+    },
+    /// The signer of a transaction couldn't be recovered.
+    #[error("failed to recover the signer of transaction {index} in block {block}")]
+    Recovery {
+        /// Number of the block.
+        block: u64,
+        /// Index of the transaction in the block.
+        index: usize,
+    },
+    /// Executing a block failed.
+    #[error("failed to execute block {block}")]
+    Execution {
+        /// Number of the block.
+        block: u64,
+        /// The execution error.
+        source: BlockExecutionError,
+    },
+}
+
+/// Output of [`execute_range`].
+#[derive(Debug)]
+pub struct RangeExecutionOutput<R> {
+    /// Results of the executed blocks, in order.
+    pub results: Vec<BlockExecutionResult<R>>,
+    /// Changes of all executed blocks merged together, with reverts for every block.
+    pub bundle: BundleState,
+    /// Gas used by all executed blocks.
+    pub gas_used: u64,
+}
+
+/// Executes a contiguous range of blocks on top of the database.
+///
+/// Each block is executed in its own environment, so blocks on either side of a hardfork are
+/// executed with the spec active at the respective block. The changes of all blocks are merged
+/// into a single [`BundleState`], `on_progress` is invoked after every block.
+///
+/// Hashes of executed blocks that are known via [`ExecutableBlock::hash`] are kept in a
+/// [`BlockHashCache`], so `BLOCKHASH` lookups of later blocks don't hit `db`.
+///
+/// Fails if the blocks are not consecutive or a block fails to execute.
+pub fn execute_range<'b, F, DB, B>(
+    factory: &F,
+    blocks: impl IntoIterator<Item = &'b B>,
+    db: DB,
+    mut on_progress: impl FnMut(&RangeProgress<<F::EvmFactory as EvmFactory>::Spec>),
+) -> Result<RangeExecutionOutput<F::Receipt>, RangeExecutionError>
+where
+    F: BlockExecutorFactory,
+    DB: Database,
+    B: ExecutableBlock<F> + 'b,
+{
+    let mut results = Vec::new();
+    let (bundle, gas_used) = run_range(
+        factory,
+        blocks,
+        db,
+        |block| Ok(block.transactions()),
+        |result, progress| {
+            on_progress(progress);
+            results.push(result);
+        },
+    )?;
+    Ok(RangeExecutionOutput { results, bundle, gas_used })
+}
+
+/// A block whose transactions still need their signers recovered, see
+/// [`execute_range_pipelined`].
+#[cfg(all(feature = "pipeline", not(any(target_os = "zkvm", target_arch = "wasm32"))))]
+pub trait PipelineBlock<F: BlockExecutorFactory>: ExecutableBlock<F> {
+    /// Returns the signed transactions of the block, in execution order.
+    fn signed_transactions(&self) -> &[F::Transaction];
+}
+
+/// Executes a contiguous range of blocks like [`execute_range`], while recovering signers and
+/// computing receipts roots on worker threads.
+///
+/// Executing a block is inherently sequential, but the work around it isn't: the signers of the
+/// next block are recovered from [`PipelineBlock::signed_transactions`] and the receipts root of
+/// the previous block is computed while the current block executes, so the executing thread only
+/// waits for the workers if they fall behind. Signers are recovered at most two blocks ahead of
+/// execution, one waiting to be executed and one being recovered, so memory use stays bounded for
+/// long ranges.
+///
+/// Returns the output of the range and the receipts roots of the executed blocks, in order.
+#[cfg(all(feature = "pipeline", not(any(target_os = "zkvm", target_arch = "wasm32"))))]
+pub fn execute_range_pipelined<F, DB, B>(
+    factory: &F,
+    blocks: &[B],
+    db: DB,
+    mut on_progress: impl FnMut(&RangeProgress<<F::EvmFactory as EvmFactory>::Spec>),
+) -> Result<(RangeExecutionOutput<F::Receipt>, Vec<B256>), RangeExecutionError>
+where
+    F: BlockExecutorFactory,
+    DB: Database,
+    B: PipelineBlock<F> + Sync,
+    F::Transaction: SignerRecoverable + Sync,
+    F::Receipt: Eip2718EncodableReceipt + Send,
+    <F::EvmFactory as EvmFactory>::Tx: FromRecoveredTx<F::Transaction>,
+{
+    use std::sync::mpsc;
+
+    std::thread::scope(|scope| {
+        let (recovered_tx, recovered_rx) = mpsc::sync_channel(1);
+        scope.spawn(move || {
+            for block in blocks {
+                let recovered = recover_block(block);
+                let failed = recovered.is_err();
+                if recovered_tx.send(recovered).is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        let (executed_tx, executed_rx) = mpsc::channel::<BlockExecutionResult<F::Receipt>>();
+        let roots = scope.spawn(move || {
+            executed_rx
+                .into_iter()
+                .map(|result| {
+                    let root = calculate_receipt_root(&result.receipts);
+                    (result, root)
+                })
+                .unzip::<_, _, Vec<_>, Vec<_>>()
+        });
+
+        let ran = run_range(
+            factory,
+            blocks,
+            db,
+            |_| recovered_rx.recv().expect("recovery worker stopped early"),
+            |result, progress| {
+                on_progress(progress);
+                // the receiver only stops once the sender is dropped
+                let _ = executed_tx.send(result);
+            },
+        );
+        let (results, receipts_roots) = roots.join().expect("receipts root worker panicked");
+        let (bundle, gas_used) = ran?;
+        Ok((RangeExecutionOutput { results, bundle, gas_used }, receipts_roots))
+    })
+}
+
+/// Recovers the signers of all transactions of the block.
+#[cfg(all(feature = "pipeline", not(any(target_os = "zkvm", target_arch = "wasm32"))))]
+fn recover_block<F, B>(block: &B) -> Result<Vec<Recovered<&F::Transaction>>, RangeExecutionError>
+where
+    F: BlockExecutorFactory,
+    B: PipelineBlock<F>,
+    F::Transaction: SignerRecoverable,
+{
+    block
+        .signed_transactions()
+        .iter()
+        .enumerate()
+        .map(|(index, tx)| {
+            tx.recover_signer()
+                .map(|signer| Recovered::new_unchecked(tx, signer))
+                .map_err(|_| RangeExecutionError::Recovery { block: block.number(), index })
+        })
+        .collect()
+}
+
+/// Executes the blocks in order, obtaining the transactions of every block from `transactions`
+/// and handing the result of every block to `on_block`.
+///
+/// Returns the merged changes of all blocks and the gas they used.
+fn run_range<'b, F, DB, B, I>(
+    factory: &F,
+    blocks: impl IntoIterator<Item = &'b B>,
+    db: DB,
+    mut transactions: impl FnMut(&'b B) -> Result<I, RangeExecutionError>,
+    mut on_block: impl FnMut(
+        BlockExecutionResult<F::Receipt>,
+        &RangeProgress<<F::EvmFactory as EvmFactory>::Spec>,
+    ),
+) -> Result<(BundleState, u64), RangeExecutionError>
+where
+    F: BlockExecutorFactory,
+    DB: Database,
+    B: ExecutableBlock<F> + 'b,
+    I: IntoIterator<Item: ExecutableTxParts<<F::EvmFactory as EvmFactory>::Tx, F::Transaction>>,
+{
+    let db = BlockHashOverlay::new(db, BlockHashCache::new());
+    let mut state = State::builder().with_database(db).with_bundle_update().build();
+    let mut executed = 0;
+    let mut gas_used = 0;
+    let mut prev: Option<(u64, <F::EvmFactory as EvmFactory>::Spec)> = None;
+    #[cfg(all(feature = "std", not(target_os = "zkvm")))]
+    let started_at = crate::time::Instant::now();
+
+    for block in blocks {
+        let number = block.number();
+        if let Some((prev_number, _)) = prev {
+            if number != prev_number + 1 {
+                return Err(RangeExecutionError::NonContiguous {
+                    expected: prev_number + 1,
+                    got: number,
+                });
+            }
+        }
+
+        let transactions = transactions(block)?;
+        let evm_env = block.evm_env(factory);
+        let spec = evm_env.cfg_env.spec;
+        let evm = factory.evm_factory().create_evm(&mut state, evm_env);
+        let result = factory
+            .create_executor(evm, block.execution_ctx())
+            .execute_block(transactions)
+            .map_err(|source| RangeExecutionError::Execution { block: number, source })?;
+        state.merge_transitions(BundleRetention::Reverts);
+        if let Some(hash) = block.hash() {
+            state.database.reader_mut().insert(number, hash);
+        }
+
+        executed += 1;
+        gas_used += result.gas_used;
+        let progress = RangeProgress {
+            block: number,
+            blocks: executed,
+            block_gas_used: result.gas_used,
+            gas_used,
+            spec,
+            fork_boundary: prev.is_some_and(|(_, prev_spec)| prev_spec != spec),
+            #[cfg(all(feature = "std", not(target_os = "zkvm")))]
+            elapsed: started_at.elapsed(),
+            #[cfg(not(all(feature = "std", not(target_os = "zkvm"))))]
+            elapsed: Duration::ZERO,
+        };
+        on_block(result, &progress);
+
+        prev = Some((number, spec));
+    }
+
+    Ok((state.take_bundle(), gas_used))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        block::ExecutableTxParts,
+        eth::{
+            receipt_builder::AlloyReceiptBuilder, spec::EthSpec, EthBlockExecutionCtx,
+            EthBlockExecutorFactory,
+        },
+        EthEvmFactory, EvmEnv,
+    };
+    use alloc::{borrow::Cow, vec};
+    use alloy_consensus::{transaction::Recovered, Header, TxEnvelope};
+    use alloy_eips::eip4895::Withdrawal;
+    use alloy_primitives::{Address, Bytes, B256, U256};
+    use revm::{
+        context::TxEnv,
+        database::{CacheDB, EmptyDB},
+        primitives::hardfork::SpecId,
+    };
+
+    type Factory = EthBlockExecutorFactory<AlloyReceiptBuilder, EthSpec, EthEvmFactory>;
+
+    const RECIPIENT: Address = Address::repeat_byte(0x42);
+
+    /// A block withdrawing `amount` gwei to [`RECIPIENT`], which only has an effect from Shanghai
+    /// on.
+    struct TestBlock {
+        header: Header,
+        withdrawals: [Withdrawal; 1],
+    }
+
+    impl TestBlock {
+        fn new(number: u64, timestamp: u64, amount: u64) -> Self {
+            let header = Header {
+                number,
+                timestamp,
+                gas_limit: 30_000_000,
+                base_fee_per_gas: Some(1_000_000_000),
+                ..Default::default()
+            };
+            let withdrawals =
+                [Withdrawal { index: 0, validator_index: 0, address: RECIPIENT, amount }];
+            Self { header, withdrawals }
+        }
+    }
+
+    impl ExecutableBlock<Factory> for TestBlock {
+        fn number(&self) -> u64 {
+            self.header.number
+        }
+
+        fn hash(&self) -> Option<B256> {
+            Some(B256::with_last_byte(self.header.number as u8))
+        }
+
+        fn evm_env(&self, factory: &Factory) -> EvmEnv {
+            EvmEnv::for_eth_block(&self.header, factory.spec().clone(), 1, None)
+        }
+
+        fn execution_ctx(&self) -> EthBlockExecutionCtx<'_> {
+            EthBlockExecutionCtx {
+                parent_hash: self.header.parent_hash,
+                parent_beacon_block_root: None,
+                ommers: &[],
+                withdrawals: Some(Cow::Borrowed(&self.withdrawals)),
+                extra_data: Bytes::new(),
+                tx_count_hint: Some(0),
+                blob_params: None,
+            }
+        }
+
+        fn transactions(
+            &self,
+        ) -> impl Iterator<Item = impl ExecutableTxParts<TxEnv, TxEnvelope> + '_> {
+            core::iter::empty::<Recovered<TxEnvelope>>()
+        }
+    }
+
+    fn factory() -> Factory {
+        Factory::new(AlloyReceiptBuilder, EthSpec::mainnet(), EthEvmFactory)
+    }
+
+    #[test]
+    fn test_execute_range_across_fork() {
+        // The last Paris and the first Shanghai block on mainnet.
+        let blocks = [
+            TestBlock::new(17_034_869, 1_681_338_443, 1),
+            TestBlock::new(17_034_870, 1_681_338_455, 2),
+        ];
+        let mut progress = vec![];
+        let output = execute_range(&factory(), &blocks, CacheDB::new(EmptyDB::default()), |p| {
+            progress.push(*p)
+        })
+        .unwrap();
+
+        assert_eq!(output.results.len(), 2);
+        assert_eq!(output.gas_used, 0);
+        assert_eq!(
+            progress
+                .iter()
+                .map(|p| (p.block, p.blocks, p.spec, p.fork_boundary))
+                .collect::<Vec<_>>(),
+            [(17_034_869, 1, SpecId::MERGE, false), (17_034_870, 2, SpecId::SHANGHAI, true)]
+        );
+
+        // Only the Shanghai block processes its withdrawal.
+        let recipient = output.bundle.account(&RECIPIENT).unwrap();
+        assert_eq!(recipient.info.as_ref().unwrap().balance, U256::from(2_000_000_000u64));
+        assert_eq!(output.bundle.reverts.len(), 2);
+    }
+
+    #[test]
+    fn test_execute_range_non_contiguous() {
+        let blocks = [
+            TestBlock::new(17_034_870, 1_681_338_455, 1),
+            TestBlock::new(17_034_872, 1_681_338_479, 1),
+        ];
+        let mut progress = vec![];
+        let err = execute_range(&factory(), &blocks, CacheDB::new(EmptyDB::default()), |p| {
+            progress.push(p.block)
+        })
+        .unwrap_err();
+
+        assert!(matches!(
+            err,
+            RangeExecutionError::NonContiguous { expected: 17_034_871, got: 17_034_872 }
+        ));
+        assert_eq!(progress, [17_034_870]);
+    }
+
+    #[cfg(all(feature = "pipeline", not(any(target_os = "zkvm", target_arch = "wasm32"))))]
+    mod pipelined {
+        use super::*;
+        use alloy_consensus::{
+            proofs::calculate_receipt_root, transaction::SignerRecoverable, SignableTransaction,
+            TxLegacy,
+        };
+        use alloy_primitives::{Signature, TxKind};
+        use revm::state::AccountInfo;
+
+        /// A London block of signed transactions.
+        struct SignedBlock {
+            header: Header,
+            transactions: Vec<TxEnvelope>,
+        }
+
+        impl SignedBlock {
+            fn new(number: u64, transactions: Vec<TxEnvelope>) -> Self {
+                let header = Header {
+                    number,
+                    gas_limit: 30_000_000,
+                    base_fee_per_gas: Some(0),
+                    ..Default::default()
+                };
+                Self { header, transactions }
+            }
+        }
+
+        impl ExecutableBlock<Factory> for SignedBlock {
+            fn number(&self) -> u64 {
+                self.header.number
+            }
+
+            fn evm_env(&self, factory: &Factory) -> EvmEnv {
+                EvmEnv::for_eth_block(&self.header, factory.spec().clone(), 1, None)
+            }
+
+            fn execution_ctx(&self) -> EthBlockExecutionCtx<'_> {
+                EthBlockExecutionCtx {
+                    parent_hash: self.header.parent_hash,
+                    parent_beacon_block_root: None,
+                    ommers: &[],
+                    withdrawals: None,
+                    extra_data: Bytes::new(),
+                    tx_count_hint: Some(self.transactions.len()),
+                    blob_params: None,
+                }
+            }
+
+            fn transactions(
+                &self,
+            ) -> impl Iterator<Item = impl ExecutableTxParts<TxEnv, TxEnvelope> + '_> {
+                self.transactions.iter().map(|tx| tx.try_to_recovered_ref().unwrap())
+            }
+        }
+
+        impl PipelineBlock<Factory> for SignedBlock {
+            fn signed_transactions(&self) -> &[TxEnvelope] {
+                &self.transactions
+            }
+        }
+
+        fn transfer(nonce: u64) -> TxEnvelope {
+            let tx = TxLegacy {
+                nonce,
+                gas_limit: 21_000,
+                to: TxKind::Call(Address::repeat_byte(0x02)),
+                value: U256::from(1),
+                ..Default::default()
+            };
+            tx.into_signed(Signature::test_signature()).into()
+        }
+
+        fn db() -> CacheDB<EmptyDB> {
+            let sender = transfer(0).recover_signer().unwrap();
+            let mut db = CacheDB::new(EmptyDB::default());
+            db.insert_account_info(sender, AccountInfo::from_balance(U256::from(100)));
+            db
+        }
+
+        #[test]
+        fn test_pipelined_execution() {
+            let blocks: Vec<_> = (0..4)
+                .map(|number| {
+                    SignedBlock::new(
+                        13_000_000 + number,
+                        (0..3).map(|i| transfer(number * 3 + i)).collect(),
+                    )
+                })
+                .collect();
+
+            let mut progress = vec![];
+            let (output, receipts_roots) =
+                execute_range_pipelined(&factory(), &blocks, db(), |p| progress.push(p.block))
+                    .unwrap();
+
+            assert_eq!(progress, [13_000_000, 13_000_001, 13_000_002, 13_000_003]);
+            assert_eq!(output.results.len(), 4);
+            assert_eq!(output.gas_used, 12 * 21_000);
+            assert_eq!(receipts_roots.len(), 4);
+            for (result, root) in output.results.iter().zip(&receipts_roots) {
+                assert_eq!(calculate_receipt_root(&result.receipts), *root);
+            }
+            let recipient = output.bundle.account(&Address::repeat_byte(0x02)).unwrap();
+            assert_eq!(recipient.info.as_ref().unwrap().balance, U256::from(12));
+
+            let sequential = execute_range(&factory(), &blocks, db(), |_| {}).unwrap();
+            assert_eq!(sequential.bundle, output.bundle);
+        }
+
+        #[test]
+        fn test_pipelined_recovery_error() {
+            // zero `r` and `s` values are not a valid signature
+            let tx = TxLegacy::default().into_signed(Signature::new(U256::ZERO, U256::ZERO, false));
+            let blocks = [SignedBlock::new(13_000_000, vec![tx.into()])];
+
+            let err = execute_range_pipelined(&factory(), &blocks, db(), |_| {}).unwrap_err();
+            assert!(matches!(err, RangeExecutionError::Recovery { block: 13_000_000, index: 0 }));
+        }
+
+        #[test]
+        fn test_pipelined_non_contiguous() {
+            let blocks = [
+                SignedBlock::new(13_000_000, vec![transfer(0)]),
+                SignedBlock::new(13_000_002, vec![transfer(1)]),
+            ];
+
+            let err = execute_range_pipelined(&factory(), &blocks, db(), |_| {}).unwrap_err();
+            assert!(matches!(
+                err,
+                RangeExecutionError::NonContiguous { expected: 13_000_001, got: 13_000_002 }
+            ));
+        }
+    }
+}
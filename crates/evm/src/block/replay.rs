@@ -0,0 +1,174 @@
+//! Replay of transactions at their position in a block.
+
+use super::{
+    chain::ExecutableBlock, BlockExecutionError, BlockExecutor, BlockExecutorFactory, TxResult,
+};
+use crate::{Database, Evm, EvmFactory};
+use revm::{context::result::ResultAndState, database::State, inspector::NoOpInspector, Inspector};
+
+/// Errors returned by [`replay_transaction`].
+#[derive(Debug, thiserror::Error)]
+pub enum ReplayError {
+    /// The block has no transaction at the given index.
+    #[error("block has no transaction at index {0}")]
+    TransactionNotFound(usize),
+    /// Executing the block up to the transaction failed.
+    #[error(transparent)]
+    Execution(#[from] BlockExecutionError),
+}
+
+/// Replays the transaction at `tx_index` of the block on top of `db`, which must hold the state
+/// of the parent block.
+///
+/// The pre-execution changes of the block and all preceding transactions are applied first, then
+/// the target transaction is executed and its result returned without committing it.
+pub fn replay_transaction<F, DB, B>(
+    factory: &F,
+    block: &B,
+    tx_index: usize,
+    db: DB,
+) -> Result<ResultAndState<<F::EvmFactory as EvmFactory>::HaltReason>, ReplayError>
+where
+    F: BlockExecutorFactory,
+    DB: Database,
+    B: ExecutableBlock<F>,
+{
+    replay_transaction_with_inspector(factory, block, tx_index, db, NoOpInspector {})
+}
+
+/// Same as [`replay_transaction`], but executes the target transaction with the given inspector.
+///
+/// The inspector is only enabled for the target transaction. It is usually provided as
+/// `&mut Inspector`, so that the trace can be read from it afterwards.
+pub fn replay_transaction_with_inspector<F, DB, B, I>(
+    factory: &F,
+    block: &B,
+    tx_index: usize,
+    db: DB,
+    inspector: I,
+) -> Result<ResultAndState<<F::EvmFactory as EvmFactory>::HaltReason>, ReplayError>
+where
+    F: BlockExecutorFactory,
+    DB: Database,
+    B: ExecutableBlock<F>,
+    I: for<'a> Inspector<<F::EvmFactory as EvmFactory>::Context<&'a mut State<DB>>>,
+{
+    let mut state = State::builder().with_database(db).build();
+    let mut evm = factory.evm_factory().create_evm_with_inspector(
+        &mut state,
+        block.evm_env(factory),
+        inspector,
+    );
+    evm.disable_inspector();
+
+    let mut executor = factory.create_executor(evm, block.execution_ctx());
+    executor.apply_pre_execution_changes()?;
+
+    let mut transactions = block.transactions();
+    for tx in transactions.by_ref().take(tx_index) {
+        executor.execute_transaction(tx)?;
+    }
+    let tx = transactions.next().ok_or(ReplayError::TransactionNotFound(tx_index))?;
+
+    executor.evm_mut().enable_inspector();
+    let output = executor.execute_transaction_without_commit(tx)?;
+    Ok(output.result().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        block::ExecutableTxParts,
+        eth::{
+            receipt_builder::AlloyReceiptBuilder, spec::EthSpec, EthBlockExecutionCtx,
+            EthBlockExecutorFactory,
+        },
+        EthEvmFactory, EvmEnv,
+    };
+    use alloy_consensus::{
+        transaction::Recovered, Header, SignableTransaction, TxEnvelope, TxLegacy,
+    };
+    use alloy_primitives::{Address, Bytes, Signature, TxKind, U256};
+    use revm::{
+        context::TxEnv,
+        database::{CacheDB, EmptyDB},
+        state::AccountInfo,
+    };
+
+    type Factory = EthBlockExecutorFactory<AlloyReceiptBuilder, EthSpec, EthEvmFactory>;
+
+    const SENDER: Address = Address::repeat_byte(0x01);
+    const RECIPIENT: Address = Address::repeat_byte(0x02);
+
+    struct TestBlock {
+        header: Header,
+        transactions: [Recovered<TxEnvelope>; 2],
+    }
+
+    impl ExecutableBlock<Factory> for TestBlock {
+        fn number(&self) -> u64 {
+            self.header.number
+        }
+
+        fn evm_env(&self, factory: &Factory) -> EvmEnv {
+            EvmEnv::for_eth_block(&self.header, factory.spec().clone(), 1, None)
+        }
+
+        fn execution_ctx(&self) -> EthBlockExecutionCtx<'_> {
+            EthBlockExecutionCtx {
+                parent_hash: self.header.parent_hash,
+                parent_beacon_block_root: None,
+                ommers: &[],
+                withdrawals: None,
+                extra_data: Bytes::new(),
+                tx_count_hint: Some(self.transactions.len()),
+                blob_params: None,
+            }
+        }
+
+        fn transactions(
+            &self,
+        ) -> impl Iterator<Item = impl ExecutableTxParts<TxEnv, TxEnvelope> + '_> {
+            self.transactions.iter()
+        }
+    }
+
+    fn transfer(nonce: u64) -> Recovered<TxEnvelope> {
+        let tx = TxLegacy {
+            nonce,
+            gas_price: 0,
+            gas_limit: 21_000,
+            to: TxKind::Call(RECIPIENT),
+            value: U256::from(1),
+            ..Default::default()
+        };
+        Recovered::new_unchecked(tx.into_signed(Signature::test_signature()).into(), SENDER)
+    }
+
+    #[test]
+    fn test_replay_transaction() {
+        // Homestead, so that no system contracts are required.
+        let block = TestBlock {
+            header: Header { number: 1_150_000, gas_limit: 30_000_000, ..Default::default() },
+            transactions: [transfer(0), transfer(1)],
+        };
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            SENDER,
+            AccountInfo { balance: U256::from(2), ..Default::default() },
+        );
+        let factory = Factory::new(AlloyReceiptBuilder, EthSpec::mainnet(), EthEvmFactory);
+
+        let ResultAndState { result, state } =
+            replay_transaction(&factory, &block, 1, db.clone()).unwrap();
+        assert!(result.is_success());
+        assert_eq!(state[&SENDER].info.nonce, 2);
+        assert_eq!(state[&RECIPIENT].info.balance, U256::from(2));
+
+        assert!(matches!(
+            replay_transaction(&factory, &block, 2, db),
+            Err(ReplayError::TransactionNotFound(2))
+        ));
+    }
+}
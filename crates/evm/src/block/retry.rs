@@ -0,0 +1,185 @@
+//! Database wrapper retrying failed reads.
+
+use alloy_primitives::{Address, B256};
+use core::time::Duration;
+use revm::{
+    bytecode::Bytecode,
+    primitives::{StorageKey, StorageValue},
+    state::AccountInfo,
+    Database, DatabaseRef,
+};
+
+/// A [`Database`] that retries failed reads of the inner database.
+///
+/// Useful for databases backed by a remote or otherwise unreliable store, where a read might fail
+/// because of a temporary backend failure. If a read keeps failing after all retries, the last
+/// error is returned and the execution fails with a
+/// [transient](super::BlockExecutionError::is_transient) error.
+#[derive(Debug, Clone)]
+pub struct RetryDatabase<DB> {
+    inner: DB,
+    max_retries: usize,
+    backoff: Option<Duration>,
+}
+
+impl<DB> RetryDatabase<DB> {
+    /// Creates a new wrapper retrying each failed read up to `max_retries` times.
+    pub const fn new(inner: DB, max_retries: usize) -> Self {
+        Self { inner, max_retries, backoff: None }
+    }
+
+    /// Sleeps for `backoff` before the first retry, doubling the delay after every failed
+    /// attempt.
+    ///
+    /// Not available on zkVM and wasm targets, which can't block the thread.
+    #[cfg(all(feature = "std", not(any(target_os = "zkvm", target_arch = "wasm32"))))]
+    pub const fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = Some(backoff);
+        self
+    }
+
+    /// Returns a reference to the inner database.
+    pub const fn inner(&self) -> &DB {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner database.
+    pub const fn inner_mut(&mut self) -> &mut DB {
+        &mut self.inner
+    }
+
+    /// Consumes the wrapper and returns the inner database.
+    pub fn into_inner(self) -> DB {
+        self.inner
+    }
+}
+
+/// Calls `f` until it succeeds or has failed `max_retries + 1` times.
+fn retry<T, E>(
+    max_retries: usize,
+    mut backoff: Option<Duration>,
+    mut f: impl FnMut() -> Result<T, E>,
+) -> Result<T, E> {
+    let mut attempt = 0;
+    loop {
+        match f() {
+            Err(_) if attempt < max_retries => {
+                attempt += 1;
+                if let Some(delay) = &mut backoff {
+                    #[cfg(all(
+                        feature = "std",
+                        not(any(target_os = "zkvm", target_arch = "wasm32"))
+                    ))]
+                    std::thread::sleep(*delay);
+                    *delay = delay.saturating_mul(2);
+                }
+            }
+            result => return result,
+        }
+    }
+}
+
+impl<DB: Database> Database for RetryDatabase<DB> {
+    type Error = DB::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let inner = &mut self.inner;
+        retry(self.max_retries, self.backoff, || inner.basic(address))
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        let inner = &mut self.inner;
+        retry(self.max_retries, self.backoff, || inner.code_by_hash(code_hash))
+    }
+
+    fn storage(
+        &mut self,
+        address: Address,
+        index: StorageKey,
+    ) -> Result<StorageValue, Self::Error> {
+        let inner = &mut self.inner;
+        retry(self.max_retries, self.backoff, || inner.storage(address, index))
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        let inner = &mut self.inner;
+        retry(self.max_retries, self.backoff, || inner.block_hash(number))
+    }
+}
+
+impl<DB: DatabaseRef> DatabaseRef for RetryDatabase<DB> {
+    type Error = DB::Error;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        retry(self.max_retries, self.backoff, || self.inner.basic_ref(address))
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        retry(self.max_retries, self.backoff, || self.inner.code_by_hash_ref(code_hash))
+    }
+
+    fn storage_ref(
+        &self,
+        address: Address,
+        index: StorageKey,
+    ) -> Result<StorageValue, Self::Error> {
+        retry(self.max_retries, self.backoff, || self.inner.storage_ref(address, index))
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        retry(self.max_retries, self.backoff, || self.inner.block_hash_ref(number))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("backend unavailable")]
+    struct Unavailable;
+
+    /// Database failing the first `failures` reads.
+    #[derive(Debug)]
+    struct FlakyDb {
+        failures: usize,
+    }
+
+    impl Database for FlakyDb {
+        type Error = Unavailable;
+
+        fn basic(&mut self, _address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+            if self.failures > 0 {
+                self.failures -= 1;
+                return Err(Unavailable);
+            }
+            Ok(None)
+        }
+
+        fn code_by_hash(&mut self, _code_hash: B256) -> Result<Bytecode, Self::Error> {
+            Ok(Bytecode::default())
+        }
+
+        fn storage(
+            &mut self,
+            _address: Address,
+            _index: StorageKey,
+        ) -> Result<StorageValue, Self::Error> {
+            Ok(StorageValue::ZERO)
+        }
+
+        fn block_hash(&mut self, _number: u64) -> Result<B256, Self::Error> {
+            Ok(B256::ZERO)
+        }
+    }
+
+    #[test]
+    fn retries_failed_reads() {
+        let mut db = RetryDatabase::new(FlakyDb { failures: 2 }, 2);
+        assert!(db.basic(Address::ZERO).is_ok());
+
+        let mut db = RetryDatabase::new(FlakyDb { failures: 3 }, 2);
+        assert!(db.basic(Address::ZERO).is_err());
+        assert_eq!(db.inner().failures, 0);
+    }
+}
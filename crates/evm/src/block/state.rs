@@ -1,9 +1,56 @@
 //! State database abstraction.
 
 use crate::Database;
-use revm::DatabaseCommit;
+use revm::{
+    database::{states::bundle_state::BundleRetention, BundleState, State},
+    DatabaseCommit,
+};
 
 /// Alias trait for [`Database`] and [`DatabaseCommit`].
 pub trait StateDB: Database + DatabaseCommit {}
 
 impl<T> StateDB for T where T: Database + DatabaseCommit {}
+
+/// A [`StateDB`] that accumulates committed changes into a [`BundleState`].
+///
+/// This is implemented for [`State`] and mutable references to it. Note that [`State`] only tracks
+/// transitions if it was built with bundle updates enabled, see
+/// [`StateBuilder::with_bundle_update`](revm::database::StateBuilder::with_bundle_update).
+pub trait BundleStateDB: StateDB {
+    /// Merges all pending transitions into the bundle state.
+    fn merge_transitions(&mut self, retention: BundleRetention);
+
+    /// Returns a reference to the accumulated [`BundleState`].
+    fn bundle_state(&self) -> &BundleState;
+
+    /// Takes the accumulated [`BundleState`], leaving an empty one in its place.
+    fn take_bundle(&mut self) -> BundleState;
+}
+
+impl<DB: Database> BundleStateDB for State<DB> {
+    fn merge_transitions(&mut self, retention: BundleRetention) {
+        Self::merge_transitions(self, retention);
+    }
+
+    fn bundle_state(&self) -> &BundleState {
+        &self.bundle_state
+    }
+
+    fn take_bundle(&mut self) -> BundleState {
+        Self::take_bundle(self)
+    }
+}
+
+impl<T: BundleStateDB> BundleStateDB for &mut T {
+    fn merge_transitions(&mut self, retention: BundleRetention) {
+        (**self).merge_transitions(retention);
+    }
+
+    fn bundle_state(&self) -> &BundleState {
+        (**self).bundle_state()
+    }
+
+    fn take_bundle(&mut self) -> BundleState {
+        (**self).take_bundle()
+    }
+}
@@ -0,0 +1,206 @@
+//! Typed summary of the state changes produced by a block.
+//!
+//! With the `serde` feature enabled, [`BlockStateDiff`] can be exported as stable, sorted JSON,
+//! which makes execution mismatches between clients easy to diff.
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloy_primitives::{Address, B256, U256};
+use revm::{database::BundleState, state::AccountInfo};
+
+#[cfg(feature = "serde")]
+use alloc::string::String;
+
+/// A value that was changed by the block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Change<T> {
+    /// Value before the block was executed.
+    pub from: T,
+    /// Value after the block was executed.
+    pub to: T,
+}
+
+impl<T: PartialEq> Change<T> {
+    /// Returns a [`Change`] if `from` and `to` differ.
+    pub fn new(from: T, to: T) -> Option<Self> {
+        (from != to).then_some(Self { from, to })
+    }
+}
+
+/// Changes made to a single account.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, rename_all = "camelCase"))]
+pub struct AccountDiff {
+    /// Whether the account did not exist before the block.
+    pub created: bool,
+    /// Whether the account was destroyed during the block.
+    pub destroyed: bool,
+    /// Balance change, if any.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub balance: Option<Change<U256>>,
+    /// Nonce change, if any.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub nonce: Option<Change<u64>>,
+    /// Code hash change, if any.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub code_hash: Option<Change<B256>>,
+    /// Changed storage slots, sorted by slot.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "BTreeMap::is_empty"))]
+    pub storage: BTreeMap<U256, Change<U256>>,
+}
+
+impl AccountDiff {
+    /// Returns `true` if the account was not modified.
+    pub fn is_empty(&self) -> bool {
+        !self.created
+            && !self.destroyed
+            && self.balance.is_none()
+            && self.nonce.is_none()
+            && self.code_hash.is_none()
+            && self.storage.is_empty()
+    }
+}
+
+/// Summary of the state changes made by a block, derived from a [`BundleState`].
+///
+/// Unlike [`BundleState`] this only contains accounts and storage slots whose values actually
+/// changed and uses sorted maps, so the output is deterministic.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct BlockStateDiff {
+    /// Changed accounts, sorted by address.
+    pub accounts: BTreeMap<Address, AccountDiff>,
+    /// Hashes of the bytecodes that were deployed.
+    pub contracts: BTreeSet<B256>,
+}
+
+impl BlockStateDiff {
+    /// Creates the diff from the given [`BundleState`].
+    pub fn from_bundle(bundle: &BundleState) -> Self {
+        let accounts = bundle
+            .state
+            .iter()
+            .filter_map(|(address, account)| {
+                let original = account.original_info.clone().unwrap_or_default();
+                let present = account.info.clone().unwrap_or_default();
+                let AccountInfo { balance, nonce, code_hash, .. } = present;
+
+                let diff = AccountDiff {
+                    created: account.original_info.is_none() && account.info.is_some(),
+                    destroyed: account.was_destroyed(),
+                    balance: Change::new(original.balance, balance),
+                    nonce: Change::new(original.nonce, nonce),
+                    code_hash: Change::new(original.code_hash, code_hash),
+                    storage: account
+                        .storage
+                        .iter()
+                        .filter_map(|(slot, value)| {
+                            Change::new(value.previous_or_original_value, value.present_value)
+                                .map(|change| (*slot, change))
+                        })
+                        .collect(),
+                };
+
+                (!diff.is_empty()).then_some((*address, diff))
+            })
+            .collect();
+
+        Self { accounts, contracts: bundle.contracts.keys().copied().collect() }
+    }
+
+    /// Returns the number of changed accounts.
+    pub fn changed_accounts(&self) -> usize {
+        self.accounts.len()
+    }
+
+    /// Returns the total number of changed storage slots.
+    pub fn changed_storage_slots(&self) -> usize {
+        self.accounts.values().map(|account| account.storage.len()).sum()
+    }
+
+    /// Returns an iterator over the addresses of the accounts created by the block.
+    pub fn created_accounts(&self) -> impl Iterator<Item = &Address> {
+        self.accounts.iter().filter(|(_, diff)| diff.created).map(|(address, _)| address)
+    }
+
+    /// Returns an iterator over the addresses of the accounts destroyed by the block.
+    pub fn destroyed_accounts(&self) -> impl Iterator<Item = &Address> {
+        self.accounts.iter().filter(|(_, diff)| diff.destroyed).map(|(address, _)| address)
+    }
+
+    /// Serializes the diff into compact JSON.
+    ///
+    /// Accounts, storage slots and contracts are sorted, so equal diffs always produce the same
+    /// output.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("state diff is serializable")
+    }
+
+    /// Serializes the diff into pretty-printed JSON, see [`BlockStateDiff::to_json`].
+    #[cfg(feature = "serde")]
+    pub fn to_json_pretty(&self) -> String {
+        serde_json::to_string_pretty(self).expect("state diff is serializable")
+    }
+}
+
+/// Converts the post-execution [`BundleState`] into a sorted [`BlockStateDiff`].
+pub fn state_diff(bundle: &BundleState) -> BlockStateDiff {
+    BlockStateDiff::from_bundle(bundle)
+}
+
+impl From<&BundleState> for BlockStateDiff {
+    fn from(bundle: &BundleState) -> Self {
+        Self::from_bundle(bundle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::BundleStateDB;
+    use alloy_primitives::address;
+    use revm::database::{states::bundle_state::BundleRetention, EmptyDB, State};
+
+    #[test]
+    fn balance_increment_diff() {
+        let beneficiary = address!("0x00000000000000000000000000000000000000aa");
+        let mut state =
+            State::builder().with_database(EmptyDB::default()).with_bundle_update().build();
+        state.increment_balances([(beneficiary, 100)]).unwrap();
+        BundleStateDB::merge_transitions(&mut state, BundleRetention::Reverts);
+
+        let diff = BlockStateDiff::from_bundle(BundleStateDB::bundle_state(&state));
+        assert_eq!(diff.changed_accounts(), 1);
+        assert_eq!(diff.changed_storage_slots(), 0);
+        assert_eq!(diff.created_accounts().collect::<Vec<_>>(), [&beneficiary]);
+
+        let account = &diff.accounts[&beneficiary];
+        assert_eq!(account.balance, Some(Change { from: U256::ZERO, to: U256::from(100) }));
+        assert_eq!(account.nonce, None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_is_sorted() {
+        let mut diff = BlockStateDiff::default();
+        for byte in [3u8, 1, 2] {
+            diff.accounts.insert(
+                Address::with_last_byte(byte),
+                AccountDiff { nonce: Change::new(0, 1), ..Default::default() },
+            );
+        }
+
+        let json = diff.to_json();
+        let first = json.find("0x0000000000000000000000000000000000000001").unwrap();
+        let second = json.find("0x0000000000000000000000000000000000000002").unwrap();
+        let third = json.find("0x0000000000000000000000000000000000000003").unwrap();
+        assert!(first < second && second < third);
+        assert!(!json.contains("balance"));
+
+        let decoded: BlockStateDiff = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, diff);
+    }
+}
@@ -1,13 +1,22 @@
+use alloc::vec::Vec;
+use alloy_primitives::Address;
 use revm::state::EvmState;
 
 /// A hook that is called after each state change.
 pub trait OnStateHook: Send + 'static {
     /// Invoked with the source of the change and the state after each system call.
     fn on_state(&mut self, source: StateChangeSource, state: &EvmState);
+
+    /// Invoked once the executor finished the block successfully.
+    ///
+    /// Hooks batching state changes deliver them here. Changes of blocks that fail to execute are
+    /// never flushed.
+    fn flush(&mut self) {}
 }
 
 /// Source of the state change
 #[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
 pub enum StateChangeSource {
     /// Transaction with its index
     Transaction(usize),
@@ -15,6 +24,8 @@ pub enum StateChangeSource {
     PreBlock(StateChangePreBlockSource),
     /// Post-block state transition
     PostBlock(StateChangePostBlockSource),
+    /// All state changes of the block, see [`HookGranularity::Block`]
+    Block,
 }
 
 /// Source of the pre-block state change
@@ -56,3 +67,194 @@ pub struct NoopHook;
 impl OnStateHook for NoopHook {
     fn on_state(&mut self, _source: StateChangeSource, _state: &EvmState) {}
 }
+
+/// How often a [`ConfiguredStateHook`] invokes the wrapped hook.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HookGranularity {
+    /// After every transaction and system call.
+    #[default]
+    Transaction,
+    /// Once per block, with the changes of all transactions and system calls merged.
+    ///
+    /// The changes are delivered with [`StateChangeSource::Block`] on
+    /// [`OnStateHook::flush`], which the executor calls once the block finished successfully.
+    /// Changes that weren't flushed are discarded when the hook is dropped, e.g. because the
+    /// block failed.
+    Block,
+}
+
+/// An [`OnStateHook`] wrapper that batches state changes and filters them by address.
+///
+/// Configure it and pass it to
+/// [`BlockExecutor::set_state_hook`](super::BlockExecutor::set_state_hook) in place of the
+/// wrapped hook, e.g. for subscribers that only need block-level diffs of a few accounts.
+#[derive(Debug)]
+pub struct ConfiguredStateHook<H: OnStateHook> {
+    inner: H,
+    granularity: HookGranularity,
+    allowlist: Option<Vec<Address>>,
+    pending: EvmState,
+}
+
+impl<H: OnStateHook> ConfiguredStateHook<H> {
+    /// Wraps the hook, passing all changes through by default.
+    pub fn new(inner: H) -> Self {
+        Self {
+            inner,
+            granularity: HookGranularity::default(),
+            allowlist: None,
+            pending: EvmState::default(),
+        }
+    }
+
+    /// Sets the granularity of the calls to the wrapped hook.
+    pub const fn with_granularity(mut self, granularity: HookGranularity) -> Self {
+        self.granularity = granularity;
+        self
+    }
+
+    /// Only passes changes of the given accounts to the wrapped hook.
+    ///
+    /// Calls without changes of these accounts are skipped.
+    pub fn with_allowlist(mut self, addresses: impl IntoIterator<Item = Address>) -> Self {
+        self.allowlist = Some(addresses.into_iter().collect());
+        self
+    }
+
+    fn is_allowed(&self, address: &Address) -> bool {
+        self.allowlist.as_ref().is_none_or(|allowlist| allowlist.contains(address))
+    }
+
+    /// Merges the changes into the pending ones, later changes overriding earlier ones.
+    fn merge(&mut self, state: &EvmState) {
+        for (address, account) in state.iter().filter(|(address, _)| self.is_allowed(address)) {
+            match self.pending.get_mut(address) {
+                Some(pending) if !account.is_selfdestructed() => {
+                    pending.info = account.info.clone();
+                    pending.status |= account.status;
+                    for (slot, value) in &account.storage {
+                        pending
+                            .storage
+                            .entry(*slot)
+                            .and_modify(|pending| pending.present_value = value.present_value)
+                            .or_insert_with(|| value.clone());
+                    }
+                }
+                _ => {
+                    self.pending.insert(*address, account.clone());
+                }
+            }
+        }
+    }
+}
+
+impl<H: OnStateHook> OnStateHook for ConfiguredStateHook<H> {
+    fn on_state(&mut self, source: StateChangeSource, state: &EvmState) {
+        match self.granularity {
+            HookGranularity::Block => self.merge(state),
+            HookGranularity::Transaction if self.allowlist.is_none() => {
+                self.inner.on_state(source, state)
+            }
+            HookGranularity::Transaction => {
+                let filtered: EvmState = state
+                    .iter()
+                    .filter(|(address, _)| self.is_allowed(address))
+                    .map(|(address, account)| (*address, account.clone()))
+                    .collect();
+                if !filtered.is_empty() {
+                    self.inner.on_state(source, &filtered);
+                }
+            }
+        }
+    }
+
+    /// Delivers the changes batched so far, if any, and flushes the wrapped hook.
+    fn flush(&mut self) {
+        if !self.pending.is_empty() {
+            let state = core::mem::take(&mut self.pending);
+            self.inner.on_state(StateChangeSource::Block, &state);
+        }
+        self.inner.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::sync::Arc;
+    use alloy_primitives::U256;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use revm::state::{Account, AccountInfo, EvmStorageSlot};
+
+    /// Records all calls.
+    #[derive(Debug, Default)]
+    struct Recorder(Vec<(StateChangeSource, EvmState)>);
+
+    impl OnStateHook for Recorder {
+        fn on_state(&mut self, source: StateChangeSource, state: &EvmState) {
+            self.0.push((source, state.clone()));
+        }
+    }
+
+    fn state(changes: &[(Address, u64, u64)]) -> EvmState {
+        changes
+            .iter()
+            .map(|(address, balance, slot_value)| {
+                let mut account = Account::from(AccountInfo::from_balance(U256::from(*balance)));
+                account.storage.insert(
+                    U256::ZERO,
+                    EvmStorageSlot::new_changed(U256::ZERO, U256::from(*slot_value), 0),
+                );
+                (*address, account)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_block_granularity_with_allowlist() {
+        let (a, b) = (Address::repeat_byte(0x01), Address::repeat_byte(0x02));
+        let mut hook = ConfiguredStateHook::new(Recorder::default())
+            .with_granularity(HookGranularity::Block)
+            .with_allowlist([a]);
+
+        hook.on_state(StateChangeSource::Transaction(0), &state(&[(a, 1, 1), (b, 1, 1)]));
+        hook.on_state(StateChangeSource::Transaction(1), &state(&[(a, 2, 5)]));
+        assert!(hook.inner.0.is_empty());
+        hook.flush();
+
+        let calls = &hook.inner.0;
+        assert_eq!(calls.len(), 1);
+        let (source, state) = &calls[0];
+        assert!(matches!(source, StateChangeSource::Block));
+        assert_eq!(state.len(), 1);
+        let account = &state[&a];
+        assert_eq!(account.info.balance, U256::from(2));
+        let slot = &account.storage[&U256::ZERO];
+        assert_eq!((slot.original_value, slot.present_value), (U256::ZERO, U256::from(5)));
+    }
+
+    #[test]
+    fn test_block_granularity_discards_on_drop() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let recorded = calls.clone();
+        let mut hook = ConfiguredStateHook::new(move |_, _: &EvmState| {
+            recorded.fetch_add(1, Ordering::Relaxed);
+        })
+        .with_granularity(HookGranularity::Block);
+
+        hook.on_state(StateChangeSource::Transaction(0), &state(&[(Address::ZERO, 1, 1)]));
+        drop(hook);
+        assert_eq!(calls.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_transaction_granularity_with_allowlist() {
+        let (a, b) = (Address::repeat_byte(0x01), Address::repeat_byte(0x02));
+        let mut hook = ConfiguredStateHook::new(Recorder::default()).with_allowlist([a]);
+
+        hook.on_state(StateChangeSource::Transaction(0), &state(&[(a, 1, 1), (b, 1, 1)]));
+        hook.on_state(StateChangeSource::Transaction(1), &state(&[(b, 2, 2)]));
+        assert_eq!(hook.inner.0.len(), 1);
+        assert!(!hook.inner.0[0].1.contains_key(&b));
+    }
+}
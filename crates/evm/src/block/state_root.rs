@@ -0,0 +1,169 @@
+//! Computation of post-execution state roots.
+
+use alloc::collections::BTreeMap;
+use alloy_primitives::{map::AddressMap, Address, B256, U256};
+use alloy_trie::{
+    root::{state_root_unhashed, storage_root_unhashed},
+    TrieAccount,
+};
+use core::convert::Infallible;
+use revm::{database::BundleState, state::AccountInfo};
+
+/// Computes the state root of a block from its post-execution [`BundleState`].
+///
+/// Implementations hold the state of the parent block, e.g. a trie database, and apply the changes
+/// of the bundle on top of it. Closures returning the root for a bundle implement this trait as
+/// well.
+pub trait StateRootProvider {
+    /// Error returned if the state root can't be computed, e.g. because trie nodes are missing.
+    type Error: core::error::Error + Send + Sync + 'static;
+
+    /// Returns the state root after applying `bundle` to the state of the parent block.
+    fn state_root(&mut self, bundle: &BundleState) -> Result<B256, Self::Error>;
+}
+
+impl<F> StateRootProvider for F
+where
+    F: FnMut(&BundleState) -> B256,
+{
+    type Error = Infallible;
+
+    fn state_root(&mut self, bundle: &BundleState) -> Result<B256, Self::Error> {
+        Ok(self(bundle))
+    }
+}
+
+/// An account of an [`InMemoryStateRoot`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct InMemoryAccount {
+    nonce: u64,
+    balance: U256,
+    code_hash: B256,
+    storage: BTreeMap<U256, U256>,
+}
+
+impl InMemoryAccount {
+    fn set_info(&mut self, info: &AccountInfo) {
+        self.nonce = info.nonce;
+        self.balance = info.balance;
+        self.code_hash = info.code_hash;
+    }
+
+    fn trie_account(&self) -> TrieAccount {
+        TrieAccount {
+            nonce: self.nonce,
+            balance: self.balance,
+            storage_root: storage_root_unhashed(
+                self.storage.iter().map(|(slot, value)| (B256::from(*slot), *value)),
+            ),
+            code_hash: self.code_hash,
+        }
+    }
+}
+
+/// A [`StateRootProvider`] keeping the whole state in memory.
+///
+/// Every call applies the bundle to the held state and rebuilds the trie from scratch, so this is
+/// only suitable for tests and small devnets.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryStateRoot {
+    accounts: AddressMap<InMemoryAccount>,
+}
+
+impl InMemoryStateRoot {
+    /// Creates an empty state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts an account of the parent state, e.g. from the genesis allocation.
+    pub fn insert_account(
+        &mut self,
+        address: Address,
+        info: &AccountInfo,
+        storage: impl IntoIterator<Item = (U256, U256)>,
+    ) {
+        let account = self.accounts.entry(address).or_default();
+        account.set_info(info);
+        account.storage.extend(storage.into_iter().filter(|(_, value)| !value.is_zero()));
+    }
+
+    /// Applies the changes of a bundle to the held state.
+    pub fn apply(&mut self, bundle: &BundleState) {
+        for (address, bundle_account) in &bundle.state {
+            let Some(info) = &bundle_account.info else {
+                self.accounts.remove(address);
+                continue;
+            };
+            let account = self.accounts.entry(*address).or_default();
+            if bundle_account.was_destroyed() {
+                account.storage.clear();
+            }
+            account.set_info(info);
+            for (slot, value) in &bundle_account.storage {
+                if value.present_value.is_zero() {
+                    account.storage.remove(slot);
+                } else {
+                    account.storage.insert(*slot, value.present_value);
+                }
+            }
+        }
+    }
+
+    /// Returns the root of the held state.
+    pub fn root(&self) -> B256 {
+        state_root_unhashed(
+            self.accounts.iter().map(|(address, account)| (*address, account.trie_account())),
+        )
+    }
+
+    /// Returns the storage root of an account, if it exists.
+    pub fn storage_root(&self, address: Address) -> Option<B256> {
+        self.accounts.get(&address).map(|account| account.trie_account().storage_root)
+    }
+}
+
+impl StateRootProvider for InMemoryStateRoot {
+    type Error = Infallible;
+
+    fn state_root(&mut self, bundle: &BundleState) -> Result<B256, Self::Error> {
+        self.apply(bundle);
+        Ok(self.root())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, KECCAK256_EMPTY};
+    use alloy_trie::EMPTY_ROOT_HASH;
+    use revm::database::{states::bundle_state::BundleRetention, State};
+
+    #[test]
+    fn test_in_memory_state_root() {
+        let mut provider = InMemoryStateRoot::new();
+        assert_eq!(provider.root(), EMPTY_ROOT_HASH);
+
+        let mut state = State::builder().with_bundle_update().build();
+        let address = address!("0x000000000000000000000000000000000000aaaa");
+        state.increment_balances([(address, 1)]).unwrap();
+        state.merge_transitions(BundleRetention::PlainState);
+
+        let root = provider.state_root(&state.take_bundle()).unwrap();
+        assert_eq!(
+            root,
+            state_root_unhashed([(
+                address,
+                TrieAccount {
+                    nonce: 0,
+                    balance: U256::from(1),
+                    storage_root: EMPTY_ROOT_HASH,
+                    code_hash: KECCAK256_EMPTY,
+                }
+            )])
+        );
+
+        let mut fixed = |_: &BundleState| B256::ZERO;
+        assert_eq!(fixed.state_root(&BundleState::default()), Ok(B256::ZERO));
+    }
+}
@@ -0,0 +1,102 @@
+//! Stateless block execution, e.g. inside zkVM guests.
+
+use crate::{
+    block::{
+        BlockExecutionError, BlockExecutionResult, BlockExecutor, BlockExecutorFactory,
+        ExecutableTxParts,
+    },
+    EvmEnv, EvmFactory,
+};
+use revm::database::{BundleState, State};
+
+/// Executes a block on top of the given pre-state, returning the execution result and the
+/// resulting state changes.
+///
+/// `db` only needs to contain the state accessed by the block, e.g. a witness loaded into a
+/// [`CacheDB`](revm::database::CacheDB), which makes this suitable as the entry point of zkVM
+/// guests. Verifying the witness against the parent state root and computing the post-state root
+/// from the returned [`BundleState`] is left to the caller.
+///
+/// Doesn't use threads, clocks or file I/O, so it is available on zkVM targets.
+pub fn execute_block_stateless<'a, F, DB, T>(
+    factory: &'a F,
+    db: DB,
+    evm_env: EvmEnv<<F::EvmFactory as EvmFactory>::Spec, <F::EvmFactory as EvmFactory>::BlockEnv>,
+    ctx: F::ExecutionCtx<'a>,
+    transactions: impl IntoIterator<Item = T>,
+) -> Result<(BlockExecutionResult<F::Receipt>, BundleState), BlockExecutionError>
+where
+    F: BlockExecutorFactory,
+    DB: revm::Database + 'a,
+    T: ExecutableTxParts<<F::EvmFactory as EvmFactory>::Tx, F::Transaction>,
+{
+    let state = State::builder().with_database(db).with_bundle_update().build();
+    let evm = factory.evm_factory().create_evm(state, evm_env);
+    let mut executor = factory.create_executor(evm, ctx);
+
+    executor.apply_pre_execution_changes()?;
+    for tx in transactions {
+        executor.execute_transaction(tx)?;
+    }
+
+    let (_, result, bundle) = executor.finish_with_bundle()?;
+    Ok((result, bundle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        eth::{
+            receipt_builder::AlloyReceiptBuilder, spec::EthSpec, EthBlockExecutionCtx,
+            EthBlockExecutorFactory,
+        },
+        EthEvmFactory,
+    };
+    use alloc::{borrow::Cow, vec::Vec};
+    use alloy_consensus::{transaction::Recovered, Header, TxEnvelope};
+    use alloy_eips::eip4895::Withdrawal;
+    use alloy_primitives::{Address, Bytes, U256};
+    use revm::database::{CacheDB, EmptyDB};
+
+    #[test]
+    fn test_execute_block_stateless() {
+        let factory =
+            EthBlockExecutorFactory::new(AlloyReceiptBuilder, EthSpec::mainnet(), EthEvmFactory);
+
+        // Shanghai, so that no system contracts are required.
+        let header = Header {
+            number: 17_034_870,
+            timestamp: 1_681_338_455,
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            ..Default::default()
+        };
+        let recipient = Address::repeat_byte(0x42);
+        let withdrawals =
+            [Withdrawal { index: 0, validator_index: 0, address: recipient, amount: 1 }];
+        let ctx = EthBlockExecutionCtx {
+            parent_hash: header.parent_hash,
+            parent_beacon_block_root: None,
+            ommers: &[],
+            withdrawals: Some(Cow::Borrowed(&withdrawals)),
+            extra_data: Bytes::new(),
+            tx_count_hint: Some(0),
+            blob_params: None,
+        };
+
+        let transactions = Vec::<Recovered<TxEnvelope>>::new();
+        let (result, bundle) = execute_block_stateless(
+            &factory,
+            CacheDB::new(EmptyDB::default()),
+            EvmEnv::for_eth_block(&header, EthSpec::mainnet(), 1, None),
+            ctx,
+            transactions.iter(),
+        )
+        .unwrap();
+
+        assert_eq!(result.gas_used, 0);
+        let account = bundle.account(&recipient).unwrap();
+        assert_eq!(account.info.as_ref().unwrap().balance, U256::from(1_000_000_000u64));
+    }
+}
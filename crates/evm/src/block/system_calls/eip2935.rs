@@ -5,9 +5,8 @@ use crate::{
     Evm,
 };
 use alloc::string::ToString;
-use alloy_eips::eip2935::HISTORY_STORAGE_ADDRESS;
 use alloy_hardforks::EthereumHardforks;
-use alloy_primitives::B256;
+use alloy_primitives::{Address, B256};
 use revm::{context::Block, context_interface::result::ResultAndState};
 
 /// Applies the pre-block call to the [EIP-2935] blockhashes contract, using the given block,
@@ -25,6 +24,7 @@ use revm::{context::Block, context_interface::result::ResultAndState};
 #[inline]
 pub(crate) fn transact_blockhashes_contract_call<Halt>(
     spec: impl EthereumHardforks,
+    history_storage: Address,
     parent_block_hash: B256,
     evm: &mut impl Evm<HaltReason = Halt>,
 ) -> Result<Option<ResultAndState<Halt>>, BlockExecutionError> {
@@ -40,7 +40,7 @@ pub(crate) fn transact_blockhashes_contract_call<Halt>(
 
     let res = match evm.transact_system_call(
         alloy_eips::eip4788::SYSTEM_ADDRESS,
-        HISTORY_STORAGE_ADDRESS,
+        history_storage,
         parent_block_hash.0.into(),
     ) {
         Ok(res) => res,
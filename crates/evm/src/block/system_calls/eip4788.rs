@@ -5,9 +5,8 @@ use crate::{
     Evm,
 };
 use alloc::{boxed::Box, string::ToString};
-use alloy_eips::eip4788::BEACON_ROOTS_ADDRESS;
 use alloy_hardforks::EthereumHardforks;
-use alloy_primitives::B256;
+use alloy_primitives::{Address, B256};
 use revm::{context::Block, context_interface::result::ResultAndState};
 
 /// Applies the pre-block call to the [EIP-4788] beacon block root contract, using the given block,
@@ -22,6 +21,7 @@ use revm::{context::Block, context_interface::result::ResultAndState};
 #[inline]
 pub(crate) fn transact_beacon_root_contract_call<Halt>(
     spec: impl EthereumHardforks,
+    beacon_roots: Address,
     parent_beacon_block_root: Option<B256>,
     evm: &mut impl Evm<HaltReason = Halt>,
 ) -> Result<Option<ResultAndState<Halt>>, BlockExecutionError> {
@@ -46,7 +46,7 @@ pub(crate) fn transact_beacon_root_contract_call<Halt>(
 
     let res = match evm.transact_system_call(
         alloy_eips::eip4788::SYSTEM_ADDRESS,
-        BEACON_ROOTS_ADDRESS,
+        beacon_roots,
         parent_beacon_block_root.0.into(),
     ) {
         Ok(res) => res,
@@ -5,8 +5,7 @@ use crate::{
     Evm,
 };
 use alloc::format;
-use alloy_eips::eip7002::WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS;
-use alloy_primitives::Bytes;
+use alloy_primitives::{Address, Bytes};
 use core::fmt::Debug;
 use revm::context_interface::result::{ExecutionResult, ResultAndState};
 
@@ -17,6 +16,7 @@ use revm::context_interface::result::{ExecutionResult, ResultAndState};
 /// Note: this does not commit the state changes to the database, it only transacts the call.
 #[inline]
 pub(crate) fn transact_withdrawal_requests_contract_call<Halt>(
+    withdrawal_requests: Address,
     evm: &mut impl Evm<HaltReason = Halt>,
 ) -> Result<ResultAndState<Halt>, BlockExecutionError> {
     // Execute EIP-7002 withdrawal requests contract call.
@@ -29,7 +29,7 @@ pub(crate) fn transact_withdrawal_requests_contract_call<Halt>(
     // validations), call the contract as `SYSTEM_ADDRESS`.
     let res = match evm.transact_system_call(
         alloy_eips::eip7002::SYSTEM_ADDRESS,
-        WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS,
+        withdrawal_requests,
         Bytes::new(),
     ) {
         Ok(res) => res,
@@ -5,8 +5,7 @@ use crate::{
     Evm,
 };
 use alloc::format;
-use alloy_eips::eip7251::CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS;
-use alloy_primitives::Bytes;
+use alloy_primitives::{Address, Bytes};
 use core::fmt::Debug;
 use revm::context_interface::result::{ExecutionResult, ResultAndState};
 
@@ -18,6 +17,7 @@ use revm::context_interface::result::{ExecutionResult, ResultAndState};
 /// Note: this does not commit the state changes to the database, it only transacts the call.
 #[inline]
 pub(crate) fn transact_consolidation_requests_contract_call<Halt>(
+    consolidation_requests: Address,
     evm: &mut impl Evm<HaltReason = Halt>,
 ) -> Result<ResultAndState<Halt>, BlockExecutionError> {
     // Execute EIP-7251 consolidation requests contract call.
@@ -31,7 +31,7 @@ pub(crate) fn transact_consolidation_requests_contract_call<Halt>(
     // trigger the system subroutine execution.
     let res = match evm.transact_system_call(
         alloy_eips::eip7002::SYSTEM_ADDRESS,
-        CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS,
+        consolidation_requests,
         Bytes::new(),
     ) {
         Ok(res) => res,
@@ -1,17 +1,22 @@
 //! System contract call functions.
 
 use crate::{
-    block::{BlockExecutionError, OnStateHook},
+    block::{BlockExecutionError, BlockValidationError, OnStateHook},
     Evm,
 };
 use alloc::{borrow::Cow, boxed::Box};
 use alloy_consensus::BlockHeader;
 use alloy_eips::{
-    eip7002::WITHDRAWAL_REQUEST_TYPE, eip7251::CONSOLIDATION_REQUEST_TYPE, eip7685::Requests,
+    eip2935::HISTORY_STORAGE_ADDRESS,
+    eip4788::BEACON_ROOTS_ADDRESS,
+    eip7002::{WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS, WITHDRAWAL_REQUEST_TYPE},
+    eip7251::{CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS, CONSOLIDATION_REQUEST_TYPE},
+    eip7685::Requests,
 };
 use alloy_hardforks::EthereumHardforks;
-use alloy_primitives::{Bytes, B256};
-use revm::{state::EvmState, DatabaseCommit};
+use alloy_primitives::{Address, Bytes, B256};
+use core::fmt::Debug;
+use revm::{context_interface::result::ExecutionResult, state::EvmState, DatabaseCommit};
 
 use super::{StateChangePostBlockSource, StateChangePreBlockSource, StateChangeSource};
 
@@ -19,6 +24,47 @@ mod eip2935;
 mod eip4788;
 mod eip7002;
 mod eip7251;
+mod policy;
+pub use policy::{
+    OnSystemCallHook, SystemCall, SystemCallOutcome, SystemCallPolicies, SystemCallPolicy,
+};
+
+/// Addresses of the system contracts called by a [`SystemCaller`].
+///
+/// Defaults to the addresses of the system contracts on Ethereum mainnet. Chains and devnets
+/// deploying them elsewhere must configure their addresses, otherwise the calls go to accounts
+/// without code and silently do nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SystemContracts {
+    /// Address of the [EIP-4788](https://eips.ethereum.org/EIPS/eip-4788) beacon roots contract.
+    pub beacon_roots: Address,
+    /// Address of the [EIP-2935](https://eips.ethereum.org/EIPS/eip-2935) block hashes contract.
+    pub history_storage: Address,
+    /// Address of the [EIP-7002](https://eips.ethereum.org/EIPS/eip-7002) withdrawal requests
+    /// contract.
+    pub withdrawal_requests: Address,
+    /// Address of the [EIP-7251](https://eips.ethereum.org/EIPS/eip-7251) consolidation requests
+    /// contract.
+    pub consolidation_requests: Address,
+}
+
+impl SystemContracts {
+    /// Returns the addresses of the system contracts on Ethereum mainnet.
+    pub const fn mainnet() -> Self {
+        Self {
+            beacon_roots: BEACON_ROOTS_ADDRESS,
+            history_storage: HISTORY_STORAGE_ADDRESS,
+            withdrawal_requests: WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS,
+            consolidation_requests: CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS,
+        }
+    }
+}
+
+impl Default for SystemContracts {
+    fn default() -> Self {
+        Self::mainnet()
+    }
+}
 
 /// An ephemeral helper type for executing system calls.
 ///
@@ -26,16 +72,43 @@ mod eip7251;
 #[derive(derive_more::Debug)]
 pub struct SystemCaller<Spec> {
     spec: Spec,
+    /// Addresses of the called system contracts.
+    contracts: SystemContracts,
+    /// Handling of failed system calls.
+    policies: SystemCallPolicies,
+    /// Outcomes of the system calls made so far, indexed by [`SystemCall`].
+    outcomes: [Option<SystemCallOutcome>; 4],
     /// Optional hook to be called after each state change.
     #[debug(skip)]
     hook: Option<Box<dyn OnStateHook>>,
+    /// Optional hook to be called with failed system calls.
+    #[debug(skip)]
+    system_call_hook: Option<Box<dyn OnSystemCallHook>>,
 }
 
 impl<Spec> SystemCaller<Spec> {
     /// Create a new system caller with the given EVM config, database, and chain spec, and creates
     /// the EVM with the given initialized config and block environment.
     pub const fn new(spec: Spec) -> Self {
-        Self { spec, hook: None }
+        Self {
+            spec,
+            contracts: SystemContracts::mainnet(),
+            policies: SystemCallPolicies::all(SystemCallPolicy::Ignore),
+            outcomes: [None, None, None, None],
+            hook: None,
+            system_call_hook: None,
+        }
+    }
+
+    /// Sets the addresses of the called system contracts.
+    pub const fn set_system_contracts(&mut self, contracts: SystemContracts) -> &mut Self {
+        self.contracts = contracts;
+        self
+    }
+
+    /// Returns the addresses of the called system contracts.
+    pub const fn system_contracts(&self) -> &SystemContracts {
+        &self.contracts
     }
 
     /// Installs a custom hook to be called after each state change.
@@ -43,6 +116,59 @@ impl<Spec> SystemCaller<Spec> {
         self.hook = hook;
         self
     }
+
+    /// Sets how failed system calls are handled.
+    pub const fn set_system_call_policies(&mut self, policies: SystemCallPolicies) -> &mut Self {
+        self.policies = policies;
+        self
+    }
+
+    /// Installs a hook to be called with failed system calls whose policy is
+    /// [`SystemCallPolicy::WarnHook`].
+    pub fn set_system_call_hook(&mut self, hook: Option<Box<dyn OnSystemCallHook>>) -> &mut Self {
+        self.system_call_hook = hook;
+        self
+    }
+
+    /// Returns the outcome of the last call to the given system contract, if it was called.
+    ///
+    /// Calls are skipped if the fork introducing the contract isn't active or for the genesis
+    /// block.
+    pub const fn system_call_outcome(&self, call: SystemCall) -> Option<&SystemCallOutcome> {
+        self.outcomes[call as usize].as_ref()
+    }
+
+    /// Records the outcome of a system call and applies its [`SystemCallPolicy`] if it failed.
+    fn check_outcome<Halt: Debug>(
+        &mut self,
+        call: SystemCall,
+        address: Address,
+        result: &ExecutionResult<Halt>,
+        state: &EvmState,
+    ) -> Result<(), BlockExecutionError> {
+        let outcome = SystemCallOutcome::new(address, result, state);
+        if !outcome.is_success() {
+            match self.policies.get(call) {
+                SystemCallPolicy::Ignore => {}
+                SystemCallPolicy::WarnHook => {
+                    tracing::warn!(?call, %address, ?outcome, "system call failed");
+                    if let Some(hook) = &mut self.system_call_hook {
+                        hook.on_system_call(call, &outcome);
+                    }
+                }
+                SystemCallPolicy::Fail => {
+                    self.outcomes[call as usize] = Some(outcome.clone());
+                    return Err(BlockValidationError::SystemCallFailed {
+                        call,
+                        outcome: Box::new(outcome),
+                    }
+                    .into());
+                }
+            }
+        }
+        self.outcomes[call as usize] = Some(outcome);
+        Ok(())
+    }
 }
 
 impl<Spec> SystemCaller<Spec>
@@ -99,10 +225,20 @@ where
         evm: &mut impl Evm<DB: DatabaseCommit>,
     ) -> Result<(), BlockExecutionError> {
         let _span = tracing::debug_span!("eip2935_blockhashes").entered();
-        let result_and_state =
-            eip2935::transact_blockhashes_contract_call(&self.spec, parent_block_hash, evm)?;
+        let result_and_state = eip2935::transact_blockhashes_contract_call(
+            &self.spec,
+            self.contracts.history_storage,
+            parent_block_hash,
+            evm,
+        )?;
 
         if let Some(res) = result_and_state {
+            self.check_outcome(
+                SystemCall::HistoryStorage,
+                self.contracts.history_storage,
+                &res.result,
+                &res.state,
+            )?;
             if let Some(hook) = &mut self.hook {
                 hook.on_state(
                     StateChangeSource::PreBlock(StateChangePreBlockSource::BlockHashesContract),
@@ -122,10 +258,20 @@ where
         evm: &mut impl Evm<DB: DatabaseCommit>,
     ) -> Result<(), BlockExecutionError> {
         let _span = tracing::debug_span!("eip4788_beacon_root").entered();
-        let result_and_state =
-            eip4788::transact_beacon_root_contract_call(&self.spec, parent_beacon_block_root, evm)?;
+        let result_and_state = eip4788::transact_beacon_root_contract_call(
+            &self.spec,
+            self.contracts.beacon_roots,
+            parent_beacon_block_root,
+            evm,
+        )?;
 
         if let Some(res) = result_and_state {
+            self.check_outcome(
+                SystemCall::BeaconRoots,
+                self.contracts.beacon_roots,
+                &res.result,
+                &res.state,
+            )?;
             if let Some(hook) = &mut self.hook {
                 hook.on_state(
                     StateChangeSource::PreBlock(StateChangePreBlockSource::BeaconRootContract),
@@ -144,7 +290,16 @@ where
         evm: &mut impl Evm<DB: DatabaseCommit>,
     ) -> Result<Bytes, BlockExecutionError> {
         let _span = tracing::debug_span!("eip7002_withdrawal_requests").entered();
-        let result_and_state = eip7002::transact_withdrawal_requests_contract_call(evm)?;
+        let result_and_state = eip7002::transact_withdrawal_requests_contract_call(
+            self.contracts.withdrawal_requests,
+            evm,
+        )?;
+        self.check_outcome(
+            SystemCall::WithdrawalRequests,
+            self.contracts.withdrawal_requests,
+            &result_and_state.result,
+            &result_and_state.state,
+        )?;
 
         if let Some(ref mut hook) = &mut self.hook {
             hook.on_state(
@@ -165,7 +320,16 @@ where
         evm: &mut impl Evm<DB: DatabaseCommit>,
     ) -> Result<Bytes, BlockExecutionError> {
         let _span = tracing::debug_span!("eip7251_consolidation_requests").entered();
-        let result_and_state = eip7251::transact_consolidation_requests_contract_call(evm)?;
+        let result_and_state = eip7251::transact_consolidation_requests_contract_call(
+            self.contracts.consolidation_requests,
+            evm,
+        )?;
+        self.check_outcome(
+            SystemCall::ConsolidationRequests,
+            self.contracts.consolidation_requests,
+            &result_and_state.result,
+            &result_and_state.state,
+        )?;
 
         if let Some(ref mut hook) = &mut self.hook {
             hook.on_state(
@@ -187,6 +351,14 @@ where
         }
     }
 
+    /// Flushes the stored `OnStateHook` once the block finished successfully, noop if hook is
+    /// `None`.
+    pub fn flush_state_hook(&mut self) {
+        if let Some(hook) = &mut self.hook {
+            hook.flush();
+        }
+    }
+
     /// Invokes the state hook with the outcome of the given closure, forwards error if any.
     pub fn try_on_state_with<'a, F, E>(&mut self, f: F) -> Result<(), E>
     where
@@ -219,3 +391,81 @@ where
         self.hook.as_mut().map(f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{eth::spec::EthSpec, EthEvmFactory, EvmEnv, EvmFactory};
+    use alloc::sync::Arc;
+    use alloy_consensus::Header;
+    use alloy_primitives::{bytes, U256};
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use revm::{
+        bytecode::Bytecode,
+        database::{CacheDB, EmptyDB},
+        state::AccountInfo,
+        Database,
+    };
+
+    #[test]
+    fn test_custom_system_contract_address() {
+        let beacon_roots = Address::repeat_byte(0x42);
+        // PUSH0 CALLDATALOAD PUSH0 SSTORE STOP
+        let code = Bytecode::new_raw(bytes!("5f355f5500"));
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(beacon_roots, AccountInfo::from_bytecode(code));
+
+        // first Cancun block on mainnet
+        let header = Header { number: 19_426_587, timestamp: 1_710_338_135, ..Default::default() };
+        let evm_env = EvmEnv::for_eth_block(&header, EthSpec::mainnet(), 1, None);
+        let mut evm = EthEvmFactory::default().create_evm(db, evm_env);
+
+        let mut caller = SystemCaller::new(EthSpec::mainnet());
+        caller.set_system_contracts(SystemContracts { beacon_roots, ..Default::default() });
+        let root = B256::repeat_byte(0x01);
+        caller.apply_beacon_root_contract_call(Some(root), &mut evm).unwrap();
+
+        let stored = evm.db_mut().storage(beacon_roots, U256::ZERO).unwrap();
+        assert_eq!(stored, U256::from_be_bytes(root.0));
+    }
+
+    #[test]
+    fn test_system_call_policy() {
+        let header = Header { number: 19_426_587, timestamp: 1_710_338_135, ..Default::default() };
+        let evm_env = EvmEnv::for_eth_block(&header, EthSpec::mainnet(), 1, None);
+        let mut evm = EthEvmFactory::default().create_evm(CacheDB::<EmptyDB>::default(), evm_env);
+        let root = Some(B256::repeat_byte(0x01));
+
+        let mut caller = SystemCaller::new(EthSpec::mainnet());
+        caller.apply_beacon_root_contract_call(root, &mut evm).unwrap();
+        assert_eq!(
+            caller.system_call_outcome(SystemCall::BeaconRoots),
+            Some(&SystemCallOutcome::MissingCode)
+        );
+
+        let failed = Arc::new(AtomicUsize::new(0));
+        let hook_failed = failed.clone();
+        caller
+            .set_system_call_policies(SystemCallPolicies::all(SystemCallPolicy::WarnHook))
+            .set_system_call_hook(Some(Box::new(move |call, outcome: &SystemCallOutcome| {
+                assert_eq!(call, SystemCall::BeaconRoots);
+                assert_eq!(*outcome, SystemCallOutcome::MissingCode);
+                hook_failed.fetch_add(1, Ordering::Relaxed);
+            })));
+        caller.apply_beacon_root_contract_call(root, &mut evm).unwrap();
+        assert_eq!(failed.load(Ordering::Relaxed), 1);
+
+        caller.set_system_call_policies(SystemCallPolicies {
+            beacon_roots: SystemCallPolicy::Fail,
+            ..Default::default()
+        });
+        let err = caller.apply_beacon_root_contract_call(root, &mut evm).unwrap_err();
+        assert!(matches!(
+            err,
+            BlockExecutionError::Validation(BlockValidationError::SystemCallFailed {
+                call: SystemCall::BeaconRoots,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,138 @@
+//! Outcomes of system calls and how failed calls are handled.
+
+use alloc::{format, string::String};
+use alloy_primitives::{Address, Bytes};
+use core::fmt::Debug;
+use revm::{context_interface::result::ExecutionResult, state::EvmState};
+
+/// A system contract called by a [`SystemCaller`](super::SystemCaller).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SystemCall {
+    /// [EIP-4788](https://eips.ethereum.org/EIPS/eip-4788) beacon roots contract.
+    BeaconRoots,
+    /// [EIP-2935](https://eips.ethereum.org/EIPS/eip-2935) block hashes contract.
+    HistoryStorage,
+    /// [EIP-7002](https://eips.ethereum.org/EIPS/eip-7002) withdrawal requests contract.
+    WithdrawalRequests,
+    /// [EIP-7251](https://eips.ethereum.org/EIPS/eip-7251) consolidation requests contract.
+    ConsolidationRequests,
+}
+
+/// Outcome of a system call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SystemCallOutcome {
+    /// The contract executed successfully.
+    Success {
+        /// Output of the call.
+        output: Bytes,
+    },
+    /// There is no code at the address of the contract, so the call did nothing.
+    MissingCode,
+    /// The call reverted.
+    Reverted {
+        /// Output of the call.
+        output: Bytes,
+    },
+    /// The call halted.
+    Halted {
+        /// Debug representation of the halt reason.
+        reason: String,
+    },
+}
+
+impl SystemCallOutcome {
+    /// Creates the outcome of a call to the contract at `address` from its result and state.
+    pub fn new<Halt: Debug>(
+        address: Address,
+        result: &ExecutionResult<Halt>,
+        state: &EvmState,
+    ) -> Self {
+        match result {
+            ExecutionResult::Success { .. }
+                if state.get(&address).is_none_or(|account| account.info.is_empty_code_hash()) =>
+            {
+                Self::MissingCode
+            }
+            ExecutionResult::Success { output, .. } => {
+                Self::Success { output: output.data().clone() }
+            }
+            ExecutionResult::Revert { output, .. } => Self::Reverted { output: output.clone() },
+            ExecutionResult::Halt { reason, .. } => Self::Halted { reason: format!("{reason:?}") },
+        }
+    }
+
+    /// Returns `true` if the contract executed successfully.
+    pub const fn is_success(&self) -> bool {
+        matches!(self, Self::Success { .. })
+    }
+}
+
+/// How a [`SystemCaller`](super::SystemCaller) handles a system call that didn't succeed, see
+/// [`SystemCallOutcome`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SystemCallPolicy {
+    /// Continue executing the block.
+    #[default]
+    Ignore,
+    /// Log a warning, report the outcome to the [`OnSystemCallHook`] and continue executing the
+    /// block.
+    WarnHook,
+    /// Fail the block with
+    /// [`BlockValidationError::SystemCallFailed`](crate::block::BlockValidationError::SystemCallFailed).
+    Fail,
+}
+
+/// [`SystemCallPolicy`] of every system call.
+///
+/// Defaults to [`SystemCallPolicy::Ignore`] for all calls. Reverted and halted calls to the
+/// withdrawal and consolidation requests contracts always fail the block regardless of the policy,
+/// because the requests of the block can't be determined.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SystemCallPolicies {
+    /// Policy of the beacon roots call.
+    pub beacon_roots: SystemCallPolicy,
+    /// Policy of the block hashes call.
+    pub history_storage: SystemCallPolicy,
+    /// Policy of the withdrawal requests call.
+    pub withdrawal_requests: SystemCallPolicy,
+    /// Policy of the consolidation requests call.
+    pub consolidation_requests: SystemCallPolicy,
+}
+
+impl SystemCallPolicies {
+    /// Applies the same policy to all calls.
+    pub const fn all(policy: SystemCallPolicy) -> Self {
+        Self {
+            beacon_roots: policy,
+            history_storage: policy,
+            withdrawal_requests: policy,
+            consolidation_requests: policy,
+        }
+    }
+
+    /// Returns the policy of the given call.
+    pub const fn get(&self, call: SystemCall) -> SystemCallPolicy {
+        match call {
+            SystemCall::BeaconRoots => self.beacon_roots,
+            SystemCall::HistoryStorage => self.history_storage,
+            SystemCall::WithdrawalRequests => self.withdrawal_requests,
+            SystemCall::ConsolidationRequests => self.consolidation_requests,
+        }
+    }
+}
+
+/// A hook that is called with the outcome of failed system calls whose policy is
+/// [`SystemCallPolicy::WarnHook`].
+pub trait OnSystemCallHook: Send + 'static {
+    /// Invoked with the failed call and its outcome.
+    fn on_system_call(&mut self, call: SystemCall, outcome: &SystemCallOutcome);
+}
+
+impl<F> OnSystemCallHook for F
+where
+    F: FnMut(SystemCall, &SystemCallOutcome) + Send + 'static,
+{
+    fn on_system_call(&mut self, call: SystemCall, outcome: &SystemCallOutcome) {
+        self(call, outcome)
+    }
+}
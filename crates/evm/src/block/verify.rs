@@ -0,0 +1,297 @@
+//! Verification of the execution outcome against a block header.
+//!
+//! Consensus checking pipelines re-execute blocks and compare the outcome with the header. With
+//! [`BlockExecutor::finish_verified`](crate::block::BlockExecutor::finish_verified) the executor
+//! performs these checks itself and reports all divergent fields at once. The same checks back
+//! [`validate_block`](crate::eth::validate::validate_block).
+//!
+//! When the receipts root diverges, [`BlockExecutionResult::receipt_preimages`] returns the exact
+//! bytes hashed into the receipts trie. OP chains hash deposit receipts differently depending on
+//! the active hardfork, use `op::receipts_root` and `op::receipt_preimages` for them.
+
+use crate::block::BlockExecutionResult;
+use alloc::vec::Vec;
+use alloy_consensus::{
+    proofs::calculate_receipt_root, BlockHeader, Eip2718EncodableReceipt, TxReceipt,
+};
+use alloy_primitives::{Bloom, Bytes, B256};
+use core::fmt;
+
+/// The outcome a block is expected to have, usually taken from its header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectedBlockOutcome<'a, R> {
+    /// Expected gas used by the block.
+    pub gas_used: u64,
+    /// Expected blob gas used by the block, not checked if `None`.
+    pub blob_gas_used: Option<u64>,
+    /// Expected receipts root.
+    pub receipts_root: B256,
+    /// Expected logs bloom, not checked if `None`.
+    pub logs_bloom: Option<Bloom>,
+    /// Expected EIP-7685 requests hash, not checked if `None`.
+    pub requests_hash: Option<B256>,
+    /// Expected receipts, used to locate the first divergent receipt on a mismatch.
+    pub receipts: Option<&'a [R]>,
+}
+
+impl<'a, R> ExpectedBlockOutcome<'a, R> {
+    /// Creates the expected outcome from gas used and receipts root.
+    pub const fn new(gas_used: u64, receipts_root: B256) -> Self {
+        Self {
+            gas_used,
+            blob_gas_used: None,
+            receipts_root,
+            logs_bloom: None,
+            requests_hash: None,
+            receipts: None,
+        }
+    }
+
+    /// Creates the expected outcome from the header of the block.
+    pub fn from_header(header: &impl BlockHeader) -> Self {
+        Self::new(header.gas_used(), header.receipts_root())
+            .with_blob_gas_used(header.blob_gas_used())
+            .with_logs_bloom(Some(header.logs_bloom()))
+            .with_requests_hash(header.requests_hash())
+    }
+
+    /// Sets the expected blob gas used.
+    pub const fn with_blob_gas_used(mut self, blob_gas_used: Option<u64>) -> Self {
+        self.blob_gas_used = blob_gas_used;
+        self
+    }
+
+    /// Sets the expected logs bloom.
+    pub const fn with_logs_bloom(mut self, logs_bloom: Option<Bloom>) -> Self {
+        self.logs_bloom = logs_bloom;
+        self
+    }
+
+    /// Sets the expected EIP-7685 requests hash.
+    pub const fn with_requests_hash(mut self, requests_hash: Option<B256>) -> Self {
+        self.requests_hash = requests_hash;
+        self
+    }
+
+    /// Sets the expected receipts, e.g. from a trusted source, to locate the first divergent
+    /// receipt on a mismatch.
+    pub const fn with_receipts(mut self, receipts: &'a [R]) -> Self {
+        self.receipts = Some(receipts);
+        self
+    }
+
+    /// Compares the execution result with the expected outcome.
+    ///
+    /// The receipts root of the result is calculated from the EIP-2718 encoding of its receipts,
+    /// use [`Self::verify_with_receipts_root`] for chains hashing receipts differently.
+    pub fn verify(&self, result: &BlockExecutionResult<R>) -> Result<(), BlockOutcomeMismatch>
+    where
+        R: Eip2718EncodableReceipt + TxReceipt + PartialEq,
+    {
+        self.verify_with_receipts_root(result, calculate_receipt_root(&result.receipts))
+    }
+
+    /// Compares the execution result, whose receipts root is `receipts_root`, with the expected
+    /// outcome.
+    pub fn verify_with_receipts_root(
+        &self,
+        result: &BlockExecutionResult<R>,
+        receipts_root: B256,
+    ) -> Result<(), BlockOutcomeMismatch>
+    where
+        R: TxReceipt + PartialEq,
+    {
+        let mut mismatch = BlockOutcomeMismatch::default();
+
+        if result.gas_used != self.gas_used {
+            mismatch.gas_used = Some(Mismatch { got: result.gas_used, expected: self.gas_used });
+        }
+        if let Some(expected) = self.blob_gas_used {
+            if result.blob_gas_used != expected {
+                mismatch.blob_gas_used = Some(Mismatch { got: result.blob_gas_used, expected });
+            }
+        }
+
+        if receipts_root != self.receipts_root {
+            mismatch.receipts_root =
+                Some(Mismatch { got: receipts_root, expected: self.receipts_root });
+            mismatch.first_divergent_receipt = self.receipts.map(|expected| {
+                result
+                    .receipts
+                    .iter()
+                    .zip(expected)
+                    .position(|(got, expected)| got != expected)
+                    .unwrap_or_else(|| result.receipts.len().min(expected.len()))
+            });
+        }
+
+        if let Some(expected) = self.logs_bloom {
+            let mut got = Bloom::ZERO;
+            for receipt in &result.receipts {
+                got.accrue_bloom(&receipt.bloom());
+            }
+            if got != expected {
+                mismatch.logs_bloom = Some(Mismatch { got, expected });
+            }
+        }
+        if let Some(expected) = self.requests_hash {
+            let got = result.requests.requests_hash();
+            if got != expected {
+                mismatch.requests_hash = Some(Mismatch { got, expected });
+            }
+        }
+
+        if mismatch.is_empty() {
+            Ok(())
+        } else {
+            Err(mismatch)
+        }
+    }
+}
+
+impl<R: Eip2718EncodableReceipt> BlockExecutionResult<R> {
+    /// Returns the EIP-2718 encoding with bloom of every receipt, which is the value of the
+    /// receipt in the receipts trie.
+    pub fn receipt_preimages(&self) -> Vec<Bytes> {
+        receipt_preimages(&self.receipts)
+    }
+}
+
+/// Returns the EIP-2718 encoding with bloom of every receipt.
+pub(crate) fn receipt_preimages<R: Eip2718EncodableReceipt>(receipts: &[R]) -> Vec<Bytes> {
+    receipts
+        .iter()
+        .map(|receipt| {
+            let mut out = Vec::with_capacity(receipt.eip2718_encoded_length_with_bloom());
+            receipt.eip2718_encode_with_bloom(&mut out);
+            out.into()
+        })
+        .collect()
+}
+
+/// A value that differs from the expected one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mismatch<T> {
+    /// The value produced by execution.
+    pub got: T,
+    /// The expected value.
+    pub expected: T,
+}
+
+impl<T: fmt::Display> fmt::Display for Mismatch<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "got {}, expected {}", self.got, self.expected)
+    }
+}
+
+/// Fields of an execution outcome that differ from the [`ExpectedBlockOutcome`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockOutcomeMismatch {
+    /// Mismatch of the gas used.
+    pub gas_used: Option<Mismatch<u64>>,
+    /// Mismatch of the blob gas used.
+    pub blob_gas_used: Option<Mismatch<u64>>,
+    /// Mismatch of the receipts root.
+    pub receipts_root: Option<Mismatch<B256>>,
+    /// Mismatch of the logs bloom.
+    pub logs_bloom: Option<Mismatch<Bloom>>,
+    /// Mismatch of the EIP-7685 requests hash.
+    pub requests_hash: Option<Mismatch<B256>>,
+    /// Index of the first receipt differing from the expected receipts, if they were provided.
+    ///
+    /// If all receipts match but their number differs, this is the index of the first missing or
+    /// extra receipt.
+    pub first_divergent_receipt: Option<usize>,
+}
+
+impl BlockOutcomeMismatch {
+    /// Returns `true` if no field differs.
+    pub const fn is_empty(&self) -> bool {
+        self.gas_used.is_none()
+            && self.blob_gas_used.is_none()
+            && self.receipts_root.is_none()
+            && self.logs_bloom.is_none()
+            && self.requests_hash.is_none()
+    }
+}
+
+impl fmt::Display for BlockOutcomeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut fields = Vec::new();
+        if let Some(mismatch) = &self.gas_used {
+            fields.push(alloc::format!("gas used: {mismatch}"));
+        }
+        if let Some(mismatch) = &self.blob_gas_used {
+            fields.push(alloc::format!("blob gas used: {mismatch}"));
+        }
+        if let Some(mismatch) = &self.receipts_root {
+            fields.push(alloc::format!("receipts root: {mismatch}"));
+        }
+        if let Some(mismatch) = &self.logs_bloom {
+            fields.push(alloc::format!("logs bloom: {mismatch}"));
+        }
+        if let Some(mismatch) = &self.requests_hash {
+            fields.push(alloc::format!("requests hash: {mismatch}"));
+        }
+        if let Some(index) = self.first_divergent_receipt {
+            fields.push(alloc::format!("first divergent receipt: {index}"));
+        }
+        f.write_str(&fields.join("; "))
+    }
+}
+
+impl core::error::Error for BlockOutcomeMismatch {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use alloy_consensus::{Receipt, ReceiptEnvelope};
+
+    fn receipt(cumulative_gas_used: u64) -> ReceiptEnvelope {
+        ReceiptEnvelope::Legacy(
+            Receipt { status: true.into(), cumulative_gas_used, logs: vec![] }.with_bloom(),
+        )
+    }
+
+    #[test]
+    fn test_verify_outcome() {
+        let receipts = vec![receipt(21_000), receipt(42_000), receipt(63_000)];
+        let result = BlockExecutionResult {
+            receipts: receipts.clone(),
+            gas_used: 63_000,
+            ..Default::default()
+        };
+        let root = calculate_receipt_root(&receipts);
+
+        let expected = ExpectedBlockOutcome::new(63_000, root).with_blob_gas_used(Some(0));
+        assert_eq!(expected.verify(&result), Ok(()));
+
+        let expected_receipts = vec![receipt(21_000), receipt(43_000), receipt(64_000)];
+        let expected =
+            ExpectedBlockOutcome::new(64_000, calculate_receipt_root(&expected_receipts))
+                .with_receipts(&expected_receipts);
+        let mismatch = expected.verify(&result).unwrap_err();
+        assert_eq!(mismatch.gas_used, Some(Mismatch { got: 63_000, expected: 64_000 }));
+        assert_eq!(mismatch.blob_gas_used, None);
+        assert!(mismatch.receipts_root.is_some());
+        assert_eq!(mismatch.first_divergent_receipt, Some(1));
+
+        let expected = ExpectedBlockOutcome::new(63_000, B256::ZERO).with_receipts(&receipts[..2]);
+        assert_eq!(expected.verify(&result).unwrap_err().first_divergent_receipt, Some(2));
+
+        let expected = ExpectedBlockOutcome::new(63_000, root)
+            .with_logs_bloom(Some(Bloom::repeat_byte(0x01)))
+            .with_requests_hash(Some(B256::ZERO));
+        let mismatch = expected.verify(&result).unwrap_err();
+        assert_eq!(
+            mismatch.logs_bloom,
+            Some(Mismatch { got: Bloom::ZERO, expected: Bloom::repeat_byte(0x01) })
+        );
+        assert_eq!(
+            mismatch.requests_hash,
+            Some(Mismatch { got: result.requests.requests_hash(), expected: B256::ZERO })
+        );
+        assert_eq!(mismatch.receipts_root, None);
+    }
+}
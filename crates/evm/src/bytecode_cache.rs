@@ -0,0 +1,263 @@
+//! Cache of analyzed bytecode shared across executions.
+//!
+//! Bytecode read from a database is analyzed, e.g. for valid jump destinations, before it can be
+//! executed. Caches like [`State`](revm::database::State) only live for a single block or call, so
+//! hot contracts are analyzed over and over. An [`AnalyzedBytecodeCache`] outlives them: it is
+//! cheap to clone and can be shared by all EVMs of a process via [`CachedBytecodeDatabase`] or
+//! [`EvmFactoryExt::create_evm_with_bytecode_cache`](crate::EvmFactoryExt::create_evm_with_bytecode_cache).
+
+use alloy_primitives::{
+    map::{B256Map, HashMap},
+    Address, B256, U256,
+};
+use revm::{
+    bytecode::Bytecode,
+    state::{Account, AccountInfo},
+    Database, DatabaseCommit, DatabaseRef,
+};
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+/// Limits of an [`AnalyzedBytecodeCache`].
+///
+/// Once a limit is exceeded, the oldest entries are evicted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BytecodeCacheLimits {
+    /// Maximum number of cached bytecodes.
+    pub max_entries: usize,
+    /// Maximum total size of the cached bytecodes in bytes.
+    pub max_bytes: usize,
+}
+
+impl Default for BytecodeCacheLimits {
+    fn default() -> Self {
+        Self { max_entries: 10_000, max_bytes: 256 * 1024 * 1024 }
+    }
+}
+
+/// Statistics of an [`AnalyzedBytecodeCache`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BytecodeCacheStats {
+    /// Number of lookups served from the cache.
+    pub hits: u64,
+    /// Number of lookups that missed the cache.
+    pub misses: u64,
+    /// Number of cached bytecodes.
+    pub entries: usize,
+    /// Total size of the cached bytecodes in bytes.
+    pub bytes: usize,
+}
+
+impl BytecodeCacheStats {
+    /// Returns the share of lookups served from the cache, or `None` if there were no lookups.
+    pub fn hit_rate(&self) -> Option<f64> {
+        let lookups = self.hits + self.misses;
+        (lookups > 0).then(|| self.hits as f64 / lookups as f64)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    codes: B256Map<Bytecode>,
+    /// Code hashes in insertion order, used for eviction.
+    order: VecDeque<B256>,
+    bytes: usize,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    entries: Mutex<Entries>,
+    limits: BytecodeCacheLimits,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// Analyzed bytecode by code hash, shared by all clones.
+#[derive(Debug, Clone, Default)]
+pub struct AnalyzedBytecodeCache {
+    inner: Arc<Inner>,
+}
+
+impl AnalyzedBytecodeCache {
+    /// Creates an empty cache with the given limits.
+    pub fn new(limits: BytecodeCacheLimits) -> Self {
+        Self { inner: Arc::new(Inner { limits, ..Default::default() }) }
+    }
+
+    /// Returns the limits of the cache.
+    pub fn limits(&self) -> BytecodeCacheLimits {
+        self.inner.limits
+    }
+
+    /// Returns the cached bytecode for the code hash.
+    pub fn get(&self, code_hash: &B256) -> Option<Bytecode> {
+        let code = self.entries().codes.get(code_hash).cloned();
+        let counter = if code.is_some() { &self.inner.hits } else { &self.inner.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        code
+    }
+
+    /// Inserts bytecode, evicting the oldest entries if a limit is exceeded.
+    ///
+    /// Bytecode larger than the byte limit is not cached.
+    pub fn insert(&self, code_hash: B256, code: Bytecode) {
+        let limits = self.inner.limits;
+        let size = code.original_byte_slice().len();
+        if limits.max_entries == 0 || size > limits.max_bytes {
+            return;
+        }
+
+        let mut entries = self.entries();
+        if entries.codes.contains_key(&code_hash) {
+            return;
+        }
+        while entries.codes.len() >= limits.max_entries || entries.bytes + size > limits.max_bytes {
+            let Some(oldest) = entries.order.pop_front() else { break };
+            if let Some(evicted) = entries.codes.remove(&oldest) {
+                entries.bytes -= evicted.original_byte_slice().len();
+            }
+        }
+        entries.codes.insert(code_hash, code);
+        entries.order.push_back(code_hash);
+        entries.bytes += size;
+    }
+
+    /// Removes all entries, keeping the statistics.
+    pub fn clear(&self) {
+        *self.entries() = Entries::default();
+    }
+
+    /// Returns the statistics of the cache.
+    pub fn stats(&self) -> BytecodeCacheStats {
+        let entries = self.entries();
+        BytecodeCacheStats {
+            hits: self.inner.hits.load(Ordering::Relaxed),
+            misses: self.inner.misses.load(Ordering::Relaxed),
+            entries: entries.codes.len(),
+            bytes: entries.bytes,
+        }
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, Entries> {
+        self.inner.entries.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// A database serving bytecode from an [`AnalyzedBytecodeCache`], populating it on misses.
+#[derive(Debug, Clone)]
+pub struct CachedBytecodeDatabase<DB> {
+    db: DB,
+    cache: AnalyzedBytecodeCache,
+}
+
+impl<DB> CachedBytecodeDatabase<DB> {
+    /// Wraps the database.
+    pub const fn new(db: DB, cache: AnalyzedBytecodeCache) -> Self {
+        Self { db, cache }
+    }
+
+    /// Returns the bytecode cache.
+    pub const fn cache(&self) -> &AnalyzedBytecodeCache {
+        &self.cache
+    }
+
+    /// Consumes the wrapper, returning the wrapped database.
+    pub fn into_inner(self) -> DB {
+        self.db
+    }
+}
+
+impl<DB: Database> Database for CachedBytecodeDatabase<DB> {
+    type Error = DB::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.db.basic(address)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        if let Some(code) = self.cache.get(&code_hash) {
+            return Ok(code);
+        }
+        let code = self.db.code_by_hash(code_hash)?;
+        self.cache.insert(code_hash, code.clone());
+        Ok(code)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.db.storage(address, index)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.db.block_hash(number)
+    }
+}
+
+impl<DB: DatabaseRef> DatabaseRef for CachedBytecodeDatabase<DB> {
+    type Error = DB::Error;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.db.basic_ref(address)
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        if let Some(code) = self.cache.get(&code_hash) {
+            return Ok(code);
+        }
+        let code = self.db.code_by_hash_ref(code_hash)?;
+        self.cache.insert(code_hash, code.clone());
+        Ok(code)
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.db.storage_ref(address, index)
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        self.db.block_hash_ref(number)
+    }
+}
+
+impl<DB: DatabaseCommit> DatabaseCommit for CachedBytecodeDatabase<DB> {
+    fn commit(&mut self, changes: HashMap<Address, Account>) {
+        self.db.commit(changes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::bytes;
+    use revm::database::{CacheDB, EmptyDB};
+
+    #[test]
+    fn test_bytecode_cache() {
+        let cache =
+            AnalyzedBytecodeCache::new(BytecodeCacheLimits { max_entries: 2, max_bytes: 1024 });
+        let codes = [bytes!("6000"), bytes!("6001"), bytes!("6002")].map(Bytecode::new_raw);
+
+        let mut db = CacheDB::new(EmptyDB::default());
+        for (i, code) in codes.iter().enumerate() {
+            db.insert_account_info(
+                Address::with_last_byte(i as u8),
+                AccountInfo::default().with_code(code.clone()),
+            );
+        }
+        let mut db = CachedBytecodeDatabase::new(db, cache.clone());
+
+        assert_eq!(db.code_by_hash(codes[0].hash_slow()).unwrap(), codes[0]);
+        assert_eq!(db.code_by_hash(codes[0].hash_slow()).unwrap(), codes[0]);
+        assert_eq!(cache.stats().hit_rate(), Some(0.5));
+
+        db.code_by_hash(codes[1].hash_slow()).unwrap();
+        db.code_by_hash(codes[2].hash_slow()).unwrap();
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.bytes), (2, 4));
+        assert!(cache.get(&codes[0].hash_slow()).is_none());
+        assert!(cache.get(&codes[2].hash_slow()).is_some());
+    }
+}
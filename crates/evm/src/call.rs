@@ -1,6 +1,7 @@
 //! Utilities for dealing with eth_call and adjacent RPC endpoints.
 
-use alloy_primitives::U256;
+use alloy_primitives::{Keccak256, B256, U256};
+use core::hash::{Hash, Hasher};
 use revm::Database;
 
 /// Insufficient funds error
@@ -56,3 +57,200 @@ where
         .unwrap_or_default()
         .saturating_to())
 }
+
+/// Key of a [`CallCache`] entry.
+///
+/// Identifies a call by the state it is executed on, e.g. the state root or hash of the block, the
+/// transaction environment and any state or block overrides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CallCacheKey {
+    state: B256,
+    call: B256,
+}
+
+impl CallCacheKey {
+    /// Creates the key of a call.
+    ///
+    /// Overrides that don't implement [`Hash`] can be passed as a digest computed by the caller.
+    pub fn new(state: B256, tx: &impl Hash, overrides: &impl Hash) -> Self {
+        let mut hasher = KeccakHasher(Keccak256::new());
+        tx.hash(&mut hasher);
+        overrides.hash(&mut hasher);
+        Self { state, call: hasher.0.finalize() }
+    }
+
+    /// Returns the identity of the state the call is executed on.
+    pub const fn state(&self) -> B256 {
+        self.state
+    }
+}
+
+/// [`Hasher`] feeding all written bytes into keccak256.
+struct KeccakHasher(Keccak256);
+
+impl Hasher for KeccakHasher {
+    fn finish(&self) -> u64 {
+        let digest = self.0.clone().finalize();
+        u64::from_be_bytes(digest[..8].try_into().expect("digest has 32 bytes"))
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+}
+
+/// Memoizes results of call-style executions, like `eth_call` or `eth_estimateGas`.
+///
+/// Entries expire after a time-to-live and can be invalidated by state, e.g. once a block is
+/// reorged out. Once the cache is full, expired entries are dropped first, then the least recently
+/// inserted ones.
+#[cfg(all(feature = "std", not(target_os = "zkvm")))]
+#[derive(Debug)]
+pub struct CallCache<V> {
+    entries: std::sync::Mutex<alloy_primitives::map::HashMap<CallCacheKey, CallCacheEntry<V>>>,
+    insertions: core::sync::atomic::AtomicU64,
+    ttl: core::time::Duration,
+    max_entries: usize,
+}
+
+#[cfg(all(feature = "std", not(target_os = "zkvm")))]
+#[derive(Debug)]
+struct CallCacheEntry<V> {
+    value: V,
+    inserted_at: crate::time::Instant,
+    /// Insertion order of the entry, used for eviction since instants may not be distinct.
+    insertion: u64,
+}
+
+#[cfg(all(feature = "std", not(target_os = "zkvm")))]
+impl<V: Clone> CallCache<V> {
+    /// Creates a cache holding up to `max_entries` results for `ttl` each.
+    pub fn new(ttl: core::time::Duration, max_entries: usize) -> Self {
+        Self { entries: Default::default(), insertions: Default::default(), ttl, max_entries }
+    }
+
+    /// Returns the cached result of the call, if it hasn't expired yet.
+    pub fn get(&self, key: &CallCacheKey) -> Option<V> {
+        let mut entries = self.entries();
+        let entry = entries.get(key)?;
+        if entry.inserted_at.elapsed() < self.ttl {
+            return Some(entry.value.clone());
+        }
+        entries.remove(key);
+        None
+    }
+
+    /// Caches the result of a call.
+    pub fn insert(&self, key: CallCacheKey, value: V) {
+        if self.max_entries == 0 {
+            return;
+        }
+        let mut entries = self.entries();
+        if entries.len() >= self.max_entries && !entries.contains_key(&key) {
+            entries.retain(|_, entry| entry.inserted_at.elapsed() < self.ttl);
+            if entries.len() >= self.max_entries {
+                let oldest = entries.iter().min_by_key(|(_, entry)| entry.insertion);
+                if let Some(oldest) = oldest.map(|(key, _)| *key) {
+                    entries.remove(&oldest);
+                }
+            }
+        }
+        let insertion = self.insertions.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+        entries.insert(
+            key,
+            CallCacheEntry { value, inserted_at: crate::time::Instant::now(), insertion },
+        );
+    }
+
+    /// Returns the cached result of the call, or executes it with `f` and caches the result if it
+    /// succeeds.
+    pub fn get_or_try_insert_with<E>(
+        &self,
+        key: CallCacheKey,
+        f: impl FnOnce() -> Result<V, E>,
+    ) -> Result<V, E> {
+        if let Some(value) = self.get(&key) {
+            return Ok(value);
+        }
+        let value = f()?;
+        self.insert(key, value.clone());
+        Ok(value)
+    }
+
+    /// Removes all results of calls executed on the given state.
+    pub fn invalidate_state(&self, state: B256) {
+        self.entries().retain(|key, _| key.state != state);
+    }
+
+    /// Removes all results.
+    pub fn clear(&self) {
+        self.entries().clear();
+    }
+
+    /// Returns the number of cached results, including expired ones.
+    pub fn len(&self) -> usize {
+        self.entries().len()
+    }
+
+    /// Returns `true` if no results are cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries().is_empty()
+    }
+
+    fn entries(
+        &self,
+    ) -> std::sync::MutexGuard<'_, alloy_primitives::map::HashMap<CallCacheKey, CallCacheEntry<V>>>
+    {
+        self.entries.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+#[cfg(all(test, feature = "std", not(target_os = "zkvm")))]
+mod tests {
+    use super::*;
+    use core::time::Duration;
+
+    #[test]
+    fn test_call_cache() {
+        let cache = CallCache::new(Duration::from_secs(60), 2);
+        let state = B256::repeat_byte(0x01);
+        let key = CallCacheKey::new(state, &(1u64, "call"), &());
+        assert_eq!(key, CallCacheKey::new(state, &(1u64, "call"), &()));
+        assert_ne!(key, CallCacheKey::new(state, &(1u64, "call"), &Some(1u8)));
+
+        let mut executions = 0;
+        for _ in 0..2 {
+            let value = cache.get_or_try_insert_with(key, || {
+                executions += 1;
+                Ok::<_, ()>(42)
+            });
+            assert_eq!(value, Ok(42));
+        }
+        assert_eq!(executions, 1);
+
+        // the least recently inserted result is evicted
+        let second = CallCacheKey::new(B256::ZERO, &2u64, &());
+        cache.insert(second, 1);
+        cache.insert(CallCacheKey::new(B256::ZERO, &3u64, &()), 2);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&key), None);
+        assert_eq!(cache.get(&second), Some(1));
+
+        cache.invalidate_state(B256::ZERO);
+        assert!(cache.is_empty());
+
+        let cache = CallCache::new(Duration::ZERO, 2);
+        cache.insert(key, 1);
+        assert_eq!(cache.get(&key), None);
+    }
+
+    #[test]
+    fn test_keccak_hasher() {
+        let mut hasher = KeccakHasher(Keccak256::new());
+        hasher.write(b"call");
+        let expected = alloy_primitives::keccak256(b"call");
+        assert_eq!(hasher.finish().to_be_bytes(), expected[..8]);
+        // finishing doesn't consume the hasher
+        assert_eq!(hasher.0.finalize(), expected);
+    }
+}
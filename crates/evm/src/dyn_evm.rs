@@ -0,0 +1,385 @@
+//! Type-erased wrappers around [`Evm`] and [`BlockExecutor`].
+//!
+//! [`DynEvm`] and [`DynBlockExecutor`] hide the database, inspector, precompiles and chain
+//! specific types behind trait objects. Transactions are passed as plain [`TxEnv`] together with
+//! their EIP-2718 encoding, errors are boxed, halt reasons are reduced to their [`HaltKind`] and
+//! receipts are converted into [`DynReceipt`]. This costs a dynamic dispatch per call and some
+//! conversions, but makes it possible to store and pass around executors of different chains behind
+//! a single type, e.g. in plugin systems or at FFI boundaries.
+
+use crate::{
+    block::{BlockExecutionError, BlockExecutionResult, BlockExecutor},
+    Evm, EvmHaltReason, HaltKind,
+};
+use alloc::{boxed::Box, vec::Vec};
+use alloy_consensus::{transaction::Recovered, ReceiptEnvelope};
+use alloy_primitives::{Address, Bytes};
+use core::{error::Error, fmt};
+use revm::{
+    context::{
+        result::{ExecutionResult, ResultAndState},
+        TxEnv,
+    },
+    DatabaseCommit,
+};
+
+/// Boxed error returned by [`DynEvm`].
+pub type DynError = Box<dyn Error + Send + Sync + 'static>;
+
+/// EVM transaction types that can be built from a plain [`TxEnv`].
+pub trait FromTxEnv {
+    /// Builds the transaction from the [`TxEnv`] and the EIP-2718 encoding of the transaction.
+    ///
+    /// Chains that charge for the transaction data, like OP stack chains, compute the fee from
+    /// `encoded`, so it must be the actual encoding of the transaction.
+    fn from_tx_env(tx: TxEnv, encoded: Bytes) -> Self;
+}
+
+impl FromTxEnv for TxEnv {
+    fn from_tx_env(tx: TxEnv, _encoded: Bytes) -> Self {
+        tx
+    }
+}
+
+#[cfg(feature = "op")]
+impl FromTxEnv for op_revm::OpTransaction<TxEnv> {
+    fn from_tx_env(tx: TxEnv, encoded: Bytes) -> Self {
+        Self { base: tx, enveloped_tx: Some(encoded), deposit: Default::default() }
+    }
+}
+
+/// Object-safe subset of [`Evm`].
+trait ErasedEvm {
+    fn chain_id(&self) -> u64;
+
+    fn transact(&mut self, tx: TxEnv, encoded: Bytes)
+        -> Result<ResultAndState<HaltKind>, DynError>;
+
+    fn transact_commit(
+        &mut self,
+        tx: TxEnv,
+        encoded: Bytes,
+    ) -> Result<ExecutionResult<HaltKind>, DynError>;
+
+    fn transact_system_call(
+        &mut self,
+        caller: Address,
+        contract: Address,
+        data: Bytes,
+    ) -> Result<ResultAndState<HaltKind>, DynError>;
+
+    fn set_inspector_enabled(&mut self, enabled: bool);
+}
+
+/// Reduces the halt reason of the result to its [`HaltKind`].
+fn erase_result<H: EvmHaltReason>(result: ResultAndState<H>) -> ResultAndState<HaltKind> {
+    let ResultAndState { result, state } = result;
+    ResultAndState { result: result.map_haltreason(|reason| reason.halt_kind()), state }
+}
+
+impl<E> ErasedEvm for E
+where
+    E: Evm<Tx: FromTxEnv, HaltReason: EvmHaltReason, DB: DatabaseCommit>,
+{
+    fn chain_id(&self) -> u64 {
+        Evm::chain_id(self)
+    }
+
+    fn transact(
+        &mut self,
+        tx: TxEnv,
+        encoded: Bytes,
+    ) -> Result<ResultAndState<HaltKind>, DynError> {
+        Ok(erase_result(self.transact_raw(E::Tx::from_tx_env(tx, encoded))?))
+    }
+
+    fn transact_commit(
+        &mut self,
+        tx: TxEnv,
+        encoded: Bytes,
+    ) -> Result<ExecutionResult<HaltKind>, DynError> {
+        let ResultAndState { result, state } = ErasedEvm::transact(self, tx, encoded)?;
+        self.db_mut().commit(state);
+        Ok(result)
+    }
+
+    fn transact_system_call(
+        &mut self,
+        caller: Address,
+        contract: Address,
+        data: Bytes,
+    ) -> Result<ResultAndState<HaltKind>, DynError> {
+        Ok(erase_result(Evm::transact_system_call(self, caller, contract, data)?))
+    }
+
+    fn set_inspector_enabled(&mut self, enabled: bool) {
+        Evm::set_inspector_enabled(self, enabled);
+    }
+}
+
+/// A type-erased [`Evm`].
+pub struct DynEvm<'a> {
+    inner: Box<dyn ErasedEvm + 'a>,
+}
+
+impl fmt::Debug for DynEvm<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynEvm").field("chain_id", &self.chain_id()).finish_non_exhaustive()
+    }
+}
+
+impl<'a> DynEvm<'a> {
+    /// Erases the type of the EVM.
+    pub fn new<E>(evm: E) -> Self
+    where
+        E: Evm<Tx: FromTxEnv, HaltReason: EvmHaltReason, DB: DatabaseCommit> + 'a,
+    {
+        Self { inner: Box::new(evm) }
+    }
+
+    /// Returns the chain ID of the environment.
+    pub fn chain_id(&self) -> u64 {
+        self.inner.chain_id()
+    }
+
+    /// Executes a transaction without committing its state changes, see [`Evm::transact`].
+    ///
+    /// `encoded` is the EIP-2718 encoding of the transaction, see [`FromTxEnv::from_tx_env`].
+    pub fn transact(
+        &mut self,
+        tx: TxEnv,
+        encoded: Bytes,
+    ) -> Result<ResultAndState<HaltKind>, DynError> {
+        self.inner.transact(tx, encoded)
+    }
+
+    /// Executes a transaction and commits its state changes, see [`Evm::transact_commit`].
+    ///
+    /// `encoded` is the EIP-2718 encoding of the transaction, see [`FromTxEnv::from_tx_env`].
+    pub fn transact_commit(
+        &mut self,
+        tx: TxEnv,
+        encoded: Bytes,
+    ) -> Result<ExecutionResult<HaltKind>, DynError> {
+        self.inner.transact_commit(tx, encoded)
+    }
+
+    /// Executes a system call, see [`Evm::transact_system_call`].
+    pub fn transact_system_call(
+        &mut self,
+        caller: Address,
+        contract: Address,
+        data: Bytes,
+    ) -> Result<ResultAndState<HaltKind>, DynError> {
+        self.inner.transact_system_call(caller, contract, data)
+    }
+
+    /// Enables or disables the inspector, see [`Evm::set_inspector_enabled`].
+    pub fn set_inspector_enabled(&mut self, enabled: bool) {
+        self.inner.set_inspector_enabled(enabled);
+    }
+}
+
+/// Receipt of any of the supported chains.
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::From)]
+pub enum DynReceipt {
+    /// Ethereum receipt.
+    Eth(ReceiptEnvelope),
+    /// OP stack receipt.
+    #[cfg(feature = "op")]
+    Op(op_alloy::consensus::OpReceiptEnvelope),
+}
+
+/// Object-safe subset of [`BlockExecutor`].
+trait ErasedBlockExecutor<T> {
+    fn apply_pre_execution_changes(&mut self) -> Result<(), BlockExecutionError>;
+
+    fn execute_transaction(&mut self, tx: &Recovered<T>) -> Result<u64, BlockExecutionError>;
+
+    fn receipts(&self) -> Vec<DynReceipt>;
+
+    fn finish(self: Box<Self>) -> Result<BlockExecutionResult<DynReceipt>, BlockExecutionError>;
+}
+
+impl<E, T> ErasedBlockExecutor<T> for E
+where
+    E: BlockExecutor<Transaction = T, Receipt: Clone + Into<DynReceipt>>,
+{
+    fn apply_pre_execution_changes(&mut self) -> Result<(), BlockExecutionError> {
+        BlockExecutor::apply_pre_execution_changes(self)
+    }
+
+    fn execute_transaction(&mut self, tx: &Recovered<T>) -> Result<u64, BlockExecutionError> {
+        BlockExecutor::execute_transaction(self, tx)
+    }
+
+    fn receipts(&self) -> Vec<DynReceipt> {
+        BlockExecutor::receipts(self).iter().cloned().map(Into::into).collect()
+    }
+
+    fn finish(self: Box<Self>) -> Result<BlockExecutionResult<DynReceipt>, BlockExecutionError> {
+        let BlockExecutionResult { receipts, requests, gas_used, blob_gas_used, da_footprint_used } =
+            BlockExecutor::finish(*self)?.1;
+        Ok(BlockExecutionResult {
+            receipts: receipts.into_iter().map(Into::into).collect(),
+            requests,
+            gas_used,
+            blob_gas_used,
+            da_footprint_used,
+        })
+    }
+}
+
+/// A type-erased [`BlockExecutor`] executing transactions of type `T`.
+pub struct DynBlockExecutor<'a, T> {
+    inner: Box<dyn ErasedBlockExecutor<T> + 'a>,
+}
+
+impl<T> fmt::Debug for DynBlockExecutor<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynBlockExecutor").finish_non_exhaustive()
+    }
+}
+
+impl<'a, T> DynBlockExecutor<'a, T> {
+    /// Erases the type of the executor.
+    pub fn new<E>(executor: E) -> Self
+    where
+        E: BlockExecutor<Transaction = T, Receipt: Clone + Into<DynReceipt>> + 'a,
+    {
+        Self { inner: Box::new(executor) }
+    }
+
+    /// Applies the pre-execution changes, see [`BlockExecutor::apply_pre_execution_changes`].
+    pub fn apply_pre_execution_changes(&mut self) -> Result<(), BlockExecutionError> {
+        self.inner.apply_pre_execution_changes()
+    }
+
+    /// Executes a transaction and commits it, returning the gas used by it.
+    pub fn execute_transaction(&mut self, tx: &Recovered<T>) -> Result<u64, BlockExecutionError> {
+        self.inner.execute_transaction(tx)
+    }
+
+    /// Returns the receipts of all executed transactions.
+    ///
+    /// This clones and converts the receipts, prefer the result of [`Self::finish`].
+    pub fn receipts(&self) -> Vec<DynReceipt> {
+        self.inner.receipts()
+    }
+
+    /// Applies the post-execution changes and returns the result of the block.
+    ///
+    /// Unlike [`BlockExecutor::finish`], the EVM is dropped.
+    pub fn finish(self) -> Result<BlockExecutionResult<DynReceipt>, BlockExecutionError> {
+        self.inner.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        eth::{
+            receipt_builder::AlloyReceiptBuilder, spec::EthSpec, EthBlockExecutionCtx,
+            EthBlockExecutor,
+        },
+        EthEvmFactory, EvmEnv, EvmFactory,
+    };
+    use alloc::borrow::Cow;
+    use alloy_consensus::{Header, SignableTransaction, TxEnvelope, TxLegacy, TxReceipt};
+    use alloy_primitives::{Signature, TxKind, U256};
+    use revm::{
+        database::{CacheDB, EmptyDB, State},
+        state::AccountInfo,
+    };
+
+    #[test]
+    fn test_dyn_evm() {
+        let caller = Address::repeat_byte(0x01);
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            caller,
+            AccountInfo { balance: U256::from(1_000_000_000_000u64), ..Default::default() },
+        );
+
+        let mut evm = DynEvm::new(EthEvmFactory.create_evm(db, EvmEnv::default()));
+        assert_eq!(evm.chain_id(), 1);
+
+        let tx = TxEnv::builder()
+            .caller(caller)
+            .to(Address::repeat_byte(0x02))
+            .value(U256::from(1))
+            .gas_limit(21_000)
+            .build()
+            .unwrap();
+        let result = evm.transact_commit(tx.clone(), Bytes::new()).unwrap();
+        assert!(matches!(result, ExecutionResult::Success { .. }));
+
+        // The nonce was committed, so the same transaction is now invalid.
+        assert!(evm.transact(tx, Bytes::new()).is_err());
+    }
+
+    #[cfg(feature = "op")]
+    #[test]
+    fn test_op_tx_keeps_encoding() {
+        let encoded = Bytes::from_static(&[0x02, 0xc0]);
+        let tx = op_revm::OpTransaction::<TxEnv>::from_tx_env(TxEnv::default(), encoded.clone());
+        assert_eq!(tx.enveloped_tx, Some(encoded));
+    }
+
+    #[test]
+    fn test_dyn_block_executor() {
+        let sender = Address::repeat_byte(0x01);
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            sender,
+            AccountInfo { balance: U256::from(1_000_000_000_000u64), ..Default::default() },
+        );
+        let mut state = State::builder().with_database(db).build();
+
+        // Shanghai, so that no system contracts are required.
+        let header = Header {
+            number: 17_034_870,
+            timestamp: 1_681_338_455,
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(0),
+            ..Default::default()
+        };
+        let evm_env = EvmEnv::for_eth_block(&header, EthSpec::mainnet(), 1, None);
+        let evm = EthEvmFactory.create_evm(&mut state, evm_env);
+        let ctx = EthBlockExecutionCtx {
+            parent_hash: header.parent_hash,
+            parent_beacon_block_root: None,
+            ommers: &[],
+            withdrawals: Some(Cow::Owned(Vec::new())),
+            extra_data: Bytes::new(),
+            tx_count_hint: None,
+            blob_params: None,
+        };
+        let mut executor = DynBlockExecutor::new(EthBlockExecutor::new(
+            evm,
+            ctx,
+            EthSpec::mainnet(),
+            AlloyReceiptBuilder,
+        ));
+
+        let tx = TxLegacy {
+            gas_limit: 21_000,
+            to: TxKind::Call(Address::repeat_byte(0x02)),
+            value: U256::from(1),
+            ..Default::default()
+        };
+        let tx = Recovered::new_unchecked(
+            TxEnvelope::from(tx.into_signed(Signature::test_signature())),
+            sender,
+        );
+
+        executor.apply_pre_execution_changes().unwrap();
+        assert_eq!(executor.execute_transaction(&tx).unwrap(), 21_000);
+        assert!(matches!(&executor.receipts()[..], [DynReceipt::Eth(receipt)] if receipt.status()));
+
+        let result = executor.finish().unwrap();
+        assert_eq!(result.gas_used, 21_000);
+        assert_eq!(result.receipts.len(), 1);
+    }
+}
@@ -91,6 +91,10 @@ where
         either::for_both!(self, evm => evm.set_inspector_enabled(enabled))
     }
 
+    fn is_inspector_enabled(&self) -> bool {
+        either::for_both!(self, evm => evm.is_inspector_enabled())
+    }
+
     fn enable_inspector(&mut self) {
         either::for_both!(self, evm => evm.enable_inspector())
     }
@@ -2,6 +2,8 @@
 
 use core::fmt::Debug;
 
+use alloc::collections::BTreeMap;
+use alloy_eips::eip7840::BlobParams;
 use alloy_primitives::U256;
 use revm::{
     context::{BlockEnv, CfgEnv},
@@ -44,6 +46,14 @@ impl<Spec, BlockEnv> EvmEnv<Spec, BlockEnv> {
         self.cfg_env.tx_gas_limit_cap = limits.tx_gas_limit_cap;
         self
     }
+
+    /// Applies the limits and disabled checks of the [`EvmConfig`].
+    ///
+    /// Blob parameters depend on the block, see [`EvmConfig::blob_params_at`].
+    pub fn with_config(mut self, config: &EvmConfig) -> Self {
+        config.apply_to_cfg(&mut self.cfg_env);
+        self
+    }
 }
 
 impl<Spec, BlockEnv: BlockEnvironment> EvmEnv<Spec, BlockEnv> {
@@ -160,7 +170,9 @@ impl<Spec, BlockEnv> From<(CfgEnv<Spec>, BlockEnv)> for EvmEnv<Spec, BlockEnv> {
 
 /// Trait for types that can be used as a block environment.
 ///
-/// Assumes that the type wraps an inner [`revm::context::BlockEnv`].
+/// Assumes that the type wraps an inner [`revm::context::BlockEnv`]. Block environments with
+/// chain-specific fields can be executed with an
+/// [`EthBlockEnvEvmFactory`](crate::eth::EthBlockEnvEvmFactory).
 pub trait BlockEnvironment: revm::context::Block + Clone + Debug + Send + Sync + 'static {
     /// Returns a mutable reference to the inner [`revm::context::BlockEnv`].
     fn inner_mut(&mut self) -> &mut revm::context::BlockEnv;
@@ -201,6 +213,114 @@ impl EvmLimitParams {
     }
 }
 
+/// Checks of the EVM that can be disabled, e.g. for tracing or simulations.
+///
+/// All checks are enabled by default. Except for the nonce check, disabling a check requires the
+/// corresponding `optional-*` feature of this crate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DisabledChecks {
+    /// Allow gas prices below the base fee of the block.
+    #[cfg(feature = "optional-no-base-fee")]
+    pub base_fee: bool,
+    /// Don't check the nonce of the caller.
+    pub nonce: bool,
+    /// Don't check that the caller can pay for the transaction.
+    #[cfg(feature = "optional-balance-check")]
+    pub balance: bool,
+    /// Don't check the gas limit of transactions against the block gas limit.
+    #[cfg(feature = "optional-block-gas-limit")]
+    pub block_gas_limit: bool,
+}
+
+impl DisabledChecks {
+    /// Disables the checks in the configuration environment.
+    ///
+    /// Checks that are enabled here are left as they are.
+    pub const fn apply<Spec>(&self, cfg_env: &mut CfgEnv<Spec>) {
+        #[cfg(feature = "optional-no-base-fee")]
+        {
+            cfg_env.disable_base_fee |= self.base_fee;
+        }
+        cfg_env.disable_nonce_check |= self.nonce;
+        #[cfg(feature = "optional-balance-check")]
+        {
+            cfg_env.disable_balance_check |= self.balance;
+        }
+        #[cfg(feature = "optional-block-gas-limit")]
+        {
+            cfg_env.disable_block_gas_limit |= self.block_gas_limit;
+        }
+    }
+}
+
+/// Chain-wide EVM configuration, e.g. loaded on node startup.
+///
+/// Applied to environments with [`EvmEnv::with_config`], or to every EVM created by a factory with
+/// [`ConfiguredEvmFactory`](crate::ConfiguredEvmFactory).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EvmConfig {
+    /// Execution limits overriding the spec defaults.
+    pub limit_params: Option<EvmLimitParams>,
+    /// Blob parameters by activation timestamp.
+    pub blob_schedule: BTreeMap<u64, BlobParams>,
+    /// Maximum gas limit of RPC calls.
+    pub rpc_gas_cap: Option<u64>,
+    /// Disabled checks.
+    pub disabled_checks: DisabledChecks,
+}
+
+impl EvmConfig {
+    /// Creates a configuration using the spec defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the execution limits.
+    pub const fn with_limit_params(mut self, limit_params: EvmLimitParams) -> Self {
+        self.limit_params = Some(limit_params);
+        self
+    }
+
+    /// Adds blob parameters activated at the given timestamp.
+    pub fn with_blob_params(mut self, timestamp: u64, params: BlobParams) -> Self {
+        self.blob_schedule.insert(timestamp, params);
+        self
+    }
+
+    /// Sets the maximum gas limit of RPC calls.
+    pub const fn with_rpc_gas_cap(mut self, gas_cap: u64) -> Self {
+        self.rpc_gas_cap = Some(gas_cap);
+        self
+    }
+
+    /// Sets the disabled checks.
+    pub const fn with_disabled_checks(mut self, disabled_checks: DisabledChecks) -> Self {
+        self.disabled_checks = disabled_checks;
+        self
+    }
+
+    /// Returns the blob parameters active at the given timestamp.
+    pub fn blob_params_at(&self, timestamp: u64) -> Option<BlobParams> {
+        self.blob_schedule.range(..=timestamp).next_back().map(|(_, params)| *params)
+    }
+
+    /// Applies the limits and disabled checks to the configuration environment.
+    pub const fn apply_to_cfg<Spec>(&self, cfg_env: &mut CfgEnv<Spec>) {
+        if let Some(limits) = self.limit_params {
+            cfg_env.limit_contract_code_size = Some(limits.max_code_size);
+            cfg_env.limit_contract_initcode_size = Some(limits.max_initcode_size);
+            cfg_env.tx_gas_limit_cap = limits.tx_gas_limit_cap;
+        }
+        self.disabled_checks.apply(cfg_env);
+    }
+
+    /// Returns the limits for RPC calls.
+    #[cfg(feature = "rpc")]
+    pub fn rpc_execution_config(&self) -> crate::rpc::RpcExecutionConfig {
+        crate::rpc::RpcExecutionConfig { gas_cap: self.rpc_gas_cap, ..Default::default() }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -246,4 +366,23 @@ mod tests {
 
         assert_eq!(evm_env.cfg_env.tx_gas_limit_cap(), revm::primitives::eip7825::TX_GAS_LIMIT_CAP);
     }
+
+    #[test]
+    fn test_evm_config() {
+        let config = EvmConfig::new()
+            .with_limit_params(EvmLimitParams::osaka())
+            .with_blob_params(100, BlobParams::cancun())
+            .with_blob_params(200, BlobParams::prague())
+            .with_disabled_checks(DisabledChecks { nonce: true, ..Default::default() });
+
+        assert_eq!(config.blob_params_at(99), None);
+        assert_eq!(config.blob_params_at(199), Some(BlobParams::cancun()));
+        assert_eq!(config.blob_params_at(200), Some(BlobParams::prague()));
+
+        let evm_env: EvmEnv<SpecId> = EvmEnv::default().with_config(&config);
+        assert_eq!(evm_env.cfg_env.tx_gas_limit_cap(), revm::primitives::eip7825::TX_GAS_LIMIT_CAP);
+        assert!(evm_env.cfg_env.disable_nonce_check);
+        #[cfg(feature = "optional-balance-check")]
+        assert!(!evm_env.cfg_env.disable_balance_check);
+    }
 }
@@ -0,0 +1,245 @@
+//! Validation of EOF ([EIP-7692]) containers.
+//!
+//! revm doesn't execute EOF, so this module only provides the structural checks of [EIP-3540],
+//! e.g. for transaction pools that want to reject malformed EOF initcode before it reaches the
+//! executor. Instructions of the code sections are not validated.
+//!
+//! [EIP-7692]: https://eips.ethereum.org/EIPS/eip-7692
+//! [EIP-3540]: https://eips.ethereum.org/EIPS/eip-3540
+
+use alloc::vec::Vec;
+
+/// Prefix of EOF containers.
+pub const EOF_MAGIC: [u8; 2] = [0xef, 0x00];
+/// Supported EOF version.
+pub const EOF_VERSION: u8 = 0x01;
+
+const KIND_TYPES: u8 = 0x01;
+const KIND_CODE: u8 = 0x02;
+const KIND_CONTAINER: u8 = 0x03;
+const KIND_DATA: u8 = 0xff;
+const TERMINATOR: u8 = 0x00;
+
+/// Size of a type section entry: inputs, outputs and max stack increase.
+const TYPE_ENTRY_SIZE: usize = 4;
+/// Maximum number of code sections.
+const MAX_CODE_SECTIONS: usize = 1024;
+/// Maximum number of container sections.
+const MAX_CONTAINER_SECTIONS: usize = 256;
+/// Outputs of a non-returning code section.
+const NON_RETURNING: u8 = 0x80;
+/// Maximum number of inputs of a code section.
+const MAX_INPUTS: u8 = 0x7f;
+/// Maximum stack increase of a code section.
+const MAX_STACK_INCREASE: u16 = 1023;
+
+/// Error returned by [`validate_eof`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum EofError {
+    /// The bytes don't start with [`EOF_MAGIC`].
+    #[error("missing EOF magic")]
+    InvalidMagic,
+    /// The version is not [`EOF_VERSION`].
+    #[error("unsupported EOF version {0}")]
+    UnsupportedVersion(u8),
+    /// The container ends before the header or a section is complete.
+    #[error("EOF container is truncated")]
+    Truncated,
+    /// A section header has an unexpected kind.
+    #[error("unexpected EOF section kind {got:#04x}, expected {expected:#04x}")]
+    UnexpectedSectionKind {
+        /// Kind found in the header.
+        got: u8,
+        /// Kind expected at this position.
+        expected: u8,
+    },
+    /// The number of code or container sections is zero or exceeds the limit.
+    #[error("invalid number of EOF sections: {0}")]
+    InvalidSectionCount(usize),
+    /// A code or container section is empty.
+    #[error("empty EOF section")]
+    EmptySection,
+    /// The size of the type section doesn't match the number of code sections.
+    #[error("EOF type section size {got} doesn't match {expected}")]
+    InvalidTypeSectionSize {
+        /// Size of the type section.
+        got: usize,
+        /// Expected size based on the number of code sections.
+        expected: usize,
+    },
+    /// The type of a code section is invalid.
+    #[error("invalid type of EOF code section {0}")]
+    InvalidCodeSectionType(usize),
+    /// There are bytes after the last section.
+    #[error("EOF container has {0} trailing bytes")]
+    TrailingBytes(usize),
+}
+
+/// Layout of a structurally valid EOF container.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EofLayout {
+    /// Sizes of the code sections.
+    pub code_sizes: Vec<usize>,
+    /// Sizes of the container sections.
+    pub container_sizes: Vec<usize>,
+    /// Size of the data section.
+    pub data_size: usize,
+    /// Size of the header.
+    pub header_size: usize,
+}
+
+/// Returns `true` if the bytes start with [`EOF_MAGIC`].
+///
+/// Legacy code starting with `0xef` is rejected since [EIP-3541], so such bytes are meant to be
+/// EOF.
+///
+/// [EIP-3541]: https://eips.ethereum.org/EIPS/eip-3541
+pub fn is_eof(bytes: &[u8]) -> bool {
+    bytes.starts_with(&EOF_MAGIC)
+}
+
+/// Validates the structure of an EOF container: header, section sizes and types.
+///
+/// The body must be complete, which is required for initcode and deployed code.
+pub fn validate_eof(bytes: &[u8]) -> Result<EofLayout, EofError> {
+    let mut reader = Reader(bytes);
+    if reader.take(2)? != EOF_MAGIC {
+        return Err(EofError::InvalidMagic);
+    }
+    let version = reader.u8()?;
+    if version != EOF_VERSION {
+        return Err(EofError::UnsupportedVersion(version));
+    }
+
+    reader.kind(KIND_TYPES)?;
+    let types_size = reader.u16()? as usize;
+
+    reader.kind(KIND_CODE)?;
+    let code_sizes = reader.sizes(MAX_CODE_SECTIONS, Reader::u16)?;
+    if types_size != code_sizes.len() * TYPE_ENTRY_SIZE {
+        return Err(EofError::InvalidTypeSectionSize {
+            got: types_size,
+            expected: code_sizes.len() * TYPE_ENTRY_SIZE,
+        });
+    }
+
+    let container_sizes = if reader.peek()? == KIND_CONTAINER {
+        reader.kind(KIND_CONTAINER)?;
+        reader.sizes(MAX_CONTAINER_SECTIONS, Reader::u32)?
+    } else {
+        Vec::new()
+    };
+
+    reader.kind(KIND_DATA)?;
+    let data_size = reader.u16()? as usize;
+    reader.kind(TERMINATOR)?;
+    let header_size = bytes.len() - reader.0.len();
+
+    let types = reader.take(types_size)?;
+    for (index, entry) in types.chunks_exact(TYPE_ENTRY_SIZE).enumerate() {
+        let (inputs, outputs) = (entry[0], entry[1]);
+        let max_stack_increase = u16::from_be_bytes([entry[2], entry[3]]);
+        let valid = if index == 0 {
+            inputs == 0 && outputs == NON_RETURNING
+        } else {
+            inputs <= MAX_INPUTS && outputs <= NON_RETURNING
+        };
+        if !valid || max_stack_increase > MAX_STACK_INCREASE {
+            return Err(EofError::InvalidCodeSectionType(index));
+        }
+    }
+
+    for size in code_sizes.iter().chain(&container_sizes).chain([&data_size]) {
+        reader.take(*size)?;
+    }
+    if !reader.0.is_empty() {
+        return Err(EofError::TrailingBytes(reader.0.len()));
+    }
+
+    Ok(EofLayout { code_sizes, container_sizes, data_size, header_size })
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], EofError> {
+        if self.0.len() < len {
+            return Err(EofError::Truncated);
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn peek(&self) -> Result<u8, EofError> {
+        self.0.first().copied().ok_or(EofError::Truncated)
+    }
+
+    fn u8(&mut self) -> Result<u8, EofError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<usize, EofError> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]) as usize)
+    }
+
+    fn u32(&mut self) -> Result<usize, EofError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+    }
+
+    fn kind(&mut self, expected: u8) -> Result<(), EofError> {
+        let got = self.u8()?;
+        if got != expected {
+            return Err(EofError::UnexpectedSectionKind { got, expected });
+        }
+        Ok(())
+    }
+
+    /// Reads the number of sections followed by their sizes, all of which must be non-zero.
+    fn sizes(
+        &mut self,
+        max: usize,
+        size: fn(&mut Self) -> Result<usize, EofError>,
+    ) -> Result<Vec<usize>, EofError> {
+        let count = self.u16()?;
+        if count == 0 || count > max {
+            return Err(EofError::InvalidSectionCount(count));
+        }
+        let sizes = (0..count).map(|_| size(self)).collect::<Result<Vec<_>, _>>()?;
+        if sizes.contains(&0) {
+            return Err(EofError::EmptySection);
+        }
+        Ok(sizes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::hex;
+
+    #[test]
+    fn test_validate_eof() {
+        // one code section with STOP, two bytes of data
+        let container = hex!("ef0001 010004 0200010001 ff0002 00 00800000 00 aabb");
+        assert!(is_eof(&container));
+        let layout = validate_eof(&container).unwrap();
+        assert_eq!(layout.code_sizes, [1]);
+        assert!(layout.container_sizes.is_empty());
+        assert_eq!((layout.data_size, layout.header_size), (2, 15));
+
+        assert_eq!(validate_eof(&hex!("6000")), Err(EofError::InvalidMagic));
+        assert_eq!(validate_eof(&container[..18]), Err(EofError::Truncated));
+        assert_eq!(
+            validate_eof(&[&container[..], &[0x00]].concat()),
+            Err(EofError::TrailingBytes(1))
+        );
+        // first code section must be non-returning
+        let returning = hex!("ef0001 010004 0200010001 ff0000 00 00000000 00");
+        assert_eq!(validate_eof(&returning), Err(EofError::InvalidCodeSectionType(0)));
+        let no_code = hex!("ef0001 010004 020000 ff0000 00 00800000");
+        assert_eq!(validate_eof(&no_code), Err(EofError::InvalidSectionCount(0)));
+    }
+}
@@ -1,7 +1,7 @@
 //! Abstraction over EVM errors.
 
-use core::{any::Any, error::Error};
-use revm::context_interface::result::{EVMError, InvalidTransaction};
+use core::{any::Any, error::Error, fmt};
+use revm::context_interface::result::{EVMError, HaltReason, InvalidTransaction, OutOfGasError};
 
 /// Abstraction over transaction validation error.
 pub trait InvalidTxError: Error + Send + Sync + Any + 'static {
@@ -33,12 +33,101 @@ pub trait InvalidTxError: Error + Send + Sync + Any + 'static {
         })
     }
 
+    /// Returns whether the error is due to the transaction nonce being higher than expected.
+    fn is_nonce_too_high(&self) -> bool {
+        self.as_invalid_tx_err()
+            .is_some_and(|err| matches!(err, InvalidTransaction::NonceTooHigh { .. }))
+    }
+
+    /// Returns whether the error is due to the sender not being able to pay for the transaction.
+    fn is_insufficient_funds(&self) -> bool {
+        self.as_invalid_tx_err().is_some_and(|err| {
+            matches!(
+                err,
+                InvalidTransaction::LackOfFundForMaxFee { .. }
+                    | InvalidTransaction::OverflowPaymentInTransaction { .. }
+            )
+        })
+    }
+
+    /// Returns whether the error is due to a fee cap being lower than the current base fee or blob
+    /// base fee.
+    fn is_fee_cap_too_low(&self) -> bool {
+        self.as_invalid_tx_err().is_some_and(|err| {
+            matches!(
+                err,
+                InvalidTransaction::GasPriceLessThanBasefee { .. }
+                    | InvalidTransaction::BlobGasPriceGreaterThanMax { .. }
+            )
+        })
+    }
+
+    /// Returns whether the error is related to the blob fields of the transaction.
+    fn is_blob_related(&self) -> bool {
+        self.as_invalid_tx_err().is_some_and(|err| {
+            matches!(
+                err,
+                InvalidTransaction::BlobGasPriceGreaterThanMax { .. }
+                    | InvalidTransaction::EmptyBlobs { .. }
+                    | InvalidTransaction::BlobCreateTransaction { .. }
+                    | InvalidTransaction::TooManyBlobs { .. }
+                    | InvalidTransaction::BlobVersionNotSupported { .. }
+                    | InvalidTransaction::BlobVersionedHashesNotSupported { .. }
+                    | InvalidTransaction::MaxFeePerBlobGasNotSupported { .. }
+                    | InvalidTransaction::Eip4844NotSupported { .. }
+            )
+        })
+    }
+
+    /// Classifies the error into the reason a transaction pool would discard the transaction for.
+    fn discard_reason(&self) -> DiscardReason {
+        if self.is_nonce_too_low() {
+            DiscardReason::NonceTooLow
+        } else if self.is_nonce_too_high() {
+            DiscardReason::NonceGap
+        } else if self.is_insufficient_funds() {
+            DiscardReason::InsufficientFunds
+        } else if self.is_fee_cap_too_low() {
+            DiscardReason::FeeCapTooLow
+        } else if self.is_gas_limit_too_high() {
+            DiscardReason::GasLimitTooHigh
+        } else {
+            DiscardReason::Invalid
+        }
+    }
+
     /// Returns the underlying [`InvalidTransaction`] if any.
     ///
     /// This is primarily used for error conversions, e.g. for rpc responses.
     fn as_invalid_tx_err(&self) -> Option<&InvalidTransaction>;
 }
 
+/// Reason for discarding a transaction from a transaction pool, see
+/// [`InvalidTxError::discard_reason`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiscardReason {
+    /// The nonce was already used by the sender.
+    NonceTooLow,
+    /// The nonce is higher than the next nonce of the sender.
+    NonceGap,
+    /// The sender can't pay for the transaction.
+    InsufficientFunds,
+    /// A fee cap is below the current base fee or blob base fee.
+    FeeCapTooLow,
+    /// The gas limit exceeds the block gas limit or the transaction gas limit cap.
+    GasLimitTooHigh,
+    /// The transaction is invalid for any other reason.
+    Invalid,
+}
+
+impl DiscardReason {
+    /// Returns `true` if the transaction can't become valid through later state changes, e.g. a
+    /// lower base fee, a higher balance or the inclusion of transactions with lower nonces.
+    pub const fn is_permanent(&self) -> bool {
+        matches!(self, Self::NonceTooLow | Self::GasLimitTooHigh | Self::Invalid)
+    }
+}
+
 impl InvalidTxError for InvalidTransaction {
     fn as_invalid_tx_err(&self) -> Option<&InvalidTransaction> {
         Some(self)
@@ -68,6 +157,14 @@ pub trait EvmError: Sized + Error + Send + Sync + 'static {
     fn is_invalid_tx_err(&self) -> bool {
         self.as_invalid_tx_err().is_some()
     }
+
+    /// Returns `true` if the error was returned by the database.
+    ///
+    /// Database errors are not caused by the executed transaction or block and might not occur
+    /// when retrying the execution.
+    fn is_database_error(&self) -> bool {
+        false
+    }
 }
 
 impl<DBError, TxError> EvmError for EVMError<DBError, TxError>
@@ -90,6 +187,10 @@ where
             err => Err(err),
         }
     }
+
+    fn is_database_error(&self) -> bool {
+        matches!(self, Self::Database(_))
+    }
 }
 
 #[cfg(feature = "op")]
@@ -101,3 +202,183 @@ impl InvalidTxError for op_revm::OpTransactionError {
         }
     }
 }
+
+/// Chain-agnostic classification of a halt reason, see [`EvmHaltReason::halt_kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HaltKind {
+    /// Execution ran out of gas.
+    OutOfGas,
+    /// The stack exceeded its maximum size.
+    StackOverflow,
+    /// An instruction needed more items than the stack holds.
+    StackUnderflow,
+    /// An unknown or not yet activated opcode was executed.
+    InvalidOpcode,
+    /// A jump targeted an invalid destination.
+    InvalidJump,
+    /// The call depth limit was reached.
+    CallTooDeep,
+    /// A value transfer exceeded the balance of the sender.
+    OutOfFunds,
+    /// A state modification was attempted during a static call.
+    StaticCallViolation,
+    /// A contract creation failed, e.g. because of an address collision or a size limit.
+    CreateFailed,
+    /// A precompile failed.
+    PrecompileError,
+    /// A deposit transaction failed.
+    FailedDeposit,
+    /// Any other halt reason.
+    Other,
+}
+
+impl HaltKind {
+    /// Returns a human readable description of the halt kind.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::OutOfGas => "out of gas",
+            Self::StackOverflow => "stack overflow",
+            Self::StackUnderflow => "stack underflow",
+            Self::InvalidOpcode => "invalid opcode",
+            Self::InvalidJump => "invalid jump destination",
+            Self::CallTooDeep => "max call depth exceeded",
+            Self::OutOfFunds => "insufficient balance for transfer",
+            Self::StaticCallViolation => "write protection",
+            Self::CreateFailed => "contract creation failed",
+            Self::PrecompileError => "precompile failed",
+            Self::FailedDeposit => "deposit failed",
+            Self::Other => "execution halted",
+        }
+    }
+}
+
+impl fmt::Display for HaltKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&HaltReason> for HaltKind {
+    fn from(reason: &HaltReason) -> Self {
+        match reason {
+            HaltReason::OutOfGas(_) => Self::OutOfGas,
+            HaltReason::StackOverflow => Self::StackOverflow,
+            HaltReason::StackUnderflow => Self::StackUnderflow,
+            HaltReason::OpcodeNotFound | HaltReason::InvalidFEOpcode | HaltReason::NotActivated => {
+                Self::InvalidOpcode
+            }
+            HaltReason::InvalidJump => Self::InvalidJump,
+            HaltReason::CallTooDeep => Self::CallTooDeep,
+            HaltReason::OutOfFunds => Self::OutOfFunds,
+            HaltReason::StateChangeDuringStaticCall | HaltReason::CallNotAllowedInsideStatic => {
+                Self::StaticCallViolation
+            }
+            HaltReason::CreateCollision
+            | HaltReason::CreateContractSizeLimit
+            | HaltReason::CreateContractStartingWithEF
+            | HaltReason::CreateInitCodeSizeLimit => Self::CreateFailed,
+            HaltReason::PrecompileError => Self::PrecompileError,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Abstraction over the reasons for halting execution.
+///
+/// Allows rendering halts consistently across chains with their own halt reason types.
+pub trait EvmHaltReason: fmt::Debug + Send + Sync + 'static {
+    /// Returns the underlying Ethereum [`HaltReason`] if any.
+    fn as_eth_halt_reason(&self) -> Option<&HaltReason>;
+
+    /// Classifies the halt reason.
+    fn halt_kind(&self) -> HaltKind {
+        self.as_eth_halt_reason().map_or(HaltKind::Other, HaltKind::from)
+    }
+
+    /// Returns whether execution ran out of gas.
+    fn is_out_of_gas(&self) -> bool {
+        self.halt_kind() == HaltKind::OutOfGas
+    }
+
+    /// Returns whether execution ran out of memory gas, i.e. tried to expand memory beyond what
+    /// the gas limit allows.
+    fn is_out_of_memory_gas(&self) -> bool {
+        self.as_eth_halt_reason().is_some_and(|reason| {
+            matches!(
+                reason,
+                HaltReason::OutOfGas(OutOfGasError::Memory | OutOfGasError::MemoryLimit)
+            )
+        })
+    }
+
+    /// Returns whether the stack exceeded its maximum size.
+    fn is_stack_overflow(&self) -> bool {
+        self.halt_kind() == HaltKind::StackOverflow
+    }
+
+    /// Returns whether an invalid or not yet activated opcode was executed.
+    fn is_invalid_opcode(&self) -> bool {
+        self.halt_kind() == HaltKind::InvalidOpcode
+    }
+}
+
+impl EvmHaltReason for HaltReason {
+    fn as_eth_halt_reason(&self) -> Option<&HaltReason> {
+        Some(self)
+    }
+}
+
+#[cfg(feature = "op")]
+impl EvmHaltReason for op_revm::OpHaltReason {
+    fn as_eth_halt_reason(&self) -> Option<&HaltReason> {
+        match self {
+            Self::Base(reason) => Some(reason),
+            Self::FailedDeposit => None,
+        }
+    }
+
+    fn halt_kind(&self) -> HaltKind {
+        match self {
+            Self::Base(reason) => reason.into(),
+            Self::FailedDeposit => HaltKind::FailedDeposit,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+
+    #[test_case::test_case(InvalidTransaction::NonceTooLow { tx: 0, state: 1 }, DiscardReason::NonceTooLow; "nonce too low")]
+    #[test_case::test_case(InvalidTransaction::NonceTooHigh { tx: 2, state: 1 }, DiscardReason::NonceGap; "nonce too high")]
+    #[test_case::test_case(InvalidTransaction::LackOfFundForMaxFee { fee: Box::new(U256::from(2)), balance: Box::new(U256::from(1)) }, DiscardReason::InsufficientFunds; "insufficient funds")]
+    #[test_case::test_case(InvalidTransaction::GasPriceLessThanBasefee, DiscardReason::FeeCapTooLow; "fee cap too low")]
+    #[test_case::test_case(InvalidTransaction::CallerGasLimitMoreThanBlock, DiscardReason::GasLimitTooHigh; "gas limit too high")]
+    #[test_case::test_case(InvalidTransaction::InvalidChainId, DiscardReason::Invalid; "invalid")]
+    fn test_discard_reason(err: InvalidTransaction, expected: DiscardReason) {
+        assert_eq!(err.discard_reason(), expected);
+    }
+
+    #[test]
+    fn test_blob_related() {
+        assert!(InvalidTransaction::EmptyBlobs.is_blob_related());
+        assert!(!InvalidTransaction::GasPriceLessThanBasefee.is_blob_related());
+    }
+
+    #[test_case::test_case(HaltReason::OutOfGas(OutOfGasError::Basic), HaltKind::OutOfGas; "out of gas")]
+    #[test_case::test_case(HaltReason::StackOverflow, HaltKind::StackOverflow; "stack overflow")]
+    #[test_case::test_case(HaltReason::OpcodeNotFound, HaltKind::InvalidOpcode; "invalid opcode")]
+    #[test_case::test_case(HaltReason::CreateCollision, HaltKind::CreateFailed; "create collision")]
+    fn test_halt_kind(reason: HaltReason, expected: HaltKind) {
+        assert_eq!(reason.halt_kind(), expected);
+    }
+
+    #[test]
+    fn test_out_of_memory_gas() {
+        let reason = HaltReason::OutOfGas(OutOfGasError::Memory);
+        assert!(reason.is_out_of_gas());
+        assert!(reason.is_out_of_memory_gas());
+        assert!(!HaltReason::OutOfGas(OutOfGasError::Basic).is_out_of_memory_gas());
+    }
+}
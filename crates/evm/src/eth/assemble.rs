@@ -0,0 +1,174 @@
+//! Assembly of executed blocks.
+
+use super::NextEvmEnvAttributes;
+use crate::{block::BlockExecutionResult, EvmEnv};
+use alloc::vec::Vec;
+use alloy_consensus::{
+    proofs::{calculate_receipt_root, calculate_transaction_root, calculate_withdrawals_root},
+    Block, BlockBody, Header, TxReceipt, EMPTY_OMMER_ROOT_HASH,
+};
+use alloy_eips::{eip2718::Encodable2718, eip4895::Withdrawals};
+use alloy_hardforks::EthereumHardforks;
+use alloy_primitives::{Bloom, Bytes, Log, B256};
+
+/// Inputs for assembling a block from the result of its execution.
+#[derive(Debug)]
+pub struct BlockAssemblerInput<'a, Spec, T, R> {
+    /// Hash of the parent block.
+    pub parent_hash: B256,
+    /// Attributes the block was built with.
+    pub attributes: &'a NextEvmEnvAttributes,
+    /// Environment the block was executed in.
+    pub evm_env: &'a EvmEnv<Spec>,
+    /// Result of executing the block.
+    pub execution_result: &'a BlockExecutionResult<R>,
+    /// State root after executing the block.
+    pub state_root: B256,
+    /// Transactions of the block, in execution order.
+    pub transactions: Vec<T>,
+    /// Withdrawals of the block.
+    ///
+    /// Ignored before Shanghai, defaults to none after.
+    pub withdrawals: Option<Withdrawals>,
+    /// Parent beacon block root, ignored before Cancun.
+    pub parent_beacon_block_root: Option<B256>,
+    /// Extra data of the block.
+    pub extra_data: Bytes,
+}
+
+impl<Spec, T, R> BlockAssemblerInput<'_, Spec, T, R> {
+    /// Returns the header fields derived from the attributes and the environment, with all
+    /// fork-specific fields unset.
+    pub(crate) fn base_header(&self) -> Header
+    where
+        T: Encodable2718,
+        R: TxReceipt<Log = Log> + Encodable2718,
+    {
+        let receipts = &self.execution_result.receipts;
+        let mut logs_bloom = Bloom::ZERO;
+        for receipt in receipts {
+            logs_bloom.accrue_bloom(&receipt.bloom());
+        }
+
+        Header {
+            parent_hash: self.parent_hash,
+            ommers_hash: EMPTY_OMMER_ROOT_HASH,
+            beneficiary: self.attributes.suggested_fee_recipient,
+            state_root: self.state_root,
+            transactions_root: calculate_transaction_root(&self.transactions),
+            receipts_root: calculate_receipt_root(receipts),
+            logs_bloom,
+            number: self.evm_env.block_env.number.saturating_to(),
+            gas_limit: self.attributes.gas_limit,
+            gas_used: self.execution_result.gas_used,
+            timestamp: self.attributes.timestamp,
+            mix_hash: self.attributes.prev_randao,
+            extra_data: self.extra_data.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Assembles an Ethereum block from the result of its execution.
+///
+/// All header fields are derived from the input: roots of the transactions, receipts and
+/// withdrawals, logs bloom, gas and blob gas used, excess blob gas and requests hash. Fork-specific
+/// fields are only set if the fork is active.
+pub fn assemble_block<Spec, T, R>(
+    input: BlockAssemblerInput<'_, Spec, T, R>,
+    chain_spec: impl EthereumHardforks,
+) -> Block<T>
+where
+    T: Encodable2718,
+    R: TxReceipt<Log = Log> + Encodable2718,
+{
+    let mut header = input.base_header();
+    let timestamp = header.timestamp;
+    let block_env = &input.evm_env.block_env;
+
+    if chain_spec.is_london_active_at_block(header.number) {
+        header.base_fee_per_gas = Some(block_env.basefee);
+    }
+
+    let withdrawals = chain_spec
+        .is_shanghai_active_at_timestamp(timestamp)
+        .then(|| input.withdrawals.unwrap_or_default());
+    header.withdrawals_root =
+        withdrawals.as_ref().map(|withdrawals| calculate_withdrawals_root(withdrawals.as_slice()));
+
+    if chain_spec.is_cancun_active_at_timestamp(timestamp) {
+        header.blob_gas_used = Some(input.execution_result.blob_gas_used);
+        header.excess_blob_gas = Some(
+            block_env
+                .blob_excess_gas_and_price
+                .as_ref()
+                .map_or(0, |blob_excess_gas_and_price| blob_excess_gas_and_price.excess_blob_gas),
+        );
+        header.parent_beacon_block_root = input.parent_beacon_block_root;
+    }
+
+    if chain_spec.is_prague_active_at_timestamp(timestamp) {
+        header.requests_hash = Some(input.execution_result.requests.requests_hash());
+    }
+
+    Block {
+        header,
+        body: BlockBody { transactions: input.transactions, ommers: Vec::new(), withdrawals },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::spec::EthSpec;
+    use alloy_consensus::{
+        constants::EMPTY_WITHDRAWALS, ReceiptEnvelope, TxEnvelope, EMPTY_ROOT_HASH,
+    };
+    use alloy_eips::eip7685::EMPTY_REQUESTS_HASH;
+    use alloy_primitives::{Address, U256};
+
+    #[test]
+    fn test_assemble_empty_block() {
+        let attributes = NextEvmEnvAttributes {
+            // Prague on mainnet
+            timestamp: 1_746_612_311,
+            suggested_fee_recipient: Address::repeat_byte(0x01),
+            prev_randao: B256::repeat_byte(0x02),
+            gas_limit: 36_000_000,
+            elasticity_multiplier: None,
+            base_fee_max_change_denominator: None,
+        };
+        let mut evm_env = EvmEnv::default();
+        evm_env.block_env.number = U256::from(22_431_084);
+        evm_env.block_env.basefee = 7;
+        let execution_result = BlockExecutionResult::<ReceiptEnvelope>::default();
+
+        let block = assemble_block(
+            BlockAssemblerInput {
+                parent_hash: B256::repeat_byte(0x03),
+                attributes: &attributes,
+                evm_env: &evm_env,
+                execution_result: &execution_result,
+                state_root: B256::repeat_byte(0x04),
+                transactions: Vec::<TxEnvelope>::new(),
+                withdrawals: None,
+                parent_beacon_block_root: Some(B256::repeat_byte(0x05)),
+                extra_data: Bytes::new(),
+            },
+            EthSpec::mainnet(),
+        );
+
+        let header = &block.header;
+        assert_eq!(header.number, 22_431_084);
+        assert_eq!(header.beneficiary, attributes.suggested_fee_recipient);
+        assert_eq!(header.transactions_root, EMPTY_ROOT_HASH);
+        assert_eq!(header.receipts_root, EMPTY_ROOT_HASH);
+        assert_eq!(header.base_fee_per_gas, Some(7));
+        assert_eq!(header.withdrawals_root, Some(EMPTY_WITHDRAWALS));
+        assert_eq!(header.blob_gas_used, Some(0));
+        assert_eq!(header.excess_blob_gas, Some(0));
+        assert_eq!(header.parent_beacon_block_root, Some(B256::repeat_byte(0x05)));
+        assert_eq!(header.requests_hash, Some(EMPTY_REQUESTS_HASH));
+        assert!(block.body.withdrawals.unwrap().is_empty());
+    }
+}
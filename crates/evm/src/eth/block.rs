@@ -10,20 +10,31 @@ use crate::{
     block::{
         state_changes::{balance_increment_state, post_block_balance_increments},
         BlockExecutionError, BlockExecutionResult, BlockExecutor, BlockExecutorFactory,
-        BlockExecutorFor, BlockValidationError, ExecutableTx, OnStateHook,
-        StateChangePostBlockSource, StateChangeSource, StateDB, SystemCaller, TxResult,
+        BlockExecutorFor, BlockValidationError, ExecutableTx, ExecutionProfile, ExecutionProfiler,
+        FeeTracker, OnStateHook, StateChangePostBlockSource, StateChangeSource, StateDB,
+        SystemCaller, TxExecutionProfile, TxResult,
     },
-    Evm, EvmFactory, FromRecoveredTx, FromTxWithEncoded, RecoveredTx,
+    Evm, EvmEnv, EvmFactory, FromRecoveredTx, FromTxWithEncoded, RecoveredTx,
 };
 use alloc::{borrow::Cow, boxed::Box, vec::Vec};
-use alloy_consensus::{Header, Transaction, TransactionEnvelope, TxReceipt};
-use alloy_eips::{eip4895::Withdrawal, eip7685::Requests, Encodable2718};
+use alloy_consensus::{
+    transaction::Recovered, Block, BlockHeader, Header, Transaction, TransactionEnvelope, TxReceipt,
+};
+use alloy_eips::{eip4895::Withdrawal, eip7685::Requests, eip7840::BlobParams, Encodable2718};
 use alloy_hardforks::EthereumHardfork;
-use alloy_primitives::{Bytes, Log, B256};
+use alloy_primitives::{Address, Bytes, Log, B256};
 use revm::{
-    context::Block, context_interface::result::ResultAndState, database::DatabaseCommitExt,
+    context::{Block as _, BlockEnv},
+    context_interface::result::ResultAndState,
+    database::DatabaseCommitExt,
+    primitives::hardfork::SpecId,
     DatabaseCommit, Inspector,
 };
+#[cfg(feature = "std")]
+use {
+    crate::events::{ExecutionEvent, ExecutionEventSender},
+    alloc::string::ToString,
+};
 
 /// Context for Ethereum block execution.
 #[derive(Debug, Clone)]
@@ -40,6 +51,45 @@ pub struct EthBlockExecutionCtx<'a> {
     pub extra_data: Bytes,
     /// Block transactions count hint. Used to preallocate the receipts vector.
     pub tx_count_hint: Option<usize>,
+    /// Blob parameters active for the block.
+    ///
+    /// If set, the executor rejects transactions that would push the block's blob gas usage over
+    /// [`BlobParams::max_blob_gas_per_block`].
+    pub blob_params: Option<BlobParams>,
+}
+
+impl<'a> EthBlockExecutionCtx<'a> {
+    /// Creates the context from the fields of a block header.
+    ///
+    /// Ommers and withdrawals are not part of the header and left empty, use
+    /// [`from_block`](Self::from_block) to include them.
+    pub fn from_header(header: &impl BlockHeader) -> Self {
+        Self {
+            parent_hash: header.parent_hash(),
+            parent_beacon_block_root: header.parent_beacon_block_root(),
+            ommers: &[],
+            withdrawals: None,
+            extra_data: header.extra_data().clone(),
+            tx_count_hint: None,
+            blob_params: None,
+        }
+    }
+
+    /// Creates the context from a block, including its ommers and withdrawals.
+    pub fn from_block<T>(block: &'a Block<T>) -> Self {
+        Self {
+            ommers: &block.body.ommers,
+            withdrawals: block.body.withdrawals.as_ref().map(|w| Cow::Borrowed(w.as_slice())),
+            tx_count_hint: Some(block.body.transactions.len()),
+            ..Self::from_header(&block.header)
+        }
+    }
+
+    /// Sets the blob parameters active for the block.
+    pub const fn with_blob_params(mut self, blob_params: Option<BlobParams>) -> Self {
+        self.blob_params = blob_params;
+        self
+    }
 }
 
 /// Block executor for Ethereum.
@@ -65,20 +115,51 @@ pub struct EthBlockExecutor<'a, Evm, Spec, R: ReceiptBuilder> {
     /// Blob gas used by the block.
     /// Before cancun activation, this is always 0.
     pub blob_gas_used: u64,
+
+    /// Per-transaction resource accounting, if enabled via
+    /// [`EthBlockExecutor::with_profiling`].
+    pub profiler: Option<ExecutionProfiler>,
+    /// Committed transactions, in the order of [`Self::receipts`], if enabled via
+    /// [`EthBlockExecutor::with_executed_transactions`].
+    pub executed_transactions: Option<Vec<ExecutedTx>>,
+    /// Fees collected by the committed transactions, if enabled via
+    /// [`EthBlockExecutor::with_fee_tracking`].
+    pub fee_tracker: Option<FeeTracker>,
+    /// EIP-6110 deposit requests parsed from the receipts of the committed transactions since
+    /// Prague, turned into a request of the block by [`BlockExecutor::finish`].
+    deposit_requests: Vec<u8>,
+    /// Stream of execution events, if enabled via [`EthBlockExecutor::with_events`].
+    #[cfg(feature = "std")]
+    pub events: Option<ExecutionEventSender<R::Receipt>>,
+}
+
+/// A transaction committed by the [`EthBlockExecutor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExecutedTx {
+    /// Hash of the transaction.
+    pub hash: B256,
+    /// Sender of the transaction.
+    pub sender: Address,
 }
 
 /// The result of executing an Ethereum transaction.
 #[derive(Debug)]
-pub struct EthTxResult<H, T> {
+pub struct EthTxResult<H, T, X = ()> {
     /// Result of the transaction execution.
     pub result: ResultAndState<H>,
     /// Blob gas used by the transaction.
     pub blob_gas_used: u64,
     /// Type of the transaction.
     pub tx_type: T,
+    /// Hash of the transaction, used to attribute receipt building errors.
+    pub tx_hash: B256,
+    /// Sender of the transaction.
+    pub signer: Address,
+    /// Additional data passed to the receipt builder, see [`ReceiptBuilder::Extra`].
+    pub extra: X,
 }
 
-impl<H, T> TxResult for EthTxResult<H, T> {
+impl<H, T, X> TxResult for EthTxResult<H, T, X> {
     type HaltReason = H;
 
     fn result(&self) -> &ResultAndState<Self::HaltReason> {
@@ -92,112 +173,196 @@ where
     R: ReceiptBuilder,
 {
     /// Creates a new [`EthBlockExecutor`]
-    pub fn new(evm: Evm, ctx: EthBlockExecutionCtx<'a>, spec: Spec, receipt_builder: R) -> Self {
+    ///
+    /// System contracts are called at the addresses of [`EthExecutorSpec::system_contracts`].
+    pub fn new(evm: Evm, ctx: EthBlockExecutionCtx<'a>, spec: Spec, receipt_builder: R) -> Self
+    where
+        Spec: EthExecutorSpec,
+    {
         let tx_count_hint = ctx.tx_count_hint.unwrap_or_default();
+        let mut system_caller = SystemCaller::new(spec.clone());
+        system_caller.set_system_contracts(spec.system_contracts());
         Self {
             evm,
             ctx,
             receipts: Vec::with_capacity(tx_count_hint),
             gas_used: 0,
             blob_gas_used: 0,
-            system_caller: SystemCaller::new(spec.clone()),
+            system_caller,
             spec,
             receipt_builder,
+            profiler: None,
+            executed_transactions: None,
+            fee_tracker: None,
+            deposit_requests: Vec::new(),
+            #[cfg(feature = "std")]
+            events: None,
         }
     }
-}
-
-impl<E, Spec, R> BlockExecutor for EthBlockExecutor<'_, E, Spec, R>
-where
-    E: Evm<DB: StateDB, Tx: FromRecoveredTx<R::Transaction> + FromTxWithEncoded<R::Transaction>>,
-    Spec: EthExecutorSpec,
-    R: ReceiptBuilder<Transaction: Transaction + Encodable2718, Receipt: TxReceipt<Log = Log>>,
-{
-    type Transaction = R::Transaction;
-    type Receipt = R::Receipt;
-    type Evm = E;
-    type Result = EthTxResult<E::HaltReason, <R::Transaction as TransactionEnvelope>::TxType>;
 
-    fn apply_pre_execution_changes(&mut self) -> Result<(), BlockExecutionError> {
-        self.system_caller.apply_blockhashes_contract_call(self.ctx.parent_hash, &mut self.evm)?;
-        self.system_caller
-            .apply_beacon_root_contract_call(self.ctx.parent_beacon_block_root, &mut self.evm)?;
-
-        Ok(())
+    /// Emits [`ExecutionEvent`]s to the given sender, see [`events::channel`].
+    ///
+    /// [`events::channel`]: crate::events::channel
+    #[cfg(feature = "std")]
+    pub fn with_events(mut self, sender: ExecutionEventSender<R::Receipt>) -> Self {
+        self.events = Some(sender);
+        self
     }
 
-    fn execute_transaction_without_commit(
-        &mut self,
-        tx: impl ExecutableTx<Self>,
-    ) -> Result<Self::Result, BlockExecutionError> {
-        let (tx_env, tx) = tx.into_parts();
-
-        // The sum of the transaction's gas limit, Tg, and the gas utilized in this block prior,
-        // must be no greater than the block's gasLimit.
-        let block_available_gas = self.evm.block().gas_limit() - self.gas_used;
+    /// Uses `buffer` to store the receipts, e.g. one taken from a
+    /// [`ReceiptPool`](crate::block::ReceiptPool), after clearing it.
+    #[cfg(feature = "perf")]
+    pub fn with_receipts_buffer(mut self, mut buffer: Vec<R::Receipt>) -> Self {
+        buffer.clear();
+        buffer.reserve(self.ctx.tx_count_hint.unwrap_or_default());
+        self.receipts = buffer;
+        self
+    }
 
-        if tx.tx().gas_limit() > block_available_gas {
-            return Err(BlockValidationError::TransactionGasLimitMoreThanAvailableBlockGas {
-                transaction_gas_limit: tx.tx().gas_limit(),
-                block_available_gas,
-            }
-            .into());
-        }
+    /// Enables collection of an [`ExecutionProfile`] for the executed transactions.
+    ///
+    /// The profile can be obtained via [`EthBlockExecutor::take_profile`] or
+    /// [`EthBlockExecutor::finish_with_profile`].
+    pub fn with_profiling(mut self) -> Self {
+        self.profiler = Some(ExecutionProfiler::default());
+        self
+    }
 
-        // Execute transaction and return the result
-        let result = self.evm.transact(tx_env).map_err(|err| {
-            let hash = tx.tx().trie_hash();
-            BlockExecutionError::evm(err, hash)
-        })?;
+    /// Takes the [`ExecutionProfile`] collected so far, if profiling is enabled.
+    pub fn take_profile(&mut self) -> Option<ExecutionProfile> {
+        self.profiler.take().map(ExecutionProfiler::into_profile)
+    }
 
-        Ok(EthTxResult {
-            result,
-            blob_gas_used: tx.tx().blob_gas_used().unwrap_or_default(),
-            tx_type: tx.tx().tx_type(),
-        })
+    /// Enables recording of the hashes and senders of committed transactions.
+    ///
+    /// Transactions that failed to execute or were never committed are not recorded, so the list
+    /// always matches the receipts. It can be obtained via
+    /// [`EthBlockExecutor::executed_transactions`] or
+    /// [`EthBlockExecutor::finish_with_executed_transactions`].
+    pub fn with_executed_transactions(mut self) -> Self {
+        self.executed_transactions = Some(Vec::with_capacity(self.receipts.capacity()));
+        self
     }
 
-    fn commit_transaction(&mut self, output: Self::Result) -> Result<u64, BlockExecutionError> {
-        let EthTxResult { result: ResultAndState { result, state }, blob_gas_used, tx_type } =
-            output;
+    /// Enables tracking of the fees collected by the block beneficiary and the given additional
+    /// recipients, e.g. fee vaults.
+    ///
+    /// The fees can be obtained via [`EthBlockExecutor::fee_tracker`] or
+    /// [`EthBlockExecutor::finish_with_fees`].
+    pub fn with_fee_tracking(mut self, recipients: impl IntoIterator<Item = Address>) -> Self
+    where
+        Evm: crate::Evm,
+    {
+        let mut tracker = FeeTracker::new([self.evm.block().beneficiary()]);
+        tracker.add_recipients(recipients);
+        self.fee_tracker = Some(tracker);
+        self
+    }
 
-        self.system_caller.on_state(StateChangeSource::Transaction(self.receipts.len()), &state);
+    /// Returns the fees collected so far, if fee tracking is enabled.
+    pub const fn fee_tracker(&self) -> Option<&FeeTracker> {
+        self.fee_tracker.as_ref()
+    }
 
-        let gas_used = result.gas_used();
+    /// Returns the transactions committed so far, if recording is enabled.
+    pub fn executed_transactions(&self) -> Option<&[ExecutedTx]> {
+        self.executed_transactions.as_deref()
+    }
 
-        // append gas used
-        self.gas_used += gas_used;
+    /// Returns the result of the transactions committed so far, without finishing the block.
+    ///
+    /// This allows time-sliced payload building to respond with whatever has been packed while
+    /// execution continues. Post-execution changes are not applied, so the result contains no
+    /// requests, and the receipts are cloned to keep transaction indices of the executor intact.
+    pub fn partial_result(&self) -> BlockExecutionResult<R::Receipt>
+    where
+        R::Receipt: Clone,
+    {
+        BlockExecutionResult {
+            receipts: self.receipts.clone(),
+            requests: Default::default(),
+            gas_used: self.gas_used,
+            blob_gas_used: self.blob_gas_used,
+            da_footprint_used: 0,
+        }
+    }
 
-        // only determine cancun fields when active
-        if self.spec.is_cancun_active_at_timestamp(self.evm.block().timestamp().saturating_to()) {
-            self.blob_gas_used = self.blob_gas_used.saturating_add(blob_gas_used);
+    /// Emits [`ExecutionEvent::BlockFailed`] for an error of a transaction, unless it is a
+    /// validation error that block builders skip.
+    fn tx_failed(&self, err: BlockExecutionError) -> BlockExecutionError {
+        if err.as_validation().is_some() {
+            return err;
         }
+        self.block_failed(err)
+    }
 
-        // Push transaction changeset and calculate header bloom filter for receipt.
-        self.receipts.push(self.receipt_builder.build_receipt(ReceiptBuilderCtx {
-            tx_type,
-            evm: &self.evm,
-            result,
-            state: &state,
-            cumulative_gas_used: self.gas_used,
-        }));
+    /// Emits [`ExecutionEvent::BlockFailed`] for an error that fails the block.
+    fn block_failed(&self, err: BlockExecutionError) -> BlockExecutionError {
+        #[cfg(feature = "std")]
+        if let Some(events) = &self.events {
+            events.send(ExecutionEvent::BlockFailed { error: err.to_string() });
+        }
+        err
+    }
+}
 
-        // Commit the state changes.
-        self.evm.db_mut().commit(state);
+impl<E, Spec, R> EthBlockExecutor<'_, E, Spec, R>
+where
+    E: Evm<DB: StateDB, Tx: FromRecoveredTx<R::Transaction> + FromTxWithEncoded<R::Transaction>>,
+    Spec: EthExecutorSpec,
+    R: ReceiptBuilder<Transaction: Transaction + Encodable2718, Receipt: TxReceipt<Log = Log>>,
+{
+    /// Invokes [`BlockExecutor::finish`] and returns the collected [`ExecutionProfile`] alongside
+    /// the [`BlockExecutionResult`].
+    ///
+    /// The profile is `None` unless profiling was enabled via
+    /// [`EthBlockExecutor::with_profiling`].
+    pub fn finish_with_profile(
+        mut self,
+    ) -> Result<(E, BlockExecutionResult<R::Receipt>, Option<ExecutionProfile>), BlockExecutionError>
+    {
+        let profile = self.take_profile();
+        let (evm, result) = self.finish()?;
+        Ok((evm, result, profile))
+    }
 
-        Ok(gas_used)
+    /// Invokes [`BlockExecutor::finish`] and returns the collected fees alongside the
+    /// [`BlockExecutionResult`].
+    ///
+    /// Only fees paid by transactions are tracked, not balance increments applied after the
+    /// transactions such as block rewards or withdrawals. The tracker is `None` unless fee
+    /// tracking was enabled via [`EthBlockExecutor::with_fee_tracking`].
+    pub fn finish_with_fees(
+        mut self,
+    ) -> Result<(E, BlockExecutionResult<R::Receipt>, Option<FeeTracker>), BlockExecutionError>
+    {
+        let fees = self.fee_tracker.take();
+        let (evm, result) = self.finish()?;
+        Ok((evm, result, fees))
     }
 
-    fn finish(
+    /// Invokes [`BlockExecutor::finish`] and returns the committed transactions alongside the
+    /// [`BlockExecutionResult`], in the order of its receipts.
+    ///
+    /// The transactions are `None` unless recording was enabled via
+    /// [`EthBlockExecutor::with_executed_transactions`].
+    pub fn finish_with_executed_transactions(
         mut self,
-    ) -> Result<(Self::Evm, BlockExecutionResult<R::Receipt>), BlockExecutionError> {
+    ) -> Result<(E, BlockExecutionResult<R::Receipt>, Option<Vec<ExecutedTx>>), BlockExecutionError>
+    {
+        let transactions = self.executed_transactions.take();
+        let (evm, result) = self.finish()?;
+        Ok((evm, result, transactions))
+    }
+
+    /// Applies the post-execution changes of the block and returns its EIP-7685 requests.
+    fn apply_post_block_changes(&mut self) -> Result<Requests, BlockExecutionError> {
         let requests = if self
             .spec
             .is_prague_active_at_timestamp(self.evm.block().timestamp().saturating_to())
         {
-            // Collect all EIP-6110 deposits
-            let deposit_requests =
-                eip6110::parse_deposits_from_receipts(&self.spec, &self.receipts)?;
+            // EIP-6110 deposits were collected while committing the transactions
+            let deposit_requests = core::mem::take(&mut self.deposit_requests);
 
             let mut requests = Requests::default();
             if !deposit_requests.is_empty() {
@@ -252,15 +417,213 @@ where
             })
         })?;
 
-        Ok((
-            self.evm,
-            BlockExecutionResult {
-                receipts: self.receipts,
-                requests,
-                gas_used: self.gas_used,
-                blob_gas_used: self.blob_gas_used,
-            },
-        ))
+        Ok(requests)
+    }
+}
+
+impl<E, Spec, R> BlockExecutor for EthBlockExecutor<'_, E, Spec, R>
+where
+    E: Evm<DB: StateDB, Tx: FromRecoveredTx<R::Transaction> + FromTxWithEncoded<R::Transaction>>,
+    Spec: EthExecutorSpec,
+    R: ReceiptBuilder<Transaction: Transaction + Encodable2718, Receipt: TxReceipt<Log = Log>>,
+{
+    type Transaction = R::Transaction;
+    type Receipt = R::Receipt;
+    type Evm = E;
+    type Result =
+        EthTxResult<E::HaltReason, <R::Transaction as TransactionEnvelope>::TxType, R::Extra>;
+
+    fn apply_pre_execution_changes(&mut self) -> Result<(), BlockExecutionError> {
+        self.system_caller.apply_blockhashes_contract_call(self.ctx.parent_hash, &mut self.evm)?;
+        self.system_caller
+            .apply_beacon_root_contract_call(self.ctx.parent_beacon_block_root, &mut self.evm)?;
+
+        #[cfg(feature = "std")]
+        if let Some(events) = &self.events {
+            events.send(ExecutionEvent::BlockStarted {
+                number: self.evm.block().number().saturating_to(),
+                timestamp: self.evm.block().timestamp().saturating_to(),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn execute_transaction_without_commit(
+        &mut self,
+        tx: impl ExecutableTx<Self>,
+    ) -> Result<Self::Result, BlockExecutionError> {
+        let (tx_env, tx) = tx.into_parts();
+        let index = self.receipts.len();
+
+        if let Some(profiler) = &mut self.profiler {
+            profiler.start_transaction();
+        }
+
+        // The sum of the transaction's gas limit, Tg, and the gas utilized in this block prior,
+        // must be no greater than the block's gasLimit.
+        let block_available_gas = self.evm.block().gas_limit() - self.gas_used;
+
+        if tx.tx().gas_limit() > block_available_gas {
+            return Err(BlockValidationError::TransactionGasLimitMoreThanAvailableBlockGas {
+                transaction_gas_limit: tx.tx().gas_limit(),
+                block_available_gas,
+            }
+            .into());
+        }
+
+        // The blob gas used by the transaction, together with the blob gas used in this block
+        // prior, must be no greater than the block's blob gas limit.
+        let blob_gas_used = tx.tx().blob_gas_used().unwrap_or_default();
+        if let Some(blob_params) = self.ctx.blob_params {
+            let block_available_blob_gas =
+                blob_params.max_blob_gas_per_block().saturating_sub(self.blob_gas_used);
+
+            if blob_gas_used > block_available_blob_gas {
+                return Err(BlockValidationError::BlobGasUsedMoreThanAvailableBlockBlobGas {
+                    transaction_blob_gas_used: blob_gas_used,
+                    block_available_blob_gas,
+                }
+                .into());
+            }
+        }
+
+        // Execute transaction and return the result
+        let result = match self.evm.transact(tx_env) {
+            Ok(result) => result,
+            Err(err) => {
+                let hash = tx.tx().trie_hash();
+                let err = BlockExecutionError::tx(index, hash, BlockExecutionError::evm(err, hash));
+                return Err(self.tx_failed(err));
+            }
+        };
+
+        Ok(EthTxResult {
+            result,
+            blob_gas_used,
+            tx_type: tx.tx().tx_type(),
+            tx_hash: tx.tx().trie_hash(),
+            signer: *tx.signer(),
+            extra: Default::default(),
+        })
+    }
+
+    fn commit_transaction(&mut self, output: Self::Result) -> Result<u64, BlockExecutionError> {
+        let EthTxResult {
+            result: ResultAndState { result, state },
+            blob_gas_used,
+            tx_type,
+            tx_hash,
+            signer,
+            extra,
+        } = output;
+        let index = self.receipts.len();
+
+        let gas_used = result.gas_used();
+        let cumulative_gas_used = self.gas_used + gas_used;
+
+        let tx_profile = self
+            .profiler
+            .is_some()
+            .then(|| TxExecutionProfile::new(index, &result, &state, blob_gas_used));
+
+        // Build the receipt before touching any block state, so that a failure leaves the
+        // executor unchanged.
+        let receipt = self
+            .receipt_builder
+            .try_build_receipt(ReceiptBuilderCtx {
+                tx_type,
+                evm: &self.evm,
+                result,
+                state: &state,
+                cumulative_gas_used,
+                extra,
+            })
+            .map_err(|err| self.tx_failed(BlockExecutionError::tx(index, tx_hash, err)))?;
+
+        let timestamp = self.evm.block().timestamp().saturating_to();
+        let deposits = if self.spec.is_prague_active_at_timestamp(timestamp) {
+            let mut deposits = Vec::new();
+            eip6110::accumulate_deposits_from_logs(
+                self.spec
+                    .deposit_contract_address()
+                    .unwrap_or(eip6110::MAINNET_DEPOSIT_CONTRACT_ADDRESS),
+                receipt.logs(),
+                &mut deposits,
+            )
+            .map_err(|err| self.tx_failed(BlockExecutionError::tx(index, tx_hash, err.into())))?;
+            deposits
+        } else {
+            Vec::new()
+        };
+
+        if let Some(tracker) = &mut self.fee_tracker {
+            if let Err(err) = tracker.record(self.evm.db_mut(), &state) {
+                let err = BlockExecutionError::tx(index, tx_hash, BlockExecutionError::other(err));
+                return Err(self.tx_failed(err));
+            }
+        }
+
+        self.system_caller.on_state(StateChangeSource::Transaction(index), &state);
+
+        // append gas used
+        self.gas_used = cumulative_gas_used;
+
+        // only determine cancun fields when active
+        if self.spec.is_cancun_active_at_timestamp(timestamp) {
+            self.blob_gas_used = self.blob_gas_used.saturating_add(blob_gas_used);
+        }
+        self.deposit_requests.extend_from_slice(&deposits);
+
+        #[cfg(feature = "std")]
+        if let Some(events) = &self.events {
+            events.send(ExecutionEvent::TxExecuted {
+                index,
+                hash: tx_hash,
+                gas_used,
+                success: receipt.status(),
+                logs: receipt.logs().to_vec(),
+            });
+        }
+
+        // Push transaction changeset and calculate header bloom filter for receipt.
+        self.receipts.push(receipt);
+        if let Some(transactions) = &mut self.executed_transactions {
+            transactions.push(ExecutedTx { hash: tx_hash, sender: signer });
+        }
+
+        // Commit the state changes.
+        self.evm.db_mut().commit(state);
+
+        if let Some((profiler, tx_profile)) = self.profiler.as_mut().zip(tx_profile) {
+            profiler.record(tx_profile);
+        }
+
+        Ok(gas_used)
+    }
+
+    fn finish(
+        mut self,
+    ) -> Result<(Self::Evm, BlockExecutionResult<R::Receipt>), BlockExecutionError> {
+        let requests = match self.apply_post_block_changes() {
+            Ok(requests) => requests,
+            Err(err) => return Err(self.block_failed(err)),
+        };
+
+        let result = BlockExecutionResult {
+            receipts: self.receipts,
+            requests,
+            gas_used: self.gas_used,
+            blob_gas_used: self.blob_gas_used,
+            da_footprint_used: 0,
+        };
+        self.system_caller.flush_state_hook();
+        #[cfg(feature = "std")]
+        if let Some(events) = &self.events {
+            events.send_block_finished(&result);
+        }
+
+        Ok((self.evm, result))
     }
 
     fn set_state_hook(&mut self, hook: Option<Box<dyn OnStateHook>>) {
@@ -346,3 +709,672 @@ where
         EthBlockExecutor::new(evm, ctx, &self.spec, &self.receipt_builder)
     }
 }
+
+impl<R, EvmF> EthBlockExecutorFactory<R, EthSpec, EvmF>
+where
+    R: ReceiptBuilder<Transaction: Transaction + Encodable2718, Receipt: TxReceipt<Log = Log>>,
+    EvmF: EvmFactory<
+        Spec = SpecId,
+        BlockEnv = BlockEnv,
+        Tx: FromRecoveredTx<R::Transaction> + FromTxWithEncoded<R::Transaction>,
+    >,
+    Self: 'static,
+{
+    /// Executes a block with recovered senders on top of `db`.
+    ///
+    /// The [`EvmEnv`] and the execution context are derived from the block itself, with the chain
+    /// id and blob parameters of the chain specification.
+    pub fn execute_block<DB: StateDB>(
+        &self,
+        db: DB,
+        block: &Block<Recovered<R::Transaction>>,
+    ) -> Result<BlockExecutionResult<R::Receipt>, BlockExecutionError> {
+        let header = &block.header;
+        let blob_params = self.spec.blob_params_at_timestamp(header.timestamp);
+        let evm_env = EvmEnv::for_eth_block(header, &self.spec, self.spec.chain_id(), blob_params);
+        let evm = self.evm_factory.create_evm(db, evm_env);
+        let ctx = EthBlockExecutionCtx::from_block(block).with_blob_params(blob_params);
+        self.create_executor(evm, ctx).execute_block(&block.body.transactions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::{BlockBody, SignableTransaction, TxEnvelope, TxLegacy};
+    use alloy_primitives::{Address, Signature, TxKind, U256};
+    use revm::{
+        database::{CacheDB, EmptyDB, State},
+        inspector::NoOpInspector,
+        state::AccountInfo,
+    };
+
+    const SENDER: Address = Address::repeat_byte(0x01);
+
+    /// The Ethereum block executor over an in-memory database.
+    type TestExecutor<R> = EthBlockExecutor<
+        'static,
+        <EthEvmFactory as EvmFactory>::Evm<State<CacheDB<EmptyDB>>, NoOpInspector>,
+        EthSpec,
+        R,
+    >;
+
+    /// Homestead, so that no system contracts are required.
+    fn header() -> Header {
+        Header { number: 1_150_000, gas_limit: 30_000_000, ..Default::default() }
+    }
+
+    /// Returns two transfers of `value` from [`SENDER`] with consecutive nonces.
+    fn transfers(value: u64) -> Vec<Recovered<TxEnvelope>> {
+        (0..2)
+            .map(|nonce| {
+                let tx = TxLegacy {
+                    nonce,
+                    gas_limit: 21_000,
+                    to: TxKind::Call(Address::repeat_byte(0x02)),
+                    value: U256::from(value),
+                    ..Default::default()
+                };
+                let tx = TxEnvelope::from(tx.into_signed(Signature::test_signature()));
+                Recovered::new_unchecked(tx, SENDER)
+            })
+            .collect()
+    }
+
+    /// Returns [`transfers`] of no value and an executor for a block with the given header on top
+    /// of `db`.
+    fn executor_fixture<R: ReceiptBuilder>(
+        header: &Header,
+        db: CacheDB<EmptyDB>,
+        receipt_builder: R,
+    ) -> (Vec<Recovered<TxEnvelope>>, TestExecutor<R>) {
+        let state = State::builder().with_database(db).build();
+        let evm_env = EvmEnv::for_eth_block(header, EthSpec::mainnet(), 1, None);
+        let evm = EthEvmFactory::default().create_evm(state, evm_env);
+        let executor = EthBlockExecutor::new(
+            evm,
+            EthBlockExecutionCtx::from_header(header),
+            EthSpec::mainnet(),
+            receipt_builder,
+        );
+        (transfers(0), executor)
+    }
+
+    #[test]
+    fn test_factory_execute_block() {
+        let block = Block {
+            header: header(),
+            body: BlockBody { transactions: transfers(1), ommers: Vec::new(), withdrawals: None },
+        };
+
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            SENDER,
+            AccountInfo { balance: U256::from(2), ..Default::default() },
+        );
+        let mut state = State::builder().with_database(db).with_bundle_update().build();
+
+        let factory = EthBlockExecutorFactory::new(
+            AlloyReceiptBuilder::default(),
+            EthSpec::mainnet(),
+            EthEvmFactory::default(),
+        );
+        let result = factory.execute_block(&mut state, &block).unwrap();
+        assert_eq!(result.gas_used, 42_000);
+        assert_eq!(result.receipts.len(), 2);
+    }
+
+    /// An inspector telling instances apart.
+    #[derive(Debug, PartialEq, Eq)]
+    struct Tagged(u8);
+
+    impl<CTX> Inspector<CTX> for Tagged {}
+
+    #[test]
+    fn test_execute_transaction_with_inspector() {
+        let transactions = transfers(0);
+        let mut state = State::builder().with_database(CacheDB::<EmptyDB>::default()).build();
+        let header = header();
+        let evm_env = EvmEnv::for_eth_block(&header, EthSpec::mainnet(), 1, None);
+        let evm =
+            EthEvmFactory::default().create_evm_with_inspector(&mut state, evm_env, Tagged(1));
+        let mut executor = EthBlockExecutor::new(
+            evm,
+            EthBlockExecutionCtx::from_header(&header),
+            EthSpec::mainnet(),
+            AlloyReceiptBuilder,
+        );
+
+        // An enabled inspector stays enabled.
+        assert!(executor.evm().is_inspector_enabled());
+        let (gas_used, inspector) =
+            executor.execute_transaction_with_inspector(&transactions[0], Tagged(2)).unwrap();
+        assert_eq!((gas_used, inspector), (21_000, Tagged(2)));
+        assert_eq!(executor.evm().inspector(), &Tagged(1));
+        assert!(executor.evm().is_inspector_enabled());
+
+        // A disabled inspector stays disabled.
+        executor.evm_mut().disable_inspector();
+        let (_, inspector) =
+            executor.execute_transaction_with_inspector(&transactions[1], Tagged(3)).unwrap();
+        assert_eq!(inspector, Tagged(3));
+        assert_eq!(executor.evm().inspector(), &Tagged(1));
+        assert!(!executor.evm().is_inspector_enabled());
+    }
+
+    /// Fails to build receipts once the block used more than 21k gas.
+    #[derive(Debug)]
+    struct FailingReceiptBuilder;
+
+    impl ReceiptBuilder for FailingReceiptBuilder {
+        type Transaction = TxEnvelope;
+        type Receipt = alloy_consensus::ReceiptEnvelope;
+        type Extra = ();
+
+        fn build_receipt<E: Evm>(
+            &self,
+            ctx: ReceiptBuilderCtx<'_, alloy_consensus::TxType, E>,
+        ) -> Self::Receipt {
+            AlloyReceiptBuilder.build_receipt(ctx)
+        }
+
+        fn try_build_receipt<E: Evm>(
+            &self,
+            ctx: ReceiptBuilderCtx<'_, alloy_consensus::TxType, E>,
+        ) -> Result<Self::Receipt, BlockExecutionError> {
+            if ctx.cumulative_gas_used > 21_000 {
+                return Err(BlockExecutionError::msg("receipt data unavailable"));
+            }
+            Ok(self.build_receipt(ctx))
+        }
+    }
+
+    #[test]
+    fn test_receipt_builder_error() {
+        let (transactions, mut executor) =
+            executor_fixture(&header(), CacheDB::default(), FailingReceiptBuilder);
+
+        executor.execute_transaction(&transactions[0]).unwrap();
+        let err = executor.execute_transaction(&transactions[1]).unwrap_err();
+        assert_eq!(err.tx_index(), Some(1));
+        assert_eq!(err.tx_hash(), Some(*transactions[1].tx_hash()));
+        // the failed transaction didn't affect the block
+        assert_eq!(executor.receipts().len(), 1);
+        assert_eq!(executor.gas_used, 21_000);
+    }
+
+    #[test]
+    fn test_executed_transactions() {
+        let (transactions, executor) =
+            executor_fixture(&header(), CacheDB::default(), AlloyReceiptBuilder);
+        let mut executor = executor.with_executed_transactions();
+
+        executor.execute_transaction(&transactions[0]).unwrap();
+        // the nonce was already used, the transaction is not recorded
+        executor.execute_transaction(&transactions[0]).unwrap_err();
+        // executed, but never committed
+        executor.execute_transaction_without_commit(&transactions[1]).unwrap();
+        assert_eq!(
+            executor.executed_transactions(),
+            Some(&[ExecutedTx { hash: *transactions[0].tx_hash(), sender: SENDER }][..])
+        );
+
+        executor.execute_transaction(&transactions[1]).unwrap();
+        let (_, result, executed) = executor.finish_with_executed_transactions().unwrap();
+        let expected: Vec<_> = transactions
+            .iter()
+            .map(|tx| ExecutedTx { hash: *tx.tx_hash(), sender: SENDER })
+            .collect();
+        assert_eq!(executed, Some(expected));
+        assert_eq!(result.receipts.len(), 2);
+    }
+
+    /// Builds receipts with the number of interop messages attached by the executor as status.
+    #[derive(Debug)]
+    struct MessagesReceiptBuilder;
+
+    impl ReceiptBuilder for MessagesReceiptBuilder {
+        type Transaction = TxEnvelope;
+        type Receipt = alloy_consensus::Receipt;
+        type Extra = u64;
+
+        fn build_receipt<E: Evm>(
+            &self,
+            ctx: ReceiptBuilderCtx<'_, alloy_consensus::TxType, E, u64>,
+        ) -> Self::Receipt {
+            alloy_consensus::Receipt {
+                status: (ctx.extra > 0).into(),
+                cumulative_gas_used: ctx.cumulative_gas_used,
+                logs: Vec::new(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_receipt_builder_extra() {
+        let (transactions, mut executor) =
+            executor_fixture(&header(), CacheDB::default(), MessagesReceiptBuilder);
+
+        let mut output = executor.execute_transaction_without_commit(&transactions[0]).unwrap();
+        assert_eq!(output.extra, 0);
+        output.extra = 2;
+        executor.commit_transaction(output).unwrap();
+        assert!(executor.receipts()[0].status());
+    }
+
+    #[test]
+    fn test_partial_result() {
+        let (transactions, mut executor) =
+            executor_fixture(&header(), CacheDB::default(), AlloyReceiptBuilder);
+        assert!(executor.partial_result().receipts.is_empty());
+
+        executor.execute_transaction(&transactions[0]).unwrap();
+        let partial = executor.partial_result();
+        assert_eq!(partial.receipts, executor.receipts);
+        assert_eq!(partial.gas_used, 21_000);
+        assert!(partial.requests.is_empty());
+
+        // execution continues after taking the partial result
+        executor.execute_transaction(&transactions[1]).unwrap();
+        let partial = executor.partial_result();
+        assert_eq!(partial.receipts.len(), 2);
+        assert_eq!(partial.receipts[1].cumulative_gas_used(), 42_000);
+
+        let (_, result) = executor.finish().unwrap();
+        assert_eq!(result.receipts, partial.receipts);
+        assert_eq!(result.gas_used, partial.gas_used);
+    }
+
+    #[test]
+    fn test_fee_tracking() {
+        let beneficiary = Address::repeat_byte(0xbe);
+        let vault = Address::repeat_byte(0xfe);
+        let transactions = [(Address::repeat_byte(0x02), 0), (vault, 7)]
+            .into_iter()
+            .enumerate()
+            .map(|(nonce, (to, value))| {
+                let tx = TxLegacy {
+                    nonce: nonce as u64,
+                    gas_price: 10,
+                    gas_limit: 21_000,
+                    to: TxKind::Call(to),
+                    value: U256::from(value),
+                    ..Default::default()
+                };
+                let tx = TxEnvelope::from(tx.into_signed(Signature::test_signature()));
+                Recovered::new_unchecked(tx, SENDER)
+            })
+            .collect::<Vec<_>>();
+
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            SENDER,
+            AccountInfo { balance: U256::from(1_000_000), ..Default::default() },
+        );
+        let header = Header { beneficiary, ..header() };
+        let (_, executor) = executor_fixture(&header, db, AlloyReceiptBuilder);
+        let mut executor = executor.with_fee_tracking([vault]);
+        for tx in &transactions {
+            executor.execute_transaction(tx).unwrap();
+        }
+        assert_eq!(executor.fee_tracker().unwrap().tx_fees().len(), 2);
+
+        let (_, result, fees) = executor.finish_with_fees().unwrap();
+        let fees = fees.unwrap();
+        assert_eq!(result.receipts.len(), 2);
+        assert_eq!(fees.recipients(), [beneficiary, vault]);
+        // the value sent to the vault counts, the block reward doesn't
+        assert_eq!(fees.tx_fees(), [U256::from(210_000), U256::from(210_007)]);
+        assert_eq!(fees.total_fees(), U256::from(420_007));
+    }
+
+    #[test]
+    fn test_deposit_requests() {
+        use alloy_sol_types::SolEvent;
+        use revm::bytecode::Bytecode;
+
+        let deposit = eip6110::DepositEvent {
+            pubkey: alloc::vec![0x01; 48].into(),
+            withdrawal_credentials: alloc::vec![0x02; 32].into(),
+            amount: alloc::vec![0x03; 8].into(),
+            signature: alloc::vec![0x04; 96].into(),
+            index: alloc::vec![0x05; 8].into(),
+        };
+        let data = deposit.encode_data();
+        let [len_hi, len_lo] = (data.len() as u16).to_be_bytes();
+        // copies the event data following the 48 bytes of code into memory and logs it
+        let mut code = alloc::vec![0x61, len_hi, len_lo, 0x60, 0x30, 0x60, 0x00, 0x39, 0x7f];
+        code.extend_from_slice(eip6110::DepositEvent::SIGNATURE_HASH.as_slice());
+        code.extend_from_slice(&[0x61, len_hi, len_lo, 0x60, 0x00, 0xa1, 0x00]);
+        assert_eq!(code.len(), 0x30);
+        code.extend_from_slice(&data);
+
+        let tx = TxLegacy {
+            gas_limit: 100_000,
+            to: TxKind::Call(eip6110::MAINNET_DEPOSIT_CONTRACT_ADDRESS),
+            ..Default::default()
+        };
+        let tx = Recovered::new_unchecked(
+            TxEnvelope::from(tx.into_signed(Signature::test_signature())),
+            SENDER,
+        );
+
+        let execute = |timestamp: u64| {
+            let mut db = CacheDB::new(EmptyDB::default());
+            db.insert_account_info(
+                eip6110::MAINNET_DEPOSIT_CONTRACT_ADDRESS,
+                AccountInfo::default().with_code(Bytecode::new_raw(code.clone().into())),
+            );
+            let header =
+                Header { number: 22_431_084, timestamp, base_fee_per_gas: Some(0), ..header() };
+            let (_, mut executor) = executor_fixture(&header, db, AlloyReceiptBuilder);
+            executor.execute_transaction(&tx).unwrap();
+            assert_eq!(executor.receipts()[0].logs().len(), 1);
+            executor.deposit_requests
+        };
+
+        // Prague
+        let mut expected = Vec::new();
+        eip6110::accumulate_deposit_from_log(
+            &Log { address: Address::ZERO, data: deposit },
+            &mut expected,
+        );
+        assert_eq!(expected.len(), 192);
+        assert_eq!(execute(1_746_612_311), expected);
+
+        // Cancun
+        assert!(execute(1_710_338_135).is_empty());
+    }
+
+    #[test]
+    fn test_execution_events() {
+        use crate::events::{self, ExecutionEvent};
+
+        let (transactions, executor) =
+            executor_fixture(&header(), CacheDB::default(), AlloyReceiptBuilder);
+        let tx = &transactions[0];
+        let (sender, receiver) = events::channel(16);
+        let result = executor.with_events(sender).execute_block([tx]).unwrap();
+
+        let events: Vec<_> = receiver.try_iter().collect();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0], ExecutionEvent::BlockStarted { number: 1_150_000, timestamp: 0 });
+        assert_eq!(
+            events[1],
+            ExecutionEvent::TxExecuted {
+                index: 0,
+                hash: tx.tx().trie_hash(),
+                gas_used: 21_000,
+                success: true,
+                logs: Vec::new(),
+            }
+        );
+        assert_eq!(events[2], ExecutionEvent::BlockFinished { result });
+    }
+
+    #[cfg(feature = "perf")]
+    #[test]
+    fn test_receipts_buffer() {
+        use crate::block::ReceiptPool;
+
+        let mut pool = ReceiptPool::new(1);
+        let mut buffer = pool.take(8);
+        buffer.push(alloy_consensus::ReceiptEnvelope::Legacy(Default::default()));
+        let ptr = buffer.as_ptr();
+
+        let (transactions, executor) =
+            executor_fixture(&header(), CacheDB::default(), AlloyReceiptBuilder);
+        let result =
+            executor.with_receipts_buffer(buffer).execute_block(&transactions[..1]).unwrap();
+
+        // the stale receipt was dropped and the pooled allocation reused
+        assert_eq!(result.receipts.len(), 1);
+        assert_eq!(result.receipts[0].cumulative_gas_used(), 21_000);
+        assert_eq!(result.receipts.as_ptr(), ptr);
+
+        pool.recycle(result.receipts);
+        assert_eq!(pool.take(0).as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_execution_events_block_failed() {
+        use crate::events::{self, ExecutionEvent};
+
+        let (transactions, executor) =
+            executor_fixture(&header(), CacheDB::default(), FailingReceiptBuilder);
+        let (events_sender, receiver) = events::channel(16);
+        let mut executor = executor.with_events(events_sender);
+
+        executor.apply_pre_execution_changes().unwrap();
+        executor.execute_transaction(&transactions[0]).unwrap();
+
+        // Validation errors are skipped by block builders and don't fail the block.
+        let err = executor.execute_transaction(&transactions[0]).unwrap_err();
+        assert!(err.as_validation().is_some());
+        assert_eq!(receiver.try_iter().count(), 2);
+
+        executor.execute_transaction(&transactions[1]).unwrap_err();
+        let events: Vec<_> = receiver.try_iter().collect();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], ExecutionEvent::BlockFailed { .. }));
+    }
+
+    #[test]
+    fn test_blob_gas_limit() {
+        use alloy_consensus::TxEip4844;
+        use alloy_eips::eip4844::DATA_GAS_PER_BLOB;
+
+        let tx = TxEip4844 {
+            gas_limit: 21_000,
+            blob_versioned_hashes: alloc::vec![B256::ZERO; 2],
+            ..Default::default()
+        };
+        let tx = Recovered::new_unchecked(
+            TxEnvelope::from(tx.into_signed(Signature::test_signature())),
+            SENDER,
+        );
+
+        let (_, mut executor) =
+            executor_fixture(&header(), CacheDB::default(), AlloyReceiptBuilder);
+        executor.ctx.blob_params = Some(BlobParams { max_blob_count: 1, ..BlobParams::cancun() });
+
+        let err = executor.execute_transaction(&tx).unwrap_err();
+        assert!(matches!(
+            err.as_validation(),
+            Some(BlockValidationError::BlobGasUsedMoreThanAvailableBlockBlobGas {
+                transaction_blob_gas_used,
+                block_available_blob_gas: DATA_GAS_PER_BLOB,
+            }) if *transaction_blob_gas_used == 2 * DATA_GAS_PER_BLOB
+        ));
+        assert!(executor.receipts().is_empty());
+    }
+
+    #[test]
+    fn test_profiling() {
+        use revm::bytecode::Bytecode;
+
+        let contract = Address::repeat_byte(0x02);
+        // reads slot 0 and writes slot 1
+        let code = alloc::vec![0x60, 0x00, 0x54, 0x50, 0x60, 0x01, 0x60, 0x01, 0x55, 0x00];
+        let transactions = [
+            TxLegacy { gas_limit: 100_000, to: TxKind::Call(contract), ..Default::default() },
+            TxLegacy { nonce: 1, gas_limit: 100_000, to: TxKind::Create, ..Default::default() },
+        ]
+        .map(|tx| {
+            Recovered::new_unchecked(
+                TxEnvelope::from(tx.into_signed(Signature::test_signature())),
+                SENDER,
+            )
+        });
+
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            contract,
+            AccountInfo::default().with_code(Bytecode::new_raw(code.into())),
+        );
+        let (_, executor) = executor_fixture(&header(), db, AlloyReceiptBuilder);
+        let mut executor = executor.with_profiling();
+        for tx in &transactions {
+            executor.execute_transaction(tx).unwrap();
+        }
+        let (_, result, profile) = executor.finish_with_profile().unwrap();
+        let profile = profile.unwrap();
+
+        assert_eq!(profile.transactions.len(), 2);
+        let [call, create] = &profile.transactions[..] else { unreachable!() };
+        assert_eq!((call.index, create.index), (0, 1));
+        assert_eq!(call.gas_used, result.receipts[0].cumulative_gas_used());
+        assert_eq!((call.storage_reads, call.storage_writes), (1, 1));
+        assert!(call.created_contracts.is_empty());
+        assert_eq!(create.created_contracts, [SENDER.create(1)]);
+        assert_eq!((create.storage_reads, create.storage_writes), (0, 0));
+        assert_eq!(profile.total_gas_used(), result.gas_used);
+        assert_eq!(profile.total_da_footprint(), 0);
+        #[cfg(feature = "std")]
+        assert_eq!(profile.total_elapsed(), call.elapsed + create.elapsed);
+
+        // profiling is disabled by default
+        let (_, executor) = executor_fixture(&header(), CacheDB::default(), AlloyReceiptBuilder);
+        assert!(executor.finish_with_profile().unwrap().2.is_none());
+    }
+
+    #[test]
+    fn test_block_state_hook_flushed_on_finish() {
+        use crate::block::{ConfiguredStateHook, HookGranularity};
+        use alloc::sync::Arc;
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        let execute = |value: u64| {
+            let calls = Arc::new(AtomicUsize::new(0));
+            let recorded = calls.clone();
+            let hook = ConfiguredStateHook::new(move |source, _: &revm::state::EvmState| {
+                assert!(matches!(source, StateChangeSource::Block));
+                recorded.fetch_add(1, Ordering::Relaxed);
+            })
+            .with_granularity(HookGranularity::Block);
+
+            let (_, executor) =
+                executor_fixture(&header(), CacheDB::default(), AlloyReceiptBuilder);
+            let result = executor
+                .with_state_hook(Some(Box::new(hook)))
+                .execute_block(&transfers(value)[..1]);
+            (result.is_ok(), calls.load(Ordering::Relaxed))
+        };
+
+        assert_eq!(execute(0), (true, 1));
+        // The sender can't pay for the value, so the changes of the failed block are discarded.
+        assert_eq!(execute(1), (false, 0));
+    }
+
+    /// Block environment of a rollup that carries the hash of its L1 origin block.
+    #[derive(Debug, Clone, Default)]
+    struct L1OriginBlockEnv {
+        inner: BlockEnv,
+        l1_origin: B256,
+    }
+
+    impl revm::context::Block for L1OriginBlockEnv {
+        fn number(&self) -> U256 {
+            self.inner.number
+        }
+
+        fn beneficiary(&self) -> Address {
+            self.inner.beneficiary
+        }
+
+        fn timestamp(&self) -> U256 {
+            self.inner.timestamp
+        }
+
+        fn gas_limit(&self) -> u64 {
+            self.inner.gas_limit
+        }
+
+        fn basefee(&self) -> u64 {
+            self.inner.basefee
+        }
+
+        fn difficulty(&self) -> U256 {
+            self.inner.difficulty
+        }
+
+        fn prevrandao(&self) -> Option<B256> {
+            self.inner.prevrandao
+        }
+
+        fn blob_excess_gas_and_price(
+            &self,
+        ) -> Option<revm::context_interface::block::BlobExcessGasAndPrice> {
+            self.inner.blob_excess_gas_and_price
+        }
+
+        fn slot_num(&self) -> u64 {
+            self.inner.slot_num
+        }
+    }
+
+    impl crate::env::BlockEnvironment for L1OriginBlockEnv {
+        fn inner_mut(&mut self) -> &mut BlockEnv {
+            &mut self.inner
+        }
+    }
+
+    #[test]
+    fn test_custom_block_env() {
+        use crate::eth::EthBlockEnvEvmFactory;
+
+        let transactions = transfers(0);
+        let header = header();
+        let EvmEnv { cfg_env, block_env } =
+            EvmEnv::for_eth_block(&header, EthSpec::mainnet(), 1, None);
+        let l1_origin = B256::repeat_byte(0x03);
+        let evm_env = EvmEnv::new(cfg_env, L1OriginBlockEnv { inner: block_env, l1_origin });
+
+        let factory = EthBlockExecutorFactory::new(
+            AlloyReceiptBuilder::default(),
+            EthSpec::mainnet(),
+            EthBlockEnvEvmFactory::<L1OriginBlockEnv>::new(),
+        );
+        let mut state = State::builder().with_database(CacheDB::<EmptyDB>::default()).build();
+        let evm = factory.evm_factory().create_evm(&mut state, evm_env);
+        let mut executor = factory.create_executor(evm, EthBlockExecutionCtx::from_header(&header));
+        assert_eq!(executor.evm().block().l1_origin, l1_origin);
+
+        executor.apply_pre_execution_changes().unwrap();
+        executor.execute_transaction(&transactions[0]).unwrap();
+        let (evm, result) = executor.finish().unwrap();
+        assert_eq!(result.gas_used, 21_000);
+        assert_eq!(evm.block().l1_origin, l1_origin);
+        assert_eq!(evm.block().inner.number, U256::from(1_150_000));
+    }
+
+    #[test]
+    fn test_ctx_from_block() {
+        let header = Header {
+            parent_hash: B256::repeat_byte(0x01),
+            parent_beacon_block_root: Some(B256::repeat_byte(0x02)),
+            extra_data: Bytes::from_static(b"extra"),
+            ..Default::default()
+        };
+        let block = Block::<TxEnvelope> {
+            header: header.clone(),
+            body: BlockBody {
+                transactions: Vec::new(),
+                ommers: alloc::vec![Header::default()],
+                withdrawals: Some(Vec::new().into()),
+            },
+        };
+
+        let ctx = EthBlockExecutionCtx::from_header(&header);
+        assert_eq!(ctx.parent_hash, header.parent_hash);
+        assert_eq!(ctx.parent_beacon_block_root, header.parent_beacon_block_root);
+        assert_eq!(ctx.extra_data, header.extra_data);
+        assert!(ctx.withdrawals.is_none());
+
+        let ctx = EthBlockExecutionCtx::from_block(&block);
+        assert_eq!(ctx.parent_beacon_block_root, header.parent_beacon_block_root);
+        assert_eq!(ctx.ommers.len(), 1);
+        assert_eq!(ctx.withdrawals.as_deref(), Some(&[][..]));
+        assert_eq!(ctx.tx_count_hint, Some(0));
+    }
+}
@@ -1,8 +1,13 @@
-use crate::EvmEnv;
+use crate::{EvmConfig, EvmEnv};
 use alloy_consensus::BlockHeader;
-use alloy_eips::{eip7825::MAX_TX_GAS_LIMIT_OSAKA, eip7840::BlobParams};
+use alloy_eips::{
+    eip1559::{BaseFeeParams, INITIAL_BASE_FEE},
+    eip7825::MAX_TX_GAS_LIMIT_OSAKA,
+    eip7840::BlobParams,
+};
 use alloy_hardforks::EthereumHardforks;
 use alloy_primitives::{Address, BlockNumber, BlockTimestamp, ChainId, B256, U256};
+use core::num::NonZeroU128;
 use revm::{
     context::{BlockEnv, CfgEnv},
     context_interface::block::BlobExcessGasAndPrice,
@@ -28,24 +33,45 @@ impl EvmEnv<SpecId> {
         Self::for_eth(EvmEnvInput::from_block_header(header), chain_spec, chain_id, blob_params)
     }
 
+    /// Create a new `EvmEnv` with [`SpecId`] from a block `header`, `chain_id` and `chain_spec`,
+    /// with the blob parameters, limits and disabled checks of the [`EvmConfig`].
+    pub fn for_eth_block_with_config(
+        header: impl BlockHeader,
+        chain_spec: impl EthereumHardforks,
+        chain_id: ChainId,
+        config: &EvmConfig,
+    ) -> Self {
+        let blob_params = config.blob_params_at(header.timestamp());
+        Self::for_eth_block(header, chain_spec, chain_id, blob_params).with_config(config)
+    }
+
     /// Create a new `EvmEnv` with [`SpecId`] from a parent block `header`, `chain_id`, `chain_spec`
     /// and optional `blob_params`.
     ///
     /// # Arguments
     ///
     /// * `header` - The parent block to make the env out of.
-    /// * `base_fee_per_gas` - Base fee per gas for the next block.
+    /// * `attributes` - The attributes of the next block. The base fee is derived from the parent
+    ///   with their [`NextEvmEnvAttributes::base_fee_params`], the first London block has the
+    ///   initial base fee.
     /// * `chain_spec` - The chain hardfork description, must implement [`EthereumHardforks`].
     /// * `chain_id` - The chain identifier.
     /// * `blob_params` - Optional parameters that sets limits on gas and count for blobs.
     pub fn for_eth_next_block(
         header: impl BlockHeader,
         attributes: NextEvmEnvAttributes,
-        base_fee_per_gas: u64,
         chain_spec: impl EthereumHardforks,
         chain_id: ChainId,
         blob_params: Option<BlobParams>,
     ) -> Self {
+        let base_fee_per_gas =
+            header.next_block_base_fee(attributes.base_fee_params()).unwrap_or_else(|| {
+                if chain_spec.is_london_active_at_block(header.number() + 1) {
+                    INITIAL_BASE_FEE
+                } else {
+                    0
+                }
+            });
         Self::for_eth(
             EvmEnvInput::for_next(header, attributes, base_fee_per_gas, blob_params),
             chain_spec,
@@ -161,6 +187,39 @@ pub struct NextEvmEnvAttributes {
     pub prev_randao: B256,
     /// Block gas limit.
     pub gas_limit: u64,
+    /// EIP-1559 elasticity multiplier, i.e. the ratio of the gas limit to the gas target.
+    ///
+    /// Defaults to the chain's value if `None`.
+    pub elasticity_multiplier: Option<NonZeroU128>,
+    /// EIP-1559 bound on the change of the base fee between blocks, as a denominator.
+    ///
+    /// Defaults to the chain's value if `None`.
+    pub base_fee_max_change_denominator: Option<NonZeroU128>,
+}
+
+impl NextEvmEnvAttributes {
+    /// Returns the EIP-1559 parameters of the next block, defaulting to the Ethereum ones.
+    pub fn base_fee_params(&self) -> BaseFeeParams {
+        self.base_fee_params_or(BaseFeeParams::ethereum())
+    }
+
+    /// Returns the EIP-1559 parameters of the next block, defaulting to `default`.
+    pub(crate) fn base_fee_params_or(&self, default: BaseFeeParams) -> BaseFeeParams {
+        BaseFeeParams {
+            max_change_denominator: self
+                .base_fee_max_change_denominator
+                .map_or(default.max_change_denominator, NonZeroU128::get),
+            elasticity_multiplier: self
+                .elasticity_multiplier
+                .map_or(default.elasticity_multiplier, NonZeroU128::get),
+        }
+    }
+
+    /// Returns the base fee of the next block with the [`Self::base_fee_params`], or `None` if
+    /// the parent block doesn't have a base fee.
+    pub fn next_block_base_fee(&self, parent: impl BlockHeader) -> Option<u64> {
+        parent.next_block_base_fee(self.base_fee_params())
+    }
 }
 
 #[cfg(feature = "engine")]
@@ -209,7 +268,7 @@ mod tests {
     use super::*;
     use crate::eth::spec::EthSpec;
     use alloy_consensus::Header;
-    use alloy_hardforks::ethereum::MAINNET_PARIS_BLOCK;
+    use alloy_hardforks::ethereum::{MAINNET_LONDON_BLOCK, MAINNET_PARIS_BLOCK};
     use alloy_primitives::B256;
 
     #[test_case::test_case(
@@ -256,4 +315,66 @@ mod tests {
 
         assert_eq!(actual_evm_env, expected_evm_env);
     }
+
+    #[test]
+    fn test_next_block_base_fee() {
+        let parent = Header {
+            gas_limit: 30_000_000,
+            gas_used: 30_000_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            ..Header::default()
+        };
+        let mut attributes = NextEvmEnvAttributes {
+            timestamp: 12,
+            suggested_fee_recipient: Address::ZERO,
+            prev_randao: B256::ZERO,
+            gas_limit: 30_000_000,
+            elasticity_multiplier: None,
+            base_fee_max_change_denominator: None,
+        };
+        assert_eq!(attributes.next_block_base_fee(&parent), Some(1_125_000_000));
+
+        // OP Stack parameters since Canyon
+        attributes.elasticity_multiplier = NonZeroU128::new(6);
+        attributes.base_fee_max_change_denominator = NonZeroU128::new(250);
+        assert_eq!(attributes.base_fee_params(), BaseFeeParams::optimism_canyon());
+        assert_eq!(attributes.next_block_base_fee(&parent), Some(1_020_000_000));
+    }
+
+    #[test]
+    fn test_for_eth_next_block_base_fee() {
+        let spec = EthSpec::mainnet();
+        let parent = Header {
+            number: MAINNET_LONDON_BLOCK - 1,
+            gas_limit: 30_000_000,
+            gas_used: 30_000_000,
+            ..Header::default()
+        };
+        let mut attributes = NextEvmEnvAttributes {
+            timestamp: 1_628_166_822,
+            suggested_fee_recipient: Address::ZERO,
+            prev_randao: B256::ZERO,
+            gas_limit: 30_000_000,
+            elasticity_multiplier: None,
+            base_fee_max_change_denominator: None,
+        };
+
+        // The first London block has the initial base fee.
+        let env = EvmEnv::for_eth_next_block(&parent, attributes.clone(), &spec, 1, None);
+        assert_eq!(env.block_env.basefee, INITIAL_BASE_FEE);
+
+        // Afterwards, it's derived from the parent with the parameters of the attributes.
+        let parent = Header {
+            number: MAINNET_LONDON_BLOCK,
+            base_fee_per_gas: Some(1_000_000_000),
+            ..parent
+        };
+        let env = EvmEnv::for_eth_next_block(&parent, attributes.clone(), &spec, 1, None);
+        assert_eq!(env.block_env.basefee, 1_125_000_000);
+
+        attributes.elasticity_multiplier = NonZeroU128::new(6);
+        attributes.base_fee_max_change_denominator = NonZeroU128::new(250);
+        let env = EvmEnv::for_eth_next_block(&parent, attributes, &spec, 1, None);
+        assert_eq!(env.block_env.basefee, 1_020_000_000);
+    }
 }
@@ -5,10 +5,16 @@ pub use env::NextEvmEnvAttributes;
 #[cfg(feature = "op")]
 pub(crate) use env::EvmEnvInput;
 
-use crate::{env::EvmEnv, evm::EvmFactory, precompiles::PrecompilesMap, Database, Evm};
+use crate::{
+    env::{BlockEnvironment, EvmEnv},
+    evm::EvmFactory,
+    precompiles::PrecompilesMap,
+    Database, Evm,
+};
 use alloy_primitives::{Address, Bytes};
 use core::{
     fmt::Debug,
+    marker::PhantomData,
     ops::{Deref, DerefMut},
 };
 use revm::{
@@ -22,13 +28,21 @@ use revm::{
     Context, ExecuteEvm, InspectEvm, Inspector, MainBuilder, MainContext, SystemCallEvm,
 };
 
+mod assemble;
+pub use assemble::{assemble_block, BlockAssemblerInput};
+
 mod block;
 pub use block::*;
 
 pub mod dao_fork;
 pub mod eip6110;
+#[cfg(feature = "engine")]
+pub mod payload;
+mod prevalidate;
+pub use prevalidate::prevalidate_tx;
 pub mod receipt_builder;
 pub mod spec;
+pub mod validate;
 
 mod env;
 pub(crate) mod spec_id;
@@ -36,11 +50,14 @@ pub(crate) mod spec_id;
 /// The Ethereum EVM context type.
 pub type EthEvmContext<DB> = Context<BlockEnv, TxEnv, CfgEnv, DB>;
 
+/// The Ethereum EVM context type with a custom block environment, see [`EthBlockEnvEvmFactory`].
+pub type EthEvmContextWithBlock<DB, B> = Context<B, TxEnv, CfgEnv, DB>;
+
 /// Helper builder to construct `EthEvm` instances in a unified way.
 #[derive(Debug)]
-pub struct EthEvmBuilder<DB: Database, I = NoOpInspector> {
+pub struct EthEvmBuilder<DB: Database, I = NoOpInspector, B = BlockEnv> {
     db: DB,
-    block_env: BlockEnv,
+    block_env: B,
     cfg_env: CfgEnv,
     inspector: I,
     inspect: bool,
@@ -50,6 +67,13 @@ pub struct EthEvmBuilder<DB: Database, I = NoOpInspector> {
 impl<DB: Database> EthEvmBuilder<DB, NoOpInspector> {
     /// Creates a builder from the provided `EvmEnv` and database.
     pub fn new(db: DB, env: EvmEnv) -> Self {
+        Self::new_with_block_env(db, env)
+    }
+}
+
+impl<DB: Database, B: BlockEnvironment> EthEvmBuilder<DB, NoOpInspector, B> {
+    /// Creates a builder from the provided `EvmEnv` with a custom block environment and database.
+    pub fn new_with_block_env(db: DB, env: EvmEnv<SpecId, B>) -> Self {
         Self {
             db,
             block_env: env.block_env,
@@ -61,9 +85,9 @@ impl<DB: Database> EthEvmBuilder<DB, NoOpInspector> {
     }
 }
 
-impl<DB: Database, I> EthEvmBuilder<DB, I> {
+impl<DB: Database, I, B: BlockEnvironment> EthEvmBuilder<DB, I, B> {
     /// Sets a custom inspector
-    pub fn inspector<J>(self, inspector: J) -> EthEvmBuilder<DB, J> {
+    pub fn inspector<J>(self, inspector: J) -> EthEvmBuilder<DB, J, B> {
         EthEvmBuilder {
             db: self.db,
             block_env: self.block_env,
@@ -75,7 +99,7 @@ impl<DB: Database, I> EthEvmBuilder<DB, I> {
     }
 
     /// Sets a custom inspector and enables invoking it during transaction execution.
-    pub fn activate_inspector<J>(self, inspector: J) -> EthEvmBuilder<DB, J> {
+    pub fn activate_inspector<J>(self, inspector: J) -> EthEvmBuilder<DB, J, B> {
         self.inspector(inspector).inspect()
     }
 
@@ -98,9 +122,9 @@ impl<DB: Database, I> EthEvmBuilder<DB, I> {
     }
 
     /// Builds the `EthEvm` instance.
-    pub fn build(self) -> EthEvm<DB, I, PrecompilesMap>
+    pub fn build(self) -> EthEvm<DB, I, PrecompilesMap, B>
     where
-        I: Inspector<EthEvmContext<DB>>,
+        I: Inspector<EthEvmContextWithBlock<DB, B>>,
     {
         let precompiles = match self.precompiles {
             Some(p) => p,
@@ -125,28 +149,31 @@ impl<DB: Database, I> EthEvmBuilder<DB, I> {
 /// This is a wrapper type around the `revm` ethereum evm with optional [`Inspector`] (tracing)
 /// support. [`Inspector`] support is configurable at runtime because it's part of the underlying
 /// [`RevmEvm`] type.
+///
+/// The block environment defaults to [`BlockEnv`], chains with additional block fields can use
+/// their own [`BlockEnvironment`], see [`EthBlockEnvEvmFactory`].
 #[expect(missing_debug_implementations)]
-pub struct EthEvm<DB: Database, I, PRECOMPILE = EthPrecompiles> {
+pub struct EthEvm<DB: Database, I, PRECOMPILE = EthPrecompiles, B: BlockEnvironment = BlockEnv> {
     inner: RevmEvm<
-        EthEvmContext<DB>,
+        EthEvmContextWithBlock<DB, B>,
         I,
-        EthInstructions<EthInterpreter, EthEvmContext<DB>>,
+        EthInstructions<EthInterpreter, EthEvmContextWithBlock<DB, B>>,
         PRECOMPILE,
         EthFrame,
     >,
     inspect: bool,
 }
 
-impl<DB: Database, I, PRECOMPILE> EthEvm<DB, I, PRECOMPILE> {
+impl<DB: Database, I, PRECOMPILE, B: BlockEnvironment> EthEvm<DB, I, PRECOMPILE, B> {
     /// Creates a new Ethereum EVM instance.
     ///
     /// The `inspect` argument determines whether the configured [`Inspector`] of the given
     /// [`RevmEvm`] should be invoked on [`Evm::transact`].
     pub const fn new(
         evm: RevmEvm<
-            EthEvmContext<DB>,
+            EthEvmContextWithBlock<DB, B>,
             I,
-            EthInstructions<EthInterpreter, EthEvmContext<DB>>,
+            EthInstructions<EthInterpreter, EthEvmContextWithBlock<DB, B>>,
             PRECOMPILE,
             EthFrame,
         >,
@@ -159,9 +186,9 @@ impl<DB: Database, I, PRECOMPILE> EthEvm<DB, I, PRECOMPILE> {
     pub fn into_inner(
         self,
     ) -> RevmEvm<
-        EthEvmContext<DB>,
+        EthEvmContextWithBlock<DB, B>,
         I,
-        EthInstructions<EthInterpreter, EthEvmContext<DB>>,
+        EthInstructions<EthInterpreter, EthEvmContextWithBlock<DB, B>>,
         PRECOMPILE,
         EthFrame,
     > {
@@ -169,18 +196,18 @@ impl<DB: Database, I, PRECOMPILE> EthEvm<DB, I, PRECOMPILE> {
     }
 
     /// Provides a reference to the EVM context.
-    pub const fn ctx(&self) -> &EthEvmContext<DB> {
+    pub const fn ctx(&self) -> &EthEvmContextWithBlock<DB, B> {
         &self.inner.ctx
     }
 
     /// Provides a mutable reference to the EVM context.
-    pub const fn ctx_mut(&mut self) -> &mut EthEvmContext<DB> {
+    pub const fn ctx_mut(&mut self) -> &mut EthEvmContextWithBlock<DB, B> {
         &mut self.inner.ctx
     }
 }
 
-impl<DB: Database, I, PRECOMPILE> Deref for EthEvm<DB, I, PRECOMPILE> {
-    type Target = EthEvmContext<DB>;
+impl<DB: Database, I, PRECOMPILE, B: BlockEnvironment> Deref for EthEvm<DB, I, PRECOMPILE, B> {
+    type Target = EthEvmContextWithBlock<DB, B>;
 
     #[inline]
     fn deref(&self) -> &Self::Target {
@@ -188,29 +215,30 @@ impl<DB: Database, I, PRECOMPILE> Deref for EthEvm<DB, I, PRECOMPILE> {
     }
 }
 
-impl<DB: Database, I, PRECOMPILE> DerefMut for EthEvm<DB, I, PRECOMPILE> {
+impl<DB: Database, I, PRECOMPILE, B: BlockEnvironment> DerefMut for EthEvm<DB, I, PRECOMPILE, B> {
     #[inline]
     fn deref_mut(&mut self) -> &mut Self::Target {
         self.ctx_mut()
     }
 }
 
-impl<DB, I, PRECOMPILE> Evm for EthEvm<DB, I, PRECOMPILE>
+impl<DB, I, PRECOMPILE, B> Evm for EthEvm<DB, I, PRECOMPILE, B>
 where
     DB: Database,
-    I: Inspector<EthEvmContext<DB>>,
-    PRECOMPILE: PrecompileProvider<EthEvmContext<DB>, Output = InterpreterResult>,
+    I: Inspector<EthEvmContextWithBlock<DB, B>>,
+    PRECOMPILE: PrecompileProvider<EthEvmContextWithBlock<DB, B>, Output = InterpreterResult>,
+    B: BlockEnvironment,
 {
     type DB = DB;
     type Tx = TxEnv;
     type Error = EVMError<DB::Error>;
     type HaltReason = HaltReason;
     type Spec = SpecId;
-    type BlockEnv = BlockEnv;
+    type BlockEnv = B;
     type Precompiles = PRECOMPILE;
     type Inspector = I;
 
-    fn block(&self) -> &BlockEnv {
+    fn block(&self) -> &B {
         &self.block
     }
 
@@ -238,7 +266,7 @@ where
         self.inner.system_call_with_caller(caller, contract, data)
     }
 
-    fn finish(self) -> (Self::DB, EvmEnv<Self::Spec>) {
+    fn finish(self) -> (Self::DB, EvmEnv<Self::Spec, B>) {
         let Context { block: block_env, cfg: cfg_env, journaled_state, .. } = self.inner.ctx;
 
         (journaled_state.database, EvmEnv { block_env, cfg_env })
@@ -248,6 +276,10 @@ where
         self.inspect = enabled;
     }
 
+    fn is_inspector_enabled(&self) -> bool {
+        self.inspect
+    }
+
     fn components(&self) -> (&Self::DB, &Self::Inspector, &Self::Precompiles) {
         (&self.inner.ctx.journaled_state.database, &self.inner.inspector, &self.inner.precompiles)
     }
@@ -290,6 +322,69 @@ impl EvmFactory for EthEvmFactory {
     }
 }
 
+/// Factory producing [`EthEvm`]s with a custom block environment `B`.
+///
+/// This allows executing Ethereum blocks with chain-specific block fields, e.g. with an
+/// [`EthBlockExecutorFactory`] using this factory. The fields are available to the executor
+/// through [`Evm::block`].
+pub struct EthBlockEnvEvmFactory<B>(PhantomData<fn() -> B>);
+
+impl<B> EthBlockEnvEvmFactory<B> {
+    /// Creates a new factory.
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<B> Debug for EthBlockEnvEvmFactory<B> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("EthBlockEnvEvmFactory").finish()
+    }
+}
+
+impl<B> Default for EthBlockEnvEvmFactory<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B> Clone for EthBlockEnvEvmFactory<B> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<B> Copy for EthBlockEnvEvmFactory<B> {}
+
+impl<B: BlockEnvironment> EvmFactory for EthBlockEnvEvmFactory<B> {
+    type Evm<DB: Database, I: Inspector<EthEvmContextWithBlock<DB, B>>> =
+        EthEvm<DB, I, Self::Precompiles, B>;
+    type Context<DB: Database> = EthEvmContextWithBlock<DB, B>;
+    type Tx = TxEnv;
+    type Error<DBError: core::error::Error + Send + Sync + 'static> = EVMError<DBError>;
+    type HaltReason = HaltReason;
+    type Spec = SpecId;
+    type BlockEnv = B;
+    type Precompiles = PrecompilesMap;
+
+    fn create_evm<DB: Database>(
+        &self,
+        db: DB,
+        input: EvmEnv<SpecId, B>,
+    ) -> Self::Evm<DB, NoOpInspector> {
+        EthEvmBuilder::new_with_block_env(db, input).build()
+    }
+
+    fn create_evm_with_inspector<DB: Database, I: Inspector<Self::Context<DB>>>(
+        &self,
+        db: DB,
+        input: EvmEnv<SpecId, B>,
+        inspector: I,
+    ) -> Self::Evm<DB, I> {
+        EthEvmBuilder::new_with_block_env(db, input).activate_inspector(inspector).build()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -345,4 +440,21 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_factory_disabled_checks() {
+        use crate::evm::EvmFactoryExt;
+
+        let factory = EthEvmFactory.disable_nonce_check();
+        #[cfg(feature = "optional-no-base-fee")]
+        let factory = factory.disable_base_fee();
+        let evm_env = factory.create_evm(EmptyDB::default(), EvmEnv::default()).into_env();
+        assert!(evm_env.cfg_env.disable_nonce_check);
+        #[cfg(feature = "optional-no-base-fee")]
+        assert!(evm_env.cfg_env.disable_base_fee);
+        #[cfg(feature = "optional-balance-check")]
+        assert!(!evm_env.cfg_env.disable_balance_check);
+        #[cfg(feature = "optional-block-gas-limit")]
+        assert!(!evm_env.cfg_env.disable_block_gas_limit);
+    }
 }
@@ -0,0 +1,224 @@
+//! Validation of engine API execution payloads.
+
+use super::{
+    spec::EthExecutorSpec,
+    validate::{validate_block_with_bundle, BlockValidityError, ParentBlock},
+};
+use crate::{
+    block::{BlockExecutionResult, StateRootProvider},
+    Database,
+};
+use alloc::{boxed::Box, string::ToString};
+use alloy_consensus::{ReceiptEnvelope, TxEnvelope};
+use alloy_eips::eip7840::BlobParams;
+use alloy_primitives::{ChainId, B256};
+use alloy_rpc_types_engine::{
+    ExecutionPayload, ExecutionPayloadSidecar, PayloadError, PayloadStatus as EnginePayloadStatus,
+    PayloadStatusEnum,
+};
+use revm::database::BundleState;
+
+/// Reason a payload is invalid.
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidPayloadError {
+    /// The payload could not be converted into a block, or its block hash doesn't match.
+    #[error(transparent)]
+    Payload(#[from] PayloadError),
+    /// Executing the block failed or its outcome doesn't match the header.
+    #[error(transparent)]
+    Block(#[from] BlockValidityError),
+}
+
+/// A payload that was executed and matches its header.
+#[derive(Debug)]
+pub struct ValidatedPayload {
+    /// Hash of the block.
+    pub block_hash: B256,
+    /// Result of executing the block.
+    pub result: BlockExecutionResult<ReceiptEnvelope>,
+    /// Post-execution state changes of the block.
+    pub bundle: BundleState,
+}
+
+/// Outcome of [`validate_payload`].
+#[derive(Debug)]
+pub enum PayloadStatus {
+    /// The payload is valid.
+    Valid(Box<ValidatedPayload>),
+    /// The payload is invalid.
+    Invalid {
+        /// Why the payload is invalid.
+        reason: InvalidPayloadError,
+    },
+    /// The payload could not be validated because state was missing or could not be read.
+    Syncing,
+}
+
+impl PayloadStatus {
+    /// Returns `true` if the payload is valid.
+    pub const fn is_valid(&self) -> bool {
+        matches!(self, Self::Valid(_))
+    }
+
+    /// Converts the status into the engine API response.
+    ///
+    /// `parent_hash` is reported as the latest valid hash of invalid payloads.
+    pub fn into_engine_status(self, parent_hash: B256) -> EnginePayloadStatus {
+        match self {
+            Self::Valid(payload) => {
+                EnginePayloadStatus::new(PayloadStatusEnum::Valid, Some(payload.block_hash))
+            }
+            Self::Invalid { reason } => EnginePayloadStatus::new(
+                PayloadStatusEnum::Invalid { validation_error: reason.to_string() },
+                Some(parent_hash),
+            ),
+            Self::Syncing => EnginePayloadStatus::new(PayloadStatusEnum::Syncing, None),
+        }
+    }
+}
+
+/// Validates a `newPayload` request.
+///
+/// Converts the payload into a block, checks its block hash and its base fee against the `parent`,
+/// executes it on top of `db`, which must hold the state of the parent block, and compares gas
+/// used, blob gas used, receipts root, logs bloom, requests hash and the state root computed by
+/// `state_root` against the header, see [`validate_block_with_bundle`].
+///
+/// Errors reading state or computing the state root, e.g. because the parent state is not
+/// available yet, result in [`PayloadStatus::Syncing`] instead of marking the payload invalid.
+#[allow(clippy::too_many_arguments)]
+pub fn validate_payload<DB, Spec, P>(
+    payload: ExecutionPayload,
+    sidecar: &ExecutionPayloadSidecar,
+    parent: ParentBlock<'_>,
+    chain_spec: Spec,
+    chain_id: ChainId,
+    blob_params: Option<BlobParams>,
+    db: DB,
+    mut state_root: P,
+) -> PayloadStatus
+where
+    DB: Database,
+    Spec: EthExecutorSpec + Clone,
+    P: StateRootProvider,
+{
+    let expected_hash = payload.block_hash();
+    let block = match payload.try_into_block_with_sidecar::<TxEnvelope>(sidecar) {
+        Ok(block) => block,
+        Err(err) => return PayloadStatus::Invalid { reason: err.into() },
+    };
+    let block_hash = block.header.hash_slow();
+    if block_hash != expected_hash {
+        return PayloadStatus::Invalid {
+            reason: PayloadError::BlockHash { execution: block_hash, consensus: expected_hash }
+                .into(),
+        };
+    }
+
+    let (result, bundle) =
+        match validate_block_with_bundle(&block, parent, chain_spec, chain_id, blob_params, db) {
+            Ok(outcome) => outcome,
+            Err(BlockValidityError::Execution(err)) if err.is_transient() => {
+                return PayloadStatus::Syncing
+            }
+            Err(err) => return PayloadStatus::Invalid { reason: err.into() },
+        };
+
+    let Ok(got) = state_root.state_root(&bundle) else { return PayloadStatus::Syncing };
+    if got != block.header.state_root {
+        return PayloadStatus::Invalid {
+            reason: BlockValidityError::StateRoot { got, expected: block.header.state_root }.into(),
+        };
+    }
+
+    PayloadStatus::Valid(Box::new(ValidatedPayload { block_hash, result, bundle }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::spec::EthSpec;
+    use alloc::vec::Vec;
+    use alloy_consensus::{constants::EMPTY_ROOT_HASH, Block, BlockBody, Header};
+    use revm::database::{CacheDB, EmptyDB};
+
+    fn payload(state_root: B256) -> (ExecutionPayload, ExecutionPayloadSidecar) {
+        // Shanghai, so that no system contracts are required.
+        let header = Header {
+            number: 17_034_870,
+            timestamp: 1_681_338_455,
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            withdrawals_root: Some(EMPTY_ROOT_HASH),
+            state_root,
+            ..Default::default()
+        };
+        let body = BlockBody::<TxEnvelope> {
+            transactions: Vec::new(),
+            ommers: Vec::new(),
+            withdrawals: Some(Default::default()),
+        };
+        ExecutionPayload::from_block_slow(&Block::new(header, body))
+    }
+
+    fn validate(
+        payload: ExecutionPayload,
+        sidecar: &ExecutionPayloadSidecar,
+        state_root: B256,
+    ) -> PayloadStatus {
+        // A parent at the gas target, so that the base fee doesn't change.
+        let parent = Header {
+            number: 17_034_869,
+            gas_limit: 30_000_000,
+            gas_used: 15_000_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            ..Default::default()
+        };
+        validate_payload(
+            payload,
+            sidecar,
+            ParentBlock::new(&parent),
+            EthSpec::mainnet(),
+            1,
+            None,
+            CacheDB::new(EmptyDB::default()),
+            |_: &BundleState| state_root,
+        )
+    }
+
+    #[test]
+    fn test_validate_payload() {
+        let root = B256::repeat_byte(0x01);
+        let (payload, sidecar) = payload(root);
+        let block_hash = payload.block_hash();
+
+        let PayloadStatus::Valid(valid) = validate(payload.clone(), &sidecar, root) else {
+            panic!("expected valid payload")
+        };
+        assert_eq!(valid.block_hash, block_hash);
+        assert_eq!(valid.result.gas_used, 0);
+
+        let status = validate(payload, &sidecar, B256::ZERO);
+        assert!(matches!(
+            status,
+            PayloadStatus::Invalid {
+                reason: InvalidPayloadError::Block(BlockValidityError::StateRoot { .. })
+            }
+        ));
+        let status = status.into_engine_status(B256::ZERO);
+        assert!(status.is_invalid());
+        assert_eq!(status.latest_valid_hash, Some(B256::ZERO));
+    }
+
+    #[test]
+    fn test_validate_payload_block_hash() {
+        let (mut payload, sidecar) = payload(B256::ZERO);
+        payload.as_v1_mut().block_hash = B256::repeat_byte(0x02);
+        assert!(matches!(
+            validate(payload, &sidecar, B256::ZERO),
+            PayloadStatus::Invalid {
+                reason: InvalidPayloadError::Payload(PayloadError::BlockHash { .. })
+            }
+        ));
+    }
+}
@@ -0,0 +1,86 @@
+//! Transaction validation without execution.
+
+use crate::{Database, EvmEnv};
+use revm::{
+    context::TxEnv,
+    context_interface::{result::EVMError, Cfg},
+    handler::{pre_execution, validation},
+    interpreter::InitialAndFloorGas,
+    Context, MainContext,
+};
+
+/// Runs the checks the executor performs before executing a transaction, without executing it.
+///
+/// This covers the environment checks (chain id, fee caps, gas limits, blob and EIP-7702 rules),
+/// intrinsic and EIP-7623 floor gas, as well as the checks against the state of the caller: nonce,
+/// code (EIP-3607) and balance. The checks honor the flags of the [`CfgEnv`](revm::context::CfgEnv)
+/// in `evm_env`, e.g. disabled nonce or balance checks.
+///
+/// Returns the initial and floor gas of the transaction. The state is not modified, so this can be
+/// used by transaction pools with the same semantics as the executor.
+pub fn prevalidate_tx<DB: Database>(
+    tx: TxEnv,
+    evm_env: &EvmEnv,
+    db: DB,
+) -> Result<InitialAndFloorGas, EVMError<DB::Error>> {
+    let mut ctx = Context::mainnet()
+        .with_block(evm_env.block_env.clone())
+        .with_cfg(evm_env.cfg_env.clone())
+        .with_tx(tx)
+        .with_db(db);
+
+    validation::validate_env::<_, EVMError<DB::Error>>(&mut ctx)?;
+    let gas =
+        validation::validate_initial_tx_gas(&ctx.tx, ctx.cfg.spec, ctx.cfg.is_eip7623_disabled())?;
+    pre_execution::validate_against_state_and_deduct_caller::<_, EVMError<DB::Error>>(&mut ctx)?;
+    Ok(gas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Address, TxKind, U256};
+    use revm::{
+        context::result::InvalidTransaction,
+        database::{CacheDB, EmptyDB},
+        primitives::hardfork::SpecId,
+        state::AccountInfo,
+    };
+
+    #[test]
+    fn test_prevalidate_tx() {
+        let caller = Address::repeat_byte(0x01);
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            caller,
+            AccountInfo { balance: U256::from(21_000), nonce: 1, ..Default::default() },
+        );
+        let mut evm_env = EvmEnv::default();
+        evm_env.cfg_env.spec = SpecId::CANCUN;
+        let tx = TxEnv {
+            caller,
+            nonce: 1,
+            gas_limit: 21_000,
+            gas_price: 1,
+            kind: TxKind::Call(Address::ZERO),
+            ..Default::default()
+        };
+
+        let gas = prevalidate_tx(tx.clone(), &evm_env, &mut db).unwrap();
+        assert_eq!(gas.initial_gas, 21_000);
+        // the caller was not charged
+        assert_eq!(db.cache.accounts[&caller].info.balance, U256::from(21_000));
+
+        let err = prevalidate_tx(TxEnv { nonce: 2, ..tx.clone() }, &evm_env, &mut db).unwrap_err();
+        assert!(matches!(
+            err,
+            EVMError::Transaction(InvalidTransaction::NonceTooHigh { tx: 2, state: 1 })
+        ));
+
+        let err = prevalidate_tx(TxEnv { value: U256::from(1), ..tx }, &evm_env, &mut db);
+        assert!(matches!(
+            err,
+            Err(EVMError::Transaction(InvalidTransaction::LackOfFundForMaxFee { .. }))
+        ));
+    }
+}
@@ -1,13 +1,15 @@
 //! Abstraction over receipt building logic to allow plugging different primitive types into
 //! [`super::EthBlockExecutor`].
 
-use crate::Evm;
+use crate::{block::BlockExecutionError, Evm};
 use alloy_consensus::{Eip658Value, ReceiptEnvelope, TransactionEnvelope, TxEnvelope, TxType};
 use revm::{context::result::ExecutionResult, state::EvmState};
 
 /// Context for building a receipt.
+///
+/// `X` is the [`ReceiptBuilder::Extra`] data attached to the transaction by the executor.
 #[derive(Debug)]
-pub struct ReceiptBuilderCtx<'a, T, E: Evm> {
+pub struct ReceiptBuilderCtx<'a, T, E: Evm, X = ()> {
     /// Transaction
     pub tx_type: T,
     /// Reference to EVM. State changes should not be committed to inner database when building
@@ -19,6 +21,8 @@ pub struct ReceiptBuilderCtx<'a, T, E: Evm> {
     pub state: &'a EvmState,
     /// Cumulative gas used.
     pub cumulative_gas_used: u64,
+    /// Additional data attached to the transaction by the executor.
+    pub extra: X,
 }
 
 /// Type that knows how to build a receipt based on execution result.
@@ -28,12 +32,46 @@ pub trait ReceiptBuilder {
     type Transaction: TransactionEnvelope;
     /// Receipt type.
     type Receipt;
+    /// Additional per-transaction data passed from the executor to the builder, e.g. fees charged
+    /// by the chain that are not part of the execution result.
+    ///
+    /// The Ethereum executor initializes it with [`Default::default`] and exposes it on
+    /// [`EthTxResult::extra`](super::EthTxResult::extra), so that wrapping executors can fill it in
+    /// between [`execute_transaction_without_commit`] and [`commit_transaction`].
+    ///
+    /// [`execute_transaction_without_commit`]: crate::block::BlockExecutor::execute_transaction_without_commit
+    /// [`commit_transaction`]: crate::block::BlockExecutor::commit_transaction
+    type Extra: Default;
 
     /// Builds a receipt given a transaction and the result of the execution.
     fn build_receipt<E: Evm>(
         &self,
-        ctx: ReceiptBuilderCtx<'_, <Self::Transaction as TransactionEnvelope>::TxType, E>,
+        ctx: ReceiptBuilderCtx<
+            '_,
+            <Self::Transaction as TransactionEnvelope>::TxType,
+            E,
+            Self::Extra,
+        >,
     ) -> Self::Receipt;
+
+    /// Fallible variant of [`ReceiptBuilder::build_receipt`], used by the block executor.
+    ///
+    /// Builders that need to read additional data, e.g. from the EVM's database, should override
+    /// this and return an error instead of falling back to a default receipt. The executor
+    /// attributes the error to the transaction and leaves the block state untouched.
+    ///
+    /// Defaults to [`ReceiptBuilder::build_receipt`].
+    fn try_build_receipt<E: Evm>(
+        &self,
+        ctx: ReceiptBuilderCtx<
+            '_,
+            <Self::Transaction as TransactionEnvelope>::TxType,
+            E,
+            Self::Extra,
+        >,
+    ) -> Result<Self::Receipt, BlockExecutionError> {
+        Ok(self.build_receipt(ctx))
+    }
 }
 
 /// Receipt builder operating on Alloy types.
@@ -44,6 +82,7 @@ pub struct AlloyReceiptBuilder;
 impl ReceiptBuilder for AlloyReceiptBuilder {
     type Transaction = TxEnvelope;
     type Receipt = ReceiptEnvelope;
+    type Extra = ();
 
     fn build_receipt<E: Evm>(&self, ctx: ReceiptBuilderCtx<'_, TxType, E>) -> Self::Receipt {
         let receipt = alloy_consensus::Receipt {
@@ -1,8 +1,10 @@
 //! Abstraction over configuration object for [`super::EthBlockExecutor`].
 
-use alloy_eips::eip6110::MAINNET_DEPOSIT_CONTRACT_ADDRESS;
+use crate::block::SystemContracts;
+use alloc::vec::Vec;
+use alloy_eips::{eip6110::MAINNET_DEPOSIT_CONTRACT_ADDRESS, eip7840::BlobParams};
 use alloy_hardforks::{EthereumChainHardforks, EthereumHardfork, EthereumHardforks, ForkCondition};
-use alloy_primitives::{address, Address};
+use alloy_primitives::{address, Address, BlockTimestamp, ChainId};
 
 /// A configuration object for [`super::EthBlockExecutor`]
 #[auto_impl::auto_impl(&, Arc)]
@@ -11,41 +13,214 @@ pub trait EthExecutorSpec: EthereumHardforks {
     ///
     /// Used by [`super::eip6110::parse_deposits_from_receipts`].
     fn deposit_contract_address(&self) -> Option<Address>;
+
+    /// Addresses of the system contracts called before and after each block.
+    ///
+    /// Defaults to the addresses of the system contracts on Ethereum mainnet.
+    fn system_contracts(&self) -> SystemContracts {
+        SystemContracts::mainnet()
+    }
 }
 
 /// Basic Ethereum specification.
 #[derive(Debug, Clone)]
 pub struct EthSpec {
+    chain_id: ChainId,
     hardforks: EthereumChainHardforks,
     deposit_contract_address: Option<Address>,
+    blob_schedule: Vec<(EthereumHardfork, BlobParams)>,
+    system_contracts: SystemContracts,
 }
 
 impl EthSpec {
     /// Creates [`EthSpec`] for Ethereum mainnet.
     pub fn mainnet() -> Self {
         Self {
+            chain_id: 1,
             hardforks: EthereumChainHardforks::mainnet(),
             deposit_contract_address: Some(MAINNET_DEPOSIT_CONTRACT_ADDRESS),
+            blob_schedule: default_blob_schedule(),
+            system_contracts: SystemContracts::mainnet(),
         }
     }
 
     /// Creates [`EthSpec`] for Ethereum Sepolia.
     pub fn sepolia() -> Self {
         Self {
+            chain_id: 11_155_111,
             hardforks: EthereumChainHardforks::sepolia(),
             deposit_contract_address: Some(address!("0x7f02c3e3c98b133055b8b348b2ac625669ed295d")),
+            blob_schedule: default_blob_schedule(),
+            system_contracts: SystemContracts::mainnet(),
         }
     }
 
     /// Creates [`EthSpec`] for Ethereum Holesky.
     pub fn holesky() -> Self {
         Self {
+            chain_id: 17_000,
             hardforks: EthereumChainHardforks::holesky(),
             deposit_contract_address: Some(address!("0x4242424242424242424242424242424242424242")),
+            blob_schedule: default_blob_schedule(),
+            system_contracts: SystemContracts::mainnet(),
+        }
+    }
+
+    /// Creates [`EthSpec`] for Ethereum Hoodi.
+    pub fn hoodi() -> Self {
+        Self {
+            chain_id: 560_048,
+            hardforks: EthereumChainHardforks::hoodi(),
+            deposit_contract_address: Some(address!("0x00000000219ab540356cBB839Cbe05303d7705Fa")),
+            blob_schedule: default_blob_schedule(),
+            system_contracts: SystemContracts::mainnet(),
+        }
+    }
+
+    /// Returns the [`EthSpec`] of the known Ethereum network with the given chain id.
+    pub fn from_chain_id(chain_id: ChainId) -> Option<Self> {
+        match chain_id {
+            1 => Some(Self::mainnet()),
+            11_155_111 => Some(Self::sepolia()),
+            17_000 => Some(Self::holesky()),
+            560_048 => Some(Self::hoodi()),
+            _ => None,
+        }
+    }
+
+    /// Returns the chain id.
+    pub const fn chain_id(&self) -> ChainId {
+        self.chain_id
+    }
+
+    /// Sets the addresses of the system contracts, for chains deploying them at nonstandard
+    /// addresses.
+    pub const fn with_system_contracts(mut self, system_contracts: SystemContracts) -> Self {
+        self.system_contracts = system_contracts;
+        self
+    }
+
+    /// Returns the blob parameters active at the given timestamp, or `None` before Cancun.
+    pub fn blob_params_at_timestamp(&self, timestamp: BlockTimestamp) -> Option<BlobParams> {
+        self.blob_schedule
+            .iter()
+            .rev()
+            .find(|(fork, _)| self.is_ethereum_fork_active_at_timestamp(*fork, timestamp))
+            .map(|(_, params)| *params)
+    }
+}
+
+#[cfg(feature = "genesis")]
+impl EthSpec {
+    /// Creates [`EthSpec`] from the `config` section of a genesis file.
+    ///
+    /// Reads the chain id, the fork blocks and timestamps, the deposit contract address and the
+    /// blob schedule. Forks missing from the blob schedule use their default blob parameters.
+    pub fn from_genesis(genesis: &alloy_genesis::Genesis) -> Self {
+        let config = &genesis.config;
+
+        let block_forks = [
+            (EthereumHardfork::Frontier, Some(0)),
+            (EthereumHardfork::Homestead, config.homestead_block),
+            (EthereumHardfork::Dao, config.dao_fork_block),
+            (EthereumHardfork::Tangerine, config.eip150_block),
+            (EthereumHardfork::SpuriousDragon, config.eip158_block),
+            (EthereumHardfork::Byzantium, config.byzantium_block),
+            (EthereumHardfork::Constantinople, config.constantinople_block),
+            (EthereumHardfork::Petersburg, config.petersburg_block),
+            (EthereumHardfork::Istanbul, config.istanbul_block),
+            (EthereumHardfork::MuirGlacier, config.muir_glacier_block),
+            (EthereumHardfork::Berlin, config.berlin_block),
+            (EthereumHardfork::London, config.london_block),
+            (EthereumHardfork::ArrowGlacier, config.arrow_glacier_block),
+            (EthereumHardfork::GrayGlacier, config.gray_glacier_block),
+        ];
+        let timestamp_forks = [
+            (EthereumHardfork::Shanghai, config.shanghai_time),
+            (EthereumHardfork::Cancun, config.cancun_time),
+            (EthereumHardfork::Prague, config.prague_time),
+            (EthereumHardfork::Osaka, config.osaka_time),
+            (EthereumHardfork::Bpo1, config.bpo1_time),
+            (EthereumHardfork::Bpo2, config.bpo2_time),
+            (EthereumHardfork::Bpo3, config.bpo3_time),
+            (EthereumHardfork::Bpo4, config.bpo4_time),
+            (EthereumHardfork::Bpo5, config.bpo5_time),
+        ];
+
+        let mut hardforks = block_forks
+            .into_iter()
+            .filter_map(|(fork, block)| Some((fork, ForkCondition::Block(block?))))
+            .collect::<Vec<_>>();
+        if let Some(total_difficulty) = config.terminal_total_difficulty {
+            let fork_block =
+                config.merge_netsplit_block.or_else(|| total_difficulty.is_zero().then_some(0));
+            hardforks.push((
+                EthereumHardfork::Paris,
+                ForkCondition::TTD {
+                    activation_block_number: fork_block.unwrap_or_default(),
+                    fork_block,
+                    total_difficulty,
+                },
+            ));
+        }
+        hardforks.extend(
+            timestamp_forks
+                .into_iter()
+                .filter_map(|(fork, timestamp)| Some((fork, ForkCondition::Timestamp(timestamp?)))),
+        );
+
+        let mut blob_schedule = default_blob_schedule();
+        for (name, params) in &config.blob_schedule {
+            let Some(fork) = BLOB_SCHEDULE_FORKS
+                .iter()
+                .find_map(|(fork, fork_name)| (*fork_name == name.as_str()).then_some(*fork))
+            else {
+                continue;
+            };
+            match blob_schedule.iter_mut().find(|(existing, _)| *existing == fork) {
+                Some(entry) => entry.1 = *params,
+                None => blob_schedule.push((fork, *params)),
+            }
+        }
+        blob_schedule.sort_by_key(|(fork, _)| {
+            BLOB_SCHEDULE_FORKS.iter().position(|(existing, _)| existing == fork)
+        });
+
+        Self {
+            chain_id: config.chain_id,
+            hardforks: EthereumChainHardforks::new(hardforks),
+            deposit_contract_address: config.deposit_contract_address,
+            blob_schedule,
+            system_contracts: SystemContracts::mainnet(),
         }
     }
 }
 
+/// Forks that can change the blob parameters, in activation order, with their blob schedule
+/// names.
+#[cfg(feature = "genesis")]
+const BLOB_SCHEDULE_FORKS: [(EthereumHardfork, &str); 8] = [
+    (EthereumHardfork::Cancun, "cancun"),
+    (EthereumHardfork::Prague, "prague"),
+    (EthereumHardfork::Osaka, "osaka"),
+    (EthereumHardfork::Bpo1, "bpo1"),
+    (EthereumHardfork::Bpo2, "bpo2"),
+    (EthereumHardfork::Bpo3, "bpo3"),
+    (EthereumHardfork::Bpo4, "bpo4"),
+    (EthereumHardfork::Bpo5, "bpo5"),
+];
+
+/// Blob schedule of the canonical networks.
+fn default_blob_schedule() -> Vec<(EthereumHardfork, BlobParams)> {
+    alloc::vec![
+        (EthereumHardfork::Cancun, BlobParams::cancun()),
+        (EthereumHardfork::Prague, BlobParams::prague()),
+        (EthereumHardfork::Osaka, BlobParams::osaka()),
+        (EthereumHardfork::Bpo1, BlobParams::bpo1()),
+        (EthereumHardfork::Bpo2, BlobParams::bpo2()),
+    ]
+}
+
 impl EthereumHardforks for EthSpec {
     fn ethereum_fork_activation(&self, fork: EthereumHardfork) -> ForkCondition {
         self.hardforks.ethereum_fork_activation(fork)
@@ -56,4 +231,38 @@ impl EthExecutorSpec for EthSpec {
     fn deposit_contract_address(&self) -> Option<Address> {
         self.deposit_contract_address
     }
+
+    fn system_contracts(&self) -> SystemContracts {
+        self.system_contracts
+    }
+}
+
+#[cfg(all(test, feature = "genesis"))]
+mod tests {
+    use super::*;
+    use alloy_genesis::Genesis;
+
+    #[test]
+    fn test_from_genesis() {
+        let mut genesis = Genesis::default();
+        genesis.config.chain_id = 1337;
+        genesis.config.london_block = Some(0);
+        genesis.config.terminal_total_difficulty = Some(Default::default());
+        genesis.config.shanghai_time = Some(0);
+        genesis.config.cancun_time = Some(0);
+        genesis.config.prague_time = Some(100);
+        let prague = BlobParams { max_blob_count: 12, ..BlobParams::prague() };
+        genesis.config.blob_schedule.insert("prague".into(), prague);
+
+        let spec = EthSpec::from_genesis(&genesis);
+
+        assert_eq!(spec.chain_id(), 1337);
+        assert!(spec.is_london_active_at_block(0));
+        assert!(spec.is_paris_active_at_block(0));
+        assert!(spec.is_cancun_active_at_timestamp(0));
+        assert!(!spec.is_prague_active_at_timestamp(99));
+        assert!(!spec.is_osaka_active_at_timestamp(u64::MAX));
+        assert_eq!(spec.blob_params_at_timestamp(99), Some(BlobParams::cancun()));
+        assert_eq!(spec.blob_params_at_timestamp(100), Some(prague));
+    }
 }
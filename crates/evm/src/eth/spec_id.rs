@@ -1,5 +1,6 @@
+use alloc::vec::Vec;
 use alloy_consensus::BlockHeader;
-use alloy_hardforks::EthereumHardforks;
+use alloy_hardforks::{EthereumHardfork, EthereumHardforks, ForkCondition};
 use alloy_primitives::{BlockNumber, BlockTimestamp};
 use revm::primitives::hardfork::SpecId;
 
@@ -12,46 +13,204 @@ where
     spec_by_timestamp_and_block_number(chain_spec, header.timestamp(), header.number())
 }
 
+/// Maps an [`EthereumHardfork`] to a spec type, e.g. a revm [`SpecId`].
+///
+/// Chains with their own spec enums implement this to reuse the fork resolution of
+/// [`custom_spec_by_timestamp_and_block_number`].
+pub trait HardforkToSpec {
+    /// Returns the spec that is active from the given hardfork on.
+    fn from_hardfork(fork: EthereumHardfork) -> Self;
+}
+
+impl HardforkToSpec for SpecId {
+    fn from_hardfork(fork: EthereumHardfork) -> Self {
+        match fork {
+            EthereumHardfork::Frontier => Self::FRONTIER,
+            EthereumHardfork::Homestead => Self::HOMESTEAD,
+            EthereumHardfork::Dao => Self::DAO_FORK,
+            EthereumHardfork::Tangerine => Self::TANGERINE,
+            EthereumHardfork::SpuriousDragon => Self::SPURIOUS_DRAGON,
+            EthereumHardfork::Byzantium => Self::BYZANTIUM,
+            EthereumHardfork::Constantinople => Self::CONSTANTINOPLE,
+            EthereumHardfork::Petersburg => Self::PETERSBURG,
+            EthereumHardfork::Istanbul => Self::ISTANBUL,
+            EthereumHardfork::MuirGlacier => Self::MUIR_GLACIER,
+            EthereumHardfork::Berlin => Self::BERLIN,
+            EthereumHardfork::London => Self::LONDON,
+            EthereumHardfork::ArrowGlacier => Self::ARROW_GLACIER,
+            EthereumHardfork::GrayGlacier => Self::GRAY_GLACIER,
+            EthereumHardfork::Paris => Self::MERGE,
+            EthereumHardfork::Shanghai => Self::SHANGHAI,
+            EthereumHardfork::Cancun => Self::CANCUN,
+            EthereumHardfork::Prague => Self::PRAGUE,
+            // Blob parameter only forks don't change the EVM spec.
+            EthereumHardfork::Osaka
+            | EthereumHardfork::Bpo1
+            | EthereumHardfork::Bpo2
+            | EthereumHardfork::Bpo3
+            | EthereumHardfork::Bpo4
+            | EthereumHardfork::Bpo5 => Self::OSAKA,
+            EthereumHardfork::Amsterdam => Self::AMSTERDAM,
+        }
+    }
+}
+
 /// Map the latest active hardfork at the given timestamp or block number to a [`SpecId`].
 pub fn spec_by_timestamp_and_block_number<C>(
     chain_spec: &C,
     timestamp: BlockTimestamp,
     block_number: BlockNumber,
 ) -> SpecId
+where
+    C: EthereumHardforks,
+{
+    custom_spec_by_timestamp_and_block_number(chain_spec, timestamp, block_number)
+}
+
+/// Map the latest active hardfork at the given timestamp or block number to a spec of type `S`.
+pub fn custom_spec_by_timestamp_and_block_number<S, C>(
+    chain_spec: &C,
+    timestamp: BlockTimestamp,
+    block_number: BlockNumber,
+) -> S
+where
+    S: HardforkToSpec,
+    C: EthereumHardforks,
+{
+    S::from_hardfork(hardfork_by_timestamp_and_block_number(chain_spec, timestamp, block_number))
+}
+
+/// Returns the latest hardfork changing the EVM spec that is active at the given timestamp or
+/// block number.
+pub fn hardfork_by_timestamp_and_block_number<C>(
+    chain_spec: &C,
+    timestamp: BlockTimestamp,
+    block_number: BlockNumber,
+) -> EthereumHardfork
 where
     C: EthereumHardforks,
 {
     if chain_spec.is_osaka_active_at_timestamp(timestamp) {
-        SpecId::OSAKA
+        EthereumHardfork::Osaka
     } else if chain_spec.is_prague_active_at_timestamp(timestamp) {
-        SpecId::PRAGUE
+        EthereumHardfork::Prague
     } else if chain_spec.is_cancun_active_at_timestamp(timestamp) {
-        SpecId::CANCUN
+        EthereumHardfork::Cancun
     } else if chain_spec.is_shanghai_active_at_timestamp(timestamp) {
-        SpecId::SHANGHAI
+        EthereumHardfork::Shanghai
     } else if chain_spec.is_paris_active_at_block(block_number) {
-        SpecId::MERGE
+        EthereumHardfork::Paris
     } else if chain_spec.is_london_active_at_block(block_number) {
-        SpecId::LONDON
+        EthereumHardfork::London
     } else if chain_spec.is_berlin_active_at_block(block_number) {
-        SpecId::BERLIN
+        EthereumHardfork::Berlin
     } else if chain_spec.is_istanbul_active_at_block(block_number) {
-        SpecId::ISTANBUL
+        EthereumHardfork::Istanbul
     } else if chain_spec.is_petersburg_active_at_block(block_number) {
-        SpecId::PETERSBURG
+        EthereumHardfork::Petersburg
     } else if chain_spec.is_byzantium_active_at_block(block_number) {
-        SpecId::BYZANTIUM
+        EthereumHardfork::Byzantium
     } else if chain_spec.is_spurious_dragon_active_at_block(block_number) {
-        SpecId::SPURIOUS_DRAGON
+        EthereumHardfork::SpuriousDragon
     } else if chain_spec.is_tangerine_whistle_active_at_block(block_number) {
-        SpecId::TANGERINE
+        EthereumHardfork::Tangerine
     } else if chain_spec.is_homestead_active_at_block(block_number) {
-        SpecId::HOMESTEAD
+        EthereumHardfork::Homestead
     } else {
-        SpecId::FRONTIER
+        EthereumHardfork::Frontier
+    }
+}
+
+/// Hardforks that change the EVM spec, in activation order.
+const SPEC_HARDFORKS: [EthereumHardfork; 14] = [
+    EthereumHardfork::Frontier,
+    EthereumHardfork::Homestead,
+    EthereumHardfork::Tangerine,
+    EthereumHardfork::SpuriousDragon,
+    EthereumHardfork::Byzantium,
+    EthereumHardfork::Petersburg,
+    EthereumHardfork::Istanbul,
+    EthereumHardfork::Berlin,
+    EthereumHardfork::London,
+    EthereumHardfork::Paris,
+    EthereumHardfork::Shanghai,
+    EthereumHardfork::Cancun,
+    EthereumHardfork::Prague,
+    EthereumHardfork::Osaka,
+];
+
+/// Block number or timestamp from which a spec is active.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Activation {
+    /// Active from the given block number on.
+    Block(BlockNumber),
+    /// Active from the given timestamp on.
+    Timestamp(BlockTimestamp),
+}
+
+impl Activation {
+    /// Converts a [`ForkCondition`] to an [`Activation`].
+    ///
+    /// Returns `None` for forks that never activate, or that activate by total difficulty
+    /// without a known fork block.
+    pub const fn from_fork_condition(condition: ForkCondition) -> Option<Self> {
+        match condition {
+            ForkCondition::Block(block) | ForkCondition::TTD { fork_block: Some(block), .. } => {
+                Some(Self::Block(block))
+            }
+            ForkCondition::Timestamp(timestamp) => Some(Self::Timestamp(timestamp)),
+            ForkCondition::TTD { fork_block: None, .. } | ForkCondition::Never => None,
+        }
+    }
+
+    /// Returns `true` if a block with the given number and timestamp is at or past the
+    /// activation.
+    pub const fn is_active_at(&self, block_number: BlockNumber, timestamp: BlockTimestamp) -> bool {
+        match *self {
+            Self::Block(block) => block_number >= block,
+            Self::Timestamp(activation) => timestamp >= activation,
+        }
     }
 }
 
+/// Returns the activation boundaries of the specs of `chain_spec`, in activation order.
+///
+/// Each item is a spec together with the block number or timestamp from which it is active, until
+/// the activation of the next item. Forks activating at the same boundary are collapsed into the
+/// latest one, and forks that never activate are skipped.
+pub fn fork_schedule<C>(chain_spec: &C) -> impl Iterator<Item = (SpecId, Activation)>
+where
+    C: EthereumHardforks,
+{
+    custom_fork_schedule(chain_spec)
+}
+
+/// Same as [`fork_schedule`], for a spec of type `S`.
+pub fn custom_fork_schedule<S, C>(chain_spec: &C) -> impl Iterator<Item = (S, Activation)>
+where
+    S: HardforkToSpec,
+    C: EthereumHardforks,
+{
+    collapse_schedule(SPEC_HARDFORKS.into_iter().filter_map(|fork| {
+        Activation::from_fork_condition(chain_spec.ethereum_fork_activation(fork))
+            .map(|activation| (S::from_hardfork(fork), activation))
+    }))
+}
+
+/// Collapses consecutive items with the same activation into the last one.
+pub(crate) fn collapse_schedule<S>(
+    schedule: impl IntoIterator<Item = (S, Activation)>,
+) -> impl Iterator<Item = (S, Activation)> {
+    let mut collapsed: Vec<(S, Activation)> = Vec::new();
+    for (spec, activation) in schedule {
+        match collapsed.last_mut() {
+            Some(last) if last.1 == activation => last.0 = spec,
+            _ => collapsed.push((spec, activation)),
+        }
+    }
+    collapsed.into_iter()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -65,7 +224,7 @@ mod tests {
             MAINNET_PRAGUE_TIMESTAMP, MAINNET_SHANGHAI_TIMESTAMP, MAINNET_SPURIOUS_DRAGON_BLOCK,
             MAINNET_TANGERINE_BLOCK,
         },
-        EthereumHardfork, ForkCondition,
+        ForkCondition,
     };
     use alloy_primitives::{BlockNumber, BlockTimestamp};
 
@@ -185,4 +344,77 @@ mod tests {
 
         assert_eq!(actual_spec, expected_spec);
     }
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum CustomSpec {
+        Legacy,
+        Modern,
+    }
+
+    impl HardforkToSpec for CustomSpec {
+        fn from_hardfork(fork: EthereumHardfork) -> Self {
+            match fork {
+                EthereumHardfork::Cancun | EthereumHardfork::Prague | EthereumHardfork::Osaka => {
+                    Self::Modern
+                }
+                _ => Self::Legacy,
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_spec_mapping() {
+        let spec = EthSpec::mainnet();
+
+        let modern: CustomSpec =
+            custom_spec_by_timestamp_and_block_number(&spec, MAINNET_CANCUN_TIMESTAMP, 0);
+        assert_eq!(modern, CustomSpec::Modern);
+
+        let legacy: CustomSpec =
+            custom_spec_by_timestamp_and_block_number(&spec, 0, MAINNET_LONDON_BLOCK);
+        assert_eq!(legacy, CustomSpec::Legacy);
+    }
+
+    #[test]
+    fn test_fork_schedule_matches_spec() {
+        let chain_spec = EthSpec::mainnet();
+        let schedule = fork_schedule(&chain_spec).collect::<Vec<_>>();
+
+        assert_eq!(schedule.first(), Some(&(SpecId::FRONTIER, Activation::Block(0))));
+        assert!(schedule.contains(&(SpecId::MERGE, Activation::Block(MAINNET_PARIS_BLOCK))));
+        assert!(schedule
+            .contains(&(SpecId::SHANGHAI, Activation::Timestamp(MAINNET_SHANGHAI_TIMESTAMP))));
+
+        for (expected, activation) in schedule {
+            let (number, timestamp) = match activation {
+                Activation::Block(number) => (number, 0),
+                Activation::Timestamp(timestamp) => (u64::MAX, timestamp),
+            };
+            assert_eq!(
+                spec_by_timestamp_and_block_number(&chain_spec, timestamp, number),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_fork_schedule_collapses_boundaries() {
+        let schedule = collapse_schedule([
+            (SpecId::FRONTIER, Activation::Block(0)),
+            (SpecId::HOMESTEAD, Activation::Block(0)),
+            (SpecId::LONDON, Activation::Block(10)),
+            (SpecId::SHANGHAI, Activation::Timestamp(0)),
+            (SpecId::CANCUN, Activation::Timestamp(0)),
+        ])
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            schedule,
+            [
+                (SpecId::HOMESTEAD, Activation::Block(0)),
+                (SpecId::LONDON, Activation::Block(10)),
+                (SpecId::CANCUN, Activation::Timestamp(0)),
+            ]
+        );
+    }
 }
@@ -0,0 +1,420 @@
+//! Dry-run validation of Ethereum blocks.
+
+use super::{
+    receipt_builder::AlloyReceiptBuilder, spec::EthExecutorSpec, EthBlockExecutionCtx,
+    EthBlockExecutor, EthEvmFactory,
+};
+use crate::{
+    block::{
+        BlockExecutionError, BlockExecutionResult, BlockExecutor, BlockOutcomeMismatch,
+        ExpectedBlockOutcome, StateRootProvider,
+    },
+    Database, EvmEnv, EvmFactory,
+};
+use alloc::{boxed::Box, vec::Vec};
+use alloy_consensus::{
+    transaction::SignerRecoverable, Block, BlockHeader, Header, ReceiptEnvelope, TxEnvelope,
+};
+use alloy_eips::{
+    eip1559::{BaseFeeParams, INITIAL_BASE_FEE},
+    eip7840::BlobParams,
+};
+use alloy_hardforks::EthereumHardforks;
+use alloy_primitives::{ChainId, B256};
+use revm::database::{states::bundle_state::BundleRetention, BundleState, State};
+
+/// Errors returned by [`validate_block`].
+#[derive(Debug, thiserror::Error)]
+pub enum BlockValidityError {
+    /// The signer of a transaction could not be recovered.
+    #[error("failed to recover signer of transaction {index}")]
+    SignerRecovery {
+        /// Index of the transaction in the block.
+        index: usize,
+    },
+    /// Execution of the block failed.
+    #[error(transparent)]
+    Execution(#[from] BlockExecutionError),
+    /// Base fee of the block differs from the one derived from its parent.
+    #[error("block base fee mismatch: got {got:?}, expected {expected:?}")]
+    BaseFee {
+        /// Base fee according to the header.
+        got: Option<u64>,
+        /// Base fee derived from the parent block.
+        expected: Option<u64>,
+    },
+    /// The execution outcome differs from the header.
+    #[error("block outcome mismatch: {0}")]
+    Outcome(Box<BlockOutcomeMismatch>),
+    /// State root differs from the header.
+    #[error("state root mismatch: got {got}, expected {expected}")]
+    StateRoot {
+        /// State root computed from the post-execution state.
+        got: B256,
+        /// State root according to the header.
+        expected: B256,
+    },
+    /// The state root could not be computed.
+    #[error("failed to compute state root: {0}")]
+    StateRootProvider(Box<dyn core::error::Error + Send + Sync>),
+}
+
+/// The parent of a block to validate.
+#[derive(Debug, Clone, Copy)]
+pub struct ParentBlock<'a> {
+    /// Header of the parent block.
+    pub header: &'a Header,
+    /// EIP-1559 parameters the base fee of the block is derived with.
+    pub base_fee_params: BaseFeeParams,
+}
+
+impl<'a> ParentBlock<'a> {
+    /// Creates a parent block whose child derives its base fee with the Ethereum parameters.
+    pub const fn new(header: &'a Header) -> Self {
+        Self { header, base_fee_params: BaseFeeParams::ethereum() }
+    }
+
+    /// Sets the EIP-1559 parameters, e.g. those of
+    /// [`NextEvmEnvAttributes::base_fee_params`](super::NextEvmEnvAttributes::base_fee_params).
+    pub const fn with_base_fee_params(mut self, base_fee_params: BaseFeeParams) -> Self {
+        self.base_fee_params = base_fee_params;
+        self
+    }
+}
+
+/// Executes `block` on top of `db` and checks the execution outcome against the block header.
+///
+/// This checks the base fee against the `parent`, builds the [`EvmEnv`] from the header, runs the
+/// [`EthBlockExecutor`] over the block body and compares gas used, blob gas used, receipts root,
+/// logs bloom and requests hash with [`ExpectedBlockOutcome::from_header`]. The state root is not
+/// checked, see [`validate_block_with_state_root`].
+///
+/// Nothing is written to `db`.
+pub fn validate_block<DB, Spec>(
+    block: &Block<TxEnvelope>,
+    parent: ParentBlock<'_>,
+    chain_spec: Spec,
+    chain_id: ChainId,
+    blob_params: Option<BlobParams>,
+    db: DB,
+) -> Result<BlockExecutionResult<ReceiptEnvelope>, BlockValidityError>
+where
+    DB: Database,
+    Spec: EthExecutorSpec + Clone,
+{
+    validate_block_with_bundle(block, parent, chain_spec, chain_id, blob_params, db)
+        .map(|(result, _)| result)
+}
+
+/// Checks the base fee of `header` against the one derived from its `parent` with the given
+/// EIP-1559 parameters, e.g. those of
+/// [`NextEvmEnvAttributes::base_fee_params`](super::NextEvmEnvAttributes::base_fee_params).
+///
+/// Blocks before London must not have a base fee and the first London block must have the
+/// initial base fee.
+pub fn validate_base_fee(
+    parent: impl BlockHeader,
+    header: impl BlockHeader,
+    chain_spec: impl EthereumHardforks,
+    base_fee_params: BaseFeeParams,
+) -> Result<(), BlockValidityError> {
+    let expected = if !chain_spec.is_london_active_at_block(header.number()) {
+        None
+    } else if chain_spec.is_london_active_at_block(parent.number()) {
+        parent.next_block_base_fee(base_fee_params)
+    } else {
+        Some(INITIAL_BASE_FEE)
+    };
+
+    let got = header.base_fee_per_gas();
+    if got != expected {
+        return Err(BlockValidityError::BaseFee { got, expected });
+    }
+    Ok(())
+}
+
+/// Same as [`validate_block`], but additionally compares the header's state root against the
+/// root computed by `state_root` from the block's post-execution [`BundleState`].
+pub fn validate_block_with_state_root<DB, Spec, P>(
+    block: &Block<TxEnvelope>,
+    parent: ParentBlock<'_>,
+    chain_spec: Spec,
+    chain_id: ChainId,
+    blob_params: Option<BlobParams>,
+    db: DB,
+    mut state_root: P,
+) -> Result<BlockExecutionResult<ReceiptEnvelope>, BlockValidityError>
+where
+    DB: Database,
+    Spec: EthExecutorSpec + Clone,
+    P: StateRootProvider,
+{
+    let (result, bundle) =
+        validate_block_with_bundle(block, parent, chain_spec, chain_id, blob_params, db)?;
+
+    let got = state_root
+        .state_root(&bundle)
+        .map_err(|err| BlockValidityError::StateRootProvider(Box::new(err)))?;
+    if got != block.header.state_root {
+        return Err(BlockValidityError::StateRoot { got, expected: block.header.state_root });
+    }
+
+    Ok(result)
+}
+
+/// Same as [`validate_block`], but also returns the block's post-execution [`BundleState`], e.g. to
+/// apply it to the underlying database.
+pub fn validate_block_with_bundle<DB, Spec>(
+    block: &Block<TxEnvelope>,
+    parent: ParentBlock<'_>,
+    chain_spec: Spec,
+    chain_id: ChainId,
+    blob_params: Option<BlobParams>,
+    db: DB,
+) -> Result<(BlockExecutionResult<ReceiptEnvelope>, BundleState), BlockValidityError>
+where
+    DB: Database,
+    Spec: EthExecutorSpec + Clone,
+{
+    let header = &block.header;
+    validate_base_fee(parent.header, header, &chain_spec, parent.base_fee_params)?;
+
+    let transactions = block
+        .body
+        .transactions
+        .iter()
+        .enumerate()
+        .map(|(index, tx)| {
+            tx.try_clone_into_recovered().map_err(|_| BlockValidityError::SignerRecovery { index })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut state = State::builder().with_database(db).with_bundle_update().build();
+    let evm_env = EvmEnv::for_eth_block(header, &chain_spec, chain_id, blob_params);
+    let evm = EthEvmFactory.create_evm(&mut state, evm_env);
+
+    let ctx = EthBlockExecutionCtx::from_block(block).with_blob_params(blob_params);
+    let result = EthBlockExecutor::new(evm, ctx, chain_spec, AlloyReceiptBuilder)
+        .execute_block(transactions.iter())?;
+
+    state.merge_transitions(BundleRetention::Reverts);
+    let bundle = state.take_bundle();
+
+    ExpectedBlockOutcome::from_header(header)
+        .verify(&result)
+        .map_err(|mismatch| BlockValidityError::Outcome(Box::new(mismatch)))?;
+
+    Ok((result, bundle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{block::verify::Mismatch, eth::spec::EthSpec};
+    use alloy_consensus::{
+        proofs::calculate_receipt_root, BlockBody, Receipt, SignableTransaction, TxLegacy,
+    };
+    use alloy_hardforks::ethereum::MAINNET_LONDON_BLOCK;
+    use alloy_primitives::{Address, Signature, TxKind, U256};
+    use revm::{
+        database::{CacheDB, EmptyDB},
+        state::AccountInfo,
+    };
+
+    /// A London parent at the gas target, so that the base fee doesn't change.
+    fn london_parent() -> Header {
+        Header {
+            number: MAINNET_LONDON_BLOCK,
+            gas_limit: 30_000_000,
+            gas_used: 15_000_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            ..Default::default()
+        }
+    }
+
+    /// A block with a single transfer on top of [`london_parent`] and a database funding its
+    /// sender.
+    fn transfer_block() -> (Block<TxEnvelope>, CacheDB<EmptyDB>) {
+        let tx = TxLegacy {
+            gas_price: 1_000_000_000,
+            gas_limit: 21_000,
+            to: TxKind::Call(Address::repeat_byte(0x02)),
+            value: U256::from(1),
+            ..Default::default()
+        };
+        let tx = TxEnvelope::from(tx.into_signed(Signature::test_signature()));
+        let sender = tx.recover_signer().unwrap();
+
+        let receipt = ReceiptEnvelope::Legacy(
+            Receipt { status: true.into(), cumulative_gas_used: 21_000, logs: Vec::new() }
+                .with_bloom(),
+        );
+        let header = Header {
+            number: MAINNET_LONDON_BLOCK + 1,
+            gas_limit: 30_000_000,
+            gas_used: 21_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            receipts_root: calculate_receipt_root(&[receipt]),
+            ..Default::default()
+        };
+        let block = Block::new(
+            header,
+            BlockBody { transactions: alloc::vec![tx], ommers: Vec::new(), withdrawals: None },
+        );
+
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            sender,
+            AccountInfo { balance: U256::from(10u128.pow(18)), ..Default::default() },
+        );
+        (block, db)
+    }
+
+    #[test]
+    fn test_validate_base_fee() {
+        let spec = EthSpec::mainnet();
+        let parent = Header {
+            number: MAINNET_LONDON_BLOCK - 1,
+            gas_limit: 30_000_000,
+            ..Default::default()
+        };
+        let mut header = Header {
+            number: MAINNET_LONDON_BLOCK,
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(INITIAL_BASE_FEE),
+            ..Default::default()
+        };
+        validate_base_fee(&parent, &header, &spec, BaseFeeParams::ethereum()).unwrap();
+
+        // empty London parent, the base fee decreases by 1/8 with Ethereum parameters and by
+        // 1/50 with pre-Canyon OP Stack parameters
+        let parent = header.clone();
+        header.number += 1;
+        header.base_fee_per_gas = Some(875_000_000);
+        validate_base_fee(&parent, &header, &spec, BaseFeeParams::ethereum()).unwrap();
+        assert!(matches!(
+            validate_base_fee(&parent, &header, &spec, BaseFeeParams::optimism()),
+            Err(BlockValidityError::BaseFee {
+                got: Some(875_000_000),
+                expected: Some(980_000_000)
+            })
+        ));
+    }
+
+    #[test]
+    fn test_validate_block_checks_base_fee() {
+        let parent = Header {
+            number: MAINNET_LONDON_BLOCK,
+            gas_limit: 30_000_000,
+            gas_used: 15_000_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            ..Default::default()
+        };
+        let block = Block::<TxEnvelope>::new(
+            Header {
+                number: MAINNET_LONDON_BLOCK + 1,
+                gas_limit: 30_000_000,
+                base_fee_per_gas: Some(900_000_000),
+                ..Default::default()
+            },
+            Default::default(),
+        );
+
+        let err = validate_block(
+            &block,
+            ParentBlock::new(&parent),
+            EthSpec::mainnet(),
+            1,
+            None,
+            revm::database::EmptyDB::default(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            BlockValidityError::BaseFee { got: Some(900_000_000), expected: Some(1_000_000_000) }
+        ));
+    }
+
+    #[test]
+    fn test_validate_block() {
+        let parent = london_parent();
+        let (block, db) = transfer_block();
+
+        let (result, bundle) = validate_block_with_bundle(
+            &block,
+            ParentBlock::new(&parent),
+            EthSpec::mainnet(),
+            1,
+            None,
+            db.clone(),
+        )
+        .unwrap();
+        assert_eq!(result.gas_used, 21_000);
+        assert_eq!(result.receipts.len(), 1);
+        assert_eq!(
+            bundle
+                .account(&Address::repeat_byte(0x02))
+                .and_then(|account| account.info.as_ref())
+                .map(|info| info.balance),
+            Some(U256::from(1))
+        );
+        // nothing was written to the database
+        assert!(!db.cache.accounts.contains_key(&Address::repeat_byte(0x02)));
+
+        let root = B256::repeat_byte(0x01);
+        let err = validate_block_with_state_root(
+            &block,
+            ParentBlock::new(&parent),
+            EthSpec::mainnet(),
+            1,
+            None,
+            db,
+            |_: &BundleState| root,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            BlockValidityError::StateRoot { got, expected: B256::ZERO } if got == root
+        ));
+    }
+
+    #[test]
+    fn test_validate_block_outcome_mismatch() {
+        let parent = london_parent();
+        let (mut block, db) = transfer_block();
+        let receipts_root = block.header.receipts_root;
+        block.header.gas_used = 42_000;
+        block.header.receipts_root = B256::ZERO;
+
+        let err =
+            validate_block(&block, ParentBlock::new(&parent), EthSpec::mainnet(), 1, None, db)
+                .unwrap_err();
+        let BlockValidityError::Outcome(mismatch) = err else {
+            panic!("expected outcome mismatch, got {err:?}")
+        };
+        assert_eq!(mismatch.gas_used, Some(Mismatch { got: 21_000, expected: 42_000 }));
+        assert_eq!(
+            mismatch.receipts_root,
+            Some(Mismatch { got: receipts_root, expected: B256::ZERO })
+        );
+        assert_eq!(mismatch.logs_bloom, None);
+    }
+
+    #[test]
+    fn test_validate_block_execution_error() {
+        let parent = london_parent();
+        let (block, _) = transfer_block();
+
+        // the sender can't pay for the transaction
+        let err = validate_block(
+            &block,
+            ParentBlock::new(&parent),
+            EthSpec::mainnet(),
+            1,
+            None,
+            EmptyDB::default(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, BlockValidityError::Execution(_)));
+    }
+}
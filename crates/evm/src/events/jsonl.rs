@@ -0,0 +1,202 @@
+use super::ExecutionEvent;
+use alloc::{format, string::String, vec::Vec};
+use alloy_primitives::{Bytes, Log, B256};
+use serde::Serialize;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+/// Writes [`ExecutionEvent`]s to JSON lines files, one event per line.
+///
+/// Files are named `{prefix}-{index:05}.jsonl` in the configured directory. With
+/// [`Self::with_max_file_size`] a new file is started once the current one would exceed the limit,
+/// which keeps files of long runs manageable for offline analysis or attaching to bug reports.
+#[derive(Debug)]
+pub struct JsonlExporter {
+    dir: PathBuf,
+    prefix: String,
+    max_file_size: Option<u64>,
+    writer: Option<BufWriter<File>>,
+    files: Vec<PathBuf>,
+    written: u64,
+}
+
+impl JsonlExporter {
+    /// Creates an exporter writing files with the given prefix to `dir`.
+    ///
+    /// No file is created before the first event is exported.
+    pub fn new(dir: impl Into<PathBuf>, prefix: impl Into<String>) -> Self {
+        Self {
+            dir: dir.into(),
+            prefix: prefix.into(),
+            max_file_size: None,
+            writer: None,
+            files: Vec::new(),
+            written: 0,
+        }
+    }
+
+    /// Starts a new file once the current one would exceed `bytes`.
+    ///
+    /// A single event larger than the limit is still written to its own file.
+    pub const fn with_max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = Some(bytes);
+        self
+    }
+
+    /// Returns the paths of all files written so far, in order.
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// Writes the event as a single line.
+    pub fn export<R: Serialize>(&mut self, event: &ExecutionEvent<R>) -> io::Result<()> {
+        let mut line = serde_json::to_vec(&Record::from(event))?;
+        line.push(b'\n');
+
+        let rotate = self
+            .max_file_size
+            .is_some_and(|max| self.written > 0 && self.written + line.len() as u64 > max);
+        if self.writer.is_none() || rotate {
+            self.rotate()?;
+        }
+        self.writer.as_mut().expect("file is open").write_all(&line)?;
+        self.written += line.len() as u64;
+        Ok(())
+    }
+
+    /// Writes all events, e.g. from the receiving half of [`channel`](super::channel), and flushes
+    /// the current file.
+    pub fn export_all<R: Serialize>(
+        &mut self,
+        events: impl IntoIterator<Item = ExecutionEvent<R>>,
+    ) -> io::Result<()> {
+        for event in events {
+            self.export(&event)?;
+        }
+        self.flush()
+    }
+
+    /// Flushes the current file.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.as_mut().map_or(Ok(()), Write::flush)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.flush()?;
+        let path = self.path(self.files.len());
+        self.writer = Some(BufWriter::new(File::create(&path)?));
+        self.files.push(path);
+        self.written = 0;
+        Ok(())
+    }
+
+    fn path(&self, index: usize) -> PathBuf {
+        Path::new(&self.dir).join(format!("{}-{index:05}.jsonl", self.prefix))
+    }
+}
+
+/// Serialized form of an [`ExecutionEvent`].
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "camelCase", rename_all_fields = "camelCase")]
+enum Record<'a, R> {
+    BlockStarted {
+        number: u64,
+        timestamp: u64,
+    },
+    TxExecuted {
+        index: usize,
+        hash: B256,
+        gas_used: u64,
+        success: bool,
+        logs: &'a [Log],
+    },
+    BlockFinished {
+        receipts: &'a [R],
+        requests: Vec<&'a Bytes>,
+        gas_used: u64,
+        blob_gas_used: u64,
+        da_footprint_used: u64,
+    },
+    BlockFailed {
+        error: &'a str,
+    },
+}
+
+impl<'a, R> From<&'a ExecutionEvent<R>> for Record<'a, R> {
+    fn from(event: &'a ExecutionEvent<R>) -> Self {
+        match event {
+            ExecutionEvent::BlockStarted { number, timestamp } => {
+                Self::BlockStarted { number: *number, timestamp: *timestamp }
+            }
+            ExecutionEvent::TxExecuted { index, hash, gas_used, success, logs } => {
+                Self::TxExecuted {
+                    index: *index,
+                    hash: *hash,
+                    gas_used: *gas_used,
+                    success: *success,
+                    logs,
+                }
+            }
+            ExecutionEvent::BlockFinished { result } => Self::BlockFinished {
+                receipts: &result.receipts,
+                requests: result.requests.iter().collect(),
+                gas_used: result.gas_used,
+                blob_gas_used: result.blob_gas_used,
+                da_footprint_used: result.da_footprint_used,
+            },
+            ExecutionEvent::BlockFailed { error } => Self::BlockFailed { error },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::BlockExecutionResult;
+
+    #[test]
+    fn test_jsonl_export_with_rotation() {
+        let dir = std::env::temp_dir().join(format!("alloy-evm-jsonl-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut exporter = JsonlExporter::new(&dir, "trace").with_max_file_size(100);
+        let events = [
+            ExecutionEvent::BlockStarted { number: 1, timestamp: 12 },
+            ExecutionEvent::TxExecuted {
+                index: 0,
+                hash: B256::ZERO,
+                gas_used: 21_000,
+                success: true,
+                logs: Vec::new(),
+            },
+            ExecutionEvent::BlockFinished {
+                result: BlockExecutionResult::<u64> {
+                    receipts: alloc::vec![1],
+                    gas_used: 21_000,
+                    ..Default::default()
+                },
+            },
+        ];
+        exporter.export_all(events).unwrap();
+
+        let lines: Vec<String> = exporter
+            .files()
+            .iter()
+            .flat_map(|path| {
+                std::fs::read_to_string(path).unwrap().lines().map(String::from).collect::<Vec<_>>()
+            })
+            .collect();
+        assert!(exporter.files().len() > 1);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], r#"{"event":"blockStarted","number":1,"timestamp":12}"#);
+        let finished: serde_json::Value = serde_json::from_str(&lines[2]).unwrap();
+        assert_eq!(finished["event"], "blockFinished");
+        assert_eq!(finished["gasUsed"], 21_000);
+        assert_eq!(finished["receipts"], serde_json::json!([1]));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,110 @@
+//! Structured stream of execution events.
+//!
+//! Indexers and other downstream consumers can subscribe to the output of an executor, e.g. via
+//! [`EthBlockExecutor::with_events`](crate::eth::EthBlockExecutor::with_events), instead of
+//! wrapping it or reconstructing transactions from [`OnStateHook`](crate::block::OnStateHook)
+//! calls.
+//!
+//! With the `serde` feature, events can be written to JSON lines files with a [`JsonlExporter`].
+
+use crate::block::BlockExecutionResult;
+use alloc::{string::String, vec::Vec};
+use alloy_primitives::{Log, B256};
+use std::sync::mpsc;
+
+#[cfg(all(feature = "serde", not(target_os = "zkvm")))]
+mod jsonl;
+#[cfg(all(feature = "serde", not(target_os = "zkvm")))]
+pub use jsonl::JsonlExporter;
+
+/// An event emitted by an executor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExecutionEvent<R> {
+    /// Execution of a block started.
+    BlockStarted {
+        /// Number of the block.
+        number: u64,
+        /// Timestamp of the block.
+        timestamp: u64,
+    },
+    /// A transaction was executed and committed.
+    TxExecuted {
+        /// Index of the transaction in the block.
+        index: usize,
+        /// Hash of the transaction.
+        hash: B256,
+        /// Gas used by the transaction.
+        gas_used: u64,
+        /// Whether the transaction succeeded.
+        success: bool,
+        /// Logs emitted by the transaction.
+        logs: Vec<Log>,
+    },
+    /// Execution of a block finished.
+    BlockFinished {
+        /// Result of the block.
+        result: BlockExecutionResult<R>,
+    },
+    /// Execution of a block failed after it started.
+    ///
+    /// Emitted for errors finishing the block and for transaction errors other than validation
+    /// errors, which block builders skip.
+    BlockFailed {
+        /// Description of the error.
+        error: String,
+    },
+}
+
+/// Sending half of an execution event stream, see [`channel`].
+///
+/// Sending blocks while the buffer of the channel is full, so that a slow consumer slows down
+/// execution instead of buffering events without limit. Events are dropped silently once the
+/// receiver is gone, so that a stopped consumer doesn't fail execution.
+#[derive(Debug)]
+pub struct ExecutionEventSender<R> {
+    sender: mpsc::SyncSender<ExecutionEvent<R>>,
+    clone_receipt: fn(&R) -> R,
+}
+
+impl<R> Clone for ExecutionEventSender<R> {
+    fn clone(&self) -> Self {
+        Self { sender: self.sender.clone(), clone_receipt: self.clone_receipt }
+    }
+}
+
+impl<R: Clone> ExecutionEventSender<R> {
+    /// Wraps the sending half of a channel.
+    pub fn new(sender: mpsc::SyncSender<ExecutionEvent<R>>) -> Self {
+        Self { sender, clone_receipt: R::clone }
+    }
+}
+
+impl<R> ExecutionEventSender<R> {
+    /// Sends the event, blocking while the buffer of the channel is full.
+    pub fn send(&self, event: ExecutionEvent<R>) {
+        let _ = self.sender.send(event);
+    }
+
+    /// Sends [`ExecutionEvent::BlockFinished`] with a copy of the result.
+    pub fn send_block_finished(&self, result: &BlockExecutionResult<R>) {
+        let result = BlockExecutionResult {
+            receipts: result.receipts.iter().map(self.clone_receipt).collect(),
+            requests: result.requests.clone(),
+            gas_used: result.gas_used,
+            blob_gas_used: result.blob_gas_used,
+            da_footprint_used: result.da_footprint_used,
+        };
+        self.send(ExecutionEvent::BlockFinished { result });
+    }
+}
+
+/// Creates an execution event stream buffering up to `bound` events.
+///
+/// The executor blocks on sending once `bound` events are pending, so the receiver must be drained
+/// on another thread unless all events of a block fit into the buffer.
+pub fn channel<R: Clone>(
+    bound: usize,
+) -> (ExecutionEventSender<R>, mpsc::Receiver<ExecutionEvent<R>>) {
+    let (sender, receiver) = mpsc::sync_channel(bound);
+    (ExecutionEventSender::new(sender), receiver)
+}
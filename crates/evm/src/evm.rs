@@ -1,6 +1,11 @@
 //! Abstraction over EVM.
 
-use crate::{env::BlockEnvironment, tracing::TxTracer, EvmEnv, EvmError, IntoTxEnv};
+use crate::{
+    env::{BlockEnvironment, EvmConfig},
+    interrupt::{Interrupt, InterruptInspector},
+    tracing::TxTracer,
+    EvmEnv, EvmError, IntoTxEnv,
+};
 use alloy_consensus::transaction::TxHashRef;
 use alloy_primitives::{Address, Bytes, B256};
 use core::{error::Error, fmt::Debug, hash::Hash};
@@ -153,6 +158,14 @@ pub trait Evm {
     /// See also [`EvmFactory::create_evm_with_inspector`].
     fn set_inspector_enabled(&mut self, enabled: bool);
 
+    /// Returns `true` if additional transactions are inspected.
+    ///
+    /// Returns `false` by default, implementations that can toggle their inspector should override
+    /// this.
+    fn is_inspector_enabled(&self) -> bool {
+        false
+    }
+
     /// Enables the configured inspector.
     ///
     /// All additional transactions will be inspected if enabled.
@@ -315,6 +328,165 @@ pub trait EvmFactoryExt: EvmFactory {
     {
         TxTracer::new(self.create_evm_with_inspector(db, input, fused_inspector))
     }
+
+    /// Creates a new EVM whose executions are aborted once `interrupt` is triggered.
+    ///
+    /// See [`transact_interruptible`](crate::interrupt::transact_interruptible).
+    fn create_interruptible_evm<DB: Database>(
+        &self,
+        db: DB,
+        input: EvmEnv<Self::Spec, Self::BlockEnv>,
+        interrupt: Interrupt,
+    ) -> Self::Evm<DB, InterruptInspector> {
+        self.create_evm_with_inspector(db, input, InterruptInspector::new(interrupt))
+    }
+
+    /// Creates a new EVM that serves analyzed bytecode from the shared `cache`.
+    ///
+    /// See [`AnalyzedBytecodeCache`](crate::AnalyzedBytecodeCache).
+    #[cfg(feature = "std")]
+    fn create_evm_with_bytecode_cache<DB: Database>(
+        &self,
+        db: DB,
+        input: EvmEnv<Self::Spec, Self::BlockEnv>,
+        cache: crate::AnalyzedBytecodeCache,
+    ) -> Self::Evm<crate::CachedBytecodeDatabase<DB>, NoOpInspector> {
+        self.create_evm(crate::CachedBytecodeDatabase::new(db, cache), input)
+    }
+
+    /// Wraps the factory to apply the [`EvmConfig`] to every EVM it creates.
+    fn with_config(self, config: EvmConfig) -> ConfiguredEvmFactory<Self>
+    where
+        Self: Sized,
+    {
+        ConfiguredEvmFactory::new(self, config)
+    }
+
+    /// Wraps the factory to create EVMs that allow gas prices below the base fee.
+    ///
+    /// See [`ConfiguredEvmFactory::disable_base_fee`].
+    #[cfg(feature = "optional-no-base-fee")]
+    fn disable_base_fee(self) -> ConfiguredEvmFactory<Self>
+    where
+        Self: Sized,
+    {
+        self.with_config(EvmConfig::default()).disable_base_fee()
+    }
+
+    /// Wraps the factory to create EVMs that don't check the nonce of the caller.
+    ///
+    /// See [`ConfiguredEvmFactory::disable_nonce_check`].
+    fn disable_nonce_check(self) -> ConfiguredEvmFactory<Self>
+    where
+        Self: Sized,
+    {
+        self.with_config(EvmConfig::default()).disable_nonce_check()
+    }
+
+    /// Wraps the factory to create EVMs that don't check the balance of the caller.
+    ///
+    /// See [`ConfiguredEvmFactory::disable_balance_check`].
+    #[cfg(feature = "optional-balance-check")]
+    fn disable_balance_check(self) -> ConfiguredEvmFactory<Self>
+    where
+        Self: Sized,
+    {
+        self.with_config(EvmConfig::default()).disable_balance_check()
+    }
+
+    /// Wraps the factory to create EVMs that don't check gas limits against the block gas limit.
+    ///
+    /// See [`ConfiguredEvmFactory::disable_block_gas_limit`].
+    #[cfg(feature = "optional-block-gas-limit")]
+    fn disable_block_gas_limit(self) -> ConfiguredEvmFactory<Self>
+    where
+        Self: Sized,
+    {
+        self.with_config(EvmConfig::default()).disable_block_gas_limit()
+    }
 }
 
 impl<T: EvmFactory> EvmFactoryExt for T {}
+
+/// An [`EvmFactory`] applying an [`EvmConfig`] to the environment of every EVM created by the
+/// inner factory.
+///
+/// Only the block independent parts of the configuration are applied: execution limits and
+/// disabled checks. Blob parameters have to be resolved when building the [`EvmEnv`] of a block.
+#[derive(Debug, Clone, Default)]
+pub struct ConfiguredEvmFactory<F> {
+    inner: F,
+    config: EvmConfig,
+}
+
+impl<F> ConfiguredEvmFactory<F> {
+    /// Wraps the factory.
+    pub const fn new(inner: F, config: EvmConfig) -> Self {
+        Self { inner, config }
+    }
+
+    /// Returns the inner factory.
+    pub const fn inner(&self) -> &F {
+        &self.inner
+    }
+
+    /// Returns the applied configuration.
+    pub const fn config(&self) -> &EvmConfig {
+        &self.config
+    }
+
+    /// Allows gas prices below the base fee of the block, e.g. for `eth_call` without fees.
+    #[cfg(feature = "optional-no-base-fee")]
+    pub const fn disable_base_fee(mut self) -> Self {
+        self.config.disabled_checks.base_fee = true;
+        self
+    }
+
+    /// Disables the nonce check of the caller.
+    pub const fn disable_nonce_check(mut self) -> Self {
+        self.config.disabled_checks.nonce = true;
+        self
+    }
+
+    /// Disables the balance check of the caller.
+    #[cfg(feature = "optional-balance-check")]
+    pub const fn disable_balance_check(mut self) -> Self {
+        self.config.disabled_checks.balance = true;
+        self
+    }
+
+    /// Disables checking the gas limit of transactions against the block gas limit.
+    #[cfg(feature = "optional-block-gas-limit")]
+    pub const fn disable_block_gas_limit(mut self) -> Self {
+        self.config.disabled_checks.block_gas_limit = true;
+        self
+    }
+}
+
+impl<F: EvmFactory> EvmFactory for ConfiguredEvmFactory<F> {
+    type Evm<DB: Database, I: Inspector<Self::Context<DB>>> = F::Evm<DB, I>;
+    type Context<DB: Database> = F::Context<DB>;
+    type Tx = F::Tx;
+    type Error<DBError: Error + Send + Sync + 'static> = F::Error<DBError>;
+    type HaltReason = F::HaltReason;
+    type Spec = F::Spec;
+    type BlockEnv = F::BlockEnv;
+    type Precompiles = F::Precompiles;
+
+    fn create_evm<DB: Database>(
+        &self,
+        db: DB,
+        evm_env: EvmEnv<Self::Spec, Self::BlockEnv>,
+    ) -> Self::Evm<DB, NoOpInspector> {
+        self.inner.create_evm(db, evm_env.with_config(&self.config))
+    }
+
+    fn create_evm_with_inspector<DB: Database, I: Inspector<Self::Context<DB>>>(
+        &self,
+        db: DB,
+        input: EvmEnv<Self::Spec, Self::BlockEnv>,
+        inspector: I,
+    ) -> Self::Evm<DB, I> {
+        self.inner.create_evm_with_inspector(db, input.with_config(&self.config), inspector)
+    }
+}
@@ -0,0 +1,160 @@
+//! Combinators for running several inspectors in one execution.
+
+use alloy_primitives::{Address, Log, U256};
+use revm::{
+    interpreter::{
+        CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter, InterpreterTypes,
+    },
+    Inspector,
+};
+
+/// An [`Inspector`] that runs two inspectors one after another.
+///
+/// Stacks of more than two inspectors are built by nesting, e.g. with [`InspectorStack::push`]:
+///
+/// ```ignore
+/// let stack = InspectorStack::new(access_list, tracer).push(custom);
+/// let evm = factory.create_evm_with_inspector(db, env, stack);
+/// ```
+///
+/// All hooks are forwarded to both inspectors, in order. If the first inspector overrides the
+/// outcome of a call or create, the second inspector is skipped for that hook, while the
+/// corresponding `call_end` or `create_end` hook is still forwarded to both.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InspectorStack<A, B> {
+    /// The inspector that is invoked first.
+    pub first: A,
+    /// The inspector that is invoked second.
+    pub second: B,
+}
+
+impl<A, B> InspectorStack<A, B> {
+    /// Creates a new stack of the given inspectors.
+    pub const fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// Returns a stack that runs `inspector` after the inspectors of this stack.
+    pub const fn push<C>(self, inspector: C) -> InspectorStack<Self, C> {
+        InspectorStack::new(self, inspector)
+    }
+
+    /// Consumes the stack and returns the inner inspectors.
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+impl<CTX, INTR, A, B> Inspector<CTX, INTR> for InspectorStack<A, B>
+where
+    INTR: InterpreterTypes,
+    A: Inspector<CTX, INTR>,
+    B: Inspector<CTX, INTR>,
+{
+    fn initialize_interp(&mut self, interp: &mut Interpreter<INTR>, context: &mut CTX) {
+        self.first.initialize_interp(interp, context);
+        self.second.initialize_interp(interp, context);
+    }
+
+    fn step(&mut self, interp: &mut Interpreter<INTR>, context: &mut CTX) {
+        self.first.step(interp, context);
+        self.second.step(interp, context);
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter<INTR>, context: &mut CTX) {
+        self.first.step_end(interp, context);
+        self.second.step_end(interp, context);
+    }
+
+    fn log(&mut self, context: &mut CTX, log: Log) {
+        self.first.log(context, log.clone());
+        self.second.log(context, log);
+    }
+
+    fn log_full(&mut self, interp: &mut Interpreter<INTR>, context: &mut CTX, log: Log) {
+        self.first.log_full(interp, context, log.clone());
+        self.second.log_full(interp, context, log);
+    }
+
+    fn call(&mut self, context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        self.first.call(context, inputs).or_else(|| self.second.call(context, inputs))
+    }
+
+    fn call_end(&mut self, context: &mut CTX, inputs: &CallInputs, outcome: &mut CallOutcome) {
+        self.first.call_end(context, inputs, outcome);
+        self.second.call_end(context, inputs, outcome);
+    }
+
+    fn create(&mut self, context: &mut CTX, inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        self.first.create(context, inputs).or_else(|| self.second.create(context, inputs))
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut CTX,
+        inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        self.first.create_end(context, inputs, outcome);
+        self.second.create_end(context, inputs, outcome);
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        Inspector::<CTX, INTR>::selfdestruct(&mut self.first, contract, target, value);
+        Inspector::<CTX, INTR>::selfdestruct(&mut self.second, contract, target, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EthEvmFactory, Evm, EvmEnv, EvmFactory};
+    use alloy_primitives::TxKind;
+    use revm::{context::TxEnv, context_interface::ContextTr, database_interface::EmptyDB};
+
+    #[derive(Debug, Default)]
+    struct CallCounter {
+        calls: usize,
+        call_ends: usize,
+    }
+
+    impl<CTX: ContextTr> Inspector<CTX> for CallCounter {
+        fn call(&mut self, _context: &mut CTX, _inputs: &mut CallInputs) -> Option<CallOutcome> {
+            self.calls += 1;
+            None
+        }
+
+        fn call_end(
+            &mut self,
+            _context: &mut CTX,
+            _inputs: &CallInputs,
+            _outcome: &mut CallOutcome,
+        ) {
+            self.call_ends += 1;
+        }
+    }
+
+    #[test]
+    fn test_stack_forwards_to_all_inspectors() {
+        let stack = InspectorStack::new(CallCounter::default(), CallCounter::default())
+            .push(CallCounter::default());
+        let mut evm =
+            EthEvmFactory.create_evm_with_inspector(EmptyDB::default(), EvmEnv::default(), stack);
+
+        let tx = TxEnv::builder()
+            .caller(Address::repeat_byte(0x01))
+            .kind(TxKind::Call(Address::repeat_byte(0x02)))
+            .gas_limit(100_000)
+            .gas_price(0)
+            .build()
+            .unwrap();
+        evm.transact(tx).unwrap();
+
+        let InspectorStack { first: InspectorStack { first, second }, second: third } =
+            evm.inspector();
+        for counter in [first, second, third] {
+            assert_eq!(counter.calls, 1);
+            assert_eq!(counter.call_ends, 1);
+        }
+    }
+}
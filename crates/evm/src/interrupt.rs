@@ -0,0 +1,250 @@
+//! Cancellation of long-running executions.
+//!
+//! An [`Interrupt`] is a cloneable token that can be triggered from another thread, or that
+//! triggers itself once a deadline has passed. The [`InterruptInspector`] checks the token while
+//! the interpreter runs and aborts execution once it is triggered. [`transact_interruptible`]
+//! reports aborted executions as [`InterruptibleError::Interrupted`].
+
+use crate::{Evm, EvmError, IntoTxEnv};
+use alloc::{string::ToString, sync::Arc};
+use core::sync::atomic::{AtomicBool, Ordering};
+use revm::{
+    context::ContextError,
+    context_interface::{result::ResultAndState, ContextTr},
+    interpreter::{InstructionResult, Interpreter, InterpreterTypes},
+    Inspector,
+};
+
+/// Number of interpreter steps between deadline checks.
+///
+/// Reading the clock on every step would slow down execution considerably.
+const DEADLINE_CHECK_INTERVAL: u32 = 1024;
+
+/// Message of the error the EVM returns for interrupted executions.
+const INTERRUPTED: &str = "execution interrupted";
+
+/// A token for aborting EVM executions.
+///
+/// Clones share the same state: triggering one triggers all of them.
+#[derive(Debug, Clone, Default)]
+pub struct Interrupt {
+    triggered: Arc<AtomicBool>,
+    #[cfg(all(feature = "std", not(target_os = "zkvm")))]
+    deadline: Option<crate::time::Instant>,
+}
+
+impl Interrupt {
+    /// Creates a new token that is only triggered manually.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new token that is triggered once `timeout` has elapsed.
+    #[cfg(all(feature = "std", not(target_os = "zkvm")))]
+    pub fn with_timeout(timeout: std::time::Duration) -> Self {
+        Self::with_deadline(crate::time::Instant::now() + timeout)
+    }
+
+    /// Creates a new token that is triggered at `deadline`.
+    #[cfg(all(feature = "std", not(target_os = "zkvm")))]
+    pub fn with_deadline(deadline: crate::time::Instant) -> Self {
+        Self { triggered: Default::default(), deadline: Some(deadline) }
+    }
+
+    /// Triggers the token, aborting all executions observing it.
+    pub fn trigger(&self) {
+        self.triggered.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the token was triggered manually or its deadline has passed.
+    pub fn is_triggered(&self) -> bool {
+        if self.triggered.load(Ordering::Relaxed) {
+            return true;
+        }
+        #[cfg(all(feature = "std", not(target_os = "zkvm")))]
+        if self.deadline.is_some_and(|deadline| crate::time::Instant::now() >= deadline) {
+            self.trigger();
+            return true;
+        }
+        false
+    }
+
+    /// Returns `true` if the token was triggered, without checking the deadline.
+    fn is_triggered_fast(&self) -> bool {
+        self.triggered.load(Ordering::Relaxed)
+    }
+}
+
+/// An [`Inspector`] that aborts execution once its [`Interrupt`] is triggered.
+///
+/// Aborted executions return an error from [`Evm::transact`]. Use [`transact_interruptible`] to
+/// tell them apart from other errors. To combine this with another inspector, use an
+/// [`InspectorStack`](crate::InspectorStack).
+#[derive(Debug, Clone)]
+pub struct InterruptInspector {
+    interrupt: Interrupt,
+    steps: u32,
+}
+
+impl InterruptInspector {
+    /// Creates a new inspector observing the given token.
+    pub const fn new(interrupt: Interrupt) -> Self {
+        Self { interrupt, steps: 0 }
+    }
+
+    /// Returns the observed token.
+    pub const fn interrupt(&self) -> &Interrupt {
+        &self.interrupt
+    }
+
+    fn should_abort(&mut self) -> bool {
+        self.steps = self.steps.wrapping_add(1);
+        if self.steps % DEADLINE_CHECK_INTERVAL == 0 {
+            self.interrupt.is_triggered()
+        } else {
+            self.interrupt.is_triggered_fast()
+        }
+    }
+}
+
+impl<CTX, INTR> Inspector<CTX, INTR> for InterruptInspector
+where
+    CTX: ContextTr,
+    INTR: InterpreterTypes,
+{
+    fn initialize_interp(&mut self, interp: &mut Interpreter<INTR>, context: &mut CTX) {
+        if self.interrupt.is_triggered() {
+            abort(interp, context);
+        }
+    }
+
+    fn step(&mut self, interp: &mut Interpreter<INTR>, context: &mut CTX) {
+        if self.should_abort() {
+            abort(interp, context);
+        }
+    }
+}
+
+/// Halts the interpreter with a fatal error, which makes the EVM return an error.
+fn abort<CTX: ContextTr, INTR: InterpreterTypes>(
+    interp: &mut Interpreter<INTR>,
+    context: &mut CTX,
+) {
+    *context.error() = Err(ContextError::Custom(INTERRUPTED.to_string()));
+    interp.halt(InstructionResult::FatalExternalError);
+}
+
+/// Error returned by [`transact_interruptible`].
+#[derive(Debug, thiserror::Error)]
+pub enum InterruptibleError<E> {
+    /// Execution was aborted because the [`Interrupt`] was triggered.
+    #[error("{INTERRUPTED}")]
+    Interrupted,
+    /// Any other EVM error.
+    #[error(transparent)]
+    Evm(E),
+}
+
+impl<E: EvmError> EvmError for InterruptibleError<E> {
+    type InvalidTransaction = E::InvalidTransaction;
+
+    fn as_invalid_tx_err(&self) -> Option<&Self::InvalidTransaction> {
+        match self {
+            Self::Evm(err) => err.as_invalid_tx_err(),
+            Self::Interrupted => None,
+        }
+    }
+
+    fn try_into_invalid_tx_err(self) -> Result<Self::InvalidTransaction, Self> {
+        match self {
+            Self::Evm(err) => err.try_into_invalid_tx_err().map_err(Self::Evm),
+            Self::Interrupted => Err(Self::Interrupted),
+        }
+    }
+
+    fn is_database_error(&self) -> bool {
+        match self {
+            Self::Evm(err) => err.is_database_error(),
+            Self::Interrupted => false,
+        }
+    }
+}
+
+/// Executes a transaction on an EVM created with an [`InterruptInspector`] observing `interrupt`.
+///
+/// Returns [`InterruptibleError::Interrupted`] if `interrupt` was triggered before or during
+/// execution.
+pub fn transact_interruptible<E: Evm>(
+    evm: &mut E,
+    tx: impl IntoTxEnv<E::Tx>,
+    interrupt: &Interrupt,
+) -> Result<ResultAndState<E::HaltReason>, InterruptibleError<E::Error>> {
+    if interrupt.is_triggered() {
+        return Err(InterruptibleError::Interrupted);
+    }
+    match evm.transact(tx) {
+        // An execution that finished right before the token was triggered is still valid.
+        Ok(result) => Ok(result),
+        Err(_) if interrupt.is_triggered_fast() => Err(InterruptibleError::Interrupted),
+        Err(err) => Err(InterruptibleError::Evm(err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EthEvmFactory, EvmEnv, EvmFactory, EvmFactoryExt};
+    use alloy_primitives::{Address, Bytes, TxKind};
+    use revm::{
+        bytecode::Bytecode,
+        context::TxEnv,
+        database::{CacheDB, EmptyDB},
+        state::AccountInfo,
+    };
+
+    const LOOP: Address = Address::repeat_byte(0x10);
+
+    fn loop_tx() -> TxEnv {
+        TxEnv::builder()
+            .caller(Address::repeat_byte(0x01))
+            .kind(TxKind::Call(LOOP))
+            .gas_limit(16_000_000)
+            .gas_price(0)
+            .build()
+            .unwrap()
+    }
+
+    fn loop_db() -> CacheDB<EmptyDB> {
+        let mut db = CacheDB::new(EmptyDB::default());
+        // JUMPDEST; PUSH0; JUMP
+        let code = Bytecode::new_raw(Bytes::from_static(&[0x5b, 0x5f, 0x56]));
+        db.insert_account_info(LOOP, AccountInfo::default().with_code(code));
+        db
+    }
+
+    #[test]
+    fn test_triggered_interrupt_aborts_execution() {
+        let interrupt = Interrupt::new();
+        let mut evm =
+            EthEvmFactory.create_interruptible_evm(loop_db(), EvmEnv::default(), interrupt.clone());
+
+        interrupt.trigger();
+        assert!(evm.transact(loop_tx()).is_err());
+        let err = transact_interruptible(&mut evm, loop_tx(), &interrupt).unwrap_err();
+        assert!(matches!(err, InterruptibleError::Interrupted));
+    }
+
+    #[cfg(all(feature = "std", not(target_os = "zkvm")))]
+    #[test]
+    fn test_timeout_aborts_execution() {
+        // An elapsed deadline is observed by the inspector without racing the clock.
+        let interrupt = Interrupt::with_timeout(core::time::Duration::ZERO);
+        let mut evm =
+            EthEvmFactory.create_interruptible_evm(loop_db(), EvmEnv::default(), interrupt.clone());
+
+        assert!(evm.transact(loop_tx()).is_err());
+        assert!(interrupt.is_triggered_fast());
+        let err = transact_interruptible(&mut evm, loop_tx(), &interrupt).unwrap_err();
+        assert!(matches!(err, InterruptibleError::Interrupted));
+    }
+}
@@ -9,15 +9,43 @@
 
 extern crate alloc;
 
+// zkVM guests can't link the C and assembly backends, the pure-Rust implementations of revm and
+// alloy are used instead.
+#[cfg(all(
+    target_os = "zkvm",
+    any(feature = "secp256k1", feature = "gmp", feature = "kzg", feature = "asm-keccak")
+))]
+compile_error!(
+    "the `secp256k1`, `gmp`, `kzg` and `asm-keccak` features use native crypto backends, which \
+     are not supported on zkVM targets"
+);
+
+#[cfg(feature = "eip4762")]
+pub mod access_witness;
 pub mod block;
+#[cfg(feature = "std")]
+pub mod bytecode_cache;
+#[cfg(feature = "std")]
+pub use bytecode_cache::{AnalyzedBytecodeCache, CachedBytecodeDatabase};
+pub mod dyn_evm;
+pub use dyn_evm::{DynBlockExecutor, DynEvm};
 pub mod evm;
-pub use evm::{Database, Evm, EvmFactory};
+pub use evm::{ConfiguredEvmFactory, Database, Evm, EvmFactory};
 pub mod eth;
 pub use eth::{EthEvm, EthEvmFactory};
 pub mod env;
-pub use env::{EvmEnv, EvmLimitParams};
+#[cfg(feature = "eof")]
+pub mod eof;
+#[cfg(feature = "std")]
+pub mod events;
+pub use env::{DisabledChecks, EvmConfig, EvmEnv, EvmLimitParams};
 pub mod error;
 pub use error::*;
+pub mod inspector;
+pub use inspector::InspectorStack;
+pub mod interrupt;
+#[cfg(feature = "std")]
+pub mod time;
 pub mod tx;
 pub use tx::*;
 pub mod traits;
@@ -28,10 +56,15 @@ pub mod call;
 pub mod op;
 #[cfg(feature = "overrides")]
 pub mod overrides;
+pub mod precompile;
 pub mod precompiles;
 pub use precompiles::MovePrecompileError;
 #[cfg(feature = "rpc")]
 pub mod rpc;
+pub mod snapshot;
+pub use snapshot::SnapshotState;
+#[cfg(feature = "storage")]
+pub mod storage;
 pub mod tracing;
 
 mod either;
@@ -41,4 +74,8 @@ mod either;
 pub use op_revm;
 pub use revm;
 
-pub use eth::spec_id::{spec, spec_by_timestamp_and_block_number};
+pub use eth::spec_id::{
+    custom_fork_schedule, custom_spec_by_timestamp_and_block_number, fork_schedule,
+    hardfork_by_timestamp_and_block_number, spec, spec_by_timestamp_and_block_number, Activation,
+    HardforkToSpec,
+};
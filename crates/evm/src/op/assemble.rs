@@ -0,0 +1,147 @@
+//! Assembly of executed OP blocks.
+
+use crate::eth::BlockAssemblerInput;
+use alloc::vec::Vec;
+use alloy_consensus::{Block, BlockBody, TxReceipt, EMPTY_ROOT_HASH};
+use alloy_eips::{eip2718::Encodable2718, eip4895::Withdrawals, eip7685::EMPTY_REQUESTS_HASH};
+use alloy_op_hardforks::OpHardforks;
+use alloy_primitives::{Log, B256};
+
+/// Error returned by [`assemble_block`] for Isthmus blocks assembled without the withdrawals
+/// storage root.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("withdrawals storage root is required since Isthmus")]
+pub struct MissingWithdrawalsStorageRoot;
+
+/// Assembles an OP block from the result of its execution.
+///
+/// Differs from [`eth::assemble_block`](crate::eth::assemble_block) in the fork-specific fields:
+/// - Withdrawals are always empty since Canyon. Since Isthmus, the withdrawals root is the storage
+///   root of the `L2ToL1MessagePasser` contract, which must be provided as
+///   `withdrawals_storage_root`, otherwise [`MissingWithdrawalsStorageRoot`] is returned.
+/// - The excess blob gas is zero since Ecotone. The blob gas used is zero before Jovian and the DA
+///   footprint of the block since, as reported in
+///   [`BlockExecutionResult::da_footprint_used`](crate::block::BlockExecutionResult::da_footprint_used).
+/// - The requests hash is always the empty requests hash since Isthmus.
+///
+/// [`BlockAssemblerInput::withdrawals`] is ignored. The Holocene EIP-1559 parameters must be
+/// encoded into [`BlockAssemblerInput::extra_data`] by the caller.
+pub fn assemble_block<Spec, T, R>(
+    input: BlockAssemblerInput<'_, Spec, T, R>,
+    chain_spec: impl OpHardforks,
+    withdrawals_storage_root: Option<B256>,
+) -> Result<Block<T>, MissingWithdrawalsStorageRoot>
+where
+    T: Encodable2718,
+    R: TxReceipt<Log = Log> + Encodable2718,
+{
+    let mut header = input.base_header();
+    let timestamp = header.timestamp;
+
+    header.base_fee_per_gas = Some(input.evm_env.block_env.basefee);
+
+    let withdrawals =
+        chain_spec.is_canyon_active_at_timestamp(timestamp).then(Withdrawals::default);
+    if withdrawals.is_some() {
+        header.withdrawals_root = Some(if chain_spec.is_isthmus_active_at_timestamp(timestamp) {
+            withdrawals_storage_root.ok_or(MissingWithdrawalsStorageRoot)?
+        } else {
+            EMPTY_ROOT_HASH
+        });
+    }
+
+    if chain_spec.is_ecotone_active_at_timestamp(timestamp) {
+        header.blob_gas_used = Some(
+            input
+                .execution_result
+                .header_blob_gas_used(chain_spec.is_jovian_active_at_timestamp(timestamp)),
+        );
+        header.excess_blob_gas = Some(0);
+        header.parent_beacon_block_root = input.parent_beacon_block_root;
+    }
+
+    if chain_spec.is_isthmus_active_at_timestamp(timestamp) {
+        header.requests_hash = Some(EMPTY_REQUESTS_HASH);
+    }
+
+    Ok(Block {
+        header,
+        body: BlockBody { transactions: input.transactions, ommers: Vec::new(), withdrawals },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{block::BlockExecutionResult, eth::NextEvmEnvAttributes, EvmEnv};
+    use alloy_op_hardforks::{OpChainHardforks, OP_MAINNET_JOVIAN_TIMESTAMP};
+    use alloy_primitives::{Address, Bytes};
+    use op_alloy::consensus::{OpReceiptEnvelope, OpTxEnvelope};
+
+    /// Isthmus on OP mainnet.
+    const ISTHMUS_TIMESTAMP: u64 = 1_750_000_000;
+    /// Canyon, but not yet Isthmus, on OP mainnet.
+    const CANYON_TIMESTAMP: u64 = 1_710_000_000;
+
+    fn assemble(
+        timestamp: u64,
+        withdrawals_storage_root: Option<B256>,
+    ) -> Result<Block<OpTxEnvelope>, MissingWithdrawalsStorageRoot> {
+        let attributes = NextEvmEnvAttributes {
+            timestamp,
+            suggested_fee_recipient: Address::repeat_byte(0x01),
+            prev_randao: B256::repeat_byte(0x02),
+            gas_limit: 30_000_000,
+            elasticity_multiplier: None,
+            base_fee_max_change_denominator: None,
+        };
+        let evm_env = EvmEnv::default();
+        let execution_result = BlockExecutionResult::<OpReceiptEnvelope> {
+            da_footprint_used: 100_000,
+            ..Default::default()
+        };
+        assemble_block(
+            BlockAssemblerInput {
+                parent_hash: B256::repeat_byte(0x03),
+                attributes: &attributes,
+                evm_env: &evm_env,
+                execution_result: &execution_result,
+                state_root: B256::repeat_byte(0x04),
+                transactions: Vec::new(),
+                withdrawals: None,
+                parent_beacon_block_root: Some(B256::repeat_byte(0x05)),
+                extra_data: Bytes::new(),
+            },
+            OpChainHardforks::op_mainnet(),
+            withdrawals_storage_root,
+        )
+    }
+
+    #[test]
+    fn test_withdrawals_root() {
+        let root = B256::repeat_byte(0x06);
+        let block = assemble(ISTHMUS_TIMESTAMP, Some(root)).unwrap();
+        assert_eq!(block.header.withdrawals_root, Some(root));
+        assert_eq!(block.header.requests_hash, Some(EMPTY_REQUESTS_HASH));
+        assert_eq!(assemble(ISTHMUS_TIMESTAMP, None), Err(MissingWithdrawalsStorageRoot));
+
+        let block = assemble(CANYON_TIMESTAMP, None).unwrap();
+        assert_eq!(block.header.withdrawals_root, Some(EMPTY_ROOT_HASH));
+        assert_eq!(block.header.requests_hash, None);
+        assert!(block.body.withdrawals.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_blob_gas_used() {
+        let root = Some(B256::repeat_byte(0x06));
+        let block = assemble(OP_MAINNET_JOVIAN_TIMESTAMP, root).unwrap();
+        assert_eq!(block.header.blob_gas_used, Some(100_000));
+        assert_eq!(block.header.excess_blob_gas, Some(0));
+
+        let block = assemble(ISTHMUS_TIMESTAMP, root).unwrap();
+        assert_eq!(block.header.blob_gas_used, Some(0));
+
+        let block = assemble(CANYON_TIMESTAMP, None).unwrap();
+        assert_eq!(block.header.blob_gas_used, None);
+    }
+}
@@ -0,0 +1,60 @@
+//! Data availability size and L1 cost estimation of OP transactions.
+//!
+//! These are the formulas the EVM charges L1 fees with, exposed so that wallets and front-ends can
+//! show the total fee of a transaction before submitting it.
+
+use alloy_primitives::U256;
+use op_revm::{L1BlockInfo, OpSpecId};
+
+/// Returns the estimated size of a transaction after compression by the batcher, as used since
+/// Fjord.
+///
+/// The estimate is a linear regression over the FastLZ compressed size of the EIP-2718 encoded
+/// transaction, but at least 100 bytes.
+pub fn estimate_da_size(encoded_tx: &[u8]) -> u64 {
+    L1BlockInfo::default().tx_estimated_size_fjord(encoded_tx).saturating_to()
+}
+
+/// Returns the L1 data fee of a transaction.
+///
+/// The formula is selected by `spec`: the FastLZ size estimate since Fjord, the blob base fee
+/// aware formula since Ecotone and the calldata gas based formula of Bedrock before. Deposits don't
+/// pay an L1 fee.
+pub fn estimate_l1_cost(encoded_tx: &[u8], l1_block_info: &L1BlockInfo, spec: OpSpecId) -> U256 {
+    l1_block_info.clone().calculate_tx_l1_cost(encoded_tx, spec)
+}
+
+/// Returns the L1 gas used by the data of a transaction, as reported in receipts.
+pub fn estimate_l1_gas_used(
+    encoded_tx: &[u8],
+    l1_block_info: &L1BlockInfo,
+    spec: OpSpecId,
+) -> U256 {
+    l1_block_info.data_gas(encoded_tx, spec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::bytes;
+
+    #[test]
+    fn test_da_estimation() {
+        // small transactions are estimated at the minimum size
+        assert_eq!(estimate_da_size(&[0x02; 32]), 100);
+
+        let l1_block_info = L1BlockInfo {
+            l1_base_fee: U256::from(1_000_000_000),
+            l1_base_fee_scalar: U256::from(1_000),
+            l1_blob_base_fee: Some(U256::from(1)),
+            l1_blob_base_fee_scalar: Some(U256::from(1)),
+            ..Default::default()
+        };
+        let deposit = bytes!("7e01");
+        assert_eq!(estimate_l1_cost(&deposit, &l1_block_info, OpSpecId::FJORD), U256::ZERO);
+
+        let tx = [0x02; 200];
+        assert!(!estimate_l1_cost(&tx, &l1_block_info, OpSpecId::FJORD).is_zero());
+        assert!(!estimate_l1_cost(&tx, &l1_block_info, OpSpecId::BEDROCK).is_zero());
+    }
+}
@@ -0,0 +1,131 @@
+//! Execution of the deposit transactions at the start of a block.
+
+use crate::{
+    block::{
+        BlockExecutionError, BlockExecutor, BlockValidationError, ExecutableTx, ExecutableTxParts,
+        PrefetchHints,
+    },
+    RecoveredTx,
+};
+use alloc::vec::Vec;
+use alloy_eips::{Encodable2718, Typed2718};
+use op_alloy::consensus::DEPOSIT_TX_TYPE_ID;
+use op_revm::OpSpecId;
+
+/// Executes and commits a batch of deposit transactions, returning the gas used by them.
+///
+/// This is meant for the first phase of building or executing a block, where all transactions are
+/// deposits. Depositor accounts are loaded with [`BlockExecutor::prefetch`] before any transaction
+/// is executed, executors over backends with expensive reads can override it to load them in a
+/// single batch. Deposits buy no gas, so they aren't subject to fee market or DA footprint checks
+/// either way.
+///
+/// Fails without executing anything if one of the transactions is not a deposit.
+pub fn execute_deposit_transactions<E, T>(
+    executor: &mut E,
+    transactions: impl IntoIterator<Item = T>,
+) -> Result<u64, BlockExecutionError>
+where
+    E: BlockExecutor<Transaction: Typed2718 + Encodable2718>,
+    T: ExecutableTx<E>,
+{
+    let transactions: Vec<_> =
+        transactions.into_iter().map(ExecutableTxParts::into_parts).collect();
+
+    let depositors = depositors(transactions.iter().map(|(_, tx)| tx))?;
+    executor.prefetch(&depositors)?;
+
+    let mut gas_used = 0;
+    for tx in transactions {
+        gas_used += executor.execute_transaction(tx)?;
+    }
+    Ok(gas_used)
+}
+
+/// Returns the gas a deposit transaction reports in its receipt.
+///
+/// Since Regolith deposits report the gas they actually used. Before, in Bedrock, system deposits
+/// report no gas and all other deposits report their full gas limit, whether they succeeded or
+/// not. Executors need this to reproduce the cumulative gas of pre-Regolith receipts.
+pub fn deposit_gas_used(
+    spec: OpSpecId,
+    is_system_transaction: bool,
+    gas_limit: u64,
+    gas_used: u64,
+) -> u64 {
+    if spec.is_enabled_in(OpSpecId::REGOLITH) {
+        gas_used
+    } else if is_system_transaction {
+        0
+    } else {
+        gas_limit
+    }
+}
+
+/// Returns the senders of the given deposits, failing if one of the transactions is not a deposit.
+fn depositors<'a, T, R>(
+    transactions: impl IntoIterator<Item = &'a R>,
+) -> Result<PrefetchHints, BlockExecutionError>
+where
+    T: Typed2718 + Encodable2718,
+    R: RecoveredTx<T> + 'a,
+{
+    let mut depositors = PrefetchHints::new();
+    for tx in transactions {
+        if tx.tx().ty() != DEPOSIT_TX_TYPE_ID {
+            return Err(BlockValidationError::msg(format_args!(
+                "transaction {} is not a deposit",
+                tx.tx().trie_hash()
+            ))
+            .into());
+        }
+        depositors.add_account(*tx.signer());
+    }
+    Ok(depositors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::{transaction::Recovered, Signed, TxLegacy};
+    use alloy_primitives::{Address, Sealed, Signature};
+    use op_alloy::consensus::{OpTxEnvelope, TxDeposit};
+
+    fn deposit(from: Address) -> Recovered<OpTxEnvelope> {
+        let tx = TxDeposit { from, gas_limit: 21_000, ..Default::default() };
+        Recovered::new_unchecked(OpTxEnvelope::Deposit(Sealed::new(tx)), from)
+    }
+
+    #[test]
+    fn test_depositors() {
+        let alice = Address::repeat_byte(0x01);
+        let bob = Address::repeat_byte(0x02);
+        let deposits = [deposit(alice), deposit(bob), deposit(alice)];
+
+        let hints = depositors(&deposits).unwrap();
+        assert_eq!(hints.len(), 2);
+        assert!(hints
+            .iter()
+            .all(|(address, slots)| [alice, bob].contains(&address) && slots.is_empty()));
+    }
+
+    #[test]
+    fn test_depositors_rejects_non_deposits() {
+        let legacy = Signed::new_unhashed(TxLegacy::default(), Signature::test_signature());
+        let transactions = [
+            deposit(Address::repeat_byte(0x01)),
+            Recovered::new_unchecked(OpTxEnvelope::Legacy(legacy), Address::repeat_byte(0x02)),
+        ];
+
+        let err = depositors(&transactions).unwrap_err();
+        assert!(matches!(err.as_validation(), Some(BlockValidationError::Other(_))), "{err:?}");
+    }
+
+    #[test_case::test_case(OpSpecId::BEDROCK, false, 100_000; "bedrock deposit uses gas limit")]
+    #[test_case::test_case(OpSpecId::BEDROCK, true, 0; "bedrock system deposit uses no gas")]
+    #[test_case::test_case(OpSpecId::REGOLITH, false, 21_000; "regolith deposit")]
+    #[test_case::test_case(OpSpecId::REGOLITH, true, 21_000; "regolith system deposit")]
+    fn test_deposit_gas_used(spec: OpSpecId, is_system_transaction: bool, expected: u64) {
+        assert_eq!(deposit_gas_used(spec, is_system_transaction, 100_000, 21_000), expected);
+    }
+}
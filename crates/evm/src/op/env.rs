@@ -3,6 +3,7 @@ use crate::{
     EvmEnv,
 };
 use alloy_consensus::BlockHeader;
+use alloy_eips::eip1559::BaseFeeParams;
 use alloy_op_hardforks::OpHardforks;
 use alloy_primitives::{ChainId, U256};
 use op_revm::OpSpecId;
@@ -35,16 +36,24 @@ impl EvmEnv<OpSpecId> {
     /// # Arguments
     ///
     /// * `header` - The parent block to make the env out of.
-    /// * `base_fee_per_gas` - Base fee per gas for the next block.
+    /// * `attributes` - The attributes of the next block. The base fee is derived from the parent
+    ///   with their EIP-1559 parameters, which default to the OP mainnet ones of the active fork.
     /// * `chain_spec` - The chain hardfork description, must implement [`OpHardforks`].
     /// * `chain_id` - The chain identifier.
     pub fn for_op_next_block(
         header: impl BlockHeader,
         attributes: NextEvmEnvAttributes,
-        base_fee_per_gas: u64,
         chain_spec: impl OpHardforks,
         chain_id: ChainId,
     ) -> Self {
+        let default_params = if chain_spec.is_canyon_active_at_timestamp(attributes.timestamp) {
+            BaseFeeParams::optimism_canyon()
+        } else {
+            BaseFeeParams::optimism()
+        };
+        let base_fee_per_gas = header
+            .next_block_base_fee(attributes.base_fee_params_or(default_params))
+            .unwrap_or_default();
         Self::for_op(
             EvmEnvInput::for_next(header, attributes, base_fee_per_gas, None),
             chain_spec,
@@ -119,3 +128,46 @@ mod payload {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::Header;
+    use alloy_op_hardforks::OpChainHardforks;
+    use alloy_primitives::{Address, B256};
+    use core::num::NonZeroU128;
+
+    #[test]
+    fn test_for_op_next_block_base_fee() {
+        let spec = OpChainHardforks::op_mainnet();
+        let parent = Header {
+            gas_limit: 30_000_000,
+            gas_used: 30_000_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            ..Default::default()
+        };
+        let attributes = |timestamp| NextEvmEnvAttributes {
+            timestamp,
+            suggested_fee_recipient: Address::ZERO,
+            prev_randao: B256::ZERO,
+            gas_limit: 30_000_000,
+            elasticity_multiplier: None,
+            base_fee_max_change_denominator: None,
+        };
+
+        // Bedrock and Canyon defaults
+        let env = EvmEnv::for_op_next_block(&parent, attributes(1_700_000_000), &spec, 10);
+        assert_eq!(env.block_env.basefee, 1_100_000_000);
+        let env = EvmEnv::for_op_next_block(&parent, attributes(1_710_000_000), &spec, 10);
+        assert_eq!(env.block_env.basefee, 1_020_000_000);
+
+        // Ethereum parameters set by the attributes
+        let attributes = NextEvmEnvAttributes {
+            elasticity_multiplier: NonZeroU128::new(2),
+            base_fee_max_change_denominator: NonZeroU128::new(8),
+            ..attributes(1_710_000_000)
+        };
+        let env = EvmEnv::for_op_next_block(&parent, attributes, &spec, 10);
+        assert_eq!(env.block_env.basefee, 1_125_000_000);
+    }
+}
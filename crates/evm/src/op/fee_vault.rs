@@ -0,0 +1,348 @@
+//! Routing of transaction fees to fee vaults.
+//!
+//! OP stack chains credit the fees paid by transactions to predeployed vaults instead of the block
+//! beneficiary. Deployments with modified fee routing can describe it with a [`FeeRouting`], apply
+//! it to the state of a block and check the state changes of a block against it.
+
+use crate::block::{BlockExecutionError, BlockStateDiff, BlockValidationError};
+use alloc::{collections::BTreeMap, vec::Vec};
+use alloy_primitives::{address, Address, U256};
+use revm::database::DatabaseCommitExt;
+
+/// Address of the `SequencerFeeVault` predeploy, which collects priority fees.
+pub const SEQUENCER_FEE_VAULT_ADDRESS: Address =
+    address!("0x4200000000000000000000000000000000000011");
+/// Address of the `BaseFeeVault` predeploy, which collects base fees.
+pub const BASE_FEE_VAULT_ADDRESS: Address = address!("0x4200000000000000000000000000000000000019");
+/// Address of the `L1FeeVault` predeploy, which collects L1 data fees.
+pub const L1_FEE_VAULT_ADDRESS: Address = address!("0x420000000000000000000000000000000000001A");
+/// Address of the `OperatorFeeVault` predeploy, which collects operator fees since Isthmus.
+pub const OPERATOR_FEE_VAULT_ADDRESS: Address =
+    address!("0x420000000000000000000000000000000000001B");
+
+/// Fees collected from the transactions of a block.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CollectedFees {
+    /// Priority fees, i.e. the tips paid on top of the base fee.
+    pub priority_fee: U256,
+    /// Base fees.
+    pub base_fee: U256,
+    /// L1 data fees.
+    pub l1_fee: U256,
+    /// Operator fees.
+    pub operator_fee: U256,
+}
+
+impl CollectedFees {
+    /// Adds the fees of another transaction or block.
+    pub fn add(&mut self, other: &Self) {
+        self.priority_fee += other.priority_fee;
+        self.base_fee += other.base_fee;
+        self.l1_fee += other.l1_fee;
+        self.operator_fee += other.operator_fee;
+    }
+}
+
+/// Error returned by [`FeeRouting::verify`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FeeRoutingError {
+    /// The balance increase of a recipient doesn't match the fees routed to it.
+    #[error("fee recipient {recipient} was credited {got}, expected {expected}")]
+    Mismatch {
+        /// Recipient whose balance doesn't match.
+        recipient: Address,
+        /// Fees routed to the recipient.
+        expected: U256,
+        /// Balance increase of the recipient.
+        got: U256,
+    },
+    /// The balance of a recipient decreased.
+    #[error("balance of fee recipient {recipient} decreased from {from} to {to}")]
+    Decreased {
+        /// Recipient whose balance decreased.
+        recipient: Address,
+        /// Balance before the block.
+        from: U256,
+        /// Balance after the block.
+        to: U256,
+    },
+}
+
+/// Recipients of the different kinds of transaction fees.
+///
+/// Defaults to the canonical fee vault predeploys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeRouting {
+    /// Recipient of priority fees.
+    pub priority_fee: Address,
+    /// Recipient of base fees.
+    pub base_fee: Address,
+    /// Recipient of L1 data fees.
+    pub l1_fee: Address,
+    /// Recipient of operator fees.
+    pub operator_fee: Address,
+}
+
+impl Default for FeeRouting {
+    fn default() -> Self {
+        Self::canonical()
+    }
+}
+
+impl FeeRouting {
+    /// Routes all fees to the canonical fee vault predeploys.
+    pub const fn canonical() -> Self {
+        Self {
+            priority_fee: SEQUENCER_FEE_VAULT_ADDRESS,
+            base_fee: BASE_FEE_VAULT_ADDRESS,
+            l1_fee: L1_FEE_VAULT_ADDRESS,
+            operator_fee: OPERATOR_FEE_VAULT_ADDRESS,
+        }
+    }
+
+    /// Sets the recipient of priority fees.
+    pub const fn with_priority_fee(mut self, recipient: Address) -> Self {
+        self.priority_fee = recipient;
+        self
+    }
+
+    /// Sets the recipient of base fees.
+    pub const fn with_base_fee(mut self, recipient: Address) -> Self {
+        self.base_fee = recipient;
+        self
+    }
+
+    /// Sets the recipient of L1 data fees.
+    pub const fn with_l1_fee(mut self, recipient: Address) -> Self {
+        self.l1_fee = recipient;
+        self
+    }
+
+    /// Sets the recipient of operator fees.
+    pub const fn with_operator_fee(mut self, recipient: Address) -> Self {
+        self.operator_fee = recipient;
+        self
+    }
+
+    /// Returns all fee recipients, e.g. to track the fees collected by a block with a
+    /// [`FeeTracker`](crate::block::FeeTracker).
+    pub const fn recipients(&self) -> [Address; 4] {
+        [self.priority_fee, self.base_fee, self.l1_fee, self.operator_fee]
+    }
+
+    /// Returns the amount credited to every recipient, merging fees routed to the same address.
+    pub fn credits(&self, fees: &CollectedFees) -> BTreeMap<Address, U256> {
+        let mut credits = BTreeMap::<Address, U256>::new();
+        for (recipient, fee) in [
+            (self.priority_fee, fees.priority_fee),
+            (self.base_fee, fees.base_fee),
+            (self.l1_fee, fees.l1_fee),
+            (self.operator_fee, fees.operator_fee),
+        ] {
+            *credits.entry(recipient).or_default() += fee;
+        }
+        credits
+    }
+
+    /// Moves the fees credited to the canonical fee vaults to the recipients of this routing.
+    ///
+    /// The EVM always credits the canonical vaults. Executors of deployments with modified fee
+    /// routing call this on [`Evm::db_mut`](crate::Evm::db_mut) after executing the transactions
+    /// of a block with the fees collected by them, before finishing the block.
+    pub fn apply<DB: DatabaseCommitExt>(
+        &self,
+        fees: &CollectedFees,
+        db: &mut DB,
+    ) -> Result<(), BlockExecutionError> {
+        // Net amount moved away from and to every address.
+        let mut moved = BTreeMap::<Address, (U256, U256)>::new();
+        for (recipient, fee) in Self::canonical().credits(fees) {
+            moved.entry(recipient).or_default().0 += fee;
+        }
+        for (recipient, fee) in self.credits(fees) {
+            moved.entry(recipient).or_default().1 += fee;
+        }
+
+        let mut debits = Vec::new();
+        let mut credits = Vec::new();
+        for (address, (debit, credit)) in moved {
+            if debit > credit {
+                debits.push((address, debit - credit));
+            } else if credit > debit {
+                credits.push((address, credit - debit));
+            }
+        }
+        if debits.is_empty() {
+            return Ok(());
+        }
+
+        // Balances can only be incremented, so debited vaults are drained and credited back.
+        let balances = db
+            .drain_balances(debits.iter().map(|(address, _)| *address))
+            .map_err(|_| BlockValidationError::IncrementBalanceFailed)?;
+        for ((address, debit), balance) in debits.into_iter().zip(balances) {
+            let remaining = U256::from(balance).checked_sub(debit).ok_or_else(|| {
+                BlockValidationError::msg(format_args!(
+                    "fee vault {address} holds less than the fees routed away from it"
+                ))
+            })?;
+            credits.push((address, remaining));
+        }
+        let credits = credits
+            .into_iter()
+            .map(|(address, amount)| {
+                let amount = u128::try_from(amount)
+                    .map_err(|_| BlockValidationError::IncrementBalanceFailed)?;
+                Ok((address, amount))
+            })
+            .collect::<Result<Vec<_>, BlockValidationError>>()?;
+        db.increment_balances(credits).map_err(|_| BlockValidationError::IncrementBalanceFailed)?;
+        Ok(())
+    }
+
+    /// Checks that the balance of every recipient increased by exactly the fees routed to it.
+    ///
+    /// Recipients are assumed to only receive fees during the block, so this can't be used for
+    /// blocks in which a recipient also sends or receives value otherwise.
+    pub fn verify(
+        &self,
+        fees: &CollectedFees,
+        diff: &BlockStateDiff,
+    ) -> Result<(), FeeRoutingError> {
+        for (recipient, expected) in self.credits(fees) {
+            let got = match diff.accounts.get(&recipient).and_then(|account| account.balance) {
+                Some(balance) if balance.to < balance.from => {
+                    return Err(FeeRoutingError::Decreased {
+                        recipient,
+                        from: balance.from,
+                        to: balance.to,
+                    })
+                }
+                Some(balance) => balance.to - balance.from,
+                None => U256::ZERO,
+            };
+            if got != expected {
+                return Err(FeeRoutingError::Mismatch { recipient, expected, got });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::{
+        state_diff::{AccountDiff, Change},
+        BundleStateDB,
+    };
+    use revm::{
+        database::{states::bundle_state::BundleRetention, CacheDB, EmptyDB, State},
+        state::AccountInfo,
+        Database,
+    };
+
+    fn credited(diff: &mut BlockStateDiff, address: Address, amount: u64) {
+        diff.accounts.insert(
+            address,
+            AccountDiff {
+                balance: Change::new(U256::from(1), U256::from(1 + amount)),
+                ..Default::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_verify_fee_routing() {
+        let fees = CollectedFees {
+            priority_fee: U256::from(10),
+            base_fee: U256::from(20),
+            l1_fee: U256::from(30),
+            operator_fee: U256::ZERO,
+        };
+
+        let mut diff = BlockStateDiff::default();
+        credited(&mut diff, SEQUENCER_FEE_VAULT_ADDRESS, 10);
+        credited(&mut diff, BASE_FEE_VAULT_ADDRESS, 20);
+        credited(&mut diff, L1_FEE_VAULT_ADDRESS, 30);
+        assert_eq!(FeeRouting::canonical().verify(&fees, &diff), Ok(()));
+
+        let custom = Address::repeat_byte(0x01);
+        let routing = FeeRouting::canonical().with_base_fee(custom).with_l1_fee(custom);
+        assert_eq!(
+            routing.verify(&fees, &diff),
+            Err(FeeRoutingError::Mismatch {
+                recipient: custom,
+                expected: U256::from(50),
+                got: U256::ZERO
+            })
+        );
+
+        let mut diff = BlockStateDiff::default();
+        credited(&mut diff, SEQUENCER_FEE_VAULT_ADDRESS, 10);
+        credited(&mut diff, custom, 50);
+        assert_eq!(routing.verify(&fees, &diff), Ok(()));
+
+        let mut diff = BlockStateDiff::default();
+        diff.accounts.insert(
+            BASE_FEE_VAULT_ADDRESS,
+            AccountDiff {
+                balance: Change::new(U256::from(5), U256::from(1)),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            FeeRouting::canonical().verify(&CollectedFees::default(), &diff),
+            Err(FeeRoutingError::Decreased {
+                recipient: BASE_FEE_VAULT_ADDRESS,
+                from: U256::from(5),
+                to: U256::from(1)
+            })
+        );
+    }
+
+    #[test]
+    fn test_apply_fee_routing() {
+        let custom = Address::repeat_byte(0x01);
+        let mut db = CacheDB::new(EmptyDB::default());
+        // Fees collected by earlier blocks stay in the canonical vault.
+        db.insert_account_info(
+            BASE_FEE_VAULT_ADDRESS,
+            AccountInfo { balance: U256::from(100), ..Default::default() },
+        );
+        let mut state = State::builder().with_database(db).with_bundle_update().build();
+
+        // Credits of the EVM.
+        let fees = CollectedFees {
+            priority_fee: U256::from(10),
+            base_fee: U256::from(20),
+            l1_fee: U256::from(30),
+            operator_fee: U256::ZERO,
+        };
+        state
+            .increment_balances([
+                (SEQUENCER_FEE_VAULT_ADDRESS, 10),
+                (BASE_FEE_VAULT_ADDRESS, 20),
+                (L1_FEE_VAULT_ADDRESS, 30),
+            ])
+            .unwrap();
+
+        let routing = FeeRouting::canonical().with_base_fee(custom).with_l1_fee(custom);
+        routing.apply(&fees, &mut state).unwrap();
+        BundleStateDB::merge_transitions(&mut state, BundleRetention::Reverts);
+
+        let diff = BlockStateDiff::from_bundle(BundleStateDB::bundle_state(&state));
+        assert_eq!(routing.verify(&fees, &diff), Ok(()));
+        assert_eq!(state.basic(BASE_FEE_VAULT_ADDRESS).unwrap().unwrap().balance, U256::from(100));
+        assert_eq!(state.basic(custom).unwrap().unwrap().balance, U256::from(50));
+        assert!(FeeRouting::canonical().verify(&fees, &diff).is_err());
+    }
+
+    #[test]
+    fn test_apply_fee_routing_insufficient_vault_balance() {
+        let mut state = State::builder().with_database(EmptyDB::default()).build();
+        let fees = CollectedFees { base_fee: U256::from(20), ..Default::default() };
+        let routing = FeeRouting::canonical().with_base_fee(Address::repeat_byte(0x01));
+        assert!(routing.apply(&fees, &mut state).is_err());
+    }
+}
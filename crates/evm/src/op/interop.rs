@@ -0,0 +1,473 @@
+//! Validation of cross-chain messages of the Interop hardfork.
+//!
+//! Transactions executing messages initiated on other chains of the dependency set call the
+//! `CrossL2Inbox` predeploy, which emits an [`ExecutingMessage`] event per message. Whether the
+//! referenced initiating message exists can only be checked against the other chains, so the
+//! check is delegated to an [`InteropValidator`], e.g. one backed by a supervisor. A transaction
+//! with an invalid executing message invalidates the block.
+//!
+//! Executing transactions also declare the messages they depend on in their access list, which
+//! [`access_list_dependencies`] extracts so they can be checked before execution.
+
+use crate::{
+    block::{
+        BlockExecutionError, BlockExecutionResult, BlockExecutor, BlockValidationError,
+        ExecutableTx, OnStateHook, TxResult,
+    },
+    Evm,
+};
+use alloc::{boxed::Box, vec::Vec};
+use alloy_consensus::Transaction;
+use alloy_eips::eip2930::AccessList;
+use alloy_primitives::{address, Address, Log, B256, U256};
+use alloy_sol_types::{sol, SolEvent};
+use op_revm::OpSpecId;
+use revm::context::Block;
+
+/// Address of the `CrossL2Inbox` predeploy.
+pub const CROSS_L2_INBOX_ADDRESS: Address = address!("0x4200000000000000000000000000000000000022");
+
+sol! {
+    /// Identifier of an initiating message.
+    #[derive(Debug, PartialEq, Eq)]
+    struct Identifier {
+        /// Address that emitted the initiating message.
+        address origin;
+        /// Number of the block the message was initiated in.
+        uint256 blockNumber;
+        /// Index of the initiating log in its block.
+        uint256 logIndex;
+        /// Timestamp of the block the message was initiated in.
+        uint256 timestamp;
+        /// Chain the message was initiated on.
+        uint256 chainId;
+    }
+
+    /// Emitted by the `CrossL2Inbox` for every executed message.
+    #[derive(Debug, PartialEq, Eq)]
+    event ExecutingMessage(bytes32 indexed msgHash, Identifier id);
+}
+
+/// Returns the executing messages emitted by the `CrossL2Inbox` among the logs.
+///
+/// Fails if a log of the `CrossL2Inbox` with the [`ExecutingMessage`] signature can't be decoded.
+pub fn executing_messages<'a>(
+    logs: impl IntoIterator<Item = &'a Log>,
+) -> Result<Vec<ExecutingMessage>, alloy_sol_types::Error> {
+    logs.into_iter()
+        .filter(|log| log.address == CROSS_L2_INBOX_ADDRESS)
+        .filter(|log| log.topics().first() == Some(&ExecutingMessage::SIGNATURE_HASH))
+        .map(|log| ExecutingMessage::decode_log(log).map(|log| log.data))
+        .collect()
+}
+
+/// Prefix of access list storage keys identifying an initiating message.
+const LOOKUP_ENTRY: u8 = 0x01;
+/// Prefix of access list storage keys holding the upper bytes of chain ids exceeding 64 bits.
+const CHAIN_ID_EXTENSION_ENTRY: u8 = 0x02;
+/// Prefix of access list storage keys holding the checksum of an executing message.
+const CHECKSUM_ENTRY: u8 = 0x03;
+
+/// Errors returned when decoding the `CrossL2Inbox` entries of an access list.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum InteropAccessListError {
+    /// A storage key has an unknown prefix or is not expected at its position.
+    #[error("unexpected interop access list entry {0}")]
+    UnexpectedEntry(B256),
+    /// The padding of a storage key is not zero.
+    #[error("invalid padding of interop access list entry {0}")]
+    InvalidPadding(B256),
+    /// The entries of a message are not terminated by a checksum.
+    #[error("interop access list entry is missing a checksum")]
+    MissingChecksum,
+}
+
+/// An initiating message a transaction depends on, as declared in its access list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageDependency {
+    /// Chain the message was initiated on.
+    pub chain_id: U256,
+    /// Number of the block the message was initiated in.
+    pub block_number: u64,
+    /// Timestamp of the block the message was initiated in.
+    pub timestamp: u64,
+    /// Index of the initiating log in its block.
+    pub log_index: u32,
+    /// Checksum of the executing message, the storage key of the checksum entry.
+    pub checksum: B256,
+}
+
+/// Decodes the messages declared by the `CrossL2Inbox` entries of an access list.
+///
+/// Each message is declared by a lookup entry, optionally followed by a chain id extension for
+/// chain ids exceeding 64 bits, and terminated by a checksum entry.
+pub fn access_list_dependencies(
+    access_list: &AccessList,
+) -> Result<Vec<MessageDependency>, InteropAccessListError> {
+    let mut keys = access_list
+        .iter()
+        .filter(|item| item.address == CROSS_L2_INBOX_ADDRESS)
+        .flat_map(|item| item.storage_keys.iter().copied());
+
+    let mut dependencies = Vec::new();
+    while let Some(lookup) = keys.next() {
+        if lookup[0] != LOOKUP_ENTRY {
+            return Err(InteropAccessListError::UnexpectedEntry(lookup));
+        }
+        if lookup[1..4] != [0; 3] {
+            return Err(InteropAccessListError::InvalidPadding(lookup));
+        }
+        let mut chain_id = [0; 32];
+        chain_id[24..].copy_from_slice(&lookup[4..12]);
+
+        let mut next = keys.next().ok_or(InteropAccessListError::MissingChecksum)?;
+        if next[0] == CHAIN_ID_EXTENSION_ENTRY {
+            if next[1..8] != [0; 7] {
+                return Err(InteropAccessListError::InvalidPadding(next));
+            }
+            chain_id[..24].copy_from_slice(&next[8..]);
+            next = keys.next().ok_or(InteropAccessListError::MissingChecksum)?;
+        }
+        if next[0] != CHECKSUM_ENTRY {
+            return Err(InteropAccessListError::UnexpectedEntry(next));
+        }
+
+        dependencies.push(MessageDependency {
+            chain_id: U256::from_be_bytes(chain_id),
+            block_number: u64::from_be_bytes(lookup[12..20].try_into().unwrap()),
+            timestamp: u64::from_be_bytes(lookup[20..28].try_into().unwrap()),
+            log_index: u32::from_be_bytes(lookup[28..].try_into().unwrap()),
+            checksum: next,
+        });
+    }
+    Ok(dependencies)
+}
+
+/// Returns the messages every transaction depends on, in order.
+///
+/// Transactions without access list or `CrossL2Inbox` entries depend on no messages.
+pub fn transaction_dependencies<'a, T: Transaction + 'a>(
+    transactions: impl IntoIterator<Item = &'a T>,
+) -> Result<Vec<Vec<MessageDependency>>, InteropAccessListError> {
+    transactions
+        .into_iter()
+        .map(|tx| tx.access_list().map_or(Ok(Vec::new()), access_list_dependencies))
+        .collect()
+}
+
+/// Block a transaction executing messages is included in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InteropContext {
+    /// Chain the block belongs to.
+    pub chain_id: u64,
+    /// Timestamp of the block.
+    pub timestamp: u64,
+}
+
+/// Validates the executing messages of transactions against the dependency set.
+pub trait InteropValidator {
+    /// Validates the messages executed by a transaction.
+    ///
+    /// Only invoked for transactions executing at least one message. Returning an error
+    /// invalidates the block.
+    fn validate_messages(
+        &mut self,
+        ctx: &InteropContext,
+        messages: &[ExecutingMessage],
+    ) -> Result<(), Box<dyn core::error::Error + Send + Sync>>;
+}
+
+/// A [`BlockExecutor`] validating executing messages with an [`InteropValidator`] before
+/// transactions are committed.
+///
+/// Validation only happens once the Interop hardfork is active, before that the executor behaves
+/// exactly like the wrapped one.
+#[derive(Debug)]
+pub struct InteropBlockExecutor<E, V> {
+    inner: E,
+    validator: V,
+    ctx: Option<InteropContext>,
+}
+
+impl<E: BlockExecutor, V> InteropBlockExecutor<E, V> {
+    /// Wraps the executor of a block executed with the given spec.
+    pub fn new(inner: E, validator: V, spec: OpSpecId) -> Self {
+        let ctx = spec.is_enabled_in(OpSpecId::INTEROP).then(|| InteropContext {
+            chain_id: inner.evm().chain_id(),
+            timestamp: inner.evm().block().timestamp().saturating_to(),
+        });
+        Self { inner, validator, ctx }
+    }
+
+    /// Returns the validator.
+    pub const fn validator(&self) -> &V {
+        &self.validator
+    }
+
+    /// Consumes the wrapper, returning the inner executor.
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+}
+
+impl<E, V> BlockExecutor for InteropBlockExecutor<E, V>
+where
+    E: BlockExecutor,
+    V: InteropValidator,
+{
+    type Transaction = E::Transaction;
+    type Receipt = E::Receipt;
+    type Evm = E::Evm;
+    type Result = E::Result;
+
+    fn apply_pre_execution_changes(&mut self) -> Result<(), BlockExecutionError> {
+        self.inner.apply_pre_execution_changes()
+    }
+
+    fn execute_transaction_without_commit(
+        &mut self,
+        tx: impl ExecutableTx<Self>,
+    ) -> Result<Self::Result, BlockExecutionError> {
+        let output = self.inner.execute_transaction_without_commit(tx)?;
+        if let Some(ctx) = &self.ctx {
+            let messages = executing_messages(output.result().result.logs())
+                .map_err(BlockValidationError::other)?;
+            if !messages.is_empty() {
+                self.validator
+                    .validate_messages(ctx, &messages)
+                    .map_err(BlockValidationError::Other)?;
+            }
+        }
+        Ok(output)
+    }
+
+    fn commit_transaction(&mut self, output: Self::Result) -> Result<u64, BlockExecutionError> {
+        self.inner.commit_transaction(output)
+    }
+
+    fn finish(
+        self,
+    ) -> Result<(Self::Evm, BlockExecutionResult<Self::Receipt>), BlockExecutionError> {
+        self.inner.finish()
+    }
+
+    fn set_state_hook(&mut self, hook: Option<Box<dyn OnStateHook>>) {
+        self.inner.set_state_hook(hook);
+    }
+
+    fn evm_mut(&mut self) -> &mut Self::Evm {
+        self.inner.evm_mut()
+    }
+
+    fn evm(&self) -> &Self::Evm {
+        self.inner.evm()
+    }
+
+    fn receipts(&self) -> &[Self::Receipt] {
+        self.inner.receipts()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        eth::{
+            receipt_builder::AlloyReceiptBuilder, spec::EthSpec, EthBlockExecutionCtx,
+            EthBlockExecutor,
+        },
+        EthEvmFactory, EvmEnv, EvmFactory,
+    };
+    use alloy_consensus::{
+        transaction::Recovered, Header, SignableTransaction, TxEnvelope, TxLegacy,
+    };
+    use alloy_eips::eip2930::AccessListItem;
+    use alloy_primitives::{Signature, TxKind};
+    use revm::{
+        bytecode::Bytecode,
+        database::{CacheDB, EmptyDB, State},
+        state::AccountInfo,
+    };
+
+    fn message() -> ExecutingMessage {
+        ExecutingMessage {
+            msgHash: B256::repeat_byte(0x01),
+            id: Identifier {
+                origin: Address::repeat_byte(0x02),
+                blockNumber: U256::from(10),
+                logIndex: U256::from(1),
+                timestamp: U256::from(100),
+                chainId: U256::from(901),
+            },
+        }
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("unknown initiating message")]
+    struct UnknownMessage;
+
+    /// A validator recording the validated messages.
+    #[derive(Debug, Default)]
+    struct RecordingValidator {
+        reject: bool,
+        validated: Vec<(InteropContext, Vec<ExecutingMessage>)>,
+    }
+
+    impl InteropValidator for RecordingValidator {
+        fn validate_messages(
+            &mut self,
+            ctx: &InteropContext,
+            messages: &[ExecutingMessage],
+        ) -> Result<(), Box<dyn core::error::Error + Send + Sync>> {
+            self.validated.push((*ctx, messages.to_vec()));
+            if self.reject {
+                return Err(Box::new(UnknownMessage));
+            }
+            Ok(())
+        }
+    }
+
+    /// Executes a call to `to` with an [`InteropBlockExecutor`] wrapping an Ethereum executor,
+    /// where the `CrossL2Inbox` emits [`message`] on every call.
+    fn execute(
+        to: Address,
+        spec: OpSpecId,
+        reject: bool,
+    ) -> (Result<(), BlockExecutionError>, usize, RecordingValidator) {
+        let data = message().encode_data();
+        let [len_hi, len_lo] = (data.len() as u16).to_be_bytes();
+        // copies the event data following the 81 bytes of code into memory and logs it
+        let mut code = alloc::vec![0x61, len_hi, len_lo, 0x60, 0x51, 0x60, 0x00, 0x39, 0x7f];
+        code.extend_from_slice(message().msgHash.as_slice());
+        code.push(0x7f);
+        code.extend_from_slice(ExecutingMessage::SIGNATURE_HASH.as_slice());
+        code.extend_from_slice(&[0x61, len_hi, len_lo, 0x60, 0x00, 0xa2, 0x00]);
+        assert_eq!(code.len(), 0x51);
+        code.extend_from_slice(&data);
+
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            CROSS_L2_INBOX_ADDRESS,
+            AccountInfo::default().with_code(Bytecode::new_raw(code.into())),
+        );
+        let mut state = State::builder().with_database(db).build();
+        let header = Header {
+            number: 1_150_000,
+            timestamp: 1_000,
+            gas_limit: 30_000_000,
+            ..Default::default()
+        };
+        let evm_env = EvmEnv::for_eth_block(&header, EthSpec::mainnet(), 901, None);
+        let evm = EthEvmFactory::default().create_evm(&mut state, evm_env);
+        let inner = EthBlockExecutor::new(
+            evm,
+            EthBlockExecutionCtx::from_header(&header),
+            EthSpec::mainnet(),
+            AlloyReceiptBuilder::default(),
+        );
+        let mut executor = InteropBlockExecutor::new(
+            inner,
+            RecordingValidator { reject, ..Default::default() },
+            spec,
+        );
+
+        let tx = TxLegacy { gas_limit: 100_000, to: TxKind::Call(to), ..Default::default() };
+        let tx = Recovered::new_unchecked(
+            TxEnvelope::from(tx.into_signed(Signature::test_signature())),
+            Address::repeat_byte(0x01),
+        );
+        let result = executor.execute_transaction(&tx).map(drop);
+        let receipts = executor.receipts().len();
+        let InteropBlockExecutor { validator, .. } = executor;
+        (result, receipts, validator)
+    }
+
+    #[test]
+    fn test_interop_block_executor() {
+        let ctx = InteropContext { chain_id: 901, timestamp: 1_000 };
+
+        let (result, receipts, validator) =
+            execute(CROSS_L2_INBOX_ADDRESS, OpSpecId::INTEROP, false);
+        result.unwrap();
+        assert_eq!(receipts, 1);
+        assert_eq!(validator.validated, [(ctx, alloc::vec![message()])]);
+
+        // a rejected message invalidates the block before the transaction is committed
+        let (result, receipts, validator) =
+            execute(CROSS_L2_INBOX_ADDRESS, OpSpecId::INTEROP, true);
+        let err = result.unwrap_err();
+        assert!(matches!(err.as_validation(), Some(BlockValidationError::Other(_))));
+        assert_eq!(receipts, 0);
+        assert_eq!(validator.validated.len(), 1);
+
+        // transactions without executing messages are not validated
+        let (result, receipts, validator) =
+            execute(Address::repeat_byte(0x03), OpSpecId::INTEROP, true);
+        result.unwrap();
+        assert_eq!(receipts, 1);
+        assert!(validator.validated.is_empty());
+
+        // nothing is validated before Interop
+        let (result, receipts, validator) =
+            execute(CROSS_L2_INBOX_ADDRESS, OpSpecId::ISTHMUS, true);
+        result.unwrap();
+        assert_eq!(receipts, 1);
+        assert!(validator.validated.is_empty());
+    }
+
+    #[test]
+    fn test_executing_messages() {
+        let message = message();
+        let log = Log { address: CROSS_L2_INBOX_ADDRESS, data: message.encode_log_data() };
+        let other = Log { address: Address::repeat_byte(0x03), data: message.encode_log_data() };
+
+        assert_eq!(executing_messages([&log, &other]).unwrap(), [message]);
+    }
+
+    #[test]
+    fn test_access_list_dependencies() {
+        let lookup = |chain_id: u64| {
+            let mut key = [0; 32];
+            key[0] = LOOKUP_ENTRY;
+            key[4..12].copy_from_slice(&chain_id.to_be_bytes());
+            key[12..20].copy_from_slice(&10u64.to_be_bytes());
+            key[20..28].copy_from_slice(&100u64.to_be_bytes());
+            key[28..].copy_from_slice(&1u32.to_be_bytes());
+            B256::from(key)
+        };
+        let mut extension = B256::ZERO;
+        extension[0] = CHAIN_ID_EXTENSION_ENTRY;
+        extension[31] = 1;
+        let mut checksum = B256::repeat_byte(0xcc);
+        checksum[0] = CHECKSUM_ENTRY;
+
+        let access_list = AccessList(alloc::vec![
+            AccessListItem { address: Address::ZERO, storage_keys: alloc::vec![B256::ZERO] },
+            AccessListItem {
+                address: CROSS_L2_INBOX_ADDRESS,
+                storage_keys: alloc::vec![lookup(901), checksum, lookup(2), extension, checksum],
+            },
+        ]);
+        let dependencies = access_list_dependencies(&access_list).unwrap();
+        assert_eq!(
+            dependencies[0],
+            MessageDependency {
+                chain_id: U256::from(901),
+                block_number: 10,
+                timestamp: 100,
+                log_index: 1,
+                checksum,
+            }
+        );
+        assert_eq!(dependencies[1].chain_id, (U256::from(1) << 64) + U256::from(2));
+
+        let missing = AccessList(alloc::vec![AccessListItem {
+            address: CROSS_L2_INBOX_ADDRESS,
+            storage_keys: alloc::vec![lookup(901)],
+        }]);
+        assert_eq!(
+            access_list_dependencies(&missing),
+            Err(InteropAccessListError::MissingChecksum)
+        );
+    }
+}
@@ -1,9 +1,31 @@
 //! Optimism EVM implementation.
 
+mod assemble;
+mod da;
+mod deposit;
 mod env;
+mod fee_vault;
+pub mod interop;
+mod receipts;
 #[cfg(feature = "rpc")]
 mod rpc;
 mod spec_id;
 mod tx;
 
-pub use spec_id::{spec, spec_by_timestamp_after_bedrock};
+pub use assemble::{assemble_block, MissingWithdrawalsStorageRoot};
+pub use da::{estimate_da_size, estimate_l1_cost, estimate_l1_gas_used};
+pub use deposit::{deposit_gas_used, execute_deposit_transactions};
+pub use fee_vault::{
+    CollectedFees, FeeRouting, FeeRoutingError, BASE_FEE_VAULT_ADDRESS, L1_FEE_VAULT_ADDRESS,
+    OPERATOR_FEE_VAULT_ADDRESS, SEQUENCER_FEE_VAULT_ADDRESS,
+};
+pub use receipts::{receipt_preimages, receipts_root};
+#[cfg(feature = "rpc")]
+pub use rpc::{into_op_rpc_receipt, into_op_rpc_receipts, OpFeeOracle, OpReceiptBlockInfo};
+pub use spec_id::{
+    fork_schedule, spec, spec_by_timestamp_after_bedrock,
+    spec_by_timestamp_after_bedrock_with_override, CustomOpHardforks,
+};
+
+#[cfg(feature = "genesis")]
+pub use spec_id::hardforks_from_genesis;
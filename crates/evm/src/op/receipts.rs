@@ -0,0 +1,221 @@
+//! Receipts roots of OP blocks.
+
+use alloc::{borrow::Cow, vec::Vec};
+use alloy_consensus::proofs::calculate_receipt_root;
+use alloy_op_hardforks::OpHardforks;
+use alloy_primitives::{Bytes, B256};
+use op_alloy::consensus::OpReceipt;
+
+/// Returns the receipts as hashed into the receipts trie of a block with the given timestamp.
+///
+/// Deposit receipts carry their nonce since Regolith, but op-geth didn't include it when computing
+/// the receipts root until Canyon fixed this. In between, the nonce is stripped.
+fn hashed_receipts(
+    receipts: &[OpReceipt],
+    chain_spec: impl OpHardforks,
+    timestamp: u64,
+) -> Cow<'_, [OpReceipt]> {
+    if chain_spec.is_regolith_active_at_timestamp(timestamp)
+        && !chain_spec.is_canyon_active_at_timestamp(timestamp)
+    {
+        Cow::Owned(
+            receipts
+                .iter()
+                .cloned()
+                .map(|mut receipt| {
+                    if let OpReceipt::Deposit(deposit) = &mut receipt {
+                        deposit.deposit_nonce = None;
+                    }
+                    receipt
+                })
+                .collect(),
+        )
+    } else {
+        Cow::Borrowed(receipts)
+    }
+}
+
+/// Calculates the receipts root of an OP block with the given timestamp.
+///
+/// Use it with
+/// [`ExpectedBlockOutcome::verify_with_receipts_root`](crate::block::ExpectedBlockOutcome::verify_with_receipts_root)
+/// to verify the outcome of OP blocks.
+pub fn receipts_root(receipts: &[OpReceipt], chain_spec: impl OpHardforks, timestamp: u64) -> B256 {
+    calculate_receipt_root(&hashed_receipts(receipts, chain_spec, timestamp))
+}
+
+/// Returns the exact bytes hashed into the receipts trie of an OP block with the given timestamp,
+/// see [`receipts_root`].
+pub fn receipt_preimages(
+    receipts: &[OpReceipt],
+    chain_spec: impl OpHardforks,
+    timestamp: u64,
+) -> Vec<Bytes> {
+    crate::block::verify::receipt_preimages(&hashed_receipts(receipts, chain_spec, timestamp))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::op::CustomOpHardforks;
+    use alloc::vec;
+    use alloy_consensus::{Header, Receipt};
+    use alloy_hardforks::ForkCondition;
+    use alloy_op_hardforks::{OpChainHardforks, OpHardfork};
+    use alloy_primitives::{b256, hex, Address, Log, LogData};
+    use op_alloy::consensus::OpDepositReceipt;
+
+    /// A block on OP mainnet between Regolith, which is active since Bedrock, and Canyon.
+    const REGOLITH_TIMESTAMP: u64 = 1_700_000_000;
+    /// A block on OP mainnet after Canyon.
+    const CANYON_TIMESTAMP: u64 = 1_710_000_000;
+
+    fn receipts(deposit_receipt_version: Option<u64>) -> Vec<OpReceipt> {
+        let inner = |cumulative_gas_used| Receipt {
+            status: true.into(),
+            cumulative_gas_used,
+            logs: vec![],
+        };
+        vec![
+            OpReceipt::Deposit(OpDepositReceipt {
+                inner: inner(21_000),
+                deposit_nonce: Some(5),
+                deposit_receipt_version,
+            }),
+            OpReceipt::Eip1559(inner(42_000)),
+        ]
+    }
+
+    #[test]
+    fn test_receipt_preimages() {
+        let spec = OpChainHardforks::op_mainnet();
+        let bloom = [0u8; 256];
+        let deposit = |prefix: &[u8], gas: &[u8], tail: &[u8]| {
+            [prefix, &hex!("01"), gas, &hex!("b90100"), &bloom, &hex!("c0"), tail].concat()
+        };
+
+        let preimages = receipt_preimages(&receipts(None), &spec, REGOLITH_TIMESTAMP);
+        assert_eq!(preimages[0], deposit(&hex!("7ef90108"), &hex!("825208"), &[]));
+        assert_eq!(preimages[1], deposit(&hex!("02f90108"), &hex!("82a410"), &[]));
+
+        let preimages = receipt_preimages(&receipts(Some(1)), &spec, CANYON_TIMESTAMP);
+        assert_eq!(preimages[0], deposit(&hex!("7ef9010a"), &hex!("825208"), &hex!("0501")));
+    }
+
+    #[test]
+    fn test_receipts_root() {
+        let spec = OpChainHardforks::op_mainnet();
+        let receipts = receipts(None);
+        let mut stripped = receipts.clone();
+        if let OpReceipt::Deposit(deposit) = &mut stripped[0] {
+            deposit.deposit_nonce = None;
+        }
+
+        // The nonce is not hashed between Regolith and Canyon.
+        let root = receipts_root(&receipts, &spec, REGOLITH_TIMESTAMP);
+        assert_eq!(root, calculate_receipt_root(&stripped));
+        assert_ne!(root, calculate_receipt_root(&receipts));
+
+        // It is since Canyon.
+        let receipts = self::receipts(Some(1));
+        assert_eq!(
+            receipts_root(&receipts, &spec, CANYON_TIMESTAMP),
+            calculate_receipt_root(&receipts)
+        );
+    }
+
+    fn log() -> Log {
+        Log {
+            address: Address::repeat_byte(0x42),
+            data: LogData::new_unchecked(vec![B256::repeat_byte(0xdd)], vec![0x01; 32].into()),
+        }
+    }
+
+    fn receipt(success: bool, cumulative_gas_used: u64, logs: Vec<Log>) -> Receipt {
+        Receipt { status: success.into(), cumulative_gas_used, logs }
+    }
+
+    fn deposit(
+        cumulative_gas_used: u64,
+        logs: Vec<Log>,
+        deposit_nonce: Option<u64>,
+        deposit_receipt_version: Option<u64>,
+    ) -> OpReceipt {
+        OpReceipt::Deposit(OpDepositReceipt {
+            inner: receipt(true, cumulative_gas_used, logs),
+            deposit_nonce,
+            deposit_receipt_version,
+        })
+    }
+
+    /// Checks the receipts root of a block against the `receiptsRoot` of its header.
+    ///
+    /// The expected roots are computed from the RLP encoding of the receipts with a plain
+    /// `HashBuilder`, independently of the receipt types.
+    fn assert_receipts_root(receipts: &[OpReceipt], chain_spec: impl OpHardforks, header: Header) {
+        assert_eq!(receipts_root(receipts, chain_spec, header.timestamp), header.receipts_root);
+    }
+
+    #[test]
+    fn test_receipts_root_pre_regolith() {
+        // Regolith is active since Bedrock on OP mainnet.
+        let spec = CustomOpHardforks::new(OpChainHardforks::op_mainnet())
+            .with_fork(OpHardfork::Regolith, ForkCondition::Timestamp(REGOLITH_TIMESTAMP));
+        // The system deposit reports no gas and the user deposit its full gas limit.
+        let receipts = [
+            deposit(0, vec![], None, None),
+            deposit(100_000, vec![log()], None, None),
+            OpReceipt::Legacy(receipt(true, 150_000, vec![log()])),
+        ];
+        let header = Header {
+            timestamp: REGOLITH_TIMESTAMP - 1,
+            receipts_root: b256!(
+                "0x9271aecc51ab2008510f66c075124638cc6a3858de19cc00e2b3e6ba34b2d6a7"
+            ),
+            ..Default::default()
+        };
+        assert_receipts_root(&receipts, &spec, header);
+    }
+
+    #[test]
+    fn test_receipts_root_regolith() {
+        let spec = OpChainHardforks::op_mainnet();
+        let receipts = [
+            deposit(45_000, vec![], Some(7), None),
+            OpReceipt::Eip1559(receipt(true, 90_000, vec![log()])),
+            OpReceipt::Eip1559(receipt(false, 120_000, vec![])),
+        ];
+        let header = Header {
+            timestamp: REGOLITH_TIMESTAMP,
+            receipts_root: b256!(
+                "0xe9551baafc093d0bf2620a3b10b45e092cc2029e9a6ed0a999d67ff58e55cbde"
+            ),
+            ..Default::default()
+        };
+        assert_receipts_root(&receipts, &spec, header);
+        // The root including the deposit nonce doesn't match the header.
+        assert_eq!(
+            calculate_receipt_root(&receipts),
+            b256!("0xfb1753bd419882b1594bdf5e7ea3c02af442485fddf1a884a5dda1aa5c21eb3a")
+        );
+    }
+
+    #[test]
+    fn test_receipts_root_canyon() {
+        let spec = OpChainHardforks::op_mainnet();
+        let receipts = [
+            deposit(45_000, vec![], Some(9), Some(1)),
+            deposit(95_000, vec![log()], Some(10), Some(1)),
+            OpReceipt::Eip2930(receipt(true, 120_000, vec![log()])),
+            OpReceipt::Eip1559(receipt(false, 150_000, vec![])),
+        ];
+        let header = Header {
+            timestamp: CANYON_TIMESTAMP,
+            receipts_root: b256!(
+                "0xb5d19ff4ca1194b5b70b0eb56c437a646c26c873aa684dc962bdc866845070d9"
+            ),
+            ..Default::default()
+        };
+        assert_receipts_root(&receipts, &spec, header);
+    }
+}
@@ -1,11 +1,23 @@
+use super::{estimate_l1_cost, estimate_l1_gas_used};
 use crate::{
     env::BlockEnvironment,
-    rpc::{EthTxEnvError, TryIntoTxEnv},
+    rpc::{
+        map_block_receipts, rpc_logs, rpc_receipt, CumulativeGasUsedError, EthTxEnvError,
+        FeeHistoryBlock, ReceiptBlockInfo, SuggestFee, TryIntoTxEnv,
+    },
     EvmEnv,
 };
+use alloc::vec::Vec;
+use alloy_consensus::{
+    transaction::Recovered, BlockHeader, Receipt, ReceiptWithBloom, Transaction, TxReceipt,
+};
+use alloy_eips::eip2718::Encodable2718;
 use alloy_primitives::Bytes;
-use op_alloy::rpc_types::OpTransactionRequest;
-use op_revm::OpTransaction;
+use op_alloy::{
+    consensus::{OpDepositReceipt, OpReceiptEnvelope, OpTxType},
+    rpc_types::{L1BlockInfo, OpTransactionReceipt, OpTransactionRequest},
+};
+use op_revm::{OpSpecId, OpTransaction};
 use revm::context::TxEnv;
 
 impl<Spec, Block: BlockEnvironment> TryIntoTxEnv<OpTransaction<TxEnv>, Spec, Block>
@@ -25,6 +37,172 @@ impl<Spec, Block: BlockEnvironment> TryIntoTxEnv<OpTransaction<TxEnv>, Spec, Blo
     }
 }
 
+/// Block an OP receipt belongs to.
+#[derive(Debug, Clone)]
+pub struct OpReceiptBlockInfo {
+    /// Chain agnostic block info.
+    pub block: ReceiptBlockInfo,
+    /// Spec active at the block.
+    pub spec: OpSpecId,
+    /// L1 block info of the block, as loaded from the `L1Block` predeploy by the EVM.
+    pub l1_block_info: op_revm::L1BlockInfo,
+}
+
+impl OpReceiptBlockInfo {
+    /// Returns the L1 fee fields of a transaction.
+    ///
+    /// All fields are empty for deposits, which don't pay L1 fees.
+    fn l1_fee_fields<T: Transaction + Encodable2718>(&self, tx: &T) -> L1BlockInfo {
+        if tx.ty() == OpTxType::Deposit as u8 {
+            return L1BlockInfo::default();
+        }
+
+        let encoded = tx.encoded_2718();
+        let l1 = &self.l1_block_info;
+        let l1_fee = estimate_l1_cost(&encoded, l1, self.spec);
+        let l1_gas_used = estimate_l1_gas_used(&encoded, l1, self.spec);
+        let is_ecotone = self.spec.is_enabled_in(OpSpecId::ECOTONE);
+        let is_isthmus = self.spec.is_enabled_in(OpSpecId::ISTHMUS);
+
+        L1BlockInfo {
+            l1_gas_price: Some(l1.l1_base_fee.saturating_to()),
+            l1_gas_used: Some(l1_gas_used.saturating_to()),
+            l1_fee: Some(l1_fee.saturating_to()),
+            // Before Ecotone the base fee scalar was reported as a decimal.
+            l1_fee_scalar: (!is_ecotone)
+                .then(|| f64::from(l1.l1_base_fee_scalar.saturating_to::<u32>()) / 1_000_000.0),
+            l1_base_fee_scalar: is_ecotone.then(|| l1.l1_base_fee_scalar.saturating_to()),
+            l1_blob_base_fee: l1.l1_blob_base_fee.map(|fee| fee.saturating_to()),
+            l1_blob_base_fee_scalar: l1
+                .l1_blob_base_fee_scalar
+                .map(|scalar| scalar.saturating_to()),
+            operator_fee_scalar: is_isthmus
+                .then(|| l1.operator_fee_scalar.map(|scalar| scalar.saturating_to()))
+                .flatten(),
+            operator_fee_constant: is_isthmus
+                .then(|| l1.operator_fee_constant.map(|constant| constant.saturating_to()))
+                .flatten(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Converts the receipt of an OP transaction into an RPC receipt.
+///
+/// Besides the fields of [`into_rpc_receipt`](crate::rpc::into_rpc_receipt), this fills in the L1
+/// fee fields of regular transactions and keeps the deposit nonce and receipt version of
+/// deposits. Deposits report an effective gas price of zero.
+pub fn into_op_rpc_receipt<T>(
+    tx: &Recovered<T>,
+    receipt: OpReceiptEnvelope,
+    gas_used: u64,
+    block_info: &OpReceiptBlockInfo,
+    tx_index: u64,
+    log_index_offset: u64,
+) -> OpTransactionReceipt
+where
+    T: Transaction + Encodable2718,
+{
+    let tx_type = receipt.tx_type();
+    let status = receipt.status_or_post_state();
+    let cumulative_gas_used = receipt.cumulative_gas_used();
+    let logs_bloom = receipt.bloom();
+    let (deposit_nonce, deposit_receipt_version) = match &receipt {
+        OpReceiptEnvelope::Deposit(receipt) => {
+            (receipt.receipt.deposit_nonce, receipt.receipt.deposit_receipt_version)
+        }
+        _ => (None, None),
+    };
+    let logs = rpc_logs(
+        receipt.into_logs(),
+        &block_info.block,
+        tx.trie_hash(),
+        tx_index,
+        log_index_offset,
+    );
+
+    let inner = Receipt { status, cumulative_gas_used, logs };
+    let inner = match tx_type {
+        OpTxType::Legacy => OpReceiptEnvelope::Legacy(ReceiptWithBloom::new(inner, logs_bloom)),
+        OpTxType::Eip2930 => OpReceiptEnvelope::Eip2930(ReceiptWithBloom::new(inner, logs_bloom)),
+        OpTxType::Eip1559 => OpReceiptEnvelope::Eip1559(ReceiptWithBloom::new(inner, logs_bloom)),
+        OpTxType::Eip7702 => OpReceiptEnvelope::Eip7702(ReceiptWithBloom::new(inner, logs_bloom)),
+        OpTxType::Deposit => OpReceiptEnvelope::Deposit(ReceiptWithBloom::new(
+            OpDepositReceipt { inner, deposit_nonce, deposit_receipt_version },
+            logs_bloom,
+        )),
+    };
+
+    let mut receipt = rpc_receipt(tx, inner, gas_used, &block_info.block, tx_index);
+    if tx_type == OpTxType::Deposit {
+        receipt.effective_gas_price = 0;
+    }
+    OpTransactionReceipt { inner: receipt, l1_block_info: block_info.l1_fee_fields(tx.inner()) }
+}
+
+/// Converts the receipts of all transactions of an OP block into RPC receipts.
+///
+/// Receipts are matched with the transactions in order, the gas used by each transaction and the
+/// log indices are derived from the preceding receipts. Fails if the cumulative gas used of a
+/// receipt is below the one of the preceding receipt.
+pub fn into_op_rpc_receipts<'a, T>(
+    block_info: &OpReceiptBlockInfo,
+    transactions: impl IntoIterator<Item = &'a Recovered<T>>,
+    receipts: impl IntoIterator<Item = OpReceiptEnvelope>,
+) -> Result<Vec<OpTransactionReceipt>, CumulativeGasUsedError>
+where
+    T: Transaction + Encodable2718 + 'a,
+{
+    map_block_receipts(transactions, receipts, |tx, receipt, gas_used, tx_index, offset| {
+        into_op_rpc_receipt(tx, receipt, gas_used, block_info, tx_index, offset)
+    })
+}
+
+/// A [`SuggestFee`] implementation following op-geth's gas price oracle.
+///
+/// OP blocks are usually not full, in which case any tip gets included and the oracle suggests
+/// [`Self::min_priority_fee`]. Once the latest block has less than [`Self::block_space_margin`]
+/// gas left, the median tip of the block increased by [`Self::median_premium_percent`] is
+/// suggested, so that transactions outbid the current ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpFeeOracle {
+    /// Minimum suggested priority fee per gas.
+    pub min_priority_fee: u128,
+    /// Gas that must be left in the latest block for it to be considered not full.
+    pub block_space_margin: u64,
+    /// Premium added to the median tip of full blocks, in percent.
+    pub median_premium_percent: u128,
+}
+
+impl Default for OpFeeOracle {
+    fn default() -> Self {
+        Self {
+            min_priority_fee: 1_000_000,
+            block_space_margin: 3 * 21_000,
+            median_premium_percent: 10,
+        }
+    }
+}
+
+impl SuggestFee for OpFeeOracle {
+    fn suggest_priority_fee<H: BlockHeader>(&self, blocks: &[FeeHistoryBlock<H>]) -> u128 {
+        let Some(latest) = blocks.last() else { return self.min_priority_fee };
+        let header = &latest.header;
+        if header.gas_used() + self.block_space_margin <= header.gas_limit() {
+            return self.min_priority_fee;
+        }
+
+        let mut tips: Vec<_> = latest.transactions.iter().map(|tx| tx.reward).collect();
+        if tips.is_empty() {
+            return self.min_priority_fee;
+        }
+        tips.sort_unstable();
+        let median = tips[tips.len() / 2];
+        let suggestion = median.saturating_mul(100 + self.median_premium_percent) / 100;
+        suggestion.max(self.min_priority_fee)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -44,4 +222,27 @@ mod tests {
         assert_eq!(tx_env.gas_price(), 0);
         assert!(tx_env.enveloped_tx().unwrap().is_empty());
     }
+
+    #[test]
+    fn test_op_fee_oracle() {
+        use crate::rpc::TxGasAndReward;
+        use alloy_consensus::Header;
+
+        let block = |gas_used, tips: &[u128]| {
+            FeeHistoryBlock::new(Header { gas_used, gas_limit: 1_000_000, ..Default::default() })
+                .with_transactions(
+                    tips.iter()
+                        .map(|reward| TxGasAndReward { gas_used: 21_000, reward: *reward })
+                        .collect(),
+                )
+        };
+        let oracle = OpFeeOracle::default();
+
+        assert_eq!(oracle.suggest_priority_fee::<Header>(&[]), 1_000_000);
+        assert_eq!(oracle.suggest_priority_fee(&[block(500_000, &[50_000_000])]), 1_000_000);
+        assert_eq!(
+            oracle.suggest_priority_fee(&[block(990_000, &[10_000_000, 20_000_000, 30_000_000])]),
+            22_000_000
+        );
+    }
 }
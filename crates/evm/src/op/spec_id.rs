@@ -1,5 +1,8 @@
+use crate::eth::spec_id::{collapse_schedule, Activation};
+use alloc::vec::Vec;
 use alloy_consensus::BlockHeader;
-use alloy_op_hardforks::OpHardforks;
+use alloy_hardforks::{EthereumHardfork, EthereumHardforks, ForkCondition};
+use alloy_op_hardforks::{OpChainHardforks, OpHardfork, OpHardforks};
 use op_revm::OpSpecId;
 
 /// Map the latest active hardfork at the given header to a revm [`OpSpecId`].
@@ -14,7 +17,22 @@ pub fn spec(chain_spec: impl OpHardforks, header: impl BlockHeader) -> OpSpecId
 /// This is only intended to be used after the Bedrock, when hardforks are activated by
 /// timestamp.
 pub fn spec_by_timestamp_after_bedrock(chain_spec: impl OpHardforks, timestamp: u64) -> OpSpecId {
-    if chain_spec.is_interop_active_at_timestamp(timestamp) {
+    spec_by_timestamp_after_bedrock_with_override(chain_spec, timestamp, |_| None)
+}
+
+/// Returns the revm [`OpSpecId`] at the given timestamp, taking `spec_override` into account.
+///
+/// The spec is derived from the canonical hardforks of `chain_spec`, same as
+/// [`spec_by_timestamp_after_bedrock`]. If `spec_override` returns a newer spec for the timestamp,
+/// that spec is used instead, so overrides can only upgrade the canonical spec.
+///
+/// See [`CustomOpHardforks::spec_override`] for a ready-made override.
+pub fn spec_by_timestamp_after_bedrock_with_override(
+    chain_spec: impl OpHardforks,
+    timestamp: u64,
+    spec_override: impl FnOnce(u64) -> Option<OpSpecId>,
+) -> OpSpecId {
+    let canonical = if chain_spec.is_interop_active_at_timestamp(timestamp) {
         OpSpecId::INTEROP
     } else if chain_spec.is_jovian_active_at_timestamp(timestamp) {
         OpSpecId::JOVIAN
@@ -34,6 +52,151 @@ pub fn spec_by_timestamp_after_bedrock(chain_spec: impl OpHardforks, timestamp:
         OpSpecId::REGOLITH
     } else {
         OpSpecId::BEDROCK
+    };
+
+    spec_override(timestamp).filter(|spec| *spec as u8 > canonical as u8).unwrap_or(canonical)
+}
+
+/// Hardforks that change the EVM spec, in activation order.
+const SPEC_HARDFORKS: [(OpHardfork, OpSpecId); 10] = [
+    (OpHardfork::Bedrock, OpSpecId::BEDROCK),
+    (OpHardfork::Regolith, OpSpecId::REGOLITH),
+    (OpHardfork::Canyon, OpSpecId::CANYON),
+    (OpHardfork::Ecotone, OpSpecId::ECOTONE),
+    (OpHardfork::Fjord, OpSpecId::FJORD),
+    (OpHardfork::Granite, OpSpecId::GRANITE),
+    (OpHardfork::Holocene, OpSpecId::HOLOCENE),
+    (OpHardfork::Isthmus, OpSpecId::ISTHMUS),
+    (OpHardfork::Jovian, OpSpecId::JOVIAN),
+    (OpHardfork::Interop, OpSpecId::INTEROP),
+];
+
+/// Returns the activation boundaries of the revm [`OpSpecId`]s of `chain_spec`, starting at
+/// Bedrock.
+///
+/// Each item is a spec together with the block number or timestamp from which it is active, until
+/// the activation of the next item. Forks activating at the same boundary are collapsed into the
+/// latest one, and forks that never activate are skipped.
+pub fn fork_schedule(chain_spec: impl OpHardforks) -> impl Iterator<Item = (OpSpecId, Activation)> {
+    collapse_schedule(SPEC_HARDFORKS.into_iter().filter_map(move |(fork, spec)| {
+        Activation::from_fork_condition(chain_spec.op_fork_activation(fork))
+            .map(|activation| (spec, activation))
+    }))
+}
+
+/// Creates [`OpChainHardforks`] from the `config` section of a genesis file.
+///
+/// Reads the Bedrock block and the timestamps of the later hardforks from the OP Stack genesis
+/// fields, e.g. `bedrockBlock` and `regolithTime`. Fields with invalid values are ignored.
+#[cfg(feature = "genesis")]
+pub fn hardforks_from_genesis(genesis: &alloy_genesis::Genesis) -> OpChainHardforks {
+    let fields = &genesis.config.extra_fields;
+    let field = |name: &str| fields.get_deserialized::<u64>(name).and_then(Result::ok);
+
+    let mut hardforks = Vec::new();
+    if let Some(block) = field("bedrockBlock") {
+        hardforks.push((OpHardfork::Bedrock, ForkCondition::Block(block)));
+    }
+    for (fork, name) in [
+        (OpHardfork::Regolith, "regolithTime"),
+        (OpHardfork::Canyon, "canyonTime"),
+        (OpHardfork::Ecotone, "ecotoneTime"),
+        (OpHardfork::Fjord, "fjordTime"),
+        (OpHardfork::Granite, "graniteTime"),
+        (OpHardfork::Holocene, "holoceneTime"),
+        (OpHardfork::Isthmus, "isthmusTime"),
+        (OpHardfork::Jovian, "jovianTime"),
+        (OpHardfork::Interop, "interopTime"),
+    ] {
+        if let Some(timestamp) = field(name) {
+            hardforks.push((fork, ForkCondition::Timestamp(timestamp)));
+        }
+    }
+
+    OpChainHardforks::new(hardforks)
+}
+
+/// Wrapper around an [`OpHardforks`] implementation for chains that deviate from the canonical
+/// hardfork schedule.
+///
+/// Activations of canonical hardforks can be overridden with [`Self::with_fork`], which is visible
+/// to everything consuming the wrapper as [`OpHardforks`]. Chain-specific forks that activate in
+/// between canonical ones are registered with [`Self::with_custom_fork`] and map to the
+/// [`OpSpecId`] the chain executes with once they are active. They are picked up by
+/// [`Self::spec_at_timestamp`], or by passing [`Self::spec_override`] to
+/// [`spec_by_timestamp_after_bedrock_with_override`].
+#[derive(Debug, Clone)]
+pub struct CustomOpHardforks<C> {
+    inner: C,
+    overrides: Vec<(OpHardfork, ForkCondition)>,
+    custom_forks: Vec<(ForkCondition, OpSpecId)>,
+}
+
+impl<C> CustomOpHardforks<C> {
+    /// Creates a new wrapper without any overrides.
+    pub const fn new(inner: C) -> Self {
+        Self { inner, overrides: Vec::new(), custom_forks: Vec::new() }
+    }
+
+    /// Overrides the activation condition of a canonical hardfork.
+    pub fn with_fork(mut self, fork: OpHardfork, condition: ForkCondition) -> Self {
+        self.overrides.retain(|(existing, _)| *existing != fork);
+        self.overrides.push((fork, condition));
+        self
+    }
+
+    /// Adds a chain-specific fork that makes the chain execute with `spec` once `condition` is
+    /// met.
+    pub fn with_custom_fork(mut self, condition: ForkCondition, spec: OpSpecId) -> Self {
+        self.custom_forks.push((condition, spec));
+        self
+    }
+
+    /// Returns the wrapped hardforks.
+    pub const fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Consumes the wrapper and returns the wrapped hardforks.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+
+    /// Returns the latest [`OpSpecId`] of the custom forks active at the given timestamp, if any.
+    pub fn spec_override(&self, timestamp: u64) -> Option<OpSpecId> {
+        self.custom_forks
+            .iter()
+            .filter(|(condition, _)| condition.active_at_timestamp(timestamp))
+            .map(|(_, spec)| *spec)
+            .max_by_key(|spec| *spec as u8)
+    }
+}
+
+impl<C: OpHardforks> CustomOpHardforks<C> {
+    /// Returns the [`OpSpecId`] at the given timestamp, taking custom forks into account.
+    ///
+    /// A custom fork only takes effect if it maps to a newer spec than the canonical hardforks
+    /// active at the timestamp.
+    pub fn spec_at_timestamp(&self, timestamp: u64) -> OpSpecId {
+        spec_by_timestamp_after_bedrock_with_override(self, timestamp, |timestamp| {
+            self.spec_override(timestamp)
+        })
+    }
+}
+
+impl<C: EthereumHardforks> EthereumHardforks for CustomOpHardforks<C> {
+    fn ethereum_fork_activation(&self, fork: EthereumHardfork) -> ForkCondition {
+        self.inner.ethereum_fork_activation(fork)
+    }
+}
+
+impl<C: OpHardforks> OpHardforks for CustomOpHardforks<C> {
+    fn op_fork_activation(&self, fork: OpHardfork) -> ForkCondition {
+        self.overrides
+            .iter()
+            .find(|(existing, _)| *existing == fork)
+            .map(|(_, condition)| *condition)
+            .unwrap_or_else(|| self.inner.op_fork_activation(fork))
     }
 }
 
@@ -153,4 +316,79 @@ mod tests {
 
         assert_eq!(actual_spec, expected_spec);
     }
+
+    #[test]
+    fn test_custom_op_hardforks_overrides_activation() {
+        let fork = CustomOpHardforks::new(OpChainHardforks::op_mainnet())
+            .with_fork(OpHardfork::Jovian, ForkCondition::Timestamp(OP_MAINNET_ISTHMUS_TIMESTAMP));
+
+        assert_eq!(
+            spec_by_timestamp_after_bedrock(&fork, OP_MAINNET_ISTHMUS_TIMESTAMP),
+            OpSpecId::JOVIAN
+        );
+        assert_eq!(
+            spec_by_timestamp_after_bedrock(&fork, OP_MAINNET_HOLOCENE_TIMESTAMP),
+            OpSpecId::HOLOCENE
+        );
+    }
+
+    #[test]
+    fn test_custom_op_hardforks_custom_fork() {
+        let feature_flag = OP_MAINNET_HOLOCENE_TIMESTAMP + 1;
+        let fork = CustomOpHardforks::new(OpChainHardforks::op_mainnet())
+            .with_custom_fork(ForkCondition::Timestamp(feature_flag), OpSpecId::ISTHMUS);
+
+        assert_eq!(fork.spec_at_timestamp(OP_MAINNET_HOLOCENE_TIMESTAMP), OpSpecId::HOLOCENE);
+        assert_eq!(fork.spec_at_timestamp(feature_flag), OpSpecId::ISTHMUS);
+        // Canonical forks newer than the custom fork take precedence.
+        assert_eq!(fork.spec_at_timestamp(OP_MAINNET_JOVIAN_TIMESTAMP), OpSpecId::JOVIAN);
+        let with_override = |timestamp| {
+            spec_by_timestamp_after_bedrock_with_override(&fork, timestamp, |timestamp| {
+                fork.spec_override(timestamp)
+            })
+        };
+        assert_eq!(with_override(feature_flag), OpSpecId::ISTHMUS);
+        assert_eq!(with_override(OP_MAINNET_HOLOCENE_TIMESTAMP), OpSpecId::HOLOCENE);
+        assert_eq!(with_override(OP_MAINNET_JOVIAN_TIMESTAMP), OpSpecId::JOVIAN);
+    }
+
+    #[test]
+    fn test_fork_schedule() {
+        let schedule = fork_schedule(OpChainHardforks::op_mainnet()).collect::<Vec<_>>();
+
+        assert_eq!(schedule.first().map(|(spec, _)| *spec), Some(OpSpecId::BEDROCK));
+        assert!(schedule
+            .contains(&(OpSpecId::ECOTONE, Activation::Timestamp(OP_MAINNET_ECOTONE_TIMESTAMP))));
+        for (expected, activation) in schedule {
+            if let Activation::Timestamp(timestamp) = activation {
+                assert_eq!(
+                    spec_by_timestamp_after_bedrock(OpChainHardforks::op_mainnet(), timestamp),
+                    expected
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "genesis")]
+    #[test]
+    fn test_hardforks_from_genesis() {
+        let genesis: alloy_genesis::Genesis = serde_json::from_str(
+            r#"{
+                "config": {
+                    "chainId": 901,
+                    "bedrockBlock": 0,
+                    "regolithTime": 0,
+                    "canyonTime": 0,
+                    "ecotoneTime": 10,
+                    "fjordTime": 20
+                }
+            }"#,
+        )
+        .unwrap();
+        let fork = hardforks_from_genesis(&genesis);
+
+        assert_eq!(spec_by_timestamp_after_bedrock(&fork, 0), OpSpecId::CANYON);
+        assert_eq!(spec_by_timestamp_after_bedrock(&fork, 10), OpSpecId::ECOTONE);
+        assert_eq!(spec_by_timestamp_after_bedrock(&fork, u64::MAX), OpSpecId::FJORD);
+    }
 }
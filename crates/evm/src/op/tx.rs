@@ -53,8 +53,10 @@ impl FromTxWithEncoded<OpTxEnvelope> for TxEnv {
 
 impl FromRecoveredTx<OpTxEnvelope> for OpTransaction<TxEnv> {
     fn from_recovered_tx(tx: &OpTxEnvelope, sender: Address) -> Self {
-        let encoded = tx.encoded_2718();
-        Self::from_encoded_tx(tx, sender, encoded.into())
+        match tx {
+            OpTxEnvelope::Deposit(tx) => Self::from_recovered_tx(tx.inner(), sender),
+            _ => Self::from_encoded_tx(tx, sender, tx.encoded_2718().into()),
+        }
     }
 }
 
@@ -197,8 +199,9 @@ impl FromTxWithEncoded<TxEip7702> for OpTransaction<TxEnv> {
 
 impl FromRecoveredTx<TxDeposit> for OpTransaction<TxEnv> {
     fn from_recovered_tx(tx: &TxDeposit, sender: Address) -> Self {
-        let encoded = tx.encoded_2718();
-        Self::from_encoded_tx(tx, sender, encoded.into())
+        // Deposits don't pay an L1 fee and don't count towards the DA footprint, so their encoding
+        // is never read. All other transactions need it for the L1 fee since Bedrock.
+        Self::from_encoded_tx(tx, sender, Bytes::new())
     }
 }
 
@@ -213,3 +216,24 @@ impl FromTxWithEncoded<TxDeposit> for OpTransaction<TxEnv> {
         Self { base, enveloped_tx: Some(encoded), deposit }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Sealed, Signature};
+
+    #[test]
+    fn test_deposit_skips_encoding() {
+        let deposit = OpTxEnvelope::Deposit(Sealed::new(TxDeposit::default()));
+        let tx = OpTransaction::<TxEnv>::from_recovered_tx(&deposit, Address::ZERO);
+        assert_eq!(tx.enveloped_tx, Some(Bytes::new()));
+        assert_eq!(tx.deposit.mint, Some(0));
+
+        let legacy = OpTxEnvelope::Legacy(Signed::new_unhashed(
+            TxLegacy::default(),
+            Signature::test_signature(),
+        ));
+        let tx = OpTransaction::<TxEnv>::from_recovered_tx(&legacy, Address::ZERO);
+        assert_eq!(tx.enveloped_tx, Some(legacy.encoded_2718().into()));
+    }
+}
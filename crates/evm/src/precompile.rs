@@ -0,0 +1,209 @@
+//! Helpers for writing stateful precompiles.
+//!
+//! A [`StatefulPrecompile`] is a native contract that can read and modify state through
+//! [`EvmInternals`](crate::EvmInternals), e.g. a staking or oracle contract of a custom chain. The
+//! [`sol_dispatch!`](crate::sol_dispatch) macro routes calls to handlers by their solidity
+//! selector, taking care of ABI decoding and encoding:
+//!
+//! ```ignore
+//! sol! {
+//!     interface ICounter {
+//!         function get() external view returns (uint256);
+//!         function increment(uint256 by) external;
+//!     }
+//! }
+//!
+//! #[derive(Debug)]
+//! struct Counter;
+//!
+//! impl StatefulPrecompile for Counter {
+//!     fn precompile_id(&self) -> PrecompileId {
+//!         PrecompileId::Custom("counter".into())
+//!     }
+//!
+//!     fn call(&self, mut input: PrecompileInput<'_>) -> PrecompileResult {
+//!         sol_dispatch!(input;
+//!             ICounter::getCall = 2_100 => |_, input| {
+//!                 Ok(read_counter(input)?)
+//!             },
+//!             ICounter::incrementCall = 22_100 => |call, input| {
+//!                 write_counter(input, read_counter(input)? + call.by)?;
+//!                 Ok(ICounter::incrementReturn {})
+//!             },
+//!         )
+//!     }
+//! }
+//!
+//! precompiles.apply_precompile(&COUNTER_ADDRESS, |_| Some(Counter.into_dyn_precompile()));
+//! ```
+
+use crate::precompiles::{DynPrecompile, PrecompileInput};
+use alloc::format;
+use alloy_primitives::Bytes;
+use revm::precompile::{PrecompileError, PrecompileId, PrecompileOutput, PrecompileResult};
+
+#[doc(hidden)]
+pub use alloy_sol_types::SolCall;
+
+/// A precompile with access to the EVM state.
+///
+/// Unlike pure precompiles, the output of a stateful precompile depends on more than its input,
+/// so its results are never cached.
+pub trait StatefulPrecompile: Send + Sync + 'static {
+    /// Returns the identifier of the precompile.
+    fn precompile_id(&self) -> PrecompileId;
+
+    /// Executes the precompile.
+    ///
+    /// State can be accessed through [`PrecompileInput::internals`].
+    fn call(&self, input: PrecompileInput<'_>) -> PrecompileResult;
+
+    /// Converts the precompile into a [`DynPrecompile`] that can be installed into a
+    /// [`PrecompilesMap`](crate::precompiles::PrecompilesMap).
+    fn into_dyn_precompile(self) -> DynPrecompile
+    where
+        Self: Sized,
+    {
+        DynPrecompile::new_stateful(self.precompile_id(), move |input| self.call(input))
+    }
+}
+
+/// Returns the 4-byte function selector of the call data, if present.
+pub fn selector(data: &[u8]) -> Option<[u8; 4]> {
+    data.get(..4).map(|selector| selector.try_into().expect("slice has 4 bytes"))
+}
+
+/// Charges `gas`, decodes the call data as `C` and invokes `handler` with it.
+///
+/// The return value of the handler is ABI-encoded into the output of the precompile. This is the
+/// building block of [`sol_dispatch!`](crate::sol_dispatch).
+pub fn call_handler<C, F>(input: &mut PrecompileInput<'_>, gas: u64, handler: F) -> PrecompileResult
+where
+    C: SolCall,
+    F: FnOnce(C, &mut PrecompileInput<'_>) -> Result<C::Return, PrecompileError>,
+{
+    if gas > input.gas {
+        return Err(PrecompileError::OutOfGas);
+    }
+    let call = C::abi_decode(input.data).map_err(|err| {
+        PrecompileError::Other(format!("failed to decode {}: {err}", C::SIGNATURE).into())
+    })?;
+    let ret = handler(call, input)?;
+    Ok(PrecompileOutput::new(gas, Bytes::from(C::abi_encode_returns(&ret))))
+}
+
+/// Returns the error for call data not matching any dispatched selector.
+pub fn unknown_selector(data: &[u8]) -> PrecompileError {
+    match selector(data) {
+        Some(selector) => PrecompileError::Other(
+            format!("unknown selector 0x{}", alloy_primitives::hex::encode(selector)).into(),
+        ),
+        None => PrecompileError::Other("missing selector".into()),
+    }
+}
+
+/// Dispatches a precompile call to a handler by its solidity function selector.
+///
+/// Each arm names a [`SolCall`] type, the gas charged for the call, and a handler receiving the
+/// decoded call and the [`PrecompileInput`]. The handler returns the call's return type, which is
+/// ABI-encoded into the output. Calls with an unknown selector fail.
+///
+/// See the [module documentation](crate::precompile) for an example.
+#[macro_export]
+macro_rules! sol_dispatch {
+    ($input:expr; $($call:ty = $gas:expr => $handler:expr),+ $(,)?) => {{
+        let input: &mut $crate::precompiles::PrecompileInput<'_> = &mut $input;
+        let selector = $crate::precompile::selector(input.data);
+        $(
+            if selector == Some(<$call as $crate::precompile::SolCall>::SELECTOR) {
+                $crate::precompile::call_handler::<$call, _>(input, $gas, $handler)
+            } else
+        )+
+        {
+            Err($crate::precompile::unknown_selector(input.data))
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{eth::EthEvmContext, EvmInternals};
+    use alloy_primitives::{address, Address, U256};
+    use alloy_sol_types::sol;
+    use revm::database::EmptyDB;
+
+    sol! {
+        interface ICounter {
+            function get() external view returns (uint256);
+            function increment(uint256 by) external;
+        }
+    }
+
+    const COUNTER: Address = address!("0x0000000000000000000000000000000000001000");
+
+    #[derive(Debug)]
+    struct Counter;
+
+    fn read(input: &mut PrecompileInput<'_>) -> Result<U256, PrecompileError> {
+        input
+            .internals
+            .sload(COUNTER, U256::ZERO)
+            .map(|slot| slot.data)
+            .map_err(|err| PrecompileError::Other(format!("{err}").into()))
+    }
+
+    impl StatefulPrecompile for Counter {
+        fn precompile_id(&self) -> PrecompileId {
+            PrecompileId::Custom("counter".into())
+        }
+
+        fn call(&self, mut input: PrecompileInput<'_>) -> PrecompileResult {
+            sol_dispatch!(input;
+                ICounter::getCall = 100 => |_, input| read(input),
+                ICounter::incrementCall = 200 => |call, input| {
+                    let value = read(input)? + call.by;
+                    input
+                        .internals
+                        .sstore(COUNTER, U256::ZERO, value)
+                        .map_err(|err| PrecompileError::Other(format!("{err}").into()))?;
+                    Ok(ICounter::incrementReturn {})
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn test_sol_dispatch() {
+        let precompile = Counter.into_dyn_precompile();
+        let mut ctx = EthEvmContext::new(EmptyDB::default(), Default::default());
+        let mut call = |data: &[u8], gas| {
+            crate::precompiles::Precompile::call(
+                &precompile,
+                PrecompileInput {
+                    data,
+                    gas,
+                    caller: Address::ZERO,
+                    value: U256::ZERO,
+                    is_static: false,
+                    target_address: COUNTER,
+                    bytecode_address: COUNTER,
+                    internals: EvmInternals::from_context(&mut ctx),
+                },
+            )
+        };
+
+        let output =
+            call(&ICounter::incrementCall { by: U256::from(3) }.abi_encode(), 1_000).unwrap();
+        assert_eq!(output.gas_used, 200);
+
+        let output = call(&ICounter::getCall {}.abi_encode(), 1_000).unwrap();
+        assert_eq!(ICounter::getCall::abi_decode_returns(&output.bytes).unwrap(), U256::from(3));
+
+        assert!(matches!(
+            call(&ICounter::getCall {}.abi_encode(), 50),
+            Err(PrecompileError::OutOfGas)
+        ));
+        assert!(call(&[0xde, 0xad, 0xbe, 0xef], 1_000).is_err());
+    }
+}
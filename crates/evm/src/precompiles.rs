@@ -1,8 +1,16 @@
 //! Helpers for dealing with Precompiles.
 
 use crate::{Database, EvmInternals};
+#[cfg(any(feature = "p256", feature = "kzg"))]
+use crate::{Evm, EvmEnv, EvmFactory};
 use alloc::{borrow::Cow, boxed::Box, string::String, sync::Arc, vec::Vec};
 use alloy_consensus::transaction::Either;
+#[cfg(feature = "kzg")]
+use alloy_eips::eip4844::{
+    env_settings::EnvKzgSettings, BlobTransactionSidecar, BlobTransactionValidationError,
+};
+#[cfg(feature = "kzg")]
+use alloy_primitives::B256;
 use alloy_primitives::{
     map::{AddressMap, AddressSet},
     Address, Bytes, U256,
@@ -266,6 +274,30 @@ impl PrecompilesMap {
         self
     }
 
+    /// Replaces the point evaluation precompile with one verifying proofs with the given KZG
+    /// settings.
+    ///
+    /// Does nothing if the point evaluation precompile is not installed, i.e. before Cancun.
+    #[cfg(feature = "kzg")]
+    pub fn set_kzg_settings(&mut self, settings: EnvKzgSettings) {
+        let address = revm::precompile::u64_to_address(0x0a);
+        if self.get(&address).is_some() {
+            self.apply_precompile(&address, |_| Some(kzg_point_evaluation(settings)));
+        }
+    }
+
+    /// Installs the RIP-7212 `P256VERIFY` precompile at [`P256VERIFY_ADDRESS`], unless a
+    /// precompile is already installed there.
+    ///
+    /// Since Osaka, `P256VERIFY` is part of the Ethereum precompiles with EIP-7951 pricing, which
+    /// is kept as is.
+    #[cfg(feature = "p256")]
+    pub fn install_p256verify(&mut self) {
+        self.apply_precompile(&P256VERIFY_ADDRESS, |existing| {
+            existing.or_else(|| Some(p256verify()))
+        });
+    }
+
     /// Moves precompiles from source addresses to destination addresses.
     ///
     /// For each `(source, dest)` pair in the iterator:
@@ -368,9 +400,9 @@ impl PrecompilesMap {
     ///
     /// - **Priority**: Static precompiles take precedence. The lookup function is only called if
     ///   the address is not found in the main precompile map.
-    /// - **Gas accounting**: Addresses resolved through this lookup are always treated as cold,
-    ///   meaning they incur cold access costs even on repeated calls within the same transaction.
-    ///   See also [`PrecompileProvider::warm_addresses`].
+    /// - **Gas accounting**: Addresses resolved through this lookup are treated as cold on first
+    ///   access, unless they are returned by [`PrecompileLookup::warm_addresses`]. See also
+    ///   [`PrecompileProvider::warm_addresses`] and [`AddressRangePrecompile`].
     /// - **Performance**: The lookup function is called on every precompile check for
     ///   non-registered addresses, so it should be efficient.
     ///
@@ -606,7 +638,8 @@ where
     }
 
     fn warm_addresses(&self) -> Box<impl Iterator<Item = Address>> {
-        Box::new(self.addresses().copied())
+        let lookup = self.lookup.iter().flat_map(|lookup| lookup.warm_addresses());
+        Box::new(self.addresses().copied().chain(lookup))
     }
 
     fn contains(&self, address: &Address) -> bool {
@@ -614,6 +647,175 @@ where
     }
 }
 
+/// Canonical address of the `P256VERIFY` precompile, as specified in RIP-7212.
+#[cfg(feature = "p256")]
+pub const P256VERIFY_ADDRESS: Address = revm::precompile::u64_to_address(0x100);
+
+/// Returns the RIP-7212 `P256VERIFY` precompile, verifying secp256r1 signatures for a base gas
+/// cost of 3450.
+#[cfg(feature = "p256")]
+pub fn p256verify() -> DynPrecompile {
+    let f: PrecompileFn = revm::precompile::secp256r1::p256_verify;
+    (PrecompileId::P256Verify, f).into()
+}
+
+/// An [`EvmFactory`] installing the RIP-7212 `P256VERIFY` precompile into every EVM created by the
+/// inner factory.
+///
+/// Works with any factory using [`PrecompilesMap`], e.g. [`EthEvmFactory`](crate::EthEvmFactory)
+/// or the OP factory. See [`PrecompilesMap::install_p256verify`].
+#[cfg(feature = "p256")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct P256VerifyEvmFactory<F>(pub F);
+
+#[cfg(feature = "p256")]
+impl<F> EvmFactory for P256VerifyEvmFactory<F>
+where
+    F: EvmFactory<Precompiles = PrecompilesMap>,
+{
+    type Evm<DB: Database, I: revm::Inspector<Self::Context<DB>>> = F::Evm<DB, I>;
+    type Context<DB: Database> = F::Context<DB>;
+    type Tx = F::Tx;
+    type Error<DBError: core::error::Error + Send + Sync + 'static> = F::Error<DBError>;
+    type HaltReason = F::HaltReason;
+    type Spec = F::Spec;
+    type BlockEnv = F::BlockEnv;
+    type Precompiles = PrecompilesMap;
+
+    fn create_evm<DB: Database>(
+        &self,
+        db: DB,
+        evm_env: EvmEnv<Self::Spec, Self::BlockEnv>,
+    ) -> Self::Evm<DB, revm::inspector::NoOpInspector> {
+        let mut evm = self.0.create_evm(db, evm_env);
+        evm.precompiles_mut().install_p256verify();
+        evm
+    }
+
+    fn create_evm_with_inspector<DB: Database, I: revm::Inspector<Self::Context<DB>>>(
+        &self,
+        db: DB,
+        input: EvmEnv<Self::Spec, Self::BlockEnv>,
+        inspector: I,
+    ) -> Self::Evm<DB, I> {
+        let mut evm = self.0.create_evm_with_inspector(db, input, inspector);
+        evm.precompiles_mut().install_p256verify();
+        evm
+    }
+}
+
+/// Returns the EIP-4844 point evaluation precompile, verifying proofs with the given KZG
+/// settings instead of the trusted setup built into revm.
+///
+/// [`EnvKzgSettings::Default`] loads the Ethereum trusted setup lazily on first use.
+#[cfg(feature = "kzg")]
+pub fn kzg_point_evaluation(settings: EnvKzgSettings) -> DynPrecompile {
+    use revm::precompile::kzg_point_evaluation::{kzg_to_versioned_hash, GAS_COST, RETURN_VALUE};
+
+    let f = move |input: PrecompileInput<'_>| {
+        if input.gas < GAS_COST {
+            return Err(PrecompileError::OutOfGas);
+        }
+        let data = input.data;
+        if data.len() != 192 {
+            return Err(PrecompileError::BlobInvalidInputLength);
+        }
+
+        let commitment: [u8; 48] = data[96..144].try_into().expect("slice has 48 bytes");
+        if kzg_to_versioned_hash(&commitment) != data[..32] {
+            return Err(PrecompileError::BlobMismatchedVersion);
+        }
+
+        let z: [u8; 32] = data[32..64].try_into().expect("slice has 32 bytes");
+        let y: [u8; 32] = data[64..96].try_into().expect("slice has 32 bytes");
+        let proof: [u8; 48] = data[144..192].try_into().expect("slice has 48 bytes");
+        let valid = settings
+            .get()
+            .verify_kzg_proof(&commitment.into(), &z.into(), &y.into(), &proof.into())
+            .unwrap_or(false);
+        if !valid {
+            return Err(PrecompileError::BlobVerifyKzgProofFailed);
+        }
+
+        Ok(PrecompileOutput::new(GAS_COST, Bytes::from_static(RETURN_VALUE)))
+    };
+    (PrecompileId::KzgPointEvaluation, f).into()
+}
+
+/// Validates the blobs of a sidecar against its commitments and proofs with the given KZG
+/// settings, and the commitments against the versioned hashes of the transaction.
+///
+/// This is the check transaction pools perform on blob transactions before accepting them, with
+/// the same settings as [`kzg_point_evaluation`].
+#[cfg(feature = "kzg")]
+pub fn validate_blob_sidecar(
+    sidecar: &BlobTransactionSidecar,
+    versioned_hashes: &[B256],
+    settings: &EnvKzgSettings,
+) -> Result<(), BlobTransactionValidationError> {
+    sidecar.validate(versioned_hashes, settings.get())
+}
+
+/// An [`EvmFactory`] verifying KZG proofs of the point evaluation precompile with custom
+/// [`EnvKzgSettings`].
+///
+/// See [`PrecompilesMap::set_kzg_settings`].
+#[cfg(feature = "kzg")]
+#[derive(Debug, Clone, Default)]
+pub struct KzgEvmFactory<F> {
+    inner: F,
+    settings: EnvKzgSettings,
+}
+
+#[cfg(feature = "kzg")]
+impl<F> KzgEvmFactory<F> {
+    /// Creates a new factory wrapping `inner`.
+    pub const fn new(inner: F, settings: EnvKzgSettings) -> Self {
+        Self { inner, settings }
+    }
+
+    /// Returns the KZG settings.
+    pub const fn settings(&self) -> &EnvKzgSettings {
+        &self.settings
+    }
+}
+
+#[cfg(feature = "kzg")]
+impl<F> EvmFactory for KzgEvmFactory<F>
+where
+    F: EvmFactory<Precompiles = PrecompilesMap>,
+{
+    type Evm<DB: Database, I: revm::Inspector<Self::Context<DB>>> = F::Evm<DB, I>;
+    type Context<DB: Database> = F::Context<DB>;
+    type Tx = F::Tx;
+    type Error<DBError: core::error::Error + Send + Sync + 'static> = F::Error<DBError>;
+    type HaltReason = F::HaltReason;
+    type Spec = F::Spec;
+    type BlockEnv = F::BlockEnv;
+    type Precompiles = PrecompilesMap;
+
+    fn create_evm<DB: Database>(
+        &self,
+        db: DB,
+        evm_env: EvmEnv<Self::Spec, Self::BlockEnv>,
+    ) -> Self::Evm<DB, revm::inspector::NoOpInspector> {
+        let mut evm = self.inner.create_evm(db, evm_env);
+        evm.precompiles_mut().set_kzg_settings(self.settings.clone());
+        evm
+    }
+
+    fn create_evm_with_inspector<DB: Database, I: revm::Inspector<Self::Context<DB>>>(
+        &self,
+        db: DB,
+        input: EvmEnv<Self::Spec, Self::BlockEnv>,
+        inspector: I,
+    ) -> Self::Evm<DB, I> {
+        let mut evm = self.inner.create_evm_with_inspector(db, input, inspector);
+        evm.precompiles_mut().set_kzg_settings(self.settings.clone());
+        evm
+    }
+}
+
 /// A mapping of precompile contracts that can be either static (builtin) or dynamic.
 ///
 /// This is an optimization that allows us to keep using the static precompiles
@@ -948,6 +1150,14 @@ pub trait PrecompileLookup: Send + Sync {
     /// Returns `Some(precompile)` if a precompile exists at the address,
     /// or `None` if no precompile is found.
     fn lookup(&self, address: &Address) -> Option<DynPrecompile>;
+
+    /// Returns the addresses resolved by this lookup that should be warm at the start of each
+    /// transaction, like regular precompiles.
+    ///
+    /// Addresses not returned here are treated as cold on first access. Defaults to none.
+    fn warm_addresses(&self) -> Box<dyn Iterator<Item = Address> + '_> {
+        Box::new(core::iter::empty())
+    }
 }
 
 /// Implement PrecompileLookup for closure types
@@ -960,6 +1170,74 @@ where
     }
 }
 
+/// A [`PrecompileLookup`] serving a whole range of 256 addresses with a single precompile.
+///
+/// The range consists of all addresses sharing the given 19-byte prefix, e.g.
+/// `0x0100000000000000000000000000000000000000..=0x01000000000000000000000000000000000000ff`. The
+/// precompile can sub-dispatch on [`AddressRangePrecompile::index`] of
+/// [`PrecompileInput::target_address`].
+///
+/// All addresses of the range are warm at the start of a transaction, so calls into the range cost
+/// the same as calls to regular precompiles.
+///
+/// ```ignore
+/// let bank = AddressRangePrecompile::new(prefix, DynPrecompile::new_stateful(id, |input| {
+///     match AddressRangePrecompile::index(&input.target_address) {
+///         0 => oracle(input),
+///         1 => staking(input),
+///         _ => Err(PrecompileError::Other("unknown precompile".into())),
+///     }
+/// }));
+/// precompiles.set_precompile_lookup(bank);
+/// ```
+#[derive(Clone, Debug)]
+pub struct AddressRangePrecompile {
+    prefix: [u8; 19],
+    precompile: DynPrecompile,
+}
+
+impl AddressRangePrecompile {
+    /// Creates a new lookup serving all addresses starting with `prefix` with `precompile`.
+    pub const fn new(prefix: [u8; 19], precompile: DynPrecompile) -> Self {
+        Self { prefix, precompile }
+    }
+
+    /// Returns the prefix shared by the addresses of the range.
+    pub const fn prefix(&self) -> &[u8; 19] {
+        &self.prefix
+    }
+
+    /// Returns `true` if `address` is part of the range.
+    pub fn contains(&self, address: &Address) -> bool {
+        address[..19] == self.prefix
+    }
+
+    /// Returns the position of `address` within its range, i.e. its last byte.
+    pub const fn index(address: &Address) -> u8 {
+        address.0 .0[19]
+    }
+
+    /// Returns an iterator over all addresses of the range.
+    pub fn addresses(&self) -> impl Iterator<Item = Address> + '_ {
+        (0..=u8::MAX).map(|index| {
+            let mut address = Address::ZERO;
+            address[..19].copy_from_slice(&self.prefix);
+            address[19] = index;
+            address
+        })
+    }
+}
+
+impl PrecompileLookup for AddressRangePrecompile {
+    fn lookup(&self, address: &Address) -> Option<DynPrecompile> {
+        self.contains(address).then(|| self.precompile.clone())
+    }
+
+    fn warm_addresses(&self) -> Box<dyn Iterator<Item = Address> + '_> {
+        Box::new(self.addresses())
+    }
+}
+
 /// Error that can occur when moving precompiles.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum MovePrecompileError {
@@ -991,6 +1269,42 @@ mod tests {
         primitives::hardfork::SpecId,
     };
 
+    #[cfg(feature = "p256")]
+    #[test]
+    fn test_p256verify_factory() {
+        use crate::{EthEvmFactory, Evm, EvmEnv, EvmFactory};
+
+        let env = |spec| {
+            let mut env = EvmEnv::default();
+            env.cfg_env.spec = spec;
+            env
+        };
+
+        let mut evm = EthEvmFactory.create_evm(EmptyDB::default(), env(SpecId::PRAGUE));
+        assert!(evm.precompiles_mut().get(&P256VERIFY_ADDRESS).is_none());
+
+        let factory = P256VerifyEvmFactory(EthEvmFactory);
+        let mut evm = factory.create_evm(EmptyDB::default(), env(SpecId::PRAGUE));
+        let precompile = evm.precompiles_mut().get(&P256VERIFY_ADDRESS).unwrap();
+        assert_eq!(precompile.precompile_id(), &PrecompileId::P256Verify);
+
+        // invalid input returns empty output instead of failing
+        let mut ctx = EthEvmContext::new(EmptyDB::default(), Default::default());
+        let output = precompile
+            .call(PrecompileInput {
+                data: &[0; 160],
+                gas: 10_000,
+                caller: Address::ZERO,
+                value: U256::ZERO,
+                is_static: false,
+                target_address: P256VERIFY_ADDRESS,
+                bytecode_address: P256VERIFY_ADDRESS,
+                internals: EvmInternals::from_context(&mut ctx),
+            })
+            .unwrap();
+        assert!(output.bytes.is_empty());
+    }
+
     #[test]
     fn test_map_precompile() {
         let eth_precompiles = EthPrecompiles::new(SpecId::default());
@@ -1135,6 +1449,132 @@ mod tests {
         assert!(sha256.supports_caching(), "sha256 precompile should support caching");
     }
 
+    #[cfg(feature = "kzg")]
+    #[test]
+    fn test_kzg_settings() {
+        use crate::{EthEvmFactory, Evm, EvmEnv, EvmFactory};
+
+        let address = revm::precompile::u64_to_address(0x0a);
+        let env = |spec| {
+            let mut env = EvmEnv::default();
+            env.cfg_env.spec = spec;
+            env
+        };
+        let factory = KzgEvmFactory::new(EthEvmFactory, EnvKzgSettings::Default);
+
+        let mut evm = factory.create_evm(EmptyDB::default(), env(SpecId::SHANGHAI));
+        assert!(evm.precompiles_mut().get(&address).is_none());
+
+        let mut evm = factory.create_evm(EmptyDB::default(), env(SpecId::CANCUN));
+        let precompile = evm.precompiles_mut().get(&address).unwrap();
+        assert_eq!(precompile.precompile_id(), &PrecompileId::KzgPointEvaluation);
+
+        let mut ctx = EthEvmContext::new(EmptyDB::default(), Default::default());
+        let mut call = |data: &[u8]| {
+            precompile.call(PrecompileInput {
+                data,
+                gas: 100_000,
+                caller: Address::ZERO,
+                value: U256::ZERO,
+                is_static: false,
+                internals: EvmInternals::from_context(&mut ctx),
+                target_address: address,
+                bytecode_address: address,
+            })
+        };
+        assert!(matches!(call(&[0; 191]), Err(PrecompileError::BlobInvalidInputLength)));
+
+        use alloy_primitives::hex;
+
+        // https://github.com/ethereum/c-kzg-4844/blob/main/tests/verify_kzg_proof/kzg-mainnet/verify_kzg_proof_case_correct_proof_31ebd010e6098750/data.yaml
+        let commitment = hex!("8f59a8d2a1a625a17f3fea0fe5eb8c896db3764f3185481bc22f91b4aaffcca25f26936857bc3a7c2539ea8ec3a952b7");
+        let z = hex!("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000000");
+        let y = hex!("1522a4a7f34e1ea350ae07c29c96c7e79655aa926122e95fe69fcbd932ca49e9");
+        let proof = hex!("a62ad71d14c5719385c0686f1871430475bf3a00f0aa3f7b8dd99a9abc2160744faf0070725e00b60ad9a026a15b1a8c");
+        let versioned_hash =
+            revm::precompile::kzg_point_evaluation::kzg_to_versioned_hash(&commitment);
+        let mut input = [&versioned_hash[..], &z, &y, &commitment, &proof].concat();
+
+        let output = call(&input).unwrap();
+        assert_eq!(output.gas_used, 50_000);
+        assert_eq!(
+            output.bytes,
+            hex!("000000000000000000000000000000000000000000000000000000000000100073eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001")
+        );
+
+        // a different evaluation doesn't match the proof
+        input[95] ^= 1;
+        assert!(matches!(call(&input), Err(PrecompileError::BlobVerifyKzgProofFailed)));
+    }
+
+    #[cfg(feature = "kzg")]
+    #[test]
+    fn test_validate_blob_sidecar() {
+        use alloy_eips::eip4844::{Blob, Bytes48};
+
+        // the commitment and proof of the zero blob are the point at infinity
+        let mut infinity = Bytes48::ZERO;
+        infinity[0] = 0xc0;
+        let sidecar = BlobTransactionSidecar {
+            blobs: alloc::vec![Blob::ZERO],
+            commitments: alloc::vec![infinity],
+            proofs: alloc::vec![infinity],
+        };
+        let hashes: Vec<_> = sidecar.versioned_hashes().collect();
+        validate_blob_sidecar(&sidecar, &hashes, &EnvKzgSettings::Default).unwrap();
+
+        assert!(validate_blob_sidecar(&sidecar, &[B256::ZERO], &EnvKzgSettings::Default).is_err());
+
+        let mut invalid = sidecar.clone();
+        invalid.blobs[0][31] = 1;
+        assert!(validate_blob_sidecar(&invalid, &hashes, &EnvKzgSettings::Default).is_err());
+    }
+
+    #[test]
+    fn test_address_range_precompile() {
+        let mut prefix = [0; 19];
+        prefix[0] = 0x01;
+        let bank = AddressRangePrecompile::new(
+            prefix,
+            DynPrecompile::new_stateful(PrecompileId::Custom("bank".into()), |input| {
+                let index = AddressRangePrecompile::index(&input.target_address);
+                Ok(PrecompileOutput::new(10, Bytes::from(vec![index])))
+            }),
+        );
+        let spec_precompiles = PrecompilesMap::from(EthPrecompiles::new(SpecId::default()))
+            .with_precompile_lookup(bank);
+        let static_count = spec_precompiles.addresses().count();
+
+        let warm: Vec<_> =
+            PrecompileProvider::<EthEvmContext<EmptyDB>>::warm_addresses(&spec_precompiles)
+                .collect();
+        assert_eq!(warm.len(), static_count + 256);
+
+        let address = address!("0x01000000000000000000000000000000000000ab");
+        assert!(warm.contains(&address));
+
+        let mut ctx = EthEvmContext::new(EmptyDB::default(), Default::default());
+        let output = spec_precompiles
+            .get(&address)
+            .unwrap()
+            .call(PrecompileInput {
+                data: &[],
+                gas: 100,
+                caller: Address::ZERO,
+                value: U256::ZERO,
+                is_static: false,
+                internals: EvmInternals::from_context(&mut ctx),
+                target_address: address,
+                bytecode_address: address,
+            })
+            .unwrap();
+        assert_eq!(output.bytes, Bytes::from(vec![0xab]));
+
+        assert!(spec_precompiles
+            .get(&address!("0x02000000000000000000000000000000000000ab"))
+            .is_none());
+    }
+
     #[test]
     fn test_precompile_lookup() {
         let eth_precompiles = EthPrecompiles::new(SpecId::default());
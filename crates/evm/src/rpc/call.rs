@@ -0,0 +1,38 @@
+use alloc::{format, string::String, vec::Vec};
+use alloy_primitives::{Bytes, Log};
+use core::fmt::Debug;
+use revm::context_interface::result::ExecutionResult;
+
+/// Outcome of an `eth_call`-style call, as returned by bindings executing calls.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct CallOutput {
+    /// Whether the call succeeded.
+    pub success: bool,
+    /// Gas used by the call.
+    pub gas_used: u64,
+    /// Return or revert data of the call.
+    pub output: Bytes,
+    /// Logs emitted by the call.
+    pub logs: Vec<Log>,
+    /// Reason the call halted, if it did.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub halt_reason: Option<String>,
+}
+
+impl<H: Debug> From<ExecutionResult<H>> for CallOutput {
+    fn from(result: ExecutionResult<H>) -> Self {
+        let halt_reason = match &result {
+            ExecutionResult::Halt { reason, .. } => Some(format!("{reason:?}")),
+            _ => None,
+        };
+        Self {
+            success: result.is_success(),
+            gas_used: result.gas_used(),
+            output: result.output().cloned().unwrap_or_default(),
+            logs: result.logs().to_vec(),
+            halt_reason,
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use crate::{env::BlockEnvironment, rpc::TryIntoTxEnv, Database, EvmEnv};
+use alloy_consensus::TxType;
+use alloy_primitives::ChainId;
+use alloy_rpc_types_eth::TransactionRequest;
+use thiserror::Error;
+
+/// Limits applied to transaction requests before they are executed, e.g. in `eth_call`.
+///
+/// Use [`RpcExecutionConfig::try_into_tx_env`] in place of [`TryIntoTxEnv::try_into_tx_env`] to
+/// apply the limits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcExecutionConfig {
+    /// Maximum gas limit of a request. Requests without a gas limit use the smaller of the cap
+    /// and the block gas limit.
+    pub gas_cap: Option<u64>,
+    /// How requests with a gas limit above [`Self::gas_cap`] are handled.
+    pub gas_cap_policy: GasCapPolicy,
+    /// Maximum number of blob versioned hashes of a request.
+    pub max_blob_count: Option<usize>,
+    /// Whether requests with a chain id different from the one of the environment are rejected.
+    pub reject_chain_id_mismatch: bool,
+    /// Whether legacy requests without a chain id are allowed.
+    pub allow_unprotected: bool,
+}
+
+impl Default for RpcExecutionConfig {
+    fn default() -> Self {
+        Self {
+            gas_cap: None,
+            gas_cap_policy: GasCapPolicy::default(),
+            max_blob_count: None,
+            reject_chain_id_mismatch: true,
+            allow_unprotected: true,
+        }
+    }
+}
+
+/// How a request with a gas limit above the gas cap is handled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GasCapPolicy {
+    /// Lower the gas limit to the gas cap.
+    #[default]
+    Clamp,
+    /// Reject the request with [`RpcExecutionError::GasLimitAboveCap`].
+    Reject,
+}
+
+/// Error returned by [`RpcExecutionConfig::try_into_tx_env`].
+#[derive(Debug, Error)]
+pub enum RpcExecutionError<E> {
+    /// The gas limit of the request is above the gas cap.
+    #[error("gas limit {gas_limit} exceeds the gas cap of {gas_cap}")]
+    GasLimitAboveCap {
+        /// Gas limit of the request.
+        gas_limit: u64,
+        /// Configured gas cap.
+        gas_cap: u64,
+    },
+    /// The request has more blob versioned hashes than allowed.
+    #[error("{count} blobs exceed the maximum of {max}")]
+    TooManyBlobs {
+        /// Number of blob versioned hashes of the request.
+        count: usize,
+        /// Configured maximum.
+        max: usize,
+    },
+    /// The chain id of the request doesn't match the chain id of the environment.
+    #[error("chain id {got} does not match the chain id {expected}")]
+    ChainIdMismatch {
+        /// Chain id of the request.
+        got: ChainId,
+        /// Chain id of the environment.
+        expected: ChainId,
+    },
+    /// The request is a legacy transaction without a chain id, see [EIP-155].
+    ///
+    /// [EIP-155]: https://eips.ethereum.org/EIPS/eip-155
+    #[error("unprotected transactions are not allowed")]
+    Unprotected,
+    /// Error converting the request into a transaction environment.
+    #[error(transparent)]
+    TxEnv(E),
+}
+
+/// Mutable access to the [`TransactionRequest`] of a chain-specific request type.
+pub trait AsTransactionRequestMut {
+    /// Returns the inner [`TransactionRequest`].
+    fn as_transaction_request_mut(&mut self) -> &mut TransactionRequest;
+
+    /// Sets the nonce of the request to the nonce of the sender in `db` if it is unset.
+    ///
+    /// Requests without a nonce otherwise use `0`, which breaks simulations that depend on the
+    /// nonce of the sender, e.g. [EIP-7702] authorizations signed by the sender itself.
+    ///
+    /// [EIP-7702]: https://eips.ethereum.org/EIPS/eip-7702
+    fn fill_nonce_from_state<DB: Database>(&mut self, db: &mut DB) -> Result<(), DB::Error> {
+        let request = self.as_transaction_request_mut();
+        if request.nonce.is_none() {
+            let sender = request.from.unwrap_or_default();
+            request.nonce = Some(db.basic(sender)?.map(|info| info.nonce).unwrap_or_default());
+        }
+        Ok(())
+    }
+}
+
+impl AsTransactionRequestMut for TransactionRequest {
+    fn as_transaction_request_mut(&mut self) -> &mut TransactionRequest {
+        self
+    }
+}
+
+#[cfg(feature = "op")]
+impl AsTransactionRequestMut for op_alloy::rpc_types::OpTransactionRequest {
+    fn as_transaction_request_mut(&mut self) -> &mut TransactionRequest {
+        self.as_mut()
+    }
+}
+
+impl RpcExecutionConfig {
+    /// Applies the limits to `request`, clamping its gas limit if configured to do so.
+    pub fn apply<E, Spec, Block: BlockEnvironment>(
+        &self,
+        request: &mut TransactionRequest,
+        evm_env: &EvmEnv<Spec, Block>,
+    ) -> Result<(), RpcExecutionError<E>> {
+        if let Some(gas_cap) = self.gas_cap {
+            match request.gas {
+                Some(gas_limit) if gas_limit > gas_cap => match self.gas_cap_policy {
+                    GasCapPolicy::Clamp => request.gas = Some(gas_cap),
+                    GasCapPolicy::Reject => {
+                        return Err(RpcExecutionError::GasLimitAboveCap { gas_limit, gas_cap })
+                    }
+                },
+                Some(_) => {}
+                None => request.gas = Some(gas_cap.min(evm_env.block_env().gas_limit())),
+            }
+        }
+
+        if let Some(max) = self.max_blob_count {
+            let count = request.blob_versioned_hashes.as_ref().map_or(0, |hashes| hashes.len());
+            if count > max {
+                return Err(RpcExecutionError::TooManyBlobs { count, max });
+            }
+        }
+
+        let expected = evm_env.cfg_env().chain_id;
+        match request.chain_id {
+            Some(got) if self.reject_chain_id_mismatch && got != expected => {
+                return Err(RpcExecutionError::ChainIdMismatch { got, expected })
+            }
+            None if !self.allow_unprotected && request.minimal_tx_type() == TxType::Legacy => {
+                return Err(RpcExecutionError::Unprotected)
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Applies the limits to `request` and converts it into a transaction environment.
+    pub fn try_into_tx_env<R, T, Spec, Block>(
+        &self,
+        mut request: R,
+        evm_env: &EvmEnv<Spec, Block>,
+    ) -> Result<T, RpcExecutionError<R::Err>>
+    where
+        R: TryIntoTxEnv<T, Spec, Block> + AsTransactionRequestMut,
+        Block: BlockEnvironment,
+    {
+        self.apply(request.as_transaction_request_mut(), evm_env)?;
+        request.try_into_tx_env(evm_env).map_err(RpcExecutionError::TxEnv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Address, B256};
+    use revm::{
+        context::{Transaction, TxEnv},
+        database::{CacheDB, EmptyDB},
+        state::AccountInfo,
+    };
+
+    fn evm_env() -> EvmEnv {
+        let mut evm_env = EvmEnv::default();
+        evm_env.block_env.gas_limit = 30_000_000;
+        evm_env
+    }
+
+    #[test_case::test_case(None, 1_000_000; "unset gas uses cap")]
+    #[test_case::test_case(Some(2_000_000), 1_000_000; "gas above cap is clamped")]
+    #[test_case::test_case(Some(21_000), 21_000; "gas below cap is kept")]
+    fn test_gas_cap_clamp(gas: Option<u64>, expected: u64) {
+        let config = RpcExecutionConfig { gas_cap: Some(1_000_000), ..Default::default() };
+        let request = TransactionRequest { gas, ..Default::default() };
+
+        let tx: TxEnv = config.try_into_tx_env(request, &evm_env()).unwrap();
+        assert_eq!(tx.gas_limit(), expected);
+    }
+
+    #[test]
+    fn test_gas_cap_reject() {
+        let config = RpcExecutionConfig {
+            gas_cap: Some(1_000_000),
+            gas_cap_policy: GasCapPolicy::Reject,
+            ..Default::default()
+        };
+        let request = TransactionRequest { gas: Some(2_000_000), ..Default::default() };
+
+        let err = config.try_into_tx_env::<_, TxEnv, _, _>(request, &evm_env()).unwrap_err();
+        assert!(matches!(
+            err,
+            RpcExecutionError::GasLimitAboveCap { gas_limit: 2_000_000, gas_cap: 1_000_000 }
+        ));
+    }
+
+    #[test]
+    fn test_max_blob_count() {
+        let config = RpcExecutionConfig { max_blob_count: Some(1), ..Default::default() };
+        let request = TransactionRequest {
+            blob_versioned_hashes: Some(vec![B256::ZERO; 2]),
+            ..Default::default()
+        };
+
+        let err = config.try_into_tx_env::<_, TxEnv, _, _>(request, &evm_env()).unwrap_err();
+        assert!(matches!(err, RpcExecutionError::TooManyBlobs { count: 2, max: 1 }));
+    }
+
+    #[test]
+    fn test_chain_id_mismatch() {
+        let mut evm_env = evm_env();
+        evm_env.cfg_env.chain_id = 10;
+        let request = TransactionRequest { chain_id: Some(1), ..Default::default() };
+
+        let err = RpcExecutionConfig::default()
+            .try_into_tx_env::<_, TxEnv, _, _>(request.clone(), &evm_env)
+            .unwrap_err();
+        assert!(matches!(err, RpcExecutionError::ChainIdMismatch { got: 1, expected: 10 }));
+
+        let config = RpcExecutionConfig { reject_chain_id_mismatch: false, ..Default::default() };
+        let tx: TxEnv = config.try_into_tx_env(request, &evm_env).unwrap();
+        assert_eq!(tx.chain_id, Some(1));
+    }
+
+    #[test]
+    fn test_unprotected() {
+        let config = RpcExecutionConfig { allow_unprotected: false, ..Default::default() };
+
+        let err = config
+            .try_into_tx_env::<_, TxEnv, _, _>(TransactionRequest::default(), &evm_env())
+            .unwrap_err();
+        assert!(matches!(err, RpcExecutionError::Unprotected));
+
+        let request = TransactionRequest { chain_id: Some(1), ..Default::default() };
+        assert!(config.try_into_tx_env::<_, TxEnv, _, _>(request, &evm_env()).is_ok());
+    }
+
+    #[test]
+    fn test_fill_nonce_from_state() {
+        let sender = Address::repeat_byte(0x01);
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(sender, AccountInfo { nonce: 7, ..Default::default() });
+
+        let mut request = TransactionRequest { from: Some(sender), ..Default::default() };
+        request.fill_nonce_from_state(&mut db).unwrap();
+        assert_eq!(request.nonce, Some(7));
+
+        let mut request =
+            TransactionRequest { from: Some(sender), nonce: Some(3), ..Default::default() };
+        request.fill_nonce_from_state(&mut db).unwrap();
+        assert_eq!(request.nonce, Some(3));
+
+        let mut request = TransactionRequest::default();
+        request.fill_nonce_from_state(&mut db).unwrap();
+        assert_eq!(request.nonce, Some(0));
+    }
+}
@@ -0,0 +1,296 @@
+//! Computation of `eth_feeHistory` responses.
+
+use super::receipt::{receipt_gas_used, CumulativeGasUsedError};
+use alloc::vec::Vec;
+use alloy_consensus::{BlockHeader, Transaction, TxReceipt};
+use alloy_eips::{eip1559::BaseFeeParams, eip7840::BlobParams};
+use alloy_rpc_types_eth::FeeHistory;
+
+/// Errors returned by [`fee_history`].
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum FeeHistoryError {
+    /// No blocks were given.
+    #[error("no blocks to compute the fee history of")]
+    EmptyRange,
+    /// The blocks are not consecutive.
+    #[error("block {got} doesn't follow block {expected}")]
+    NonContiguous {
+        /// Number of the block expected next.
+        expected: u64,
+        /// Number of the given block.
+        got: u64,
+    },
+    /// The reward percentiles are not monotonically increasing values between 0 and 100.
+    #[error("invalid reward percentiles")]
+    InvalidPercentiles,
+    /// The base fee of the block after the range could not be computed, e.g. because the last
+    /// block predates London.
+    #[error("base fee of the next block is unknown")]
+    MissingNextBaseFee,
+}
+
+/// Gas used by a transaction and the tip per gas it paid, used to compute reward percentiles.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TxGasAndReward {
+    /// Gas used by the transaction.
+    pub gas_used: u64,
+    /// Tip per gas paid to the beneficiary.
+    pub reward: u128,
+}
+
+impl TxGasAndReward {
+    /// Collects the gas used and rewards of the transactions of a block from their receipts.
+    ///
+    /// Transactions are matched with the receipts in order. Fails if the cumulative gas used of a
+    /// receipt is below the one of the preceding receipt.
+    pub fn from_receipts<'a, T, R>(
+        base_fee: Option<u64>,
+        transactions: impl IntoIterator<Item = &'a T>,
+        receipts: impl IntoIterator<Item = &'a R>,
+    ) -> Result<Vec<Self>, CumulativeGasUsedError>
+    where
+        T: Transaction + 'a,
+        R: TxReceipt + 'a,
+    {
+        let mut cumulative_gas_used = 0;
+        transactions
+            .into_iter()
+            .zip(receipts)
+            .enumerate()
+            .map(|(index, (tx, receipt))| {
+                let gas_used =
+                    receipt_gas_used(index, receipt.cumulative_gas_used(), cumulative_gas_used)?;
+                cumulative_gas_used = receipt.cumulative_gas_used();
+                let reward = tx.effective_tip_per_gas(base_fee.unwrap_or_default()).unwrap_or(0);
+                Ok(Self { gas_used, reward })
+            })
+            .collect()
+    }
+}
+
+/// A block of the range passed to [`fee_history`].
+#[derive(Debug, Clone)]
+pub struct FeeHistoryBlock<H> {
+    /// Header of the block.
+    pub header: H,
+    /// Gas used and rewards of the transactions of the block.
+    ///
+    /// Only needed if reward percentiles are requested, the gas used ratio is taken from the
+    /// header.
+    pub transactions: Vec<TxGasAndReward>,
+}
+
+impl<H> FeeHistoryBlock<H> {
+    /// Creates a block without transactions, sufficient if no reward percentiles are requested.
+    pub const fn new(header: H) -> Self {
+        Self { header, transactions: Vec::new() }
+    }
+
+    /// Sets the transactions of the block.
+    pub fn with_transactions(mut self, transactions: Vec<TxGasAndReward>) -> Self {
+        self.transactions = transactions;
+        self
+    }
+}
+
+/// Computes the `eth_feeHistory` response for a range of consecutive blocks.
+///
+/// The base fee and blob base fee of the block after the range are computed from the last header
+/// with the same rules used to derive the environment of the next block, using the parameters
+/// returned by `base_fee_params` and `blob_params` for the given timestamp. Blob fields are zero
+/// for blocks before Cancun.
+///
+/// Rewards are only computed if `reward_percentiles` are given, in which case they must be
+/// monotonically increasing values between 0 and 100.
+pub fn fee_history<H: BlockHeader>(
+    blocks: impl IntoIterator<Item = FeeHistoryBlock<H>>,
+    reward_percentiles: Option<&[f64]>,
+    base_fee_params: impl Fn(u64) -> BaseFeeParams,
+    blob_params: impl Fn(u64) -> Option<BlobParams>,
+) -> Result<FeeHistory, FeeHistoryError> {
+    if let Some(percentiles) = reward_percentiles {
+        let in_range = percentiles.iter().all(|p| (0.0..=100.0).contains(p));
+        if !in_range || percentiles.windows(2).any(|w| w[0] > w[1]) {
+            return Err(FeeHistoryError::InvalidPercentiles);
+        }
+    }
+
+    let mut history = FeeHistory::default();
+    let mut rewards = reward_percentiles.map(|_| Vec::new());
+    let mut last: Option<H> = None;
+
+    for block in blocks {
+        let header = block.header;
+        match &last {
+            Some(prev) if header.number() != prev.number() + 1 => {
+                return Err(FeeHistoryError::NonContiguous {
+                    expected: prev.number() + 1,
+                    got: header.number(),
+                });
+            }
+            None => history.oldest_block = header.number(),
+            _ => {}
+        }
+
+        history.base_fee_per_gas.push(header.base_fee_per_gas().unwrap_or_default().into());
+        history.gas_used_ratio.push(ratio(header.gas_used(), header.gas_limit()));
+
+        let params = blob_params(header.timestamp());
+        history
+            .base_fee_per_blob_gas
+            .push(params.and_then(|params| header.blob_fee(params)).unwrap_or_default());
+        history.blob_gas_used_ratio.push(params.map_or(0.0, |params| {
+            ratio(header.blob_gas_used().unwrap_or_default(), params.max_blob_gas_per_block())
+        }));
+
+        if let (Some(rewards), Some(percentiles)) = (&mut rewards, reward_percentiles) {
+            rewards.push(reward_percentiles_of(block.transactions, percentiles));
+        }
+        last = Some(header);
+    }
+
+    let last = last.ok_or(FeeHistoryError::EmptyRange)?;
+    let next_base_fee = last
+        .next_block_base_fee(base_fee_params(last.timestamp()))
+        .ok_or(FeeHistoryError::MissingNextBaseFee)?;
+    history.base_fee_per_gas.push(next_base_fee.into());
+
+    let params = blob_params(last.timestamp());
+    history.base_fee_per_blob_gas.push(
+        params
+            .and_then(|params| {
+                last.maybe_next_block_excess_blob_gas(Some(params))
+                    .map(|excess| params.calc_blob_fee(excess))
+            })
+            .unwrap_or_default(),
+    );
+    history.reward = rewards;
+
+    Ok(history)
+}
+
+/// Returns `used / limit`, or zero if the limit is zero.
+fn ratio(used: u64, limit: u64) -> f64 {
+    if limit == 0 {
+        0.0
+    } else {
+        used as f64 / limit as f64
+    }
+}
+
+/// Computes the rewards at the given percentiles of gas used, weighting transactions by the gas
+/// they used.
+fn reward_percentiles_of(mut transactions: Vec<TxGasAndReward>, percentiles: &[f64]) -> Vec<u128> {
+    if transactions.is_empty() {
+        return alloc::vec![0; percentiles.len()];
+    }
+    transactions.sort_unstable_by_key(|tx| tx.reward);
+
+    let total_gas_used: u64 = transactions.iter().map(|tx| tx.gas_used).sum();
+    let mut index = 0;
+    let mut cumulative_gas_used = transactions[0].gas_used;
+    percentiles
+        .iter()
+        .map(|percentile| {
+            let threshold = (total_gas_used as f64 * percentile / 100.0) as u64;
+            while cumulative_gas_used < threshold && index < transactions.len() - 1 {
+                index += 1;
+                cumulative_gas_used += transactions[index].gas_used;
+            }
+            transactions[index].reward
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::{Header, Receipt, TxLegacy};
+    use alloy_primitives::Log;
+
+    fn header(number: u64, gas_used: u64, base_fee: u64) -> Header {
+        Header {
+            number,
+            gas_used,
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(base_fee),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_fee_history() {
+        let blocks = [
+            FeeHistoryBlock::new(header(10, 15_000_000, 1_000_000_000)).with_transactions(
+                alloc::vec![
+                    TxGasAndReward { gas_used: 21_000, reward: 3 },
+                    TxGasAndReward { gas_used: 50_000, reward: 1 },
+                    TxGasAndReward { gas_used: 29_000, reward: 2 },
+                ],
+            ),
+            FeeHistoryBlock::new(header(11, 30_000_000, 1_000_000_000)),
+        ];
+        let history = fee_history(
+            blocks,
+            Some(&[0.0, 50.0, 60.0, 100.0]),
+            |_| BaseFeeParams::ethereum(),
+            |_| None,
+        )
+        .unwrap();
+
+        assert_eq!(history.oldest_block, 10);
+        assert_eq!(history.gas_used_ratio, [0.5, 1.0]);
+        // full block, so the base fee increases by 12.5%
+        assert_eq!(history.base_fee_per_gas, [1_000_000_000, 1_000_000_000, 1_125_000_000]);
+        assert_eq!(history.base_fee_per_blob_gas, [0, 0, 0]);
+        assert_eq!(history.reward.unwrap(), [[1, 1, 2, 3], [0, 0, 0, 0]]);
+    }
+
+    #[test]
+    fn test_fee_history_errors() {
+        let no_blocks = core::iter::empty::<FeeHistoryBlock<Header>>();
+        assert_eq!(
+            fee_history(no_blocks, None, |_| BaseFeeParams::ethereum(), |_| None),
+            Err(FeeHistoryError::EmptyRange)
+        );
+
+        let blocks =
+            [FeeHistoryBlock::new(header(10, 0, 1)), FeeHistoryBlock::new(header(12, 0, 1))];
+        assert_eq!(
+            fee_history(blocks, None, |_| BaseFeeParams::ethereum(), |_| None),
+            Err(FeeHistoryError::NonContiguous { expected: 11, got: 12 })
+        );
+
+        let blocks = [FeeHistoryBlock::new(header(10, 0, 1))];
+        assert_eq!(
+            fee_history(blocks, Some(&[50.0, 10.0]), |_| BaseFeeParams::ethereum(), |_| None),
+            Err(FeeHistoryError::InvalidPercentiles)
+        );
+    }
+
+    #[test]
+    fn test_tx_gas_and_reward_from_receipts() {
+        let tx = TxLegacy { gas_price: 5, ..Default::default() };
+        let txs = [tx.clone(), tx];
+        let receipt = |cumulative_gas_used| Receipt::<Log> {
+            cumulative_gas_used,
+            status: true.into(),
+            logs: Vec::new(),
+        };
+
+        let receipts = [receipt(21_000), receipt(71_000)];
+        assert_eq!(
+            TxGasAndReward::from_receipts(Some(2), &txs, &receipts).unwrap(),
+            [
+                TxGasAndReward { gas_used: 21_000, reward: 3 },
+                TxGasAndReward { gas_used: 50_000, reward: 3 }
+            ]
+        );
+
+        let receipts = [receipt(71_000), receipt(21_000)];
+        assert_eq!(
+            TxGasAndReward::from_receipts(Some(2), &txs, &receipts),
+            Err(CumulativeGasUsedError { index: 1, got: 21_000, previous: 71_000 })
+        );
+    }
+}
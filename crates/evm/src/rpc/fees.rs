@@ -1,3 +1,6 @@
+use super::FeeHistoryBlock;
+use alloc::vec::Vec;
+use alloy_consensus::{constants::GWEI_TO_WEI, BlockHeader};
 use alloy_primitives::{B256, U256};
 use core::cmp::min;
 use thiserror::Error;
@@ -18,7 +21,45 @@ pub struct CallFees {
     pub max_fee_per_blob_gas: Option<U256>,
 }
 
+/// How [`CallFees::ensure_fees_with_mode`] treats requests that only specify blob fields.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CallFeesMode {
+    /// Blob transactions must specify their blob versioned hashes.
+    #[default]
+    Strict,
+    /// Requests with only `maxFeePerBlobGas` or `blobVersionedHashes` use the base fee of the
+    /// block as gas price and the blob gas price of the block if `maxFeePerBlobGas` is
+    /// missing, e.g. for `eth_call` and `eth_estimateGas` of blob transactions whose blobs are
+    /// not known yet.
+    BlobEstimation,
+}
+
 impl CallFees {
+    /// Ensures the fields of a `TransactionRequest` are not conflicting, in
+    /// [`CallFeesMode::Strict`] mode.
+    ///
+    /// See [`Self::ensure_fees_with_mode`].
+    pub fn ensure_fees(
+        call_gas_price: Option<U256>,
+        call_max_fee: Option<U256>,
+        call_priority_fee: Option<U256>,
+        block_base_fee: U256,
+        blob_versioned_hashes: Option<&[B256]>,
+        max_fee_per_blob_gas: Option<U256>,
+        block_blob_fee: Option<U256>,
+    ) -> Result<Self, CallFeesError> {
+        Self::ensure_fees_with_mode(
+            CallFeesMode::Strict,
+            call_gas_price,
+            call_max_fee,
+            call_priority_fee,
+            block_base_fee,
+            blob_versioned_hashes,
+            max_fee_per_blob_gas,
+            block_blob_fee,
+        )
+    }
+
     /// Ensures the fields of a `TransactionRequest` are not conflicting.
     ///
     /// # EIP-4844 transactions
@@ -38,8 +79,13 @@ impl CallFees {
     ///
     /// This mirrors geth's behaviour when transaction requests are executed: <https://github.com/ethereum/go-ethereum/blob/380688c636a654becc8f114438c2a5d93d2db032/core/state_transition.go#L306-L306>
     ///
+    /// A non-zero `maxFeePerBlobGas` below the blob gas price of the block is rejected with
+    /// [`CallFeesError::BlobFeeCapTooLow`].
+    ///
     /// [`BlockEnv`]: revm::context::BlockEnv
-    pub fn ensure_fees(
+    #[allow(clippy::too_many_arguments)]
+    pub fn ensure_fees_with_mode(
+        mode: CallFeesMode,
         call_gas_price: Option<U256>,
         call_max_fee: Option<U256>,
         call_priority_fee: Option<U256>,
@@ -89,6 +135,32 @@ impl CallFees {
         let has_blob_hashes =
             blob_versioned_hashes.as_ref().map(|blobs| !blobs.is_empty()).unwrap_or(false);
 
+        if let (Some(max_fee_per_blob_gas), Some(block_blob_fee)) =
+            (max_fee_per_blob_gas, block_blob_fee)
+        {
+            // only enforce the blob fee cap if provided input is not zero
+            if !max_fee_per_blob_gas.is_zero() && max_fee_per_blob_gas < block_blob_fee {
+                return Err(CallFeesError::BlobFeeCapTooLow {
+                    max_fee_per_blob_gas,
+                    block_blob_fee,
+                });
+            }
+        }
+
+        if mode == CallFeesMode::BlobEstimation
+            && call_gas_price.is_none()
+            && call_max_fee.is_none()
+            && call_priority_fee.is_none()
+            && (has_blob_hashes || max_fee_per_blob_gas.is_some())
+        {
+            // blob fields only, derive the execution fees from the block
+            return Ok(Self {
+                gas_price: block_base_fee,
+                max_priority_fee_per_gas: None,
+                max_fee_per_blob_gas: max_fee_per_blob_gas.or(block_blob_fee),
+            });
+        }
+
         match (call_gas_price, call_max_fee, call_priority_fee, max_fee_per_blob_gas) {
             (gas_price, None, None, None) => {
                 // either legacy transaction or no fee fields are specified
@@ -162,12 +234,91 @@ pub enum CallFeesError {
     /// Blob transaction has no versioned hashes
     #[error("blob transaction missing blob hashes")]
     BlobTransactionMissingBlobHashes,
+    /// Thrown if the max fee per blob gas is less than the blob gas price of the block
+    #[error(
+        "max fee per blob gas {max_fee_per_blob_gas} less than block blob gas fee {block_blob_fee}"
+    )]
+    BlobFeeCapTooLow {
+        /// Max fee per blob gas of the request.
+        max_fee_per_blob_gas: U256,
+        /// Blob gas price of the block.
+        block_blob_fee: U256,
+    },
+}
+
+/// Suggests the priority fee per gas for new transactions, e.g. for `eth_maxPriorityFeePerGas`.
+pub trait SuggestFee {
+    /// Suggests a priority fee per gas based on the given recent blocks, ordered from oldest to
+    /// newest.
+    ///
+    /// The transactions of each block are expected to be filled, see
+    /// [`TxGasAndReward::from_receipts`](super::TxGasAndReward::from_receipts).
+    fn suggest_priority_fee<H: BlockHeader>(&self, blocks: &[FeeHistoryBlock<H>]) -> u128;
+}
+
+/// A [`SuggestFee`] implementation suggesting a percentile of the lowest tips paid in recent
+/// blocks, following geth's gas price oracle.
+///
+/// From each of the last [`Self::blocks`] blocks, the [`Self::samples_per_block`] lowest tips at or
+/// above [`Self::ignore_price`] are sampled. The suggestion is the [`Self::percentile`] of all
+/// samples, capped at [`Self::max_price`], or [`Self::default_price`] if there are no samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PercentileFeeOracle {
+    /// Number of recent blocks to sample.
+    pub blocks: usize,
+    /// Number of tips sampled per block.
+    pub samples_per_block: usize,
+    /// Percentile of the sampled tips to suggest, between 0 and 100.
+    pub percentile: u8,
+    /// Tips below this value are not sampled.
+    pub ignore_price: u128,
+    /// Upper bound of the suggestion.
+    pub max_price: u128,
+    /// Suggestion if no tips were sampled.
+    pub default_price: u128,
+}
+
+impl Default for PercentileFeeOracle {
+    fn default() -> Self {
+        Self {
+            blocks: 20,
+            samples_per_block: 3,
+            percentile: 60,
+            ignore_price: 2,
+            max_price: 500 * GWEI_TO_WEI as u128,
+            default_price: GWEI_TO_WEI as u128,
+        }
+    }
+}
+
+impl SuggestFee for PercentileFeeOracle {
+    fn suggest_priority_fee<H: BlockHeader>(&self, blocks: &[FeeHistoryBlock<H>]) -> u128 {
+        let mut samples = Vec::new();
+        for block in blocks.iter().rev().take(self.blocks) {
+            let mut tips: Vec<_> = block
+                .transactions
+                .iter()
+                .map(|tx| tx.reward)
+                .filter(|tip| *tip >= self.ignore_price)
+                .collect();
+            tips.sort_unstable();
+            samples.extend(tips.into_iter().take(self.samples_per_block));
+        }
+        if samples.is_empty() {
+            return self.default_price.min(self.max_price);
+        }
+
+        samples.sort_unstable();
+        let index = (samples.len() - 1) * self.percentile.min(100) as usize / 100;
+        samples[index].min(self.max_price)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use alloy_consensus::constants::GWEI_TO_WEI;
+    use crate::rpc::TxGasAndReward;
+    use alloy_consensus::Header;
 
     #[test]
     fn test_ensure_0_fallback() {
@@ -207,6 +358,64 @@ mod tests {
         assert_eq!(max_fee_per_blob_gas, Some(U256::from(99)));
     }
 
+    #[test]
+    fn test_blob_estimation_fees() {
+        let base_fee = U256::from(99);
+        let blob_fee = U256::from(10);
+
+        // no blob hashes, rejected in strict mode
+        let err =
+            CallFees::ensure_fees(None, None, None, base_fee, None, Some(blob_fee), Some(blob_fee))
+                .unwrap_err();
+        assert!(matches!(err, CallFeesError::BlobTransactionMissingBlobHashes));
+
+        let CallFees { gas_price, max_priority_fee_per_gas, max_fee_per_blob_gas } =
+            CallFees::ensure_fees_with_mode(
+                CallFeesMode::BlobEstimation,
+                None,
+                None,
+                None,
+                base_fee,
+                None,
+                Some(blob_fee),
+                Some(blob_fee),
+            )
+            .unwrap();
+        assert_eq!(gas_price, base_fee);
+        assert_eq!(max_priority_fee_per_gas, None);
+        assert_eq!(max_fee_per_blob_gas, Some(blob_fee));
+
+        let CallFees { max_fee_per_blob_gas, .. } = CallFees::ensure_fees_with_mode(
+            CallFeesMode::BlobEstimation,
+            None,
+            None,
+            None,
+            base_fee,
+            Some(&[B256::ZERO]),
+            None,
+            Some(blob_fee),
+        )
+        .unwrap();
+        assert_eq!(max_fee_per_blob_gas, Some(blob_fee));
+
+        let err = CallFees::ensure_fees_with_mode(
+            CallFeesMode::BlobEstimation,
+            None,
+            None,
+            None,
+            base_fee,
+            None,
+            Some(U256::from(9)),
+            Some(blob_fee),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            CallFeesError::BlobFeeCapTooLow { max_fee_per_blob_gas, block_blob_fee }
+                if max_fee_per_blob_gas == U256::from(9) && block_blob_fee == blob_fee
+        ));
+    }
+
     #[test]
     fn test_eip_1559_fees() {
         let CallFees { gas_price, .. } = CallFees::ensure_fees(
@@ -278,4 +487,27 @@ mod tests {
         );
         assert!(call_fees.is_err());
     }
+
+    #[test]
+    fn test_percentile_fee_oracle() {
+        let block = |tips: &[u128]| {
+            FeeHistoryBlock::new(Header::default()).with_transactions(
+                tips.iter()
+                    .map(|reward| TxGasAndReward { gas_used: 21_000, reward: *reward })
+                    .collect(),
+            )
+        };
+        let oracle = PercentileFeeOracle { max_price: 50, ..Default::default() };
+
+        // lowest three tips of each block, ignoring tips below 2
+        let blocks = [block(&[1, 10, 20, 30, 40]), block(&[5, 6, 7, 8])];
+        assert_eq!(oracle.suggest_priority_fee(&blocks), 10);
+
+        assert_eq!(oracle.suggest_priority_fee(&[block(&[100])]), 50);
+        assert_eq!(oracle.suggest_priority_fee(&[block(&[])]), 50);
+        assert_eq!(
+            PercentileFeeOracle::default().suggest_priority_fee(&[block(&[])]),
+            GWEI_TO_WEI as u128
+        );
+    }
 }
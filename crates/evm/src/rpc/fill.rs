@@ -0,0 +1,210 @@
+use crate::{
+    env::BlockEnvironment,
+    rpc::{gas::request_min_gas_limit, AsTransactionRequestMut, CallFees, CallFeesError},
+    Database, EvmEnv,
+};
+use alloy_primitives::U256;
+use alloy_rpc_types_eth::TransactionRequest;
+use revm::primitives::hardfork::SpecId;
+use thiserror::Error;
+
+/// Fields of a [`TransactionRequest`] that were inferred by [`fill_request_defaults`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InferredFields {
+    /// The transaction type.
+    pub tx_type: bool,
+    /// The chain id, taken from the environment.
+    pub chain_id: bool,
+    /// The nonce, taken from the state.
+    pub nonce: bool,
+    /// The max fee per gas and max priority fee per gas.
+    pub fees: bool,
+    /// The max fee per blob gas.
+    pub max_fee_per_blob_gas: bool,
+    /// The gas limit, estimated.
+    pub gas: bool,
+}
+
+/// A fully specified [`TransactionRequest`] returned by [`fill_request_defaults`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilledRequest {
+    /// The filled request.
+    pub request: TransactionRequest,
+    /// The fields that were inferred.
+    pub inferred: InferredFields,
+}
+
+/// Error returned by [`fill_request_defaults`].
+#[derive(Debug, Error)]
+pub enum FillRequestError<E> {
+    /// The fee fields of the request are invalid.
+    #[error(transparent)]
+    Fees(#[from] CallFeesError),
+    /// Failed to load the sender or to estimate the gas limit.
+    #[error(transparent)]
+    Other(E),
+}
+
+/// Fills the missing fields of a request, e.g. for `eth_fillTransaction`.
+///
+/// - The chain id is taken from the environment.
+/// - The nonce is the nonce of the sender in `state`.
+/// - Requests without `gasPrice` are priced as EIP-1559 transactions with no priority fee and a max
+///   fee of twice the base fee. Blob requests without `maxFeePerBlobGas` pay twice the blob gas
+///   price of the block.
+/// - The transaction type is the preferred type of the filled request.
+/// - The gas limit is computed by `estimate_gas`, which is called with the otherwise complete
+///   request, and raised to the intrinsic gas and EIP-7623 floor of the request if it is below, see
+///   [`min_gas_limit`](super::min_gas_limit).
+pub fn fill_request_defaults<DB, Spec, Block, E>(
+    mut request: TransactionRequest,
+    evm_env: &EvmEnv<Spec, Block>,
+    state: &mut DB,
+    estimate_gas: impl FnOnce(&TransactionRequest) -> Result<u64, E>,
+) -> Result<FilledRequest, FillRequestError<E>>
+where
+    DB: Database,
+    Spec: Into<SpecId> + Clone,
+    Block: BlockEnvironment,
+    E: From<DB::Error>,
+{
+    let mut inferred = InferredFields::default();
+    let block_env = evm_env.block_env();
+
+    if request.chain_id.is_none() {
+        request.chain_id = Some(evm_env.cfg_env().chain_id);
+        inferred.chain_id = true;
+    }
+
+    if request.nonce.is_none() {
+        request.fill_nonce_from_state(state).map_err(|err| FillRequestError::Other(err.into()))?;
+        inferred.nonce = true;
+    }
+
+    if request.gas_price.is_none() {
+        let base_fee = block_env.basefee() as u128;
+        if request.max_priority_fee_per_gas.is_none() {
+            request.max_priority_fee_per_gas = Some(0);
+            inferred.fees = true;
+        }
+        if request.max_fee_per_gas.is_none() {
+            let priority_fee = request.max_priority_fee_per_gas.unwrap_or_default();
+            request.max_fee_per_gas = Some(base_fee.saturating_mul(2).saturating_add(priority_fee));
+            inferred.fees = true;
+        }
+    }
+
+    let has_blobs = request.blob_versioned_hashes.as_ref().is_some_and(|hashes| !hashes.is_empty());
+    if has_blobs && request.max_fee_per_blob_gas.is_none() {
+        request.max_fee_per_blob_gas =
+            Some(block_env.blob_gasprice().unwrap_or_default().saturating_mul(2));
+        inferred.max_fee_per_blob_gas = true;
+    }
+
+    CallFees::ensure_fees(
+        request.gas_price.map(U256::from),
+        request.max_fee_per_gas.map(U256::from),
+        request.max_priority_fee_per_gas.map(U256::from),
+        U256::from(block_env.basefee()),
+        request.blob_versioned_hashes.as_deref(),
+        request.max_fee_per_blob_gas.map(U256::from),
+        block_env.blob_gasprice().map(U256::from),
+    )?;
+
+    if request.transaction_type.is_none() {
+        request.transaction_type = Some(request.preferred_type() as u8);
+        inferred.tx_type = true;
+    }
+
+    if request.gas.is_none() {
+        let estimate = estimate_gas(&request).map_err(FillRequestError::Other)?;
+        let min_gas_limit = request_min_gas_limit(&request, evm_env.cfg_env().spec.clone().into());
+        request.gas = Some(estimate.max(min_gas_limit));
+        inferred.gas = true;
+    }
+
+    Ok(FilledRequest { request, inferred })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::TxType;
+    use alloy_primitives::{bytes, Address, TxKind};
+    use core::convert::Infallible;
+    use revm::{
+        database::{CacheDB, EmptyDB},
+        state::AccountInfo,
+    };
+
+    #[test]
+    fn test_fill_request_defaults() {
+        let sender = Address::repeat_byte(0x01);
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(sender, AccountInfo { nonce: 3, ..Default::default() });
+        let mut evm_env: EvmEnv = EvmEnv::default();
+        evm_env.cfg_env.chain_id = 10;
+        evm_env.block_env.basefee = 7;
+
+        let request = TransactionRequest {
+            from: Some(sender),
+            to: Some(TxKind::Call(Address::ZERO)),
+            ..Default::default()
+        };
+        let FilledRequest { request, inferred } =
+            fill_request_defaults(request, &evm_env, &mut db, |_| Ok::<_, Infallible>(21_000))
+                .unwrap();
+        assert_eq!(request.chain_id, Some(10));
+        assert_eq!(request.nonce, Some(3));
+        assert_eq!(request.max_fee_per_gas, Some(14));
+        assert_eq!(request.max_priority_fee_per_gas, Some(0));
+        assert_eq!(request.transaction_type, Some(TxType::Eip1559 as u8));
+        assert_eq!(request.gas, Some(21_000));
+        assert_eq!(
+            inferred,
+            InferredFields {
+                tx_type: true,
+                chain_id: true,
+                nonce: true,
+                fees: true,
+                max_fee_per_blob_gas: false,
+                gas: true,
+            }
+        );
+
+        // legacy request with everything but the gas limit
+        let request = TransactionRequest {
+            from: Some(sender),
+            to: Some(TxKind::Call(Address::ZERO)),
+            gas_price: Some(10),
+            nonce: Some(0),
+            chain_id: Some(10),
+            ..Default::default()
+        };
+        let FilledRequest { request, inferred } =
+            fill_request_defaults(request, &evm_env, &mut db, |_| Ok::<_, Infallible>(50_000))
+                .unwrap();
+        assert_eq!(request.transaction_type, Some(TxType::Legacy as u8));
+        assert_eq!(request.max_fee_per_gas, None);
+        assert_eq!(inferred, InferredFields { tx_type: true, gas: true, ..Default::default() });
+    }
+
+    #[test]
+    fn test_fill_request_gas_floor() {
+        let mut evm_env: EvmEnv = EvmEnv::default();
+        evm_env.cfg_env.spec = SpecId::PRAGUE;
+        let request = TransactionRequest {
+            to: Some(TxKind::Call(Address::ZERO)),
+            input: bytes!("00ff").into(),
+            gas_price: Some(0),
+            ..Default::default()
+        };
+        let mut db = CacheDB::new(EmptyDB::default());
+
+        // an estimate below the EIP-7623 floor is raised
+        let FilledRequest { request, .. } =
+            fill_request_defaults(request, &evm_env, &mut db, |_| Ok::<_, Infallible>(21_000))
+                .unwrap();
+        assert_eq!(request.gas, Some(21_050));
+    }
+}
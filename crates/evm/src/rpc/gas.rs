@@ -0,0 +1,74 @@
+use alloy_primitives::TxKind;
+use alloy_rpc_types_eth::TransactionRequest;
+use revm::{
+    context_interface::Transaction,
+    interpreter::gas::{calculate_initial_tx_gas, calculate_initial_tx_gas_for_tx},
+    primitives::hardfork::SpecId,
+};
+
+/// Returns the intrinsic gas of a transaction under the given spec.
+///
+/// This is the gas charged before execution: the base cost, calldata, contract creation and
+/// initcode, as well as access list and EIP-7702 authorization costs. A transaction with a lower
+/// gas limit is rejected by the executor.
+pub fn intrinsic_gas(tx: impl Transaction, spec: SpecId) -> u64 {
+    calculate_initial_tx_gas_for_tx(tx, spec).initial_gas
+}
+
+/// Returns the minimum gas used by a transaction with the given calldata under EIP-7623, or zero
+/// before Prague.
+///
+/// A transaction needs a gas limit of at least the maximum of its [`intrinsic_gas`] and this floor.
+pub fn eip7623_floor_gas(calldata: &[u8], spec: SpecId) -> u64 {
+    calculate_initial_tx_gas(spec, calldata, false, 0, 0, 0).floor_gas
+}
+
+/// Returns the minimum gas limit of a transaction under the given spec.
+pub fn min_gas_limit(tx: impl Transaction, spec: SpecId) -> u64 {
+    let gas = calculate_initial_tx_gas_for_tx(tx, spec);
+    gas.initial_gas.max(gas.floor_gas)
+}
+
+/// Returns the minimum gas limit of a transaction request under the given spec, see
+/// [`min_gas_limit`].
+pub(crate) fn request_min_gas_limit(request: &TransactionRequest, spec: SpecId) -> u64 {
+    let input = request.input.input().map_or(&[][..], |input| input.as_ref());
+    let is_create = matches!(request.to, None | Some(TxKind::Create));
+    let (accounts, storages) = request.access_list.as_ref().map_or((0, 0), |access_list| {
+        (
+            access_list.len() as u64,
+            access_list.iter().map(|item| item.storage_keys.len() as u64).sum(),
+        )
+    });
+    let authorizations = request.authorization_list.as_ref().map_or(0, |list| list.len() as u64);
+    let gas = calculate_initial_tx_gas(spec, input, is_create, accounts, storages, authorizations);
+    gas.initial_gas.max(gas.floor_gas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{bytes, Address};
+    use revm::context::TxEnv;
+
+    #[test]
+    fn test_intrinsic_and_floor_gas() {
+        let tx =
+            TxEnv { kind: TxKind::Call(Address::ZERO), data: bytes!("00ff"), ..Default::default() };
+        assert_eq!(intrinsic_gas(&tx, SpecId::PRAGUE), 21_000 + 4 + 16);
+        assert_eq!(intrinsic_gas(&tx, SpecId::HOMESTEAD), 21_000 + 4 + 68);
+
+        assert_eq!(eip7623_floor_gas(&tx.data, SpecId::CANCUN), 0);
+        assert_eq!(eip7623_floor_gas(&tx.data, SpecId::PRAGUE), 21_000 + 5 * 10);
+        assert_eq!(min_gas_limit(&tx, SpecId::PRAGUE), 21_050);
+        assert_eq!(min_gas_limit(&tx, SpecId::CANCUN), 21_020);
+
+        let request = TransactionRequest {
+            to: Some(TxKind::Call(Address::ZERO)),
+            input: bytes!("00ff").into(),
+            ..Default::default()
+        };
+        assert_eq!(request_min_gas_limit(&request, SpecId::PRAGUE), 21_050);
+        assert_eq!(request_min_gas_limit(&request, SpecId::CANCUN), 21_020);
+    }
+}
@@ -1,7 +1,23 @@
 //! RPC-related traits and implementations.
 
+mod call;
+mod config;
+mod fee_history;
 mod fees;
+mod fill;
+mod gas;
+mod parity;
+mod receipt;
 mod transaction;
 
-pub use fees::{CallFees, CallFeesError};
+pub use call::CallOutput;
+pub use config::{AsTransactionRequestMut, GasCapPolicy, RpcExecutionConfig, RpcExecutionError};
+pub use fee_history::{fee_history, FeeHistoryBlock, FeeHistoryError, TxGasAndReward};
+pub use fees::{CallFees, CallFeesError, CallFeesMode, PercentileFeeOracle, SuggestFee};
+pub use fill::{fill_request_defaults, FillRequestError, FilledRequest, InferredFields};
+pub use gas::{eip7623_floor_gas, intrinsic_gas, min_gas_limit};
+pub use parity::{reward_traces, state_diff, ParityTracer, TraceTxInfo};
+pub use receipt::{into_rpc_receipt, into_rpc_receipts, CumulativeGasUsedError, ReceiptBlockInfo};
+#[cfg(feature = "op")]
+pub(crate) use receipt::{map_block_receipts, rpc_logs, rpc_receipt};
 pub use transaction::{EthTxEnvError, TryIntoTxEnv};
@@ -0,0 +1,536 @@
+//! Parity style traces, as returned by the `trace_` RPC namespace.
+
+use crate::block::calc::{base_block_reward, block_reward, ommer_reward};
+use alloc::{format, string::String, vec::Vec};
+use alloy_consensus::BlockHeader;
+use alloy_hardforks::EthereumHardforks;
+use alloy_primitives::{Address, Bytes, B256, U256, U64};
+use alloy_rpc_types_trace::parity::{
+    AccountDiff, Action, CallAction, CallOutput, CallType, ChangedType, CreateAction, CreateOutput,
+    CreationMethod, Delta, LocalizedTransactionTrace, RewardAction, RewardType, SelfdestructAction,
+    StateDiff, StorageDelta, TraceOutput, TraceResults, TransactionTrace, VmExecutedOperation,
+    VmInstruction, VmTrace,
+};
+use revm::{
+    bytecode::opcode::{OpCode, SSTORE},
+    context::result::ExecutionResult,
+    context_interface::ContextTr,
+    interpreter::{
+        interpreter_types::Jumps, CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome,
+        CreateScheme, InstructionResult, Interpreter, InterpreterResult,
+    },
+    primitives::KECCAK_EMPTY,
+    state::{AccountInfo, EvmState},
+    Database, Inspector,
+};
+
+/// Position of a transaction in its block, attached to localized traces.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TraceTxInfo {
+    /// Hash of the block.
+    pub block_hash: Option<B256>,
+    /// Number of the block.
+    pub block_number: Option<u64>,
+    /// Hash of the transaction.
+    pub transaction_hash: Option<B256>,
+    /// Index of the transaction in the block.
+    pub transaction_position: Option<u64>,
+}
+
+impl TraceTxInfo {
+    /// Attaches the position to a trace.
+    pub const fn localize(&self, trace: TransactionTrace) -> LocalizedTransactionTrace {
+        LocalizedTransactionTrace {
+            trace,
+            block_hash: self.block_hash,
+            block_number: self.block_number,
+            transaction_hash: self.transaction_hash,
+            transaction_position: self.transaction_position,
+        }
+    }
+}
+
+/// An instruction that is currently executing.
+#[derive(Debug)]
+struct PendingStep {
+    /// Gas remaining before the instruction.
+    gas: u64,
+    /// Number of stack items pushed by the instruction.
+    outputs: usize,
+    /// Storage slot and value written by an `SSTORE`.
+    store: Option<StorageDelta>,
+}
+
+/// [`Inspector`] recording Parity style call traces and, optionally, VM traces.
+///
+/// Traces are recorded in call order, with trace addresses pointing to their position in the
+/// call tree. The tracer records a single transaction, a fresh instance should be used per
+/// transaction.
+#[derive(Debug, Default)]
+pub struct ParityTracer {
+    /// Recorded call traces.
+    traces: Vec<TransactionTrace>,
+    /// Indices of the traces of the frames that are currently executing.
+    stack: Vec<usize>,
+    /// Whether VM traces are recorded.
+    record_vm_trace: bool,
+    /// VM traces of the frames that are currently executing.
+    vm_stack: Vec<VmTrace>,
+    /// VM trace of the outermost frame, once it returned.
+    vm_trace: Option<VmTrace>,
+    /// The instruction that is currently executing.
+    pending_step: Option<PendingStep>,
+}
+
+impl ParityTracer {
+    /// Creates a tracer recording call traces only.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Additionally records the VM trace of the transaction.
+    pub const fn with_vm_trace(mut self) -> Self {
+        self.record_vm_trace = true;
+        self
+    }
+
+    /// Returns the call traces recorded so far.
+    pub fn traces(&self) -> &[TransactionTrace] {
+        &self.traces
+    }
+
+    /// Consumes the tracer, returning the call traces.
+    pub fn into_traces(self) -> Vec<TransactionTrace> {
+        self.traces
+    }
+
+    /// Consumes the tracer, returning the call traces localized to the given transaction.
+    pub fn into_localized_traces(self, info: TraceTxInfo) -> Vec<LocalizedTransactionTrace> {
+        self.traces.into_iter().map(|trace| info.localize(trace)).collect()
+    }
+
+    /// Consumes the tracer, returning the [`TraceResults`] of the transaction.
+    ///
+    /// The state diff is computed separately with [`state_diff`], from the state changes of the
+    /// transaction before they are committed.
+    pub fn into_trace_results<H>(
+        self,
+        result: &ExecutionResult<H>,
+        state_diff: Option<StateDiff>,
+    ) -> TraceResults {
+        TraceResults {
+            output: result.output().cloned().unwrap_or_default(),
+            state_diff,
+            trace: self.traces,
+            vm_trace: self.vm_trace,
+            transaction_hash: None,
+        }
+    }
+
+    /// Starts the trace of a frame with the given action.
+    fn push_trace(&mut self, action: Action) {
+        let trace_address = match self.stack.last() {
+            Some(&parent) => {
+                let parent = &mut self.traces[parent];
+                let mut address = parent.trace_address.clone();
+                address.push(parent.subtraces);
+                parent.subtraces += 1;
+                address
+            }
+            None => Vec::new(),
+        };
+        self.stack.push(self.traces.len());
+        self.traces.push(TransactionTrace {
+            action,
+            error: None,
+            result: None,
+            subtraces: 0,
+            trace_address,
+        });
+    }
+
+    /// Ends the trace of the innermost frame.
+    fn pop_trace(&mut self, result: &InterpreterResult, output: impl FnOnce(u64) -> TraceOutput) {
+        let Some(index) = self.stack.pop() else { return };
+        let trace = &mut self.traces[index];
+        if result.is_ok() {
+            trace.result = Some(output(result.gas.spent()));
+        } else {
+            trace.error = Some(parity_error(result.result));
+        }
+    }
+
+    /// Starts the VM trace of a frame.
+    fn push_vm_trace(&mut self) {
+        if self.record_vm_trace {
+            self.vm_stack.push(VmTrace { code: Bytes::new(), ops: Vec::new() });
+        }
+    }
+
+    /// Ends the VM trace of the innermost frame, attaching it to the instruction that started it.
+    fn pop_vm_trace(&mut self) {
+        let Some(trace) = self.vm_stack.pop() else { return };
+        match self.vm_stack.last_mut().and_then(|parent| parent.ops.last_mut()) {
+            Some(op) => op.sub = Some(trace),
+            None => self.vm_trace = Some(trace),
+        }
+    }
+}
+
+impl<CTX: ContextTr> Inspector<CTX> for ParityTracer {
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut CTX) {
+        let Some(trace) = self.vm_stack.last_mut() else { return };
+        if trace.ops.is_empty() {
+            trace.code = interp.bytecode.original_bytes();
+        }
+
+        let opcode = interp.bytecode.opcode();
+        let store = match (opcode, interp.stack.data().as_slice()) {
+            (SSTORE, [.., value, key]) => Some(StorageDelta { key: *key, val: *value }),
+            _ => None,
+        };
+        let op = OpCode::new(opcode);
+        self.pending_step = Some(PendingStep {
+            gas: interp.gas.remaining(),
+            outputs: op.map_or(0, |op| op.outputs() as usize),
+            store,
+        });
+
+        trace.ops.push(VmInstruction {
+            pc: interp.bytecode.pc(),
+            cost: 0,
+            ex: None,
+            sub: None,
+            op: op.map(|op| op.as_str().into()),
+            idx: None,
+        });
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, _context: &mut CTX) {
+        let Some(step) = self.pending_step.take() else { return };
+        let Some(op) = self.vm_stack.last_mut().and_then(|trace| trace.ops.last_mut()) else {
+            return;
+        };
+
+        let used = interp.gas.remaining();
+        let stack = interp.stack.data();
+        op.cost = step.gas.saturating_sub(used);
+        op.ex = Some(VmExecutedOperation {
+            used,
+            push: stack[stack.len().saturating_sub(step.outputs)..].to_vec(),
+            mem: None,
+            store: step.store,
+        });
+    }
+
+    fn call(&mut self, context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        let call_type = match inputs.scheme {
+            CallScheme::Call => CallType::Call,
+            CallScheme::CallCode => CallType::CallCode,
+            CallScheme::DelegateCall => CallType::DelegateCall,
+            CallScheme::StaticCall => CallType::StaticCall,
+        };
+        let from = match inputs.scheme {
+            CallScheme::DelegateCall | CallScheme::CallCode => inputs.target_address,
+            _ => inputs.caller,
+        };
+        self.push_trace(Action::Call(CallAction {
+            from,
+            call_type,
+            gas: inputs.gas_limit,
+            input: inputs.input.bytes(context),
+            to: inputs.bytecode_address,
+            value: inputs.call_value(),
+        }));
+        self.push_vm_trace();
+        None
+    }
+
+    fn call_end(&mut self, _context: &mut CTX, _inputs: &CallInputs, outcome: &mut CallOutcome) {
+        let output = outcome.result.output.clone();
+        self.pop_trace(&outcome.result, |gas_used| {
+            TraceOutput::Call(CallOutput { gas_used, output })
+        });
+        self.pop_vm_trace();
+    }
+
+    fn create(&mut self, _context: &mut CTX, inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        let creation_method = match inputs.scheme() {
+            CreateScheme::Create2 { .. } => CreationMethod::Create2,
+            _ => CreationMethod::Create,
+        };
+        self.push_trace(Action::Create(CreateAction {
+            from: inputs.caller(),
+            gas: inputs.gas_limit(),
+            init: inputs.init_code().clone(),
+            value: inputs.value(),
+            creation_method,
+        }));
+        self.push_vm_trace();
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut CTX,
+        _inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        let address = outcome.address.unwrap_or_default();
+        let code = outcome.result.output.clone();
+        self.pop_trace(&outcome.result, |gas_used| {
+            TraceOutput::Create(CreateOutput { address, code, gas_used })
+        });
+        self.pop_vm_trace();
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        self.push_trace(Action::Selfdestruct(SelfdestructAction {
+            address: contract,
+            balance: value,
+            refund_address: target,
+        }));
+        self.stack.pop();
+    }
+}
+
+/// Returns the error message Parity reports for a failed frame.
+fn parity_error(result: InstructionResult) -> String {
+    match result {
+        InstructionResult::Revert => "Reverted".into(),
+        InstructionResult::OutOfGas
+        | InstructionResult::MemoryOOG
+        | InstructionResult::MemoryLimitOOG
+        | InstructionResult::PrecompileOOG
+        | InstructionResult::InvalidOperandOOG => "Out of gas".into(),
+        InstructionResult::OpcodeNotFound | InstructionResult::InvalidFEOpcode => {
+            "Bad instruction".into()
+        }
+        InstructionResult::InvalidJump => "Bad jump destination".into(),
+        InstructionResult::StackUnderflow => "Stack underflow".into(),
+        InstructionResult::StackOverflow => "Out of stack".into(),
+        result => format!("{result:?}"),
+    }
+}
+
+/// Computes the Parity state diff of a transaction.
+///
+/// `state` are the state changes of the transaction and `db` the database it was executed on,
+/// before the changes are committed.
+pub fn state_diff<DB: Database>(state: &EvmState, db: &mut DB) -> Result<StateDiff, DB::Error> {
+    let mut diff = StateDiff::default();
+    for (address, account) in state {
+        if !account.is_touched() {
+            continue;
+        }
+        let pre = db.basic(*address)?.filter(|info| !info.is_empty());
+        let pre_code = match &pre {
+            Some(info) => code(info, db)?,
+            None => Bytes::new(),
+        };
+        let post = (!account.is_selfdestructed() && !account.is_empty()).then_some(&account.info);
+        let post_code = match post {
+            Some(info) => code(info, db)?,
+            None => Bytes::new(),
+        };
+
+        let mut account_diff = match (&pre, post) {
+            (None, None) => continue,
+            (None, Some(post)) => AccountDiff {
+                balance: Delta::Added(post.balance),
+                code: Delta::Added(post_code),
+                nonce: Delta::Added(U64::from(post.nonce)),
+                storage: Default::default(),
+            },
+            (Some(pre), None) => AccountDiff {
+                balance: Delta::Removed(pre.balance),
+                code: Delta::Removed(pre_code),
+                nonce: Delta::Removed(U64::from(pre.nonce)),
+                storage: Default::default(),
+            },
+            (Some(pre), Some(post)) => AccountDiff {
+                balance: delta(pre.balance, post.balance),
+                code: delta(pre_code, post_code),
+                nonce: delta(U64::from(pre.nonce), U64::from(post.nonce)),
+                storage: Default::default(),
+            },
+        };
+
+        for (slot, value) in &account.storage {
+            let (from, to) = (value.original_value(), value.present_value());
+            let delta = match (pre.is_some(), post.is_some()) {
+                (false, true) if !to.is_zero() => Delta::Added(to.into()),
+                (true, false) if !from.is_zero() => Delta::Removed(from.into()),
+                (true, true) if from != to => {
+                    Delta::Changed(ChangedType { from: from.into(), to: to.into() })
+                }
+                _ => continue,
+            };
+            account_diff.storage.insert((*slot).into(), delta);
+        }
+
+        let unchanged = matches!(account_diff.balance, Delta::Unchanged)
+            && matches!(account_diff.code, Delta::Unchanged)
+            && matches!(account_diff.nonce, Delta::Unchanged)
+            && account_diff.storage.is_empty();
+        if !unchanged {
+            diff.0.insert(*address, account_diff);
+        }
+    }
+    Ok(diff)
+}
+
+/// Returns the code of an account, loading it from the database if necessary.
+fn code<DB: Database>(info: &AccountInfo, db: &mut DB) -> Result<Bytes, DB::Error> {
+    if info.code_hash == KECCAK_EMPTY {
+        return Ok(Bytes::new());
+    }
+    Ok(match &info.code {
+        Some(code) => code.original_bytes(),
+        None => db.code_by_hash(info.code_hash)?.original_bytes(),
+    })
+}
+
+/// Returns the delta between two values.
+fn delta<T: PartialEq>(from: T, to: T) -> Delta<T> {
+    if from == to {
+        Delta::Unchanged
+    } else {
+        Delta::Changed(ChangedType { from, to })
+    }
+}
+
+/// Returns the reward pseudo-traces of a block before the merge.
+///
+/// Parity reports block and ommer rewards as traces without transaction. No traces are returned
+/// for blocks after the merge, which don't pay rewards.
+pub fn reward_traces<H: BlockHeader>(
+    spec: impl EthereumHardforks,
+    block_hash: B256,
+    header: &H,
+    ommers: &[H],
+) -> Vec<LocalizedTransactionTrace> {
+    let Some(base_reward) = base_block_reward(spec, header.number()) else {
+        return Vec::new();
+    };
+    let info = TraceTxInfo {
+        block_hash: Some(block_hash),
+        block_number: Some(header.number()),
+        ..Default::default()
+    };
+    let reward = |author, reward_type, value: u128| {
+        info.localize(TransactionTrace {
+            action: Action::Reward(RewardAction { author, reward_type, value: U256::from(value) }),
+            error: None,
+            result: None,
+            subtraces: 0,
+            trace_address: Vec::new(),
+        })
+    };
+
+    let mut traces = Vec::with_capacity(ommers.len() + 1);
+    traces.push(reward(
+        header.beneficiary(),
+        RewardType::Block,
+        block_reward(base_reward, ommers.len()),
+    ));
+    traces.extend(ommers.iter().map(|ommer| {
+        reward(
+            ommer.beneficiary(),
+            RewardType::Uncle,
+            ommer_reward(base_reward, header.number(), ommer.number()),
+        )
+    }));
+    traces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EthEvmFactory, Evm, EvmEnv, EvmFactory};
+    use alloy_consensus::{constants::ETH_TO_WEI, Header};
+    use alloy_hardforks::EthereumChainHardforks;
+    use alloy_primitives::{bytes, TxKind};
+    use revm::{
+        bytecode::Bytecode,
+        context::TxEnv,
+        database::{CacheDB, EmptyDB},
+    };
+
+    const SENDER: Address = Address::repeat_byte(0x01);
+    const CONTRACT: Address = Address::repeat_byte(0x02);
+
+    #[test]
+    fn test_parity_traces() {
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            SENDER,
+            AccountInfo { balance: U256::from(10), ..Default::default() },
+        );
+        // SSTORE(0, 1), then CALL(gas, 0x03, 0, 0, 0, 0, 0)
+        let code = bytes!("600160005560006000600060006000600361fffff100");
+        db.insert_account_info(CONTRACT, AccountInfo::default().with_code(Bytecode::new_raw(code)));
+
+        let mut tracer = ParityTracer::new().with_vm_trace();
+        let mut evm =
+            EthEvmFactory.create_evm_with_inspector(&mut db, EvmEnv::default(), &mut tracer);
+        let tx = TxEnv::builder()
+            .caller(SENDER)
+            .kind(TxKind::Call(CONTRACT))
+            .value(U256::from(1))
+            .gas_limit(1_000_000)
+            .gas_price(0)
+            .build()
+            .unwrap();
+        let result = evm.transact(tx).unwrap();
+        drop(evm);
+
+        let traces = tracer.traces();
+        assert_eq!(traces.len(), 2);
+        assert_eq!(traces[0].subtraces, 1);
+        assert_eq!(traces[1].trace_address, [0]);
+        let Action::Call(call) = &traces[1].action else { panic!("expected call") };
+        assert_eq!((call.from, call.to), (CONTRACT, Address::with_last_byte(3)));
+
+        let diff = state_diff(&result.state, &mut db).unwrap();
+        assert_eq!(
+            diff.0[&SENDER].balance,
+            Delta::Changed(ChangedType { from: U256::from(10), to: U256::from(9) })
+        );
+        assert_eq!(
+            diff.0[&CONTRACT].storage[&B256::ZERO],
+            Delta::Changed(ChangedType { from: B256::ZERO, to: B256::with_last_byte(1) })
+        );
+
+        let results = tracer.into_trace_results(&result.result, Some(diff));
+        let vm_trace = results.vm_trace.unwrap();
+        assert_eq!(vm_trace.ops[0].op.as_deref(), Some("PUSH1"));
+        assert_eq!(vm_trace.ops[0].ex.as_ref().unwrap().push, [U256::from(1)]);
+        assert!(vm_trace.ops.iter().any(|op| op.sub.is_some()));
+    }
+
+    #[test]
+    fn test_reward_traces() {
+        let header = Header { number: 126, beneficiary: SENDER, ..Default::default() };
+        let ommer = Header { number: 125, beneficiary: CONTRACT, ..Default::default() };
+        let traces = reward_traces(
+            EthereumChainHardforks::mainnet(),
+            B256::ZERO,
+            &header,
+            core::slice::from_ref(&ommer),
+        );
+
+        assert_eq!(traces.len(), 2);
+        let Action::Reward(block) = &traces[0].trace.action else { panic!("expected reward") };
+        assert_eq!(block.value, U256::from(ETH_TO_WEI * 5 + ((ETH_TO_WEI * 5) >> 5)));
+        let Action::Reward(uncle) = &traces[1].trace.action else { panic!("expected reward") };
+        assert_eq!(uncle.author, CONTRACT);
+        assert_eq!(uncle.reward_type, RewardType::Uncle);
+        assert_eq!(uncle.value, U256::from(ETH_TO_WEI * 5 * 7 / 8));
+
+        let header = Header { number: 15_537_394, ..Default::default() };
+        assert!(
+            reward_traces(EthereumChainHardforks::mainnet(), B256::ZERO, &header, &[]).is_empty()
+        );
+    }
+}
@@ -0,0 +1,278 @@
+//! Conversion of receipts into RPC receipts.
+
+use alloc::vec::Vec;
+use alloy_consensus::{
+    transaction::Recovered, BlockHeader, Receipt, ReceiptEnvelope, ReceiptWithBloom, Transaction,
+    TxReceipt,
+};
+use alloy_eips::{eip2718::Encodable2718, eip7840::BlobParams};
+use alloy_primitives::{Log as PrimitiveLog, TxKind, B256};
+use alloy_rpc_types_eth::{Log, TransactionReceipt};
+
+/// Block a receipt belongs to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReceiptBlockInfo {
+    /// Hash of the block.
+    pub hash: B256,
+    /// Number of the block.
+    pub number: u64,
+    /// Timestamp of the block.
+    pub timestamp: u64,
+    /// Base fee of the block, used to compute the effective gas price.
+    pub base_fee: Option<u64>,
+    /// Blob gas price of the block, reported for blob transactions.
+    pub blob_gas_price: Option<u128>,
+}
+
+impl ReceiptBlockInfo {
+    /// Creates the block info from the hash and header of the block.
+    ///
+    /// `blob_params` are the blob parameters active at the block and are used to compute the blob
+    /// gas price.
+    pub fn from_header(
+        hash: B256,
+        header: &impl BlockHeader,
+        blob_params: Option<BlobParams>,
+    ) -> Self {
+        Self {
+            hash,
+            number: header.number(),
+            timestamp: header.timestamp(),
+            base_fee: header.base_fee_per_gas(),
+            blob_gas_price: blob_params.and_then(|params| header.blob_fee(params)),
+        }
+    }
+}
+
+/// Error returned when the cumulative gas used of a receipt is below the one of the preceding
+/// receipt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "cumulative gas used {got} of receipt {index} is below {previous} of the preceding receipt"
+)]
+pub struct CumulativeGasUsedError {
+    /// Index of the receipt in the block.
+    pub index: usize,
+    /// Cumulative gas used of the receipt.
+    pub got: u64,
+    /// Cumulative gas used of the preceding receipt.
+    pub previous: u64,
+}
+
+/// Returns the gas used by the transaction of the receipt at `index` from its cumulative gas used
+/// and the one of the preceding receipt.
+pub(crate) fn receipt_gas_used(
+    index: usize,
+    cumulative_gas_used: u64,
+    previous: u64,
+) -> Result<u64, CumulativeGasUsedError> {
+    cumulative_gas_used.checked_sub(previous).ok_or(CumulativeGasUsedError {
+        index,
+        got: cumulative_gas_used,
+        previous,
+    })
+}
+
+/// Converts the logs of a transaction into RPC logs, numbering them from `log_index_offset`.
+pub(crate) fn rpc_logs(
+    logs: Vec<PrimitiveLog>,
+    block_info: &ReceiptBlockInfo,
+    tx_hash: B256,
+    tx_index: u64,
+    log_index_offset: u64,
+) -> Vec<Log> {
+    logs.into_iter()
+        .enumerate()
+        .map(|(index, inner)| Log {
+            inner,
+            block_hash: Some(block_info.hash),
+            block_number: Some(block_info.number),
+            block_timestamp: Some(block_info.timestamp),
+            transaction_hash: Some(tx_hash),
+            transaction_index: Some(tx_index),
+            log_index: Some(log_index_offset + index as u64),
+            removed: false,
+        })
+        .collect()
+}
+
+/// Builds the chain agnostic fields of an RPC receipt around `inner`.
+pub(crate) fn rpc_receipt<T, R>(
+    tx: &Recovered<T>,
+    inner: R,
+    gas_used: u64,
+    block_info: &ReceiptBlockInfo,
+    tx_index: u64,
+) -> TransactionReceipt<R>
+where
+    T: Transaction + Encodable2718,
+{
+    let from = tx.signer();
+    let (to, contract_address) = match tx.kind() {
+        TxKind::Call(to) => (Some(to), None),
+        TxKind::Create => (None, Some(from.create(tx.nonce()))),
+    };
+    let blob_gas_used = tx.blob_gas_used().filter(|gas| *gas > 0);
+
+    TransactionReceipt {
+        inner,
+        transaction_hash: tx.trie_hash(),
+        transaction_index: Some(tx_index),
+        block_hash: Some(block_info.hash),
+        block_number: Some(block_info.number),
+        gas_used,
+        effective_gas_price: tx.effective_gas_price(block_info.base_fee),
+        blob_gas_used,
+        blob_gas_price: blob_gas_used.and(block_info.blob_gas_price),
+        from,
+        to,
+        contract_address,
+    }
+}
+
+/// Converts the receipts of a block into RPC receipts using `f`, which is invoked with the
+/// transaction, its receipt, the gas used by it, its index and the index of its first log.
+pub(crate) fn map_block_receipts<'a, T, R, O>(
+    transactions: impl IntoIterator<Item = &'a Recovered<T>>,
+    receipts: impl IntoIterator<Item = R>,
+    mut f: impl FnMut(&'a Recovered<T>, R, u64, u64, u64) -> O,
+) -> Result<Vec<O>, CumulativeGasUsedError>
+where
+    T: 'a,
+    R: TxReceipt,
+{
+    let mut cumulative_gas_used = 0;
+    let mut log_index_offset = 0;
+    transactions
+        .into_iter()
+        .zip(receipts)
+        .enumerate()
+        .map(|(tx_index, (tx, receipt))| {
+            let gas_used =
+                receipt_gas_used(tx_index, receipt.cumulative_gas_used(), cumulative_gas_used)?;
+            cumulative_gas_used = receipt.cumulative_gas_used();
+            let offset = log_index_offset;
+            log_index_offset += receipt.logs().len() as u64;
+            Ok(f(tx, receipt, gas_used, tx_index as u64, offset))
+        })
+        .collect()
+}
+
+/// Converts the receipt of an Ethereum transaction into an RPC receipt.
+///
+/// `gas_used` is the gas used by the transaction alone, `tx_index` its index in the block and
+/// `log_index_offset` the number of logs emitted by the preceding transactions of the block. See
+/// [`into_rpc_receipts`] to convert all receipts of a block at once.
+pub fn into_rpc_receipt<T>(
+    tx: &Recovered<T>,
+    receipt: ReceiptEnvelope,
+    gas_used: u64,
+    block_info: &ReceiptBlockInfo,
+    tx_index: u64,
+    log_index_offset: u64,
+) -> TransactionReceipt
+where
+    T: Transaction + Encodable2718,
+{
+    let tx_type = receipt.tx_type();
+    let status = receipt.status_or_post_state();
+    let cumulative_gas_used = receipt.cumulative_gas_used();
+    let logs_bloom = receipt.bloom();
+    let logs =
+        rpc_logs(receipt.into_logs(), block_info, tx.trie_hash(), tx_index, log_index_offset);
+
+    let inner = ReceiptEnvelope::from_typed(
+        tx_type,
+        ReceiptWithBloom::new(Receipt { status, cumulative_gas_used, logs }, logs_bloom),
+    );
+    rpc_receipt(tx, inner, gas_used, block_info, tx_index)
+}
+
+/// Converts the receipts of all transactions of an Ethereum block into RPC receipts.
+///
+/// Receipts are matched with the transactions in order, the gas used by each transaction and the
+/// log indices are derived from the preceding receipts. Fails if the cumulative gas used of a
+/// receipt is below the one of the preceding receipt.
+pub fn into_rpc_receipts<'a, T>(
+    block_info: &ReceiptBlockInfo,
+    transactions: impl IntoIterator<Item = &'a Recovered<T>>,
+    receipts: impl IntoIterator<Item = ReceiptEnvelope>,
+) -> Result<Vec<TransactionReceipt>, CumulativeGasUsedError>
+where
+    T: Transaction + Encodable2718 + 'a,
+{
+    map_block_receipts(transactions, receipts, |tx, receipt, gas_used, tx_index, offset| {
+        into_rpc_receipt(tx, receipt, gas_used, block_info, tx_index, offset)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::{SignableTransaction, TxEip1559, TxEnvelope, TxType};
+    use alloy_primitives::{Address, Bytes, LogData, Signature};
+
+    fn tx(nonce: u64, kind: TxKind) -> Recovered<TxEnvelope> {
+        let tx = TxEip1559 {
+            nonce,
+            to: kind,
+            max_fee_per_gas: 3,
+            max_priority_fee_per_gas: 1,
+            ..Default::default()
+        };
+        Recovered::new_unchecked(
+            tx.into_signed(Signature::test_signature()).into(),
+            Address::repeat_byte(0x01),
+        )
+    }
+
+    fn receipt(cumulative_gas_used: u64, logs: usize) -> ReceiptEnvelope {
+        let log = PrimitiveLog {
+            address: Address::ZERO,
+            data: LogData::new_unchecked(Vec::new(), Bytes::new()),
+        };
+        let receipt =
+            Receipt { status: true.into(), cumulative_gas_used, logs: alloc::vec![log; logs] };
+        ReceiptEnvelope::from_typed(TxType::Eip1559, receipt.into_with_bloom())
+    }
+
+    #[test]
+    fn test_into_rpc_receipts() {
+        let block_info = ReceiptBlockInfo {
+            hash: B256::repeat_byte(0xaa),
+            number: 10,
+            timestamp: 100,
+            base_fee: Some(2),
+            blob_gas_price: Some(1),
+        };
+        let txs = [tx(0, TxKind::Call(Address::repeat_byte(0x02))), tx(1, TxKind::Create)];
+        let receipts =
+            into_rpc_receipts(&block_info, &txs, [receipt(21_000, 2), receipt(71_000, 3)]).unwrap();
+
+        assert_eq!(receipts[0].gas_used, 21_000);
+        assert_eq!(receipts[1].gas_used, 50_000);
+        assert_eq!(receipts[0].effective_gas_price, 3);
+        assert_eq!(receipts[0].blob_gas_price, None);
+        assert_eq!(receipts[1].transaction_index, Some(1));
+        assert_eq!(receipts[1].contract_address, Some(Address::repeat_byte(0x01).create(1)));
+
+        let log_indices: Vec<_> = receipts
+            .iter()
+            .flat_map(|receipt| receipt.inner.logs().iter().map(|log| log.log_index))
+            .collect();
+        assert_eq!(log_indices, [Some(0), Some(1), Some(2), Some(3), Some(4)]);
+        assert_eq!(receipts[1].inner.logs()[0].transaction_hash, Some(txs[1].trie_hash()));
+    }
+
+    #[test]
+    fn test_into_rpc_receipts_decreasing_gas() {
+        let txs = [tx(0, TxKind::Create), tx(1, TxKind::Create)];
+        let err = into_rpc_receipts(
+            &ReceiptBlockInfo::default(),
+            &txs,
+            [receipt(71_000, 0), receipt(21_000, 0)],
+        )
+        .unwrap_err();
+        assert_eq!(err, CumulativeGasUsedError { index: 1, got: 21_000, previous: 71_000 });
+    }
+}
@@ -35,12 +35,42 @@ pub enum EthTxEnvError {
     /// Both data and input fields are set and not equal.
     #[error(transparent)]
     Input(#[from] TransactionInputError),
+    /// The request has more blobs than allowed per transaction.
+    #[error("{count} blobs exceed the maximum of {max} per transaction")]
+    TooManyBlobs {
+        /// Number of blobs of the request.
+        count: usize,
+        /// Maximum number of blobs per transaction.
+        max: u64,
+    },
+    /// The blob versioned hashes of the request don't match its sidecar.
+    #[error("blob versioned hashes don't match the sidecar")]
+    BlobHashesMismatch,
 }
 
 impl<Spec, Block: BlockEnvironment> TryIntoTxEnv<TxEnv, Spec, Block> for TransactionRequest {
     type Err = EthTxEnvError;
 
-    fn try_into_tx_env(self, evm_env: &EvmEnv<Spec, Block>) -> Result<TxEnv, Self::Err> {
+    fn try_into_tx_env(mut self, evm_env: &EvmEnv<Spec, Block>) -> Result<TxEnv, Self::Err> {
+        // Derive the versioned hashes from the sidecar if they're missing
+        if let Some(sidecar) = &self.sidecar {
+            match &self.blob_versioned_hashes {
+                Some(hashes) if !hashes.iter().copied().eq(sidecar.versioned_hashes()) => {
+                    return Err(EthTxEnvError::BlobHashesMismatch);
+                }
+                Some(_) => {}
+                None => self.populate_blob_hashes(),
+            }
+        }
+
+        if let (Some(hashes), Some(max)) =
+            (&self.blob_versioned_hashes, evm_env.cfg_env().max_blobs_per_tx)
+        {
+            if hashes.len() as u64 > max {
+                return Err(EthTxEnvError::TooManyBlobs { count: hashes.len(), max });
+            }
+        }
+
         // Ensure that if versioned hashes are set, they're not empty
         if self.blob_versioned_hashes.as_ref().is_some_and(|hashes| hashes.is_empty()) {
             return Err(CallFeesError::BlobTransactionMissingBlobHashes.into());
@@ -121,3 +151,38 @@ impl<Spec, Block: BlockEnvironment> TryIntoTxEnv<TxEnv, Spec, Block> for Transac
         Ok(env)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{vec, vec::Vec};
+    use alloy_consensus::BlobTransactionSidecar;
+
+    #[test]
+    fn test_sidecar_blob_hashes() {
+        let sidecar = BlobTransactionSidecar {
+            blobs: vec![Default::default(); 2],
+            commitments: vec![Default::default(); 2],
+            proofs: vec![Default::default(); 2],
+        };
+        let hashes: Vec<_> = sidecar.versioned_hashes().collect();
+        let request = TransactionRequest {
+            max_fee_per_blob_gas: Some(1),
+            sidecar: Some(sidecar.into()),
+            ..Default::default()
+        };
+        let mut evm_env: EvmEnv = EvmEnv::default();
+
+        let tx: TxEnv = request.clone().try_into_tx_env(&evm_env).unwrap();
+        assert_eq!(tx.blob_hashes, hashes);
+
+        let mismatch =
+            TransactionRequest { blob_versioned_hashes: Some(vec![]), ..request.clone() };
+        let err = mismatch.try_into_tx_env(&evm_env).map(|_: TxEnv| ()).unwrap_err();
+        assert!(matches!(err, EthTxEnvError::BlobHashesMismatch));
+
+        evm_env.cfg_env.max_blobs_per_tx = Some(1);
+        let err = request.try_into_tx_env(&evm_env).map(|_: TxEnv| ()).unwrap_err();
+        assert!(matches!(err, EthTxEnvError::TooManyBlobs { count: 2, max: 1 }));
+    }
+}
@@ -0,0 +1,176 @@
+//! Copy-on-write snapshots of post-block state.
+//!
+//! Serving calls against the latest state usually requires a lock around the [`State`] that the
+//! chain is executed on. [`SnapshotState`] instead keeps the changes of executed blocks in
+//! reference counted maps on top of a shared database: cloning a snapshot is cheap, and advancing
+//! it with [`SnapshotState::apply`] only copies the entries that are touched while other clones
+//! are alive. Readers execute against their own clone while the writer advances the chain.
+//!
+//! [`State`]: revm::database::State
+
+use alloc::sync::Arc;
+use alloy_primitives::{
+    map::{AddressMap, B256Map, HashMap},
+    Address, B256, U256,
+};
+use revm::{
+    bytecode::Bytecode, database::BundleState, database_interface::DatabaseRef, state::AccountInfo,
+    Database,
+};
+
+/// An account changed on top of the database of a [`SnapshotState`].
+#[derive(Debug, Clone, Default)]
+struct SnapshotAccount {
+    /// The account info, `None` if the account doesn't exist.
+    info: Option<AccountInfo>,
+    /// Changed storage slots.
+    storage: HashMap<U256, U256>,
+    /// Whether `storage` holds all non-zero slots, e.g. because the account was destroyed.
+    storage_known: bool,
+}
+
+/// A cheaply clonable view of the state after a block.
+///
+/// Reads are served from the changes applied with [`SnapshotState::apply`] first, falling back to
+/// the shared database. Clones are not affected by changes applied to other clones, so a writer
+/// can advance its snapshot while readers execute against the ones they hold. To share snapshots
+/// between threads, the database has to be `Send + Sync`.
+#[derive(Debug)]
+pub struct SnapshotState<DB> {
+    db: Arc<DB>,
+    accounts: Arc<AddressMap<Arc<SnapshotAccount>>>,
+    contracts: Arc<B256Map<Bytecode>>,
+}
+
+impl<DB> Clone for SnapshotState<DB> {
+    fn clone(&self) -> Self {
+        Self {
+            db: self.db.clone(),
+            accounts: self.accounts.clone(),
+            contracts: self.contracts.clone(),
+        }
+    }
+}
+
+impl<DB> SnapshotState<DB> {
+    /// Creates a snapshot of the state in the database.
+    pub fn new(db: DB) -> Self {
+        Self::with_shared_database(Arc::new(db))
+    }
+
+    /// Creates a snapshot of the state in a database that is shared with other components.
+    pub fn with_shared_database(db: Arc<DB>) -> Self {
+        Self { db, accounts: Default::default(), contracts: Default::default() }
+    }
+
+    /// Returns the underlying database.
+    pub const fn database(&self) -> &Arc<DB> {
+        &self.db
+    }
+
+    /// Returns the number of accounts changed on top of the database.
+    pub fn changed_accounts(&self) -> usize {
+        self.accounts.len()
+    }
+
+    /// Applies the changes of a block, e.g. the bundle of a [`State`](revm::database::State) after
+    /// executing it.
+    ///
+    /// Other clones of this snapshot keep their view of the state.
+    pub fn apply(&mut self, bundle: &BundleState) {
+        let accounts = Arc::make_mut(&mut self.accounts);
+        for (address, bundle_account) in &bundle.state {
+            let account = Arc::make_mut(accounts.entry(*address).or_default());
+            if bundle_account.was_destroyed() || bundle_account.info.is_none() {
+                account.storage.clear();
+                account.storage_known = true;
+            }
+            account.info = bundle_account.info.clone();
+            account.storage.extend(
+                bundle_account.storage.iter().map(|(slot, value)| (*slot, value.present_value)),
+            );
+        }
+
+        if !bundle.contracts.is_empty() {
+            Arc::make_mut(&mut self.contracts)
+                .extend(bundle.contracts.iter().map(|(hash, code)| (*hash, code.clone())));
+        }
+    }
+}
+
+impl<DB: DatabaseRef> DatabaseRef for SnapshotState<DB> {
+    type Error = DB::Error;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        match self.accounts.get(&address) {
+            Some(account) => Ok(account.info.clone()),
+            None => self.db.basic_ref(address),
+        }
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        match self.contracts.get(&code_hash) {
+            Some(code) => Ok(code.clone()),
+            None => self.db.code_by_hash_ref(code_hash),
+        }
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let Some(account) = self.accounts.get(&address) else {
+            return self.db.storage_ref(address, index);
+        };
+        match account.storage.get(&index) {
+            Some(value) => Ok(*value),
+            None if account.storage_known => Ok(U256::ZERO),
+            None => self.db.storage_ref(address, index),
+        }
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        self.db.block_hash_ref(number)
+    }
+}
+
+impl<DB: DatabaseRef> Database for SnapshotState<DB> {
+    type Error = DB::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.basic_ref(address)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.code_by_hash_ref(code_hash)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.storage_ref(address, index)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.block_hash_ref(number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::database::{states::bundle_state::BundleRetention, EmptyDB, State};
+
+    #[test]
+    fn test_snapshot_isolation() {
+        let address = Address::repeat_byte(0x01);
+        let mut writer = SnapshotState::new(EmptyDB::default());
+        let reader = writer.clone();
+
+        let mut state = State::builder().with_database(writer.clone()).with_bundle_update().build();
+        state.increment_balances([(address, 7)]).unwrap();
+        state.merge_transitions(BundleRetention::PlainState);
+        writer.apply(&state.take_bundle());
+
+        assert_eq!(writer.changed_accounts(), 1);
+        assert_eq!(writer.basic_ref(address).unwrap().unwrap().balance, U256::from(7));
+        assert_eq!(reader.changed_accounts(), 0);
+        assert_eq!(reader.basic_ref(address).unwrap(), None);
+        assert!(Arc::ptr_eq(writer.database(), reader.database()));
+    }
+}
@@ -0,0 +1,308 @@
+//! Persistent state backed by [`redb`].
+//!
+//! [`RedbDatabase`] stores plain state in four tables: accounts, storage slots, bytecodes and
+//! block hashes. It implements [`Database`] for reads, changes are written in batches with
+//! [`RedbDatabase::commit_bundle`]. Execute blocks on top of a [`State`](revm::database::State)
+//! wrapping the database and commit its [`BundleState`] once done.
+
+use alloy_primitives::{Address, Bytes, B256, KECCAK256_EMPTY, U256};
+use redb::{ReadableTable, TableDefinition, TableHandle, WriteTransaction};
+use revm::{
+    bytecode::Bytecode,
+    database::BundleState,
+    database_interface::{DBErrorMarker, DatabaseRef},
+    state::AccountInfo,
+    Database,
+};
+use std::path::Path;
+
+/// Accounts by address, see [`encode_account`].
+const ACCOUNTS: TableDefinition<&[u8], &[u8]> = TableDefinition::new("accounts");
+/// Storage values by address and slot.
+const STORAGE: TableDefinition<&[u8], &[u8]> = TableDefinition::new("storage");
+/// Bytecodes by code hash.
+const BYTECODES: TableDefinition<&[u8], &[u8]> = TableDefinition::new("bytecodes");
+/// Block hashes by block number.
+const BLOCK_HASHES: TableDefinition<u64, &[u8]> = TableDefinition::new("block_hashes");
+
+/// Errors returned by [`RedbDatabase`].
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    /// The underlying database failed.
+    #[error(transparent)]
+    Redb(#[from] redb::Error),
+    /// A stored value could not be decoded.
+    #[error("corrupted {0} entry")]
+    Corrupted(String),
+    /// No bytecode is stored for the code hash of an account.
+    #[error("missing bytecode for code hash {0}")]
+    MissingCode(B256),
+}
+
+impl DBErrorMarker for StorageError {}
+
+/// Converts any of the error types of [`redb`] into a [`StorageError`].
+fn redb_err(err: impl Into<redb::Error>) -> StorageError {
+    StorageError::Redb(err.into())
+}
+
+/// A [`Database`] persisting plain state with [`redb`].
+#[derive(Debug)]
+pub struct RedbDatabase {
+    db: redb::Database,
+}
+
+impl RedbDatabase {
+    /// Opens the database at the given path, creating it if it doesn't exist.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, StorageError> {
+        Self::init(redb::Database::create(path).map_err(redb_err)?)
+    }
+
+    /// Creates a database that is only kept in memory, e.g. for tests.
+    pub fn in_memory() -> Result<Self, StorageError> {
+        let db = redb::Database::builder()
+            .create_with_backend(redb::backends::InMemoryBackend::new())
+            .map_err(redb_err)?;
+        Self::init(db)
+    }
+
+    /// Creates all tables, so that reads never fail because of a missing table.
+    fn init(db: redb::Database) -> Result<Self, StorageError> {
+        let tx = db.begin_write().map_err(redb_err)?;
+        tx.open_table(ACCOUNTS).map_err(redb_err)?;
+        tx.open_table(STORAGE).map_err(redb_err)?;
+        tx.open_table(BYTECODES).map_err(redb_err)?;
+        tx.open_table(BLOCK_HASHES).map_err(redb_err)?;
+        tx.commit().map_err(redb_err)?;
+        Ok(Self { db })
+    }
+
+    /// Runs `f` in a write transaction and commits it.
+    fn write(
+        &self,
+        f: impl FnOnce(&WriteTransaction) -> Result<(), StorageError>,
+    ) -> Result<(), StorageError> {
+        let tx = self.db.begin_write().map_err(redb_err)?;
+        f(&tx)?;
+        tx.commit().map_err(redb_err)
+    }
+
+    /// Inserts an account with its code and storage, e.g. from the genesis allocation.
+    pub fn insert_account(
+        &self,
+        address: Address,
+        info: &AccountInfo,
+        storage: impl IntoIterator<Item = (U256, U256)>,
+    ) -> Result<(), StorageError> {
+        self.write(|tx| {
+            if let Some(code) = &info.code {
+                let mut bytecodes = tx.open_table(BYTECODES).map_err(redb_err)?;
+                bytecodes
+                    .insert(info.code_hash.as_slice(), code.original_byte_slice())
+                    .map_err(redb_err)?;
+            }
+            let mut accounts = tx.open_table(ACCOUNTS).map_err(redb_err)?;
+            accounts
+                .insert(address.as_slice(), encode_account(info).as_slice())
+                .map_err(redb_err)?;
+            let mut slots = tx.open_table(STORAGE).map_err(redb_err)?;
+            for (slot, value) in storage {
+                slots
+                    .insert(
+                        storage_key(address, slot).as_slice(),
+                        value.to_be_bytes::<32>().as_slice(),
+                    )
+                    .map_err(redb_err)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Stores the hash of a block, served by [`DatabaseRef::block_hash_ref`].
+    pub fn insert_block_hash(&self, number: u64, hash: B256) -> Result<(), StorageError> {
+        self.write(|tx| {
+            let mut hashes = tx.open_table(BLOCK_HASHES).map_err(redb_err)?;
+            hashes.insert(number, hash.as_slice()).map_err(redb_err)?;
+            Ok(())
+        })
+    }
+
+    /// Writes the changes of a bundle in a single transaction.
+    ///
+    /// Storage of destroyed accounts is wiped before the new values of the bundle are written.
+    pub fn commit_bundle(&self, bundle: &BundleState) -> Result<(), StorageError> {
+        self.write(|tx| {
+            let mut accounts = tx.open_table(ACCOUNTS).map_err(redb_err)?;
+            let mut slots = tx.open_table(STORAGE).map_err(redb_err)?;
+            let mut bytecodes = tx.open_table(BYTECODES).map_err(redb_err)?;
+
+            for (hash, code) in &bundle.contracts {
+                bytecodes.insert(hash.as_slice(), code.original_byte_slice()).map_err(redb_err)?;
+            }
+
+            for (address, account) in &bundle.state {
+                if account.was_destroyed() || account.info.is_none() {
+                    let start = storage_key(*address, U256::ZERO);
+                    let end = storage_key(*address, U256::MAX);
+                    slots
+                        .retain_in(start.as_slice()..=end.as_slice(), |_, _| false)
+                        .map_err(redb_err)?;
+                }
+                match &account.info {
+                    Some(info) => accounts
+                        .insert(address.as_slice(), encode_account(info).as_slice())
+                        .map(drop),
+                    None => accounts.remove(address.as_slice()).map(drop),
+                }
+                .map_err(redb_err)?;
+
+                for (slot, value) in &account.storage {
+                    let key = storage_key(*address, *slot);
+                    if value.present_value.is_zero() {
+                        slots.remove(key.as_slice()).map_err(redb_err)?;
+                    } else {
+                        slots
+                            .insert(
+                                key.as_slice(),
+                                value.present_value.to_be_bytes::<32>().as_slice(),
+                            )
+                            .map_err(redb_err)?;
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Reads a value from a table keyed by bytes.
+    fn get<T>(
+        &self,
+        table: TableDefinition<'_, &[u8], &[u8]>,
+        key: &[u8],
+        decode: impl FnOnce(&[u8]) -> Option<T>,
+    ) -> Result<Option<T>, StorageError> {
+        let tx = self.db.begin_read().map_err(redb_err)?;
+        let name = table.name().to_owned();
+        let table = tx.open_table(table).map_err(redb_err)?;
+        let Some(value) = table.get(key).map_err(redb_err)? else { return Ok(None) };
+        decode(value.value()).map(Some).ok_or(StorageError::Corrupted(name))
+    }
+}
+
+/// Encodes an account as nonce, balance and code hash.
+fn encode_account(info: &AccountInfo) -> [u8; 72] {
+    let mut encoded = [0; 72];
+    encoded[..8].copy_from_slice(&info.nonce.to_be_bytes());
+    encoded[8..40].copy_from_slice(&info.balance.to_be_bytes::<32>());
+    encoded[40..].copy_from_slice(info.code_hash.as_slice());
+    encoded
+}
+
+/// Decodes an account encoded with [`encode_account`].
+fn decode_account(encoded: &[u8]) -> Option<AccountInfo> {
+    let encoded: &[u8; 72] = encoded.try_into().ok()?;
+    Some(AccountInfo {
+        nonce: u64::from_be_bytes(encoded[..8].try_into().ok()?),
+        balance: U256::from_be_slice(&encoded[8..40]),
+        code_hash: B256::from_slice(&encoded[40..]),
+        ..Default::default()
+    })
+}
+
+/// Returns the key of a storage slot.
+fn storage_key(address: Address, slot: U256) -> [u8; 52] {
+    let mut key = [0; 52];
+    key[..20].copy_from_slice(address.as_slice());
+    key[20..].copy_from_slice(&slot.to_be_bytes::<32>());
+    key
+}
+
+impl DatabaseRef for RedbDatabase {
+    type Error = StorageError;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.get(ACCOUNTS, address.as_slice(), decode_account)
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        if code_hash == KECCAK256_EMPTY {
+            return Ok(Bytecode::default());
+        }
+        let code = self.get(BYTECODES, code_hash.as_slice(), |code| {
+            Bytecode::new_raw_checked(Bytes::copy_from_slice(code)).ok()
+        })?;
+        code.ok_or(StorageError::MissingCode(code_hash))
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let value = self.get(STORAGE, storage_key(address, index).as_slice(), |value| {
+            (value.len() == 32).then(|| U256::from_be_slice(value))
+        })?;
+        Ok(value.unwrap_or_default())
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        let tx = self.db.begin_read().map_err(redb_err)?;
+        let table = tx.open_table(BLOCK_HASHES).map_err(redb_err)?;
+        let Some(hash) = table.get(number).map_err(redb_err)? else { return Ok(B256::ZERO) };
+        B256::try_from(hash.value())
+            .map_err(|_| StorageError::Corrupted(BLOCK_HASHES.name().to_owned()))
+    }
+}
+
+impl Database for RedbDatabase {
+    type Error = StorageError;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.basic_ref(address)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.code_by_hash_ref(code_hash)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.storage_ref(address, index)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.block_hash_ref(number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::bytes;
+    use revm::database::{states::bundle_state::BundleRetention, State};
+
+    #[test]
+    fn test_commit_bundle() {
+        let db = RedbDatabase::in_memory().unwrap();
+        let address = Address::repeat_byte(0x01);
+        let code = Bytecode::new_raw(bytes!("6000"));
+        let info = AccountInfo::default().with_code(code.clone());
+        db.insert_account(address, &info, [(U256::from(1), U256::from(2))]).unwrap();
+        db.insert_block_hash(10, B256::repeat_byte(0x0a)).unwrap();
+
+        let mut state = State::builder().with_database(db).with_bundle_update().build();
+        state.increment_balances([(address, 5)]).unwrap();
+        state.merge_transitions(BundleRetention::PlainState);
+        let bundle = state.take_bundle();
+        let mut db = state.database;
+        db.commit_bundle(&bundle).unwrap();
+
+        let account = db.basic(address).unwrap().unwrap();
+        assert_eq!(account.balance, U256::from(5));
+        assert_eq!(account.code_hash, info.code_hash);
+        assert_eq!(db.code_by_hash(info.code_hash).unwrap(), code);
+        assert_eq!(db.code_by_hash(KECCAK256_EMPTY).unwrap(), Bytecode::default());
+        assert!(matches!(
+            db.code_by_hash(B256::repeat_byte(0x0c)),
+            Err(StorageError::MissingCode(hash)) if hash == B256::repeat_byte(0x0c)
+        ));
+        assert_eq!(db.storage(address, U256::from(1)).unwrap(), U256::from(2));
+        assert_eq!(db.block_hash(10).unwrap(), B256::repeat_byte(0x0a));
+        assert_eq!(db.basic(Address::ZERO).unwrap(), None);
+    }
+}
@@ -0,0 +1,9 @@
+//! Clock used to measure execution time.
+//!
+//! [`std::time::Instant`] panics on `wasm32-unknown-unknown`, so wasm targets use the
+//! browser-backed implementation of `web-time` instead.
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+pub use web_time::Instant;
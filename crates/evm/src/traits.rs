@@ -2,7 +2,7 @@
 
 use crate::Database;
 use alloc::boxed::Box;
-use alloy_primitives::{Address, Bytes, Log, TxKind, B256, U256};
+use alloy_primitives::{Address, Bytes, Log, TxKind, B256, KECCAK256_EMPTY, U256};
 use core::{error::Error, fmt, fmt::Debug};
 use revm::{
     context::{
@@ -10,9 +10,10 @@ use revm::{
         result::InvalidTransaction,
         Block, Cfg, ContextTr, DBErrorMarker, JournalTr,
     },
-    interpreter::{SStoreResult, StateLoad},
-    primitives::{StorageKey, StorageValue},
-    state::{Account, AccountInfo, Bytecode},
+    interpreter::{SStoreResult, SelfDestructResult, StateLoad},
+    primitives::{hardfork::SpecId, StorageKey, StorageValue},
+    state::{Account, AccountInfo, Bytecode, EvmState, TransientStorage},
+    Journal,
 };
 
 /// Erased error type.
@@ -35,6 +36,10 @@ pub enum EvmInternalsError {
     /// Database error.
     #[error(transparent)]
     Database(ErasedError),
+    /// An account could not be created because an account with code or a nonzero nonce already
+    /// exists at its address.
+    #[error("account {0} already exists")]
+    AccountCollision(Address),
 }
 
 impl EvmInternalsError {
@@ -360,6 +365,45 @@ trait EvmInternalsTr: Database<Error = ErasedError> + Debug {
             .map_err(|e| EvmInternalsError::database(e.unwrap_db_error()))
     }
 
+    /// Creates an account with the given balance, nonce and code.
+    ///
+    /// Fails with [`EvmInternalsError::AccountCollision`] if the account already has code or a
+    /// nonzero nonce. The account is marked as created, and all changes are journaled.
+    fn create_account(
+        &mut self,
+        address: Address,
+        info: AccountInfo,
+    ) -> Result<(), EvmInternalsError> {
+        let existing = self.load_account(address)?;
+        if existing.data.info.nonce != 0 || !existing.data.info.is_empty_code_hash() {
+            return Err(EvmInternalsError::AccountCollision(address));
+        }
+
+        let code = match info.code {
+            Some(code) => code,
+            None if info.code_hash != KECCAK256_EMPTY => {
+                self.code_by_hash(info.code_hash).map_err(EvmInternalsError::Database)?
+            }
+            None => Bytecode::default(),
+        };
+
+        let mut account = self.load_account_mut(address)?.data;
+        account.mark_created();
+        account.set_balance(info.balance);
+        account.set_nonce(info.nonce);
+        if info.code_hash != KECCAK256_EMPTY {
+            account.set_code(info.code_hash, code);
+        }
+        account.touch();
+        Ok(())
+    }
+
+    fn selfdestruct(
+        &mut self,
+        address: Address,
+        beneficiary: Address,
+    ) -> Result<StateLoad<SelfDestructResult>, EvmInternalsError>;
+
     fn log(&mut self, log: Log);
 
     fn tload(&mut self, address: Address, key: StorageKey) -> StorageValue;
@@ -436,6 +480,16 @@ where
         self.0.transfer(from, to, balance).map_err(EvmInternalsError::database)
     }
 
+    fn selfdestruct(
+        &mut self,
+        address: Address,
+        beneficiary: Address,
+    ) -> Result<StateLoad<SelfDestructResult>, EvmInternalsError> {
+        self.0
+            .selfdestruct(address, beneficiary, false)
+            .map_err(|e| EvmInternalsError::database(e.unwrap_db_error()))
+    }
+
     fn log(&mut self, log: Log) {
         self.0.log(log);
     }
@@ -461,11 +515,38 @@ where
     }
 }
 
+/// Configuration values captured from the [`Cfg`] of the EVM.
+#[derive(Debug, Clone, Copy)]
+struct CfgInfo {
+    chain_id: u64,
+    spec_id: SpecId,
+    #[cfg(feature = "op")]
+    op_spec_id: Option<op_revm::OpSpecId>,
+    tx_gas_limit_cap: u64,
+    max_code_size: usize,
+    max_initcode_size: usize,
+}
+
+impl CfgInfo {
+    fn new<C: Cfg<Spec: 'static>>(cfg: &C) -> Self {
+        let spec = cfg.spec();
+        Self {
+            chain_id: cfg.chain_id(),
+            #[cfg(feature = "op")]
+            op_spec_id: (&spec as &dyn core::any::Any).downcast_ref().copied(),
+            spec_id: spec.into(),
+            tx_gas_limit_cap: cfg.tx_gas_limit_cap(),
+            max_code_size: cfg.max_code_size(),
+            max_initcode_size: cfg.max_initcode_size(),
+        }
+    }
+}
+
 /// Helper type exposing hooks into EVM and access to evm internal settings.
 pub struct EvmInternals<'a> {
     internals: Box<dyn EvmInternalsTr + 'a>,
     block_env: &'a (dyn Block + 'a),
-    chain_id: u64,
+    cfg: CfgInfo,
     tx_origin: Address,
     tx_env: &'a dyn TransactionTr,
 }
@@ -475,7 +556,7 @@ impl<'a> EvmInternals<'a> {
     pub fn new<T>(
         journal: &'a mut T,
         block_env: &'a dyn Block,
-        cfg_env: &'a impl Cfg,
+        cfg_env: &'a impl Cfg<Spec: 'static>,
         tx_env: &'a dyn TransactionTr,
     ) -> Self
     where
@@ -484,7 +565,7 @@ impl<'a> EvmInternals<'a> {
         Self {
             internals: Box::new(EvmInternalsImpl(journal)),
             block_env,
-            chain_id: cfg_env.chain_id(),
+            cfg: CfgInfo::new(cfg_env),
             tx_origin: tx_env.caller(),
             tx_env,
         }
@@ -493,7 +574,7 @@ impl<'a> EvmInternals<'a> {
     /// Creates a new [`EvmInternals`] instance from a [`ContextTr`].
     pub fn from_context<CTX>(ctx: &'a mut CTX) -> Self
     where
-        CTX: ContextTr<Journal: JournalTr<Database: Database> + Debug>,
+        CTX: ContextTr<Cfg: Cfg<Spec: 'static>, Journal: JournalTr<Database: Database> + Debug>,
     {
         let (block, tx, cfg, journaled_state, ..) = ctx.all_mut();
         Self::new(journaled_state, block, cfg, tx)
@@ -516,7 +597,41 @@ impl<'a> EvmInternals<'a> {
 
     /// Returns the chain ID.
     pub const fn chain_id(&self) -> u64 {
-        self.chain_id
+        self.cfg.chain_id
+    }
+
+    /// Returns the active [`SpecId`].
+    ///
+    /// For chains with a custom spec type, this is the Ethereum spec it maps to.
+    pub const fn spec_id(&self) -> SpecId {
+        self.cfg.spec_id
+    }
+
+    /// Returns the active [`OpSpecId`](op_revm::OpSpecId), or `None` if the EVM is not
+    /// configured with an OP spec.
+    #[cfg(feature = "op")]
+    pub const fn op_spec_id(&self) -> Option<op_revm::OpSpecId> {
+        self.cfg.op_spec_id
+    }
+
+    /// Returns `true` if the active spec is at least `spec_id`.
+    pub const fn is_enabled_in(&self, spec_id: SpecId) -> bool {
+        self.cfg.spec_id.is_enabled_in(spec_id)
+    }
+
+    /// Returns the transaction gas limit cap.
+    pub const fn tx_gas_limit_cap(&self) -> u64 {
+        self.cfg.tx_gas_limit_cap
+    }
+
+    /// Returns the maximum code size.
+    pub const fn max_code_size(&self) -> usize {
+        self.cfg.max_code_size
+    }
+
+    /// Returns the maximum initcode size.
+    pub const fn max_initcode_size(&self) -> usize {
+        self.cfg.max_initcode_size
     }
 
     /// Returns the caller of the top-level call.
@@ -662,6 +777,33 @@ impl<'a> EvmInternals<'a> {
         self.internals.sstore(address, key, value)
     }
 
+    /// Creates an account with the given balance, nonce and code.
+    ///
+    /// The account is marked as created, e.g. so that it can be destroyed after Cancun, and its
+    /// balance, nonce and code are set through the journal, so the creation is reverted together
+    /// with the enclosing call. Returns
+    /// [`EvmInternalsError::AccountCollision`] if the account already has code or a nonzero nonce.
+    pub fn create_account(
+        &mut self,
+        address: Address,
+        info: AccountInfo,
+    ) -> Result<(), EvmInternalsError> {
+        self.internals.create_account(address, info)
+    }
+
+    /// Destroys the account at `address`, transferring its balance to `beneficiary`.
+    ///
+    /// Follows the semantics of the `SELFDESTRUCT` opcode for the active spec, i.e. after Cancun
+    /// the account is only removed if it was created in the same transaction (EIP-6780). The
+    /// destruction is journaled and reverted together with the enclosing call.
+    pub fn selfdestruct(
+        &mut self,
+        address: Address,
+        beneficiary: Address,
+    ) -> Result<StateLoad<SelfDestructResult>, EvmInternalsError> {
+        self.internals.selfdestruct(address, beneficiary)
+    }
+
     /// Logs the log in Journal state.
     pub fn log(&mut self, log: Log) {
         self.internals.log(log);
@@ -701,3 +843,202 @@ impl<'a> fmt::Debug for EvmInternals<'a> {
             .finish_non_exhaustive()
     }
 }
+
+/// Read-only counterpart of [`EvmInternals`].
+///
+/// Reads state already loaded into the journal without requiring exclusive access to it, which
+/// makes it suitable for inspector hooks or gas pre-checks of precompiles. Unlike
+/// [`EvmInternals`], it never reads from the database: accounts and storage slots that were not
+/// loaded by the current transaction yet are reported as `None`.
+#[derive(Clone, Copy)]
+pub struct EvmInternalsRef<'a> {
+    state: &'a EvmState,
+    transient_storage: &'a TransientStorage,
+    block_env: &'a (dyn Block + 'a),
+    cfg: CfgInfo,
+    tx_origin: Address,
+    tx_env: &'a dyn TransactionTr,
+}
+
+impl<'a> EvmInternalsRef<'a> {
+    /// Creates a new [`EvmInternalsRef`] instance.
+    pub fn new(
+        state: &'a EvmState,
+        transient_storage: &'a TransientStorage,
+        block_env: &'a dyn Block,
+        cfg_env: &'a impl Cfg<Spec: 'static>,
+        tx_env: &'a dyn TransactionTr,
+    ) -> Self {
+        Self {
+            state,
+            transient_storage,
+            block_env,
+            cfg: CfgInfo::new(cfg_env),
+            tx_origin: tx_env.caller(),
+            tx_env,
+        }
+    }
+
+    /// Creates a new [`EvmInternalsRef`] instance from a [`ContextTr`] backed by a [`Journal`].
+    pub fn from_context<CTX, DB>(ctx: &'a CTX) -> Self
+    where
+        CTX: ContextTr<Cfg: Cfg<Spec: 'static>, Journal = Journal<DB>>,
+        DB: Database,
+    {
+        let journal = ctx.journal_ref();
+        Self::new(
+            &journal.inner.state,
+            &journal.inner.transient_storage,
+            ctx.block(),
+            ctx.cfg(),
+            ctx.tx(),
+        )
+    }
+
+    /// Returns the  evm's block information.
+    pub const fn block_env(&self) -> impl Block + 'a {
+        self.block_env
+    }
+
+    /// Returns the chain ID.
+    pub const fn chain_id(&self) -> u64 {
+        self.cfg.chain_id
+    }
+
+    /// Returns the active [`SpecId`].
+    ///
+    /// For chains with a custom spec type, this is the Ethereum spec it maps to.
+    pub const fn spec_id(&self) -> SpecId {
+        self.cfg.spec_id
+    }
+
+    /// Returns the active [`OpSpecId`](op_revm::OpSpecId), or `None` if the EVM is not
+    /// configured with an OP spec.
+    #[cfg(feature = "op")]
+    pub const fn op_spec_id(&self) -> Option<op_revm::OpSpecId> {
+        self.cfg.op_spec_id
+    }
+
+    /// Returns `true` if the active spec is at least `spec_id`.
+    pub const fn is_enabled_in(&self, spec_id: SpecId) -> bool {
+        self.cfg.spec_id.is_enabled_in(spec_id)
+    }
+
+    /// Returns the transaction gas limit cap.
+    pub const fn tx_gas_limit_cap(&self) -> u64 {
+        self.cfg.tx_gas_limit_cap
+    }
+
+    /// Returns the maximum code size.
+    pub const fn max_code_size(&self) -> usize {
+        self.cfg.max_code_size
+    }
+
+    /// Returns the maximum initcode size.
+    pub const fn max_initcode_size(&self) -> usize {
+        self.cfg.max_initcode_size
+    }
+
+    /// Returns the caller of the top-level call.
+    pub const fn tx_origin(&self) -> Address {
+        self.tx_origin
+    }
+
+    /// Returns the current transaction information.
+    pub fn tx_env(&self) -> &dyn TransactionTr {
+        self.tx_env
+    }
+
+    /// Returns the account if it is loaded in the journal.
+    pub fn account(&self, address: Address) -> Option<&'a Account> {
+        self.state.get(&address)
+    }
+
+    /// Returns the balance of the account if it is loaded in the journal.
+    pub fn balance(&self, address: Address) -> Option<U256> {
+        self.account(address).map(|account| account.info.balance)
+    }
+
+    /// Returns the nonce of the account if it is loaded in the journal.
+    pub fn nonce(&self, address: Address) -> Option<u64> {
+        self.account(address).map(|account| account.info.nonce)
+    }
+
+    /// Returns the code of the account if the account and its code are loaded in the journal.
+    pub fn code(&self, address: Address) -> Option<&'a Bytecode> {
+        self.account(address)?.info.code.as_ref()
+    }
+
+    /// Returns the current value of the storage slot if it is loaded in the journal.
+    pub fn sload(&self, address: Address, key: StorageKey) -> Option<StorageValue> {
+        self.account(address)?.storage.get(&key).map(|slot| slot.present_value)
+    }
+
+    /// Returns the transient storage value.
+    pub fn tload(&self, address: Address, key: StorageKey) -> StorageValue {
+        self.transient_storage.get(&(address, key)).copied().unwrap_or_default()
+    }
+}
+
+impl<'a> fmt::Debug for EvmInternalsRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EvmInternalsRef")
+            .field("state", &self.state)
+            .field("block_env", &"{{}}")
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::EthEvmContext;
+    use alloy_primitives::Bytes;
+    use revm::database::EmptyDB;
+
+    const ACCOUNT: Address = Address::repeat_byte(0x01);
+
+    fn info() -> AccountInfo {
+        AccountInfo::default()
+            .with_balance(U256::from(10))
+            .with_nonce(5)
+            .with_code(Bytecode::new_raw(Bytes::from_static(&[0x00])))
+    }
+
+    #[test]
+    fn test_create_account() {
+        let mut ctx = EthEvmContext::new(EmptyDB::default(), Default::default());
+        let mut internals = EvmInternals::from_context(&mut ctx);
+
+        internals.create_account(ACCOUNT, info()).unwrap();
+        let account = internals.load_account(ACCOUNT).unwrap().data;
+        assert!(account.is_created());
+        assert!(account.is_touched());
+        assert_eq!(account.info.balance, U256::from(10));
+        assert_eq!(account.info.nonce, 5);
+        assert_eq!(account.info.code_hash, info().code_hash);
+
+        assert!(matches!(
+            internals.create_account(ACCOUNT, info()),
+            Err(EvmInternalsError::AccountCollision(address)) if address == ACCOUNT
+        ));
+    }
+
+    #[test]
+    fn test_create_account_reverted() {
+        let mut ctx = EthEvmContext::new(EmptyDB::default(), Default::default());
+        let mut internals = EvmInternals::from_context(&mut ctx);
+
+        let checkpoint = internals.checkpoint();
+        internals.create_account(ACCOUNT, info()).unwrap();
+        internals.checkpoint_revert(checkpoint);
+
+        let account = internals.load_account(ACCOUNT).unwrap().data;
+        assert!(!account.is_created());
+        assert_eq!(account.info.nonce, 0);
+        assert!(account.info.is_empty_code_hash());
+
+        // The reverted account can be created again.
+        internals.create_account(ACCOUNT, info()).unwrap();
+    }
+}
@@ -6,15 +6,18 @@
 
 use alloc::sync::Arc;
 use alloy_consensus::{
-    crypto::secp256k1, transaction::Recovered, EthereumTxEnvelope, Signed, TxEip1559, TxEip2930,
-    TxEip4844, TxEip4844Variant, TxEip7702, TxLegacy,
+    crypto::{secp256k1, RecoveryError},
+    transaction::{Recovered, SignerRecoverable},
+    EthereumTxEnvelope, Signed, TxEip1559, TxEip2930, TxEip4844, TxEip4844Variant, TxEip7702,
+    TxLegacy,
 };
 use alloy_eips::{
-    eip2718::WithEncoded,
+    eip2718::{Decodable2718, Eip2718Error, WithEncoded},
     eip7702::{RecoveredAuthority, RecoveredAuthorization},
     Typed2718,
 };
 use alloy_primitives::{Address, Bytes, TxKind};
+use core::ops::Range;
 use revm::{context::TxEnv, context_interface::either::Either};
 
 /// Trait marking types that can be converted into a transaction environment.
@@ -497,6 +500,22 @@ impl<T, TxEnv: FromTxWithEncoded<T>> ToTxEnv<TxEnv> for WithEncoded<&Recovered<T
     }
 }
 
+/// Pairs transactions with their encodings in `buffer`, e.g. the RLP of a block body.
+///
+/// The encodings are slices sharing the allocation of `buffer`, so no bytes are copied. The
+/// returned transactions can be passed to
+/// [`BlockExecutor::execute_transactions`](crate::block::BlockExecutor::execute_transactions).
+///
+/// # Panics
+///
+/// Panics if a range is out of the bounds of `buffer`.
+pub fn with_encoded_slices<'a, T: 'a>(
+    buffer: &'a Bytes,
+    transactions: impl IntoIterator<Item = (&'a Recovered<T>, Range<usize>)> + 'a,
+) -> impl Iterator<Item = WithEncoded<&'a Recovered<T>>> + 'a {
+    transactions.into_iter().map(|(tx, range)| WithEncoded::new(buffer.slice(range), tx))
+}
+
 impl<Eip4844: AsRef<TxEip4844>> FromTxWithEncoded<EthereumTxEnvelope<Eip4844>> for TxEnv {
     fn from_encoded_tx(tx: &EthereumTxEnvelope<Eip4844>, caller: Address, encoded: Bytes) -> Self {
         match tx {
@@ -523,6 +542,55 @@ impl<Eip4844: AsRef<TxEip4844>> FromRecoveredTx<EthereumTxEnvelope<Eip4844>> for
     }
 }
 
+/// Error returned by [`tx_env_from_encoded`].
+#[derive(Debug, thiserror::Error)]
+pub enum EncodedTxError {
+    /// The bytes are not a valid EIP-2718 envelope.
+    #[error(transparent)]
+    Decode(#[from] Eip2718Error),
+    /// The signer could not be recovered from the signature.
+    #[error(transparent)]
+    Recovery(#[from] RecoveryError),
+}
+
+/// Decodes an EIP-2718 encoded transaction, recovers its signer and builds the transaction
+/// environment for it, e.g. to simulate a transaction submitted with `eth_sendRawTransaction`.
+///
+/// The chain is selected by the type parameters, e.g. `TxEnvelope` and [`TxEnv`] for Ethereum or
+/// `OpTxEnvelope` and `OpTransaction<TxEnv>` for OP, which keeps the encoded bytes to compute the
+/// L1 fee.
+///
+/// Returns the environment together with the signer.
+pub fn tx_env_from_encoded<Tx, TxEnv>(
+    encoded: impl Into<Bytes>,
+) -> Result<(TxEnv, Address), EncodedTxError>
+where
+    Tx: Decodable2718 + SignerRecoverable,
+    TxEnv: FromTxWithEncoded<Tx>,
+{
+    let encoded = encoded.into();
+    let tx = Tx::decode_2718_exact(&encoded)?;
+    let sender = tx.recover_signer()?;
+    Ok((TxEnv::from_encoded_tx(&tx, sender, encoded), sender))
+}
+
+/// Recovers the signers of the transactions in parallel, e.g. of all transactions of a block
+/// before executing it.
+///
+/// The returned transactions are in the same order and can be passed to the block executor
+/// directly. Fails if the signer of any transaction can't be recovered.
+#[cfg(feature = "rayon")]
+pub fn recover_signers_parallel<T>(
+    transactions: impl rayon::iter::IntoParallelIterator<Item = T>,
+) -> Result<alloc::vec::Vec<Recovered<T>>, RecoveryError>
+where
+    T: SignerRecoverable + Send,
+{
+    use rayon::iter::ParallelIterator;
+
+    transactions.into_par_iter().map(SignerRecoverable::try_into_recovered).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -566,4 +634,68 @@ mod tests {
         assert_recoverable::<Recovered<MyTransaction>>();
         assert_recoverable::<WithEncoded<Recovered<MyTransaction>>>();
     }
+
+    #[test]
+    fn test_tx_env_from_encoded() {
+        use alloy_consensus::{SignableTransaction, TxEnvelope};
+        use alloy_eips::Encodable2718;
+        use alloy_primitives::{Signature, U256};
+
+        let tx = TxLegacy {
+            nonce: 1,
+            gas_limit: 21_000,
+            to: TxKind::Call(Address::repeat_byte(0x02)),
+            value: U256::from(1),
+            ..Default::default()
+        };
+        let envelope = TxEnvelope::from(tx.into_signed(Signature::test_signature()));
+        let encoded = envelope.encoded_2718();
+
+        let (tx_env, sender) = tx_env_from_encoded::<TxEnvelope, TxEnv>(encoded.clone()).unwrap();
+        assert_eq!(sender, envelope.recover_signer().unwrap());
+        assert_eq!(tx_env.caller, sender);
+        assert_eq!(tx_env.nonce, 1);
+        assert_eq!(tx_env.value, U256::from(1));
+
+        let mut trailing = encoded;
+        trailing.push(0);
+        assert!(matches!(
+            tx_env_from_encoded::<TxEnvelope, TxEnv>(trailing),
+            Err(EncodedTxError::Decode(_))
+        ));
+    }
+
+    #[test]
+    fn test_with_encoded_slices() {
+        let buffer = Bytes::from_static(&[1, 2, 3, 4, 5]);
+        let txs = [
+            Recovered::new_unchecked(MyTransaction, Address::ZERO),
+            Recovered::new_unchecked(MyTransaction, Address::ZERO),
+        ];
+        let encoded: alloc::vec::Vec<_> =
+            with_encoded_slices(&buffer, [(&txs[0], 0..2), (&txs[1], 2..5)]).collect();
+        assert_eq!(encoded[0].encoded_bytes()[..], [1, 2]);
+        assert_eq!(encoded[1].encoded_bytes()[..], [3, 4, 5]);
+        assert_eq!(encoded[1].encoded_bytes().as_ptr(), buffer[2..].as_ptr());
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn test_recover_signers_parallel() {
+        use alloy_consensus::{SignableTransaction, TxEnvelope};
+        use alloy_primitives::Signature;
+
+        let txs: alloc::vec::Vec<TxEnvelope> = (0..8)
+            .map(|nonce| {
+                TxLegacy { nonce, gas_limit: 21_000, ..Default::default() }
+                    .into_signed(Signature::test_signature())
+                    .into()
+            })
+            .collect();
+        let recovered = recover_signers_parallel(txs.clone()).unwrap();
+        for (tx, recovered) in txs.iter().zip(&recovered) {
+            assert_eq!(recovered.inner(), tx);
+            assert_eq!(recovered.signer(), tx.recover_signer().unwrap());
+        }
+    }
 }
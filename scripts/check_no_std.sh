@@ -5,17 +5,39 @@ no_std_packages=(
   alloy-evm
 )
 
+# Feature sets that must build without `std`, e.g. for zkVM guests.
+no_std_features=(
+  ""
+  "op"
+  "serde,genesis,p256"
+)
+
+# Crates wrapping native crypto backends, which zkVM guests can't link.
+native_crypto_crates="secp256k1-sys|c-kzg|blst|gmp-mpfr-sys"
+
 for package in "${no_std_packages[@]}"; do
-  cmd="cargo +stable build -p $package --target riscv32imac-unknown-none-elf --no-default-features"
-  if [ -n "$CI" ]; then
-    echo "::group::$cmd"
-  else
-    printf "\n%s:\n  %s\n" "$package" "$cmd"
-  fi
-
-  $cmd
-
-  if [ -n "$CI" ]; then
-    echo "::endgroup::"
-  fi
+  for features in "${no_std_features[@]}"; do
+    cmd="cargo +stable build -p $package --target riscv32imac-unknown-none-elf --no-default-features"
+    if [ -n "$features" ]; then
+      cmd="$cmd --features $features"
+    fi
+
+    if [ -n "$CI" ]; then
+      echo "::group::$cmd"
+    else
+      printf "\n%s:\n  %s\n" "$package" "$cmd"
+    fi
+
+    $cmd
+
+    if cargo +stable tree -p "$package" --target riscv32imac-unknown-none-elf --no-default-features \
+      ${features:+--features "$features"} -e normal --prefix none | grep -Eq "^($native_crypto_crates) "; then
+      echo "$package with features \"$features\" depends on a native crypto backend"
+      exit 1
+    fi
+
+    if [ -n "$CI" ]; then
+      echo "::endgroup::"
+    fi
+  done
 done